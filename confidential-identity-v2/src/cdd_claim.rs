@@ -12,10 +12,15 @@
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
 use cryptography_core::{
-    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+    codec_wrapper::{
+        RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder, TypeTag,
+        Version, Versioned,
+    },
     RistrettoPoint, Scalar,
 };
 use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_512};
 
 use crate::{
@@ -24,13 +29,21 @@ use crate::{
     UserKeys,
 };
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CddClaim {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub claim_c_1_hat: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub claim_o_1_hat: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     claim_a_1_hat: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     claim_r_1_hat: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_a: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_r0: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_r1: Scalar,
 }
 
@@ -79,6 +92,11 @@ impl Decode for CddClaim {
     }
 }
 
+impl Versioned for CddClaim {
+    const TYPE_TAG: TypeTag = *b"CCL2";
+    const VERSION: Version = 1;
+}
+
 impl CddClaim {
     pub fn new<R: RngCore + CryptoRng>(
         identity_signature: &IdentitySignature,
@@ -121,6 +121,8 @@ use cryptography_core::{
     cdd_claim::PedersenGenerators, CompressedRistretto, RistrettoPoint, Scalar,
 };
 use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use sha3::{digest::FixedOutput, Digest, Sha3_512};
 use zeroize::Zeroize;
 
@@ -141,27 +143,38 @@ pub mod sign;
 
 const PUBLIC_KEY_ZKP_SIG_MSG: &str = "Polymath ZKP Fixed Message Proof";
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UserKeys {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub public: RistrettoPoint,
     private: PrivateKey,
 }
 
 #[derive(Zeroize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[zeroize(drop)] // Overwrite secret key material with null bytes when it goes out of scope.
 pub struct PrivateKey {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) key: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) nonce: [u8; 32],
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IssuerKeys {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub public: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub private: Scalar,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[allow(non_snake_case)]
 pub struct IdentityZkProof {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     R: CompressedRistretto,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     s: Scalar,
 }
 
@@ -7,14 +7,23 @@ use crate::{cdd_claim::CddClaim, get_g, get_g1, get_g2, UserKeys};
 use codec::Encode;
 use cryptography_core::{RistrettoPoint, Scalar};
 use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_512};
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScopeClaim {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     scope_did: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     claim: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_a: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_b: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_ss: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     proof_tt: RistrettoPoint,
 }
 
@@ -12,42 +12,70 @@
 use crate::{get_g, IssuerKeys};
 use cryptography_core::{RistrettoPoint, Scalar};
 use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_512};
 
 /// TODO: needs better name
 /// Created by the Issuer and sent over the wire to the User.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Step1PublicData {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_b: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_z: RistrettoPoint,
 }
 
 /// TODO: needs better name
-pub struct Step1SecretData(Scalar);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Step1SecretData(
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))] Scalar,
+);
 
 /// TODO: needs better name
 /// Created by the Issuer and sent over the wire to the User.
-pub struct Step2PublicData(Scalar);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Step2PublicData(
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))] Scalar,
+);
 
 /// TODO: needs better name
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Step2SecretData {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     alpha: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     beta2: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     h: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_a_prime: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_b_prime: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_z_prime: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     sigma_c_prime: Scalar,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IdentitySignature {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub h: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub sigma_z_prime: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub sigma_c_prime: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub sigma_r_prime: Scalar,
 }
 
-pub struct IdentitySignaturePrivateKey(pub(crate) Scalar);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IdentitySignaturePrivateKey(
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))] pub(crate) Scalar,
+);
 
 /// TODO: needs better name
 /// Given the `user_public_key` and the `issuer_keypair`, the Issuer computes and returns
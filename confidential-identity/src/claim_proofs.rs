@@ -44,7 +44,10 @@ use blake2::{Blake2b, Blake2s, Digest};
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
 use cryptography_core::{
     cdd_claim::pedersen_commitments::{generate_blinding_factor, PedersenGenerators},
-    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+    codec_wrapper::{
+        RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder, TypeTag,
+        Version, Versioned,
+    },
 };
 use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
 use rand_core::{CryptoRng, RngCore};
@@ -75,7 +78,9 @@ pub type CddId = cryptography_core::cdd_claim::CddId;
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScopeClaimData {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub scope_did: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub investor_unique_id: Scalar,
 }
 
@@ -93,9 +98,13 @@ impl ScopeClaimData {
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScopeClaimProofData {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub scope_did: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub scope_did_hash: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub investor_did: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub investor_unique_id: Scalar,
 }
 
@@ -107,6 +116,7 @@ pub struct ScopeClaimProofData {
 pub struct ScopeClaimProof {
     pub proof_scope_id_wellformed: Signature,
     pub proof_scope_id_cdd_id_match: ZkProofData,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub scope_id: RistrettoPoint,
 }
 
@@ -139,14 +149,22 @@ impl Decode for ScopeClaimProof {
     }
 }
 
+impl Versioned for ScopeClaimProof {
+    const TYPE_TAG: TypeTag = *b"CSCP";
+    const VERSION: Version = 1;
+}
+
 const ZK_PROOF_DATA_CHG_RESPONSES: usize = 2;
 
 /// Stores the zero knowlegde proof data for scope_id and cdd_id matching.
 #[derive(Debug, Clone, PartialEq, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ZkProofData {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     challenge_responses: [Scalar; ZK_PROOF_DATA_CHG_RESPONSES],
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     subtract_expressions_res: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     blinded_scope_did_hash: RistrettoPoint,
 }
 
@@ -384,6 +402,31 @@ mod tests {
         result.unwrap();
     }
 
+    #[test]
+    fn scope_claim_proof_versioned_codec() {
+        let mut rng = StdRng::from_seed(SEED);
+        let cdd_claim = CddClaimData::new(&[1u8; 32], &[2u8; 32]);
+        let scope_claim = ScopeClaimData::new(&[4u8; 32], &[2u8; 32]);
+        let cdd_id = Provider::create_cdd_id(&cdd_claim);
+        let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
+
+        let encoded = proof.encode_versioned();
+        let decoded = ScopeClaimProof::decode_versioned(&mut &encoded[..]).unwrap();
+        assert_eq!(decoded, proof);
+        Verifier::verify_scope_claim_proof(
+            &decoded,
+            &cdd_claim.investor_did,
+            &scope_claim.scope_did,
+            &cdd_id,
+        )
+        .unwrap();
+
+        // Other types and unversioned encodings are rejected.
+        let cdd_id_encoded = cdd_id.encode_versioned();
+        assert!(ScopeClaimProof::decode_versioned(&mut &cdd_id_encoded[..]).is_err());
+        assert!(ScopeClaimProof::decode_versioned(&mut &proof.encode()[..]).is_err());
+    }
+
     #[test]
     fn test_zkp_proof() {
         let mut rng = StdRng::from_seed(SEED);
@@ -22,9 +22,12 @@ use zeroize::Zeroize;
 /// Instances of this secret are automatically overwritten with zeroes when they
 /// fall out of scope.
 #[derive(Zeroize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[zeroize(drop)] // Overwrite secret key material with null bytes when it goes out of scope.
 pub struct SecretKey {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) key: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) nonce: [u8; 32],
 }
 
@@ -46,7 +49,9 @@ impl SecretKey {
 
 /// A Schnorr public key.
 #[derive(Copy, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PublicKey {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) key: RistrettoPoint,
 }
 
@@ -55,7 +60,9 @@ pub struct PublicKey {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[allow(non_snake_case)]
 pub struct Signature {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) R: CompressedRistretto,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub(crate) s: Scalar,
 }
 
@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use cryptography_core::asset_proofs::{CipherText, ElgamalSecretKey};
+use cryptography_core::asset_proofs::{CipherText, DiscreteLogTable, ElgamalSecretKey};
 use curve25519_dalek::scalar::Scalar;
 
 use rand::{rngs::StdRng, SeedableRng};
@@ -22,13 +22,40 @@ fn bench_elgamal_decrypt(
     );
 }
 
+fn bench_elgamal_decrypt_with_table(
+    c: &mut Criterion,
+    elg_secret: ElgamalSecretKey,
+    cipher: CipherText,
+) {
+    let label = "elgamal dec with table bench".to_string();
+    let tables: Vec<(String, DiscreteLogTable)> = [12u32, 16, 20]
+        .iter()
+        .map(|bits| {
+            (
+                format!("baby steps bits ({:?})", bits),
+                DiscreteLogTable::new(32, *bits).unwrap(),
+            )
+        })
+        .collect();
+
+    c.bench_function_over_inputs(
+        &label,
+        move |b, (_label, table)| {
+            b.iter(|| {
+                elg_secret.decrypt_with_table(&cipher, table).unwrap();
+            })
+        },
+        tables,
+    );
+}
+
 fn bench_elgamal(c: &mut Criterion) {
     let mut rng = StdRng::from_seed([42u8; 32]);
 
     let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
     let elg_pub = elg_secret.get_public_key();
 
-    let encrypted_values: Vec<(String, CipherText)> = (0..6)
+    let encrypted_values: Vec<(String, CipherText)> = (0..10)
         .map(|i| {
             let value = 10u32.pow(i);
             let encryptd_value = elg_pub.encrypt_value(value.into(), &mut rng).1;
@@ -36,7 +63,10 @@ fn bench_elgamal(c: &mut Criterion) {
         })
         .collect();
 
-    bench_elgamal_decrypt(c, elg_secret, encrypted_values);
+    let max_value = elg_pub.encrypt_value(u32::max_value().into(), &mut rng).1;
+
+    bench_elgamal_decrypt(c, elg_secret.clone(), encrypted_values);
+    bench_elgamal_decrypt_with_table(c, elg_secret, max_value);
 }
 
 criterion_group! {
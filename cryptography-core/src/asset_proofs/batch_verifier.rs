@@ -0,0 +1,321 @@
+//! The `batch_verifier` library verifies many non-interactive
+//! sigma proofs at once.
+//!
+//! Every sigma proof verifier can express its checks as a list of
+//! `VerificationEquation`s, i.e., multiscalar multiplications that must
+//! evaluate to the identity point. Given the equations of all the proofs in
+//! a batch, the batch verifier multiplies each equation by a fresh random
+//! weight and sums them up. The batch is accepted only if the resulting
+//! single multiscalar multiplication is the identity. The weights are drawn
+//! from a transcript of all the equations, so they can not be predicted by
+//! the provers, and an invalid proof makes this sum non-zero except with
+//! negligible probability. Since no external randomness is involved, every
+//! verifier of a batch gets the same result.
+//!
+//! Proofs of different kinds can be mixed in the same batch.
+
+use crate::asset_proofs::{
+    encryption_proofs::{
+        non_interactive_challenge, AssetProofVerifier, VerificationEquation, ZKProofResponse,
+    },
+    errors::{ErrorKind, Fallible},
+    transcript::TranscriptProtocol,
+};
+
+use curve25519_dalek::{
+    ristretto::RistrettoPoint,
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
+use merlin::Transcript;
+use sp_std::prelude::*;
+
+/// The domain label for the transcript of the batched equations.
+const BATCH_VERIFIER_LABEL: &[u8] = b"PolymathBatchVerifier";
+
+/// Collects the verification equations of several sigma proofs and checks
+/// all of them with a single multiscalar multiplication.
+#[derive(Default)]
+pub struct BatchVerifier {
+    /// The equations of each added proof, or the error that was raised
+    /// while computing them.
+    proofs: Vec<Fallible<Vec<VerificationEquation>>>,
+}
+
+impl BatchVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a non-interactive proof to the batch.
+    /// The proof is checked when `verify` is called.
+    ///
+    /// # Inputs
+    /// `verifier` Any verifier that implements the `AssetProofVerifier` trait.
+    /// `proof`    Prover's initial message and final response.
+    pub fn add<Verifier: AssetProofVerifier>(
+        &mut self,
+        verifier: &Verifier,
+        proof: &ZKProofResponse<Verifier::ZKInitialMessage, Verifier::ZKFinalResponse>,
+    ) {
+        let (initial_message, final_response) = proof;
+        let equations = non_interactive_challenge(initial_message).and_then(|challenge| {
+            verifier.verification_equations(&challenge, initial_message, final_response)
+        });
+        self.proofs.push(equations);
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Verifies all the proofs of the batch.
+    /// Returns a `BatchVerificationError` with the index of the first invalid proof
+    /// if the batch is rejected.
+    pub fn verify(&self) -> Fallible<()> {
+        let mut transcript = Transcript::new(BATCH_VERIFIER_LABEL);
+        for (index, equations) in self.proofs.iter().enumerate() {
+            let equations = equations
+                .as_ref()
+                .map_err(|_| ErrorKind::BatchVerificationError { index })?;
+            for equation in equations {
+                ensure!(
+                    equation.scalars.len() == equation.points.len(),
+                    ErrorKind::BatchVerificationError { index }
+                );
+                transcript.append_u64(b"terms", equation.scalars.len() as u64);
+                for (scalar, point) in equation.scalars.iter().zip(&equation.points) {
+                    transcript.append_message(b"scalar", scalar.as_bytes());
+                    transcript.append_message(b"point", point.compress().as_bytes());
+                }
+            }
+        }
+        let mut rng = transcript.create_verifier_rng();
+
+        let mut scalars: Vec<Scalar> = Vec::new();
+        let mut points: Vec<RistrettoPoint> = Vec::new();
+        for equations in self.proofs.iter().flatten() {
+            for equation in equations {
+                let weight = Scalar::random(&mut rng);
+                scalars.extend(equation.scalars.iter().map(|scalar| weight * scalar));
+                points.extend_from_slice(&equation.points);
+            }
+        }
+
+        if RistrettoPoint::vartime_multiscalar_mul(&scalars, &points).is_identity() {
+            return Ok(());
+        }
+
+        // Find the proof that invalidated the batch.
+        for (index, equations) in self.proofs.iter().enumerate() {
+            if let Ok(equations) = equations {
+                ensure!(
+                    equations.iter().all(VerificationEquation::holds),
+                    ErrorKind::BatchVerificationError { index }
+                );
+            }
+        }
+        Err(ErrorKind::VerificationError.into())
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::{
+        ciphertext_refreshment_proof::{
+            CipherTextRefreshmentProverAwaitingChallenge, CipherTextRefreshmentVerifier,
+        },
+        correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
+        encrypting_same_value_proof::{
+            EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
+        },
+        encryption_proofs::{single_property_prover, single_property_verifier},
+        membership_proof::{MembershipProofVerifier, MembershipProverAwaitingChallenge},
+        one_out_of_many_proof::OooNProofGenerators,
+        wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
+        ElgamalSecretKey,
+    };
+    use bulletproofs::PedersenGens;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+    use zeroize::Zeroizing;
+
+    const SEED_1: [u8; 32] = [42u8; 32];
+    const BASE: u32 = 4;
+    const EXPONENT: u32 = 3;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn batch_sigma_proofs() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let gens = PedersenGens::default();
+        let secret_value = Scalar::from(17u32);
+
+        let secret_key1 = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key1 = secret_key1.get_public_key();
+        let secret_key2 = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key2 = secret_key2.get_public_key();
+
+        let (w, cipher) = pub_key1.encrypt_value(secret_value, &mut rng);
+        let cipher2 = pub_key2.encrypt(&w);
+        let (_, refreshed_cipher) = pub_key1.encrypt_value(secret_value, &mut rng);
+
+        // Correctness proof.
+        let correctness_verifier = CorrectnessVerifier {
+            value: secret_value,
+            pub_key: pub_key1,
+            cipher,
+            pc_gens: &gens,
+        };
+        let correctness_proof = single_property_prover(
+            CorrectnessProverAwaitingChallenge {
+                pub_key: pub_key1,
+                w: w.clone(),
+                pc_gens: &gens,
+            },
+            &mut rng,
+        )
+        .unwrap();
+
+        // Wellformedness proof.
+        let wellformedness_verifier = WellformednessVerifier {
+            pub_key: pub_key1,
+            cipher,
+            pc_gens: &gens,
+        };
+        let wellformedness_proof = single_property_prover(
+            WellformednessProverAwaitingChallenge {
+                pub_key: pub_key1,
+                w: Zeroizing::new(w.clone()),
+                pc_gens: &gens,
+            },
+            &mut rng,
+        )
+        .unwrap();
+
+        // Ciphertext refreshment proof.
+        let refreshment_verifier =
+            CipherTextRefreshmentVerifier::new(pub_key1, cipher, refreshed_cipher, &gens);
+        let refreshment_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                secret_key1,
+                cipher,
+                refreshed_cipher,
+                &gens,
+            ),
+            &mut rng,
+        )
+        .unwrap();
+
+        // Encrypting same value proof.
+        let same_value_verifier = EncryptingSameValueVerifier {
+            pub_key1,
+            pub_key2,
+            cipher1: cipher,
+            cipher2,
+            pc_gens: &gens,
+        };
+        let same_value_proof = single_property_prover(
+            EncryptingSameValueProverAwaitingChallenge {
+                pub_key1,
+                pub_key2,
+                w: Zeroizing::new(w),
+                pc_gens: &gens,
+            },
+            &mut rng,
+        )
+        .unwrap();
+
+        // Membership proof.
+        let generators = OooNProofGenerators::new(EXPONENT, BASE);
+        let elements: Vec<Scalar> = (0..40u32).map(Scalar::from).collect();
+        let blinding = Scalar::random(&mut rng);
+        let member_commitment = generators.com_gens.commit(secret_value, blinding);
+        let membership_verifier = MembershipProofVerifier {
+            secret_element_com: member_commitment,
+            elements_set: &elements,
+            generators: &generators,
+        };
+        let membership_proof = single_property_prover(
+            MembershipProverAwaitingChallenge::new(
+                secret_value,
+                blinding,
+                &generators,
+                &elements,
+                BASE,
+                EXPONENT,
+            )
+            .unwrap(),
+            &mut rng,
+        )
+        .unwrap();
+        assert!(single_property_verifier(&membership_verifier, membership_proof.clone()).is_ok());
+
+        // Positive test: all the proofs verify in one batch.
+        assert!(BatchVerifier::new().verify().is_ok());
+        let mut batch = BatchVerifier::new();
+        batch.add(&correctness_verifier, &correctness_proof);
+        batch.add(&wellformedness_verifier, &wellformedness_proof);
+        batch.add(&refreshment_verifier, &refreshment_proof);
+        batch.add(&same_value_verifier, &same_value_proof);
+        batch.add(&membership_verifier, &membership_proof);
+        assert_eq!(batch.len(), 5);
+        assert!(batch.verify().is_ok());
+
+        // Negative test: a proof checked against the wrong statement.
+        let wrong_correctness_verifier = CorrectnessVerifier {
+            value: secret_value + Scalar::one(),
+            pub_key: pub_key1,
+            cipher,
+            pc_gens: &gens,
+        };
+        let mut batch = BatchVerifier::new();
+        batch.add(&wellformedness_verifier, &wellformedness_proof);
+        batch.add(&refreshment_verifier, &refreshment_proof);
+        batch.add(&wrong_correctness_verifier, &correctness_proof);
+        batch.add(&membership_verifier, &membership_proof);
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 2 }
+        );
+
+        // Negative test: a member that is not in the verifier's set.
+        let other_elements: Vec<Scalar> = (100..140u32).map(Scalar::from).collect();
+        let wrong_membership_verifier = MembershipProofVerifier {
+            secret_element_com: member_commitment,
+            elements_set: &other_elements,
+            generators: &generators,
+        };
+        let mut batch = BatchVerifier::new();
+        batch.add(&wrong_membership_verifier, &membership_proof);
+        batch.add(&same_value_verifier, &same_value_proof);
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 0 }
+        );
+
+        // Negative test: a proof whose equations can not be computed.
+        let empty_membership_verifier = MembershipProofVerifier {
+            secret_element_com: member_commitment,
+            elements_set: &[],
+            generators: &generators,
+        };
+        let mut batch = BatchVerifier::new();
+        batch.add(&correctness_verifier, &correctness_proof);
+        batch.add(&empty_membership_verifier, &membership_proof);
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 1 }
+        );
+    }
+}
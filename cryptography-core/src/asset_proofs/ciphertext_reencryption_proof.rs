@@ -0,0 +1,547 @@
+//! The proof that 2 cipher texts encrypt the same value
+//! under 2 different public keys, where the owner of the
+//! first key has re-encrypted the value under the second key.
+//! This proof is useful to rotate the keys of an account
+//! without revealing its balance.
+//!
+//! Given `(X1, Y1)` under `pub_key1 = secret_key1 * g`, and
+//! `(X2, Y2) = (r * pub_key2, r * g + value * h)`, the prover
+//! knows `d = 1 / secret_key1` and `r` such that:
+//! ```text
+//! g       = d * pub_key1
+//! X2      = r * pub_key2
+//! Y1 - Y2 = d * X1 - r * g
+//! ```
+//! The last equation holds since `d * X1 = Y1 - value * h`.
+
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{
+        single_property_prover, AssetProofProver, AssetProofProverAwaitingChallenge,
+    },
+    CommitmentWitness, ElgamalSecretKey,
+};
+use crate::{
+    asset_proofs::{
+        encryption_proofs::{
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
+        },
+        errors::{ErrorKind, Fallible},
+        transcript::{TranscriptProtocol, UpdateTranscript},
+        CipherText, ElgamalPublicKey,
+    },
+    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+};
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
+};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
+use zeroize::{Zeroize, Zeroizing};
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::prelude::*;
+
+/// The domain label for the ciphertext re-encryption proof.
+pub const CIPHERTEXT_REENCRYPTION_FINAL_RESPONSE_LABEL: &[u8] =
+    b"PolymathCipherTextReencryptionFinalResponse";
+/// The domain label for the challenge.
+pub const CIPHERTEXT_REENCRYPTION_PROOF_CHALLENGE_LABEL: &[u8] =
+    b"PolymathCipherTextReencryptionChallenge";
+
+// ------------------------------------------------------------------------
+// Proof of two ciphertext encrypting the same value under two
+// different public keys, both known to the owner
+// ------------------------------------------------------------------------
+
+#[derive(PartialEq, Copy, Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CipherTextReencryptionFinalResponse {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    z1: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    z2: Scalar,
+}
+
+impl Encode for CipherTextReencryptionFinalResponse {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        ScalarEncoder(&self.z1).size_hint() + ScalarEncoder(&self.z2).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        ScalarEncoder(&self.z1).encode_to(dest);
+        ScalarEncoder(&self.z2).encode_to(dest);
+    }
+}
+
+impl Decode for CipherTextReencryptionFinalResponse {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let z1 = <ScalarDecoder>::decode(input)?.0;
+        let z2 = <ScalarDecoder>::decode(input)?.0;
+
+        Ok(CipherTextReencryptionFinalResponse { z1, z2 })
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CipherTextReencryptionInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    a1: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    a2: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    a3: RistrettoPoint,
+}
+
+/// A default implementation used for testing.
+impl Default for CipherTextReencryptionInitialMessage {
+    fn default() -> Self {
+        CipherTextReencryptionInitialMessage {
+            a1: RISTRETTO_BASEPOINT_POINT,
+            a2: RISTRETTO_BASEPOINT_POINT,
+            a3: RISTRETTO_BASEPOINT_POINT,
+        }
+    }
+}
+
+impl Encode for CipherTextReencryptionInitialMessage {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        RistrettoPointEncoder(&self.a1).size_hint()
+            + RistrettoPointEncoder(&self.a2).size_hint()
+            + RistrettoPointEncoder(&self.a3).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        RistrettoPointEncoder(&self.a1).encode_to(dest);
+        RistrettoPointEncoder(&self.a2).encode_to(dest);
+        RistrettoPointEncoder(&self.a3).encode_to(dest);
+    }
+}
+
+impl Decode for CipherTextReencryptionInitialMessage {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let a1 = <RistrettoPointDecoder>::decode(input)?.0;
+        let a2 = <RistrettoPointDecoder>::decode(input)?.0;
+        let a3 = <RistrettoPointDecoder>::decode(input)?.0;
+
+        Ok(CipherTextReencryptionInitialMessage { a1, a2, a3 })
+    }
+}
+
+impl UpdateTranscript for CipherTextReencryptionInitialMessage {
+    fn update_transcript(&self, transcript: &mut Transcript) -> Fallible<()> {
+        transcript.append_domain_separator(CIPHERTEXT_REENCRYPTION_PROOF_CHALLENGE_LABEL);
+        transcript.append_validated_point(b"A1", &self.a1.compress())?;
+        transcript.append_validated_point(b"A2", &self.a2.compress())?;
+        transcript.append_validated_point(b"A3", &self.a3.compress())?;
+        Ok(())
+    }
+}
+
+/// Holds the non-interactive proofs of equality of a cipher text and its
+/// re-encryption under a new public key.
+pub type CipherEqualRotatedPubKeyProof =
+    ZKProofResponse<CipherTextReencryptionInitialMessage, CipherTextReencryptionFinalResponse>;
+
+#[cfg(not(feature = "verify-only"))]
+pub struct CipherTextReencryptionProverAwaitingChallenge<'a> {
+    /// The secret key of the first cipher text.
+    secret_key: ElgamalSecretKey,
+
+    /// The public key of the second cipher text.
+    new_pub_key: ElgamalPublicKey,
+
+    /// The witness of the second cipher text.
+    new_witness: Zeroizing<CommitmentWitness>,
+
+    /// The X part of the first cipher text.
+    x: RistrettoPoint,
+    pc_gens: &'a PedersenGens,
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> CipherTextReencryptionProverAwaitingChallenge<'a> {
+    pub fn new(
+        secret_key: ElgamalSecretKey,
+        new_pub_key: ElgamalPublicKey,
+        new_witness: CommitmentWitness,
+        ciphertext: CipherText,
+        gens: &'a PedersenGens,
+    ) -> Self {
+        CipherTextReencryptionProverAwaitingChallenge {
+            secret_key,
+            new_pub_key,
+            new_witness: Zeroizing::new(new_witness),
+            x: ciphertext.x,
+            pc_gens: gens,
+        }
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct CipherTextReencryptionProver {
+    /// The inverse of the first secret key.
+    inverse_secret: Scalar,
+
+    /// The blinding factor of the second cipher text.
+    blinding: Scalar,
+
+    /// The randomness generated in the first round.
+    u1: Scalar,
+
+    /// The randomness generated in the first round.
+    u2: Scalar,
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofProverAwaitingChallenge for CipherTextReencryptionProverAwaitingChallenge<'a> {
+    type ZKInitialMessage = CipherTextReencryptionInitialMessage;
+    type ZKFinalResponse = CipherTextReencryptionFinalResponse;
+    type ZKProver = CipherTextReencryptionProver;
+
+    fn create_transcript_rng<T: RngCore + CryptoRng>(
+        &self,
+        rng: &mut T,
+        transcript: &Transcript,
+    ) -> TranscriptRng {
+        transcript
+            .build_rng()
+            .rekey_with_witness_bytes(b"secret_key", self.secret_key.secret.as_bytes())
+            .rekey_with_witness_bytes(b"w_blinding", self.new_witness.blinding().as_bytes())
+            .finalize(rng)
+    }
+
+    fn generate_initial_message(
+        &self,
+        rng: &mut TranscriptRng,
+    ) -> (Self::ZKProver, Self::ZKInitialMessage) {
+        let rand_commitment1 = Scalar::random(rng);
+        let rand_commitment2 = Scalar::random(rng);
+        let pub_key = self.secret_key.get_public_key();
+
+        let initial_message = CipherTextReencryptionInitialMessage {
+            a1: rand_commitment1 * pub_key.pub_key,
+            a2: rand_commitment2 * self.new_pub_key.pub_key,
+            a3: rand_commitment1 * self.x - rand_commitment2 * self.pc_gens.B_blinding,
+        };
+
+        let prover = CipherTextReencryptionProver {
+            inverse_secret: self.secret_key.secret.invert(),
+            blinding: self.new_witness.blinding(),
+            u1: rand_commitment1,
+            u2: rand_commitment2,
+        };
+        (prover, initial_message)
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl AssetProofProver<CipherTextReencryptionFinalResponse> for CipherTextReencryptionProver {
+    fn apply_challenge(&self, c: &ZKPChallenge) -> CipherTextReencryptionFinalResponse {
+        CipherTextReencryptionFinalResponse {
+            z1: self.u1 + c.x() * self.inverse_secret,
+            z2: self.u2 + c.x() * self.blinding,
+        }
+    }
+}
+
+pub struct CipherTextReencryptionVerifier<'a> {
+    /// The public key of the first cipher text.
+    pub pub_key1: ElgamalPublicKey,
+
+    /// The public key of the second cipher text.
+    pub pub_key2: ElgamalPublicKey,
+
+    /// The first cipher text.
+    pub cipher1: CipherText,
+
+    /// The second cipher text.
+    pub cipher2: CipherText,
+
+    /// The ciphertext generators.
+    pub pc_gens: &'a PedersenGens,
+}
+
+impl<'a> AssetProofVerifier for CipherTextReencryptionVerifier<'a> {
+    type ZKInitialMessage = CipherTextReencryptionInitialMessage;
+    type ZKFinalResponse = CipherTextReencryptionFinalResponse;
+
+    fn verify(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let c = challenge.x();
+        let g = self.pc_gens.B_blinding;
+
+        ensure!(
+            final_response.z1 * self.pub_key1.pub_key == initial_message.a1 + c * g,
+            ErrorKind::CiphertextReencryptionFinalResponseVerificationError { check: 1 }
+        );
+        ensure!(
+            final_response.z2 * self.pub_key2.pub_key == initial_message.a2 + c * self.cipher2.x,
+            ErrorKind::CiphertextReencryptionFinalResponseVerificationError { check: 2 }
+        );
+        ensure!(
+            final_response.z1 * self.cipher1.x - final_response.z2 * g
+                == initial_message.a3 + c * (self.cipher1.y - self.cipher2.y),
+            ErrorKind::CiphertextReencryptionFinalResponseVerificationError { check: 3 }
+        );
+        Ok(())
+    }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let c = *challenge.x();
+        let g = self.pc_gens.B_blinding;
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(final_response.z1, self.pub_key1.pub_key)
+                .term(-Scalar::one(), initial_message.a1)
+                .term(-c, g),
+            VerificationEquation::new()
+                .term(final_response.z2, self.pub_key2.pub_key)
+                .term(-Scalar::one(), initial_message.a2)
+                .term(-c, self.cipher2.x),
+            VerificationEquation::new()
+                .term(final_response.z1, self.cipher1.x)
+                .term(-final_response.z2, g)
+                .term(-Scalar::one(), initial_message.a3)
+                .term(-c, self.cipher1.y)
+                .term(c, self.cipher2.y),
+        ])
+    }
+}
+
+// ------------------------------------------------------------------------
+// CipherText Re-encryption Method
+// ------------------------------------------------------------------------
+
+#[cfg(not(feature = "verify-only"))]
+fn reencrypt_helper<R: RngCore + CryptoRng>(
+    cipher_text: &CipherText,
+    secret_key: &ElgamalSecretKey,
+    new_pub_key: ElgamalPublicKey,
+    value: Scalar,
+    rng: &mut R,
+) -> Fallible<(CipherText, CipherEqualRotatedPubKeyProof)> {
+    let gens = PedersenGens::default();
+    let (new_witness, new_cipher_text) = new_pub_key.encrypt_value(value, rng);
+    let proof = single_property_prover(
+        CipherTextReencryptionProverAwaitingChallenge::new(
+            secret_key.clone(),
+            new_pub_key,
+            new_witness,
+            *cipher_text,
+            &gens,
+        ),
+        rng,
+    )?;
+
+    Ok((new_cipher_text, proof))
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl CipherText {
+    /// Decrypts the cipher text and encrypts its value under a new public key.
+    /// Returns the new cipher text along with the proof that both cipher texts
+    /// encrypt the same value.
+    pub fn reencrypt<R: RngCore + CryptoRng>(
+        &self,
+        secret_key: &ElgamalSecretKey,
+        new_pub_key: ElgamalPublicKey,
+        rng: &mut R,
+    ) -> Fallible<(CipherText, CipherEqualRotatedPubKeyProof)> {
+        let value: Scalar = secret_key.decrypt(self)?.into();
+        reencrypt_helper(self, secret_key, new_pub_key, value, rng)
+    }
+
+    /// Same as `reencrypt`, but uses the `hint` instead of decrypting the cipher text.
+    pub fn reencrypt_with_hint<R: RngCore + CryptoRng>(
+        &self,
+        secret_key: &ElgamalSecretKey,
+        new_pub_key: ElgamalPublicKey,
+        hint: &Scalar,
+        rng: &mut R,
+    ) -> Fallible<(CipherText, CipherEqualRotatedPubKeyProof)> {
+        secret_key.verify(self, hint)?;
+        reencrypt_helper(self, secret_key, new_pub_key, *hint, rng)
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [17u8; 32];
+    const SEED_2: [u8; 32] = [19u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ciphertext_reencryption_proof() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_value = Scalar::from(13u32);
+
+        let elg_secret1 = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub1 = elg_secret1.get_public_key();
+        let elg_pub2 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (_, ciphertext1) = elg_pub1.encrypt_value(secret_value, &mut rng);
+        let (w2, ciphertext2) = elg_pub2.encrypt_value(secret_value, &mut rng);
+
+        let prover = CipherTextReencryptionProverAwaitingChallenge::new(
+            elg_secret1,
+            elg_pub2,
+            w2.clone(),
+            ciphertext1,
+            &gens,
+        );
+        let verifier = CipherTextReencryptionVerifier {
+            pub_key1: elg_pub1,
+            pub_key2: elg_pub2,
+            cipher1: ciphertext1,
+            cipher2: ciphertext2,
+            pc_gens: &gens,
+        };
+        let mut transcript = Transcript::new(CIPHERTEXT_REENCRYPTION_FINAL_RESPONSE_LABEL);
+
+        // Positive tests
+        let mut transcript_rng = prover.create_transcript_rng(&mut rng, &transcript);
+        let (prover, initial_message) = prover.generate_initial_message(&mut transcript_rng);
+        initial_message.update_transcript(&mut transcript).unwrap();
+        let challenge = transcript
+            .scalar_challenge(CIPHERTEXT_REENCRYPTION_PROOF_CHALLENGE_LABEL)
+            .unwrap();
+        let final_response = prover.apply_challenge(&challenge);
+
+        let result = verifier.verify(&challenge, &initial_message, &final_response);
+        assert!(result.is_ok());
+
+        // Negative tests
+        let bad_initial_message = CipherTextReencryptionInitialMessage::default();
+        let result = verifier.verify(&challenge, &bad_initial_message, &final_response);
+        assert_err!(
+            result,
+            ErrorKind::CiphertextReencryptionFinalResponseVerificationError { check: 1 }
+        );
+
+        let bad_final_response = CipherTextReencryptionFinalResponse::default();
+        assert_err!(
+            verifier.verify(&challenge, &initial_message, &bad_final_response),
+            ErrorKind::CiphertextReencryptionFinalResponseVerificationError { check: 1 }
+        );
+
+        // A cipher text of a different value under the new key.
+        let bad_ciphertext2 = elg_pub2.encrypt(&CommitmentWitness::new(
+            secret_value + Scalar::one(),
+            w2.blinding(),
+        ));
+        let bad_verifier = CipherTextReencryptionVerifier {
+            cipher2: bad_ciphertext2,
+            ..verifier
+        };
+        assert_err!(
+            bad_verifier.verify(&challenge, &initial_message, &final_response),
+            ErrorKind::CiphertextReencryptionFinalResponseVerificationError { check: 3 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn verify_ciphertext_reencryption_method() {
+        let mut rng = StdRng::from_seed(SEED_2);
+        let gens = PedersenGens::default();
+        let old_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let old_pub = old_secret.get_public_key();
+        let new_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let new_pub = new_secret.get_public_key();
+        let (_, cipher) = old_pub.encrypt_value(3u32.into(), &mut rng);
+
+        let (new_cipher, proof) = cipher.reencrypt(&old_secret, new_pub, &mut rng).unwrap();
+        assert_eq!(new_secret.decrypt(&new_cipher).unwrap(), 3);
+
+        let verifier = CipherTextReencryptionVerifier {
+            pub_key1: old_pub,
+            pub_key2: new_pub,
+            cipher1: cipher,
+            cipher2: new_cipher,
+            pc_gens: &gens,
+        };
+        assert!(encryption_proofs::single_property_verifier(&verifier, proof).is_ok());
+
+        // A balance outside of the range of the default decryption table.
+        let large_value = Scalar::from(u64::MAX);
+        let (_, cipher) = old_pub.encrypt_value(large_value, &mut rng);
+        let (new_cipher, proof) = cipher
+            .reencrypt_with_hint(&old_secret, new_pub, &large_value, &mut rng)
+            .unwrap();
+        assert!(new_secret.verify(&new_cipher, &large_value).is_ok());
+
+        let verifier = CipherTextReencryptionVerifier {
+            pub_key1: old_pub,
+            pub_key2: new_pub,
+            cipher1: cipher,
+            cipher2: new_cipher,
+            pc_gens: &gens,
+        };
+        assert!(encryption_proofs::single_property_verifier(&verifier, proof).is_ok());
+
+        // A wrong hint is rejected.
+        assert_err!(
+            cipher.reencrypt_with_hint(&old_secret, new_pub, &Scalar::one(), &mut rng),
+            ErrorKind::CipherTextDecryptionError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn serialize_deserialize_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let new_pub = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (_, cipher) = elg_pub.encrypt_value(Scalar::from(13u32), &mut rng);
+
+        let (_, (initial_message0, final_response0)) =
+            cipher.reencrypt(&elg_secret, new_pub, &mut rng).unwrap();
+
+        let init_bytes = initial_message0.encode();
+        let mut init_slice = &init_bytes[..];
+        let recovered_initial_message =
+            <CipherTextReencryptionInitialMessage>::decode(&mut init_slice).unwrap();
+        assert_eq!(recovered_initial_message, initial_message0);
+
+        let final_bytes = final_response0.encode();
+        let mut final_slice = &final_bytes[..];
+        let recovered_final_response =
+            <CipherTextReencryptionFinalResponse>::decode(&mut final_slice).unwrap();
+        assert_eq!(recovered_final_response, final_response0);
+    }
+}
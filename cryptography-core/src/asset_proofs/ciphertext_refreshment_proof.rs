@@ -5,15 +5,19 @@
 //! For more details see sections 3.6 and 5.3 of the
 //! whitepaper.
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofSimulator},
+    ElgamalSecretKey,
+};
 use crate::{
     asset_proofs::{
         encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-            ZKProofResponse,
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
         },
         errors::{ErrorKind, Fallible},
         transcript::{TranscriptProtocol, UpdateTranscript},
-        CipherText, ElgamalPublicKey, ElgamalSecretKey,
+        CipherText, ElgamalPublicKey,
     },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
 };
@@ -22,13 +26,18 @@ use bulletproofs::PedersenGens;
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
 };
-use merlin::{Transcript, TranscriptRng};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroize;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::prelude::*;
 
 /// The domain label for the ciphertext refreshment proof.
 pub const CIPHERTEXT_REFRESHMENT_FINAL_RESPONSE_LABEL: &[u8] =
@@ -44,7 +53,9 @@ pub const CIPHERTEXT_REFRESHMENT_PROOF_CHALLENGE_LABEL: &[u8] =
 
 #[derive(PartialEq, Copy, Clone, Default, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct CipherTextRefreshmentFinalResponse(Scalar);
+pub struct CipherTextRefreshmentFinalResponse(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] Scalar,
+);
 
 impl Encode for CipherTextRefreshmentFinalResponse {
     #[inline]
@@ -68,7 +79,9 @@ impl Decode for CipherTextRefreshmentFinalResponse {
 #[derive(PartialEq, Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CipherTextRefreshmentInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     b: RistrettoPoint,
 }
 
@@ -117,6 +130,7 @@ impl UpdateTranscript for CipherTextRefreshmentInitialMessage {
 pub type CipherEqualSamePubKeyProof =
     ZKProofResponse<CipherTextRefreshmentInitialMessage, CipherTextRefreshmentFinalResponse>;
 
+#[cfg(not(feature = "verify-only"))]
 pub struct CipherTextRefreshmentProverAwaitingChallenge<'a> {
     /// The public key used for the elgamal encryption.
     secret_key: ElgamalSecretKey,
@@ -127,6 +141,7 @@ pub struct CipherTextRefreshmentProverAwaitingChallenge<'a> {
     pc_gens: &'a PedersenGens,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> CipherTextRefreshmentProverAwaitingChallenge<'a> {
     pub fn new(
         secret_key: ElgamalSecretKey,
@@ -142,6 +157,7 @@ impl<'a> CipherTextRefreshmentProverAwaitingChallenge<'a> {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Zeroize)]
 #[zeroize(drop)]
 pub struct CipherTextRefreshmentProver {
@@ -152,6 +168,7 @@ pub struct CipherTextRefreshmentProver {
     u: Scalar,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for CipherTextRefreshmentProverAwaitingChallenge<'a> {
     type ZKInitialMessage = CipherTextRefreshmentInitialMessage;
     type ZKFinalResponse = CipherTextRefreshmentFinalResponse;
@@ -187,6 +204,7 @@ impl<'a> AssetProofProverAwaitingChallenge for CipherTextRefreshmentProverAwaiti
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<CipherTextRefreshmentFinalResponse> for CipherTextRefreshmentProver {
     fn apply_challenge(&self, c: &ZKPChallenge) -> CipherTextRefreshmentFinalResponse {
         CipherTextRefreshmentFinalResponse(self.u + c.x() * self.secret_key.secret)
@@ -244,6 +262,43 @@ impl<'a> AssetProofVerifier for CipherTextRefreshmentVerifier<'a> {
         );
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        z: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let c = *challenge.x();
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(z.0, self.y)
+                .term(-Scalar::one(), initial_message.a)
+                .term(-c, self.x),
+            VerificationEquation::new()
+                .term(z.0, self.pc_gens.B_blinding)
+                .term(-Scalar::one(), initial_message.b)
+                .term(-c, self.pub_key.pub_key),
+        ])
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofSimulator for CipherTextRefreshmentVerifier<'a> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let z = Scalar::random(rng);
+
+        let initial_message = CipherTextRefreshmentInitialMessage {
+            a: z * self.y - challenge.x() * self.x,
+            b: z * self.pc_gens.B_blinding - challenge.x() * self.pub_key.pub_key,
+        };
+        (initial_message, CipherTextRefreshmentFinalResponse(z))
+    }
 }
 
 // ------------------------------------------------------------------------
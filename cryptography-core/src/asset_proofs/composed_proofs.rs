@@ -0,0 +1,919 @@
+//! The `composed_proofs` library combines Sigma protocols into
+//! proofs of compound statements.
+//!
+//! The AND-composition proves that two statements are both true. The
+//! two sub-proofs are generated side by side and answer the same
+//! challenge.
+//!
+//! The OR-composition proves that at least one of two statements is
+//! true, without revealing which one. It follows the construction of
+//! Cramer, Damgård and Schoenmakers: the prover simulates a proof of the
+//! statement whose witness it does not know using a challenge of its
+//! choice, and answers the rest of the verifier's challenge honestly
+//! for the other statement. The verifier only checks that the two
+//! sub-challenges add up to its challenge:
+//! ```text
+//! Prover                                     Verifier
+//! - picks the simulated challenge `c_s`
+//! - simulates the unknown branch for `c_s`
+//! - generates the initial message
+//!   of the known branch
+//!               --> (initial message left, initial message right)
+//!                                <-- (challenge c)
+//! - answers the known branch with
+//!   the challenge `c - c_s`
+//!               --> (c_left, final response left, final response right)
+//!                                            - c_right = c - c_left
+//!                                            - verifies the left branch with c_left
+//!                                            - verifies the right branch with c_right
+//! ```
+//!
+//! Both compositions are themselves Sigma protocols, and can be nested to
+//! express statements such as "the amount is zero OR the receiver is known".
+//! Only verifiers that implement the `AssetProofSimulator` trait can be used
+//! as branches of an OR-composition.
+
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::encryption_proofs::{
+    AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofSimulator,
+};
+use crate::{
+    asset_proofs::{
+        encryption_proofs::{AssetProofVerifier, VerificationEquation, ZKPChallenge},
+        errors::Fallible,
+        transcript::{TranscriptProtocol, UpdateTranscript},
+    },
+    codec_wrapper::{ScalarDecoder, ScalarEncoder},
+};
+
+use curve25519_dalek::scalar::Scalar;
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::{convert::TryFrom, prelude::*};
+
+/// The domain label for the AND-composition of proofs.
+pub const AND_COMPOSITION_CHALLENGE_LABEL: &[u8] = b"PolymathAndCompositionChallenge";
+/// The domain label for the OR-composition of proofs.
+pub const OR_COMPOSITION_CHALLENGE_LABEL: &[u8] = b"PolymathOrCompositionChallenge";
+
+// ------------------------------------------------------------------------
+// AND-Composition
+// ------------------------------------------------------------------------
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AndInitialMessage<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L: Encode, R: Encode> Encode for AndInitialMessage<L, R> {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.left.size_hint() + self.right.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.left.encode_to(dest);
+        self.right.encode_to(dest);
+    }
+}
+
+impl<L: Decode, R: Decode> Decode for AndInitialMessage<L, R> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let left = L::decode(input)?;
+        let right = R::decode(input)?;
+
+        Ok(AndInitialMessage { left, right })
+    }
+}
+
+impl<L: UpdateTranscript, R: UpdateTranscript> UpdateTranscript for AndInitialMessage<L, R> {
+    fn update_transcript(&self, transcript: &mut Transcript) -> Fallible<()> {
+        transcript.append_domain_separator(AND_COMPOSITION_CHALLENGE_LABEL);
+        self.left.update_transcript(transcript)?;
+        self.right.update_transcript(transcript)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AndFinalResponse<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L: Encode, R: Encode> Encode for AndFinalResponse<L, R> {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.left.size_hint() + self.right.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.left.encode_to(dest);
+        self.right.encode_to(dest);
+    }
+}
+
+impl<L: Decode, R: Decode> Decode for AndFinalResponse<L, R> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let left = L::decode(input)?;
+        let right = R::decode(input)?;
+
+        Ok(AndFinalResponse { left, right })
+    }
+}
+
+/// Proves both the `left` and the `right` statements.
+#[cfg(not(feature = "verify-only"))]
+pub struct AndProverAwaitingChallenge<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub struct AndProver<L, R> {
+    left: L,
+    right: R,
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<L, R> AssetProofProverAwaitingChallenge for AndProverAwaitingChallenge<L, R>
+where
+    L: AssetProofProverAwaitingChallenge,
+    R: AssetProofProverAwaitingChallenge,
+{
+    type ZKInitialMessage = AndInitialMessage<L::ZKInitialMessage, R::ZKInitialMessage>;
+    type ZKFinalResponse = AndFinalResponse<L::ZKFinalResponse, R::ZKFinalResponse>;
+    type ZKProver = AndProver<L::ZKProver, R::ZKProver>;
+
+    fn create_transcript_rng<T: RngCore + CryptoRng>(
+        &self,
+        rng: &mut T,
+        transcript: &Transcript,
+    ) -> TranscriptRng {
+        // The resulting RNG is seeded with the secrets of both provers.
+        let mut left_rng = self.left.create_transcript_rng(rng, transcript);
+        self.right.create_transcript_rng(&mut left_rng, transcript)
+    }
+
+    fn generate_initial_message(
+        &self,
+        rng: &mut TranscriptRng,
+    ) -> (Self::ZKProver, Self::ZKInitialMessage) {
+        let (left_prover, left) = self.left.generate_initial_message(rng);
+        let (right_prover, right) = self.right.generate_initial_message(rng);
+
+        (
+            AndProver {
+                left: left_prover,
+                right: right_prover,
+            },
+            AndInitialMessage { left, right },
+        )
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<L, R, LF, RF> AssetProofProver<AndFinalResponse<LF, RF>> for AndProver<L, R>
+where
+    L: AssetProofProver<LF>,
+    R: AssetProofProver<RF>,
+{
+    fn apply_challenge(&self, c: &ZKPChallenge) -> AndFinalResponse<LF, RF> {
+        AndFinalResponse {
+            left: self.left.apply_challenge(c),
+            right: self.right.apply_challenge(c),
+        }
+    }
+}
+
+/// Verifies that both the `left` and the `right` statements are true.
+pub struct AndVerifier<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L: AssetProofVerifier, R: AssetProofVerifier> AssetProofVerifier for AndVerifier<L, R> {
+    type ZKInitialMessage = AndInitialMessage<L::ZKInitialMessage, R::ZKInitialMessage>;
+    type ZKFinalResponse = AndFinalResponse<L::ZKFinalResponse, R::ZKFinalResponse>;
+
+    fn verify(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        self.left
+            .verify(challenge, &initial_message.left, &final_response.left)?;
+        self.right
+            .verify(challenge, &initial_message.right, &final_response.right)
+    }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let mut equations = self.left.verification_equations(
+            challenge,
+            &initial_message.left,
+            &final_response.left,
+        )?;
+        equations.extend(self.right.verification_equations(
+            challenge,
+            &initial_message.right,
+            &final_response.right,
+        )?);
+        Ok(equations)
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<L: AssetProofSimulator, R: AssetProofSimulator> AssetProofSimulator for AndVerifier<L, R> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let (left_message, left_response) = self.left.simulate(challenge, rng);
+        let (right_message, right_response) = self.right.simulate(challenge, rng);
+
+        (
+            AndInitialMessage {
+                left: left_message,
+                right: right_message,
+            },
+            AndFinalResponse {
+                left: left_response,
+                right: right_response,
+            },
+        )
+    }
+}
+
+// ------------------------------------------------------------------------
+// OR-Composition
+// ------------------------------------------------------------------------
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrInitialMessage<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L: Encode, R: Encode> Encode for OrInitialMessage<L, R> {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.left.size_hint() + self.right.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.left.encode_to(dest);
+        self.right.encode_to(dest);
+    }
+}
+
+impl<L: Decode, R: Decode> Decode for OrInitialMessage<L, R> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let left = L::decode(input)?;
+        let right = R::decode(input)?;
+
+        Ok(OrInitialMessage { left, right })
+    }
+}
+
+impl<L: UpdateTranscript, R: UpdateTranscript> UpdateTranscript for OrInitialMessage<L, R> {
+    fn update_transcript(&self, transcript: &mut Transcript) -> Fallible<()> {
+        transcript.append_domain_separator(OR_COMPOSITION_CHALLENGE_LABEL);
+        self.left.update_transcript(transcript)?;
+        self.right.update_transcript(transcript)?;
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrFinalResponse<L, R> {
+    /// The challenge of the left branch. The challenge of the right branch is
+    /// the difference between the verifier's challenge and this one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub left_challenge: Scalar,
+    pub left: L,
+    pub right: R,
+}
+
+impl<L: Encode, R: Encode> Encode for OrFinalResponse<L, R> {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        ScalarEncoder(&self.left_challenge).size_hint()
+            + self.left.size_hint()
+            + self.right.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        ScalarEncoder(&self.left_challenge).encode_to(dest);
+        self.left.encode_to(dest);
+        self.right.encode_to(dest);
+    }
+}
+
+impl<L: Decode, R: Decode> Decode for OrFinalResponse<L, R> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let left_challenge = <ScalarDecoder>::decode(input)?.0;
+        let left = L::decode(input)?;
+        let right = R::decode(input)?;
+
+        Ok(OrFinalResponse {
+            left_challenge,
+            left,
+            right,
+        })
+    }
+}
+
+/// Proves that either the left or the right statement is true.
+/// The prover holds the witness of one branch, and the verifier of the other
+/// branch, which is used to simulate its proof.
+///
+/// The types of both branches must be given, even though only one prover and
+/// one verifier are used, so that the resulting proof does not depend on which
+/// branch is known.
+#[cfg(not(feature = "verify-only"))]
+pub enum OrProverAwaitingChallenge<'a, LP, LV, RP, RV> {
+    /// The prover knows the witness of the left statement.
+    Left { prover: LP, right: &'a RV },
+    /// The prover knows the witness of the right statement.
+    Right { left: &'a LV, prover: RP },
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub enum OrProver<LP, LF, RP, RF> {
+    Left {
+        prover: LP,
+        right_challenge: Scalar,
+        right_response: RF,
+    },
+    Right {
+        left_challenge: Scalar,
+        left_response: LF,
+        prover: RP,
+    },
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a, LP, LV, RP, RV> AssetProofProverAwaitingChallenge
+    for OrProverAwaitingChallenge<'a, LP, LV, RP, RV>
+where
+    LP: AssetProofProverAwaitingChallenge,
+    RP: AssetProofProverAwaitingChallenge,
+    LV: AssetProofSimulator<
+        ZKInitialMessage = LP::ZKInitialMessage,
+        ZKFinalResponse = LP::ZKFinalResponse,
+    >,
+    RV: AssetProofSimulator<
+        ZKInitialMessage = RP::ZKInitialMessage,
+        ZKFinalResponse = RP::ZKFinalResponse,
+    >,
+    LP::ZKFinalResponse: Clone,
+    RP::ZKFinalResponse: Clone,
+{
+    type ZKInitialMessage = OrInitialMessage<LP::ZKInitialMessage, RP::ZKInitialMessage>;
+    type ZKFinalResponse = OrFinalResponse<LP::ZKFinalResponse, RP::ZKFinalResponse>;
+    type ZKProver = OrProver<LP::ZKProver, LP::ZKFinalResponse, RP::ZKProver, RP::ZKFinalResponse>;
+
+    fn create_transcript_rng<T: RngCore + CryptoRng>(
+        &self,
+        rng: &mut T,
+        transcript: &Transcript,
+    ) -> TranscriptRng {
+        match self {
+            OrProverAwaitingChallenge::Left { prover, .. } => {
+                prover.create_transcript_rng(rng, transcript)
+            }
+            OrProverAwaitingChallenge::Right { prover, .. } => {
+                prover.create_transcript_rng(rng, transcript)
+            }
+        }
+    }
+
+    fn generate_initial_message(
+        &self,
+        rng: &mut TranscriptRng,
+    ) -> (Self::ZKProver, Self::ZKInitialMessage) {
+        // The verifier rejects a zero challenge, which happens with negligible probability.
+        let simulated_challenge = ZKPChallenge::from_scalar_unchecked(Scalar::random(rng));
+
+        match self {
+            OrProverAwaitingChallenge::Left { prover, right } => {
+                let (right_message, right_response) = right.simulate(&simulated_challenge, rng);
+                let (left_prover, left_message) = prover.generate_initial_message(rng);
+                (
+                    OrProver::Left {
+                        prover: left_prover,
+                        right_challenge: *simulated_challenge.x(),
+                        right_response,
+                    },
+                    OrInitialMessage {
+                        left: left_message,
+                        right: right_message,
+                    },
+                )
+            }
+            OrProverAwaitingChallenge::Right { left, prover } => {
+                let (left_message, left_response) = left.simulate(&simulated_challenge, rng);
+                let (right_prover, right_message) = prover.generate_initial_message(rng);
+                (
+                    OrProver::Right {
+                        left_challenge: *simulated_challenge.x(),
+                        left_response,
+                        prover: right_prover,
+                    },
+                    OrInitialMessage {
+                        left: left_message,
+                        right: right_message,
+                    },
+                )
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<LP, LF, RP, RF> AssetProofProver<OrFinalResponse<LF, RF>> for OrProver<LP, LF, RP, RF>
+where
+    LP: AssetProofProver<LF>,
+    RP: AssetProofProver<RF>,
+    LF: Clone,
+    RF: Clone,
+{
+    fn apply_challenge(&self, c: &ZKPChallenge) -> OrFinalResponse<LF, RF> {
+        match self {
+            OrProver::Left {
+                prover,
+                right_challenge,
+                right_response,
+            } => {
+                let left_challenge = c.x() - right_challenge;
+                OrFinalResponse {
+                    left_challenge,
+                    left: prover
+                        .apply_challenge(&ZKPChallenge::from_scalar_unchecked(left_challenge)),
+                    right: right_response.clone(),
+                }
+            }
+            OrProver::Right {
+                left_challenge,
+                left_response,
+                prover,
+            } => {
+                let right_challenge = c.x() - left_challenge;
+                OrFinalResponse {
+                    left_challenge: *left_challenge,
+                    left: left_response.clone(),
+                    right: prover
+                        .apply_challenge(&ZKPChallenge::from_scalar_unchecked(right_challenge)),
+                }
+            }
+        }
+    }
+}
+
+/// Verifies that either the `left` or the `right` statement is true.
+pub struct OrVerifier<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L, R> OrVerifier<L, R> {
+    /// Splits the verifier's challenge into the challenges of the two branches.
+    fn split_challenge<LF, RF>(
+        challenge: &ZKPChallenge,
+        final_response: &OrFinalResponse<LF, RF>,
+    ) -> Fallible<(ZKPChallenge, ZKPChallenge)> {
+        let left_challenge = ZKPChallenge::try_from(final_response.left_challenge)?;
+        let right_challenge =
+            ZKPChallenge::try_from(challenge.x() - final_response.left_challenge)?;
+        Ok((left_challenge, right_challenge))
+    }
+}
+
+impl<L: AssetProofVerifier, R: AssetProofVerifier> AssetProofVerifier for OrVerifier<L, R> {
+    type ZKInitialMessage = OrInitialMessage<L::ZKInitialMessage, R::ZKInitialMessage>;
+    type ZKFinalResponse = OrFinalResponse<L::ZKFinalResponse, R::ZKFinalResponse>;
+
+    fn verify(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let (left_challenge, right_challenge) = Self::split_challenge(challenge, final_response)?;
+
+        self.left
+            .verify(&left_challenge, &initial_message.left, &final_response.left)?;
+        self.right.verify(
+            &right_challenge,
+            &initial_message.right,
+            &final_response.right,
+        )
+    }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let (left_challenge, right_challenge) = Self::split_challenge(challenge, final_response)?;
+
+        let mut equations = self.left.verification_equations(
+            &left_challenge,
+            &initial_message.left,
+            &final_response.left,
+        )?;
+        equations.extend(self.right.verification_equations(
+            &right_challenge,
+            &initial_message.right,
+            &final_response.right,
+        )?);
+        Ok(equations)
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<L: AssetProofSimulator, R: AssetProofSimulator> AssetProofSimulator for OrVerifier<L, R> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let left_challenge = Scalar::random(rng);
+        let right_challenge = challenge.x() - left_challenge;
+        let (left_message, left_response) = self
+            .left
+            .simulate(&ZKPChallenge::from_scalar_unchecked(left_challenge), rng);
+        let (right_message, right_response) = self
+            .right
+            .simulate(&ZKPChallenge::from_scalar_unchecked(right_challenge), rng);
+
+        (
+            OrInitialMessage {
+                left: left_message,
+                right: right_message,
+            },
+            OrFinalResponse {
+                left_challenge,
+                left: left_response,
+                right: right_response,
+            },
+        )
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::{
+        correctness_proof::{
+            CorrectnessFinalResponse, CorrectnessInitialMessage,
+            CorrectnessProverAwaitingChallenge, CorrectnessVerifier,
+        },
+        decryption_proof::DecryptionVerifier,
+        encrypting_same_value_proof::{
+            EncryptingSameValueFinalResponse, EncryptingSameValueInitialMessage,
+            EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
+        },
+        encryption_proofs::{single_property_prover, single_property_verifier},
+        errors::ErrorKind,
+        wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
+        BatchVerifier, CipherText, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+    };
+    use bulletproofs::PedersenGens;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+    use zeroize::Zeroizing;
+
+    const SEED_1: [u8; 32] = [42u8; 32];
+    const SEED_2: [u8; 32] = [7u8; 32];
+
+    /// "The amount is zero OR the receiver's cipher text encrypts the same value".
+    type AmountOrSameValueProver<'a> = OrProverAwaitingChallenge<
+        'a,
+        CorrectnessProverAwaitingChallenge<'a>,
+        CorrectnessVerifier<'a>,
+        EncryptingSameValueProverAwaitingChallenge<'a>,
+        EncryptingSameValueVerifier<'a>,
+    >;
+
+    fn encrypt_twice(
+        witness: &CommitmentWitness,
+        pub_key1: ElgamalPublicKey,
+        pub_key2: ElgamalPublicKey,
+    ) -> (CipherText, CipherText) {
+        (pub_key1.encrypt(witness), pub_key2.encrypt(witness))
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn and_composition() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (w, cipher) = pub_key.encrypt_value(6u32.into(), &mut rng);
+
+        let prover = AndProverAwaitingChallenge {
+            left: CorrectnessProverAwaitingChallenge {
+                pub_key,
+                w: w.clone(),
+                pc_gens: &gens,
+            },
+            right: WellformednessProverAwaitingChallenge {
+                pub_key,
+                w: Zeroizing::new(w),
+                pc_gens: &gens,
+            },
+        };
+        let verifier = AndVerifier {
+            left: CorrectnessVerifier {
+                value: 6u32.into(),
+                pub_key,
+                cipher,
+                pc_gens: &gens,
+            },
+            right: WellformednessVerifier {
+                pub_key,
+                cipher,
+                pc_gens: &gens,
+            },
+        };
+        let proof = single_property_prover(prover, &mut rng).unwrap();
+
+        // Positive tests
+        assert!(single_property_verifier(&verifier, proof).is_ok());
+        let mut batch = BatchVerifier::new();
+        batch.add(&verifier, &proof);
+        assert!(batch.verify().is_ok());
+
+        // Negative tests
+        let bad_verifier = AndVerifier {
+            left: CorrectnessVerifier {
+                value: 7u32.into(),
+                pub_key,
+                cipher,
+                pc_gens: &gens,
+            },
+            right: verifier.right,
+        };
+        assert_err!(
+            single_property_verifier(&bad_verifier, proof),
+            ErrorKind::CorrectnessFinalResponseVerificationError { check: 2 }
+        );
+
+        let (initial_message, final_response) = proof;
+        let bad_initial_message = AndInitialMessage {
+            left: CorrectnessInitialMessage::default(),
+            right: initial_message.right,
+        };
+        assert!(
+            single_property_verifier(&verifier, (bad_initial_message, final_response)).is_err()
+        );
+
+        let bad_final_response = AndFinalResponse {
+            left: CorrectnessFinalResponse::from(Scalar::one()),
+            right: final_response.right,
+        };
+        assert!(
+            single_property_verifier(&verifier, (initial_message, bad_final_response)).is_err()
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn or_composition() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_2);
+        let pub_key1 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let pub_key2 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+
+        // The amount is zero, the sender knows the witness of the left branch.
+        let zero_witness = CommitmentWitness::new(Scalar::zero(), Scalar::random(&mut rng));
+        let (zero_cipher1, _) = encrypt_twice(&zero_witness, pub_key1, pub_key2);
+        // The receiver's cipher text encrypts an unrelated value.
+        let (_, other_cipher2) = pub_key2.encrypt_value(5u32.into(), &mut rng);
+
+        let left_verifier = CorrectnessVerifier {
+            value: Scalar::zero(),
+            pub_key: pub_key1,
+            cipher: zero_cipher1,
+            pc_gens: &gens,
+        };
+        let right_verifier = EncryptingSameValueVerifier {
+            pub_key1,
+            pub_key2,
+            cipher1: zero_cipher1,
+            cipher2: other_cipher2,
+            pc_gens: &gens,
+        };
+        let prover: AmountOrSameValueProver = OrProverAwaitingChallenge::Left {
+            prover: CorrectnessProverAwaitingChallenge {
+                pub_key: pub_key1,
+                w: zero_witness,
+                pc_gens: &gens,
+            },
+            right: &right_verifier,
+        };
+        let verifier = OrVerifier {
+            left: left_verifier,
+            right: right_verifier,
+        };
+        let proof = single_property_prover(prover, &mut rng).unwrap();
+        assert!(single_property_verifier(&verifier, proof).is_ok());
+
+        // The amount is not zero, the sender knows the witness of the right branch.
+        let witness = CommitmentWitness::new(5u32.into(), Scalar::random(&mut rng));
+        let (cipher1, cipher2) = encrypt_twice(&witness, pub_key1, pub_key2);
+        let left_verifier = CorrectnessVerifier {
+            value: Scalar::zero(),
+            pub_key: pub_key1,
+            cipher: cipher1,
+            pc_gens: &gens,
+        };
+        let right_verifier = EncryptingSameValueVerifier {
+            pub_key1,
+            pub_key2,
+            cipher1,
+            cipher2,
+            pc_gens: &gens,
+        };
+        let prover: AmountOrSameValueProver = OrProverAwaitingChallenge::Right {
+            left: &left_verifier,
+            prover: EncryptingSameValueProverAwaitingChallenge {
+                pub_key1,
+                pub_key2,
+                w: Zeroizing::new(witness),
+                pc_gens: &gens,
+            },
+        };
+        let verifier2 = OrVerifier {
+            left: left_verifier,
+            right: right_verifier,
+        };
+        let proof2 = single_property_prover(prover, &mut rng).unwrap();
+        assert!(single_property_verifier(&verifier2, proof2).is_ok());
+
+        let mut batch = BatchVerifier::new();
+        batch.add(&verifier, &proof);
+        batch.add(&verifier2, &proof2);
+        assert!(batch.verify().is_ok());
+
+        // Negative tests
+        // A proof is bound to its statements.
+        assert!(single_property_verifier(&verifier2, proof).is_err());
+        assert!(single_property_verifier(&verifier, proof2).is_err());
+
+        // The sub-challenges must add up to the verifier's challenge.
+        let (initial_message, final_response) = proof;
+        let bad_final_response = OrFinalResponse {
+            left_challenge: final_response.left_challenge + Scalar::one(),
+            ..final_response
+        };
+        assert!(
+            single_property_verifier(&verifier, (initial_message, bad_final_response)).is_err()
+        );
+
+        let bad_final_response = OrFinalResponse {
+            left_challenge: Scalar::zero(),
+            ..final_response
+        };
+        assert_err!(
+            single_property_verifier(&verifier, (initial_message, bad_final_response)),
+            ErrorKind::VerificationError
+        );
+        let mut batch = BatchVerifier::new();
+        batch.add(&verifier2, &proof2);
+        batch.add(&verifier, &(initial_message, bad_final_response));
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 1 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn simulated_proofs() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let other_pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let (_, cipher) = pub_key.encrypt_value(9u32.into(), &mut rng);
+        let (_, other_cipher) = other_pub_key.encrypt_value(10u32.into(), &mut rng);
+        let challenge = ZKPChallenge::try_from(Scalar::random(&mut rng)).unwrap();
+
+        // The simulated proofs of false statements are accepted for the chosen challenge.
+        let correctness_verifier = CorrectnessVerifier {
+            value: 3u32.into(),
+            pub_key,
+            cipher,
+            pc_gens: &gens,
+        };
+        let (initial_message, final_response) = correctness_verifier.simulate(&challenge, &mut rng);
+        assert!(correctness_verifier
+            .verify(&challenge, &initial_message, &final_response)
+            .is_ok());
+
+        let decryption_verifier = DecryptionVerifier {
+            value: 3u32.into(),
+            pub_key,
+            cipher,
+            pc_gens: &gens,
+        };
+        let (initial_message, final_response) = decryption_verifier.simulate(&challenge, &mut rng);
+        assert!(decryption_verifier
+            .verify(&challenge, &initial_message, &final_response)
+            .is_ok());
+
+        let composed_verifier = OrVerifier {
+            left: AndVerifier {
+                left: correctness_verifier,
+                right: decryption_verifier,
+            },
+            right: WellformednessVerifier {
+                pub_key: other_pub_key,
+                cipher: other_cipher,
+                pc_gens: &gens,
+            },
+        };
+        let (initial_message, final_response) = composed_verifier.simulate(&challenge, &mut rng);
+        assert!(composed_verifier
+            .verify(&challenge, &initial_message, &final_response)
+            .is_ok());
+
+        // But they are rejected by the non-interactive verifier.
+        assert!(
+            single_property_verifier(&composed_verifier, (initial_message, final_response))
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn serialize_deserialize_proof() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_2);
+        let pub_key1 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let pub_key2 = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        let witness = CommitmentWitness::new(0u32.into(), Scalar::random(&mut rng));
+        let (cipher1, cipher2) = encrypt_twice(&witness, pub_key1, pub_key2);
+        let right_verifier = EncryptingSameValueVerifier {
+            pub_key1,
+            pub_key2,
+            cipher1,
+            cipher2,
+            pc_gens: &gens,
+        };
+        let prover: AmountOrSameValueProver = OrProverAwaitingChallenge::Left {
+            prover: CorrectnessProverAwaitingChallenge {
+                pub_key: pub_key1,
+                w: witness,
+                pc_gens: &gens,
+            },
+            right: &right_verifier,
+        };
+        let (initial_message0, final_response0) = single_property_prover(prover, &mut rng).unwrap();
+
+        let init_bytes = initial_message0.encode();
+        let mut init_slice = &init_bytes[..];
+        let recovered_initial_message = <OrInitialMessage<
+            CorrectnessInitialMessage,
+            EncryptingSameValueInitialMessage,
+        >>::decode(&mut init_slice)
+        .unwrap();
+        assert_eq!(recovered_initial_message, initial_message0);
+
+        let final_bytes = final_response0.encode();
+        let mut final_slice = &final_bytes[..];
+        let recovered_final_response = <OrFinalResponse<
+            CorrectnessFinalResponse,
+            EncryptingSameValueFinalResponse,
+        >>::decode(&mut final_slice)
+        .unwrap();
+        assert_eq!(recovered_final_response, final_response0);
+    }
+}
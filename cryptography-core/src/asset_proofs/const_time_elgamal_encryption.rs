@@ -27,8 +27,9 @@
 //! ciphertexts can share the same `X`.
 use bulletproofs::PedersenGens;
 use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
-use rand_core::{CryptoRng, RngCore};
 
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -36,14 +37,17 @@ use codec::{Decode, Encode, Error as CodecError, Input, Output};
 use sha3::{digest::FixedOutput, Digest, Sha3_256};
 use sp_std::prelude::*;
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::elgamal_encryption::{CommitmentWitness, ElgamalPublicKey};
 use crate::{
-    asset_proofs::elgamal_encryption::{
-        CipherText, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+    asset_proofs::{
+        elgamal_encryption::{CipherText, ElgamalSecretKey},
+        Balance,
     },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder},
 };
 
-use super::errors::Fallible;
+use super::errors::{ErrorKind, Fallible};
 
 /// This data structure wraps a twisted Elgamal cipher text with the
 /// regular Elgamal cipher text.
@@ -58,7 +62,9 @@ pub struct CipherTextWithHint {
     // The twisted Elgamal cipher text.
     pub elgamal_cipher: CipherText,
 
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub y: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub z: [u8; 32],
 }
 
@@ -108,6 +114,7 @@ fn xor_with_one_time_pad(key: RistrettoPoint, data: &[u8; 32]) -> [u8; 32] {
     result
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl ElgamalPublicKey {
     pub fn const_time_encrypt<R: RngCore + CryptoRng>(
         &self,
@@ -147,20 +154,51 @@ impl ElgamalPublicKey {
 }
 
 impl ElgamalSecretKey {
-    /// Decrypt a cipher text that is known to encrypt a u32.
-    pub fn const_time_decrypt(&self, cipher_text: &CipherTextWithHint) -> Fallible<u32> {
-        // random_2 * h = Y - X / secret_key
-        let random_2_h = cipher_text.y - self.secret.invert() * cipher_text.elgamal_cipher.x;
+    /// Decrypt a cipher text that is known to encrypt a balance.
+    /// Since the whole scalar is encrypted, this works for both the 32-bit
+    /// and the 64-bit balances.
+    pub fn const_time_decrypt(&self, cipher_text: &CipherTextWithHint) -> Fallible<Balance> {
+        // random_1 * g = X / secret_key
+        let random_1_g = self.secret.invert() * cipher_text.elgamal_cipher.x;
+        decrypt_with_blinding_point(cipher_text, random_1_g)
+    }
 
-        use byteorder::{ByteOrder, LittleEndian};
+    /// Decrypt a twisted Elgamal cipher text, using a constant time encryption
+    /// of the same value as the hint. Unlike `decrypt`, this is not limited to
+    /// the range of the discrete log table.
+    pub fn decrypt_with_hint(
+        &self,
+        cipher_text: &CipherText,
+        hint: &CipherTextWithHint,
+    ) -> Fallible<Balance> {
+        let value = self.const_time_decrypt(hint)?;
+        self.verify(cipher_text, &value.into())
+            .map_err(|_| ErrorKind::CipherTextDecryptionError)?;
+        Ok(value)
+    }
+}
 
-        let decrypted_msg = xor_with_one_time_pad(random_2_h, &cipher_text.z);
-        let decrypted_u32 = LittleEndian::read_u32(&decrypted_msg);
+/// Decrypts a cipher text given `random_1 * g`, which is the part of `Y`
+/// that is unlocked by the secret key.
+pub(crate) fn decrypt_with_blinding_point(
+    cipher_text: &CipherTextWithHint,
+    random_1_g: RistrettoPoint,
+) -> Fallible<Balance> {
+    // random_2 * h = Y - random_1 * g
+    let random_2_h = cipher_text.y - random_1_g;
 
-        // Verify that the same value was encrypted using twisted Elgamal encryption.
-        self.verify(&cipher_text.elgamal_cipher, &decrypted_u32.into())?;
-        Ok(decrypted_u32)
-    }
+    use byteorder::{ByteOrder, LittleEndian};
+
+    let decrypted_msg = xor_with_one_time_pad(random_2_h, &cipher_text.z);
+    let decrypted_balance: Balance = LittleEndian::read_u64(&decrypted_msg);
+
+    // Verify that the same value was encrypted using twisted Elgamal encryption.
+    let gens = PedersenGens::default();
+    ensure!(
+        Scalar::from(decrypted_balance) * gens.B == cipher_text.elgamal_cipher.y - random_1_g,
+        ErrorKind::CipherTextDecryptionError
+    );
+    Ok(decrypted_balance)
 }
 
 // ------------------------------------------------------------------------
@@ -171,7 +209,6 @@ impl ElgamalSecretKey {
 mod tests {
     extern crate wasm_bindgen_test;
     use super::*;
-    use crate::asset_proofs::errors::ErrorKind;
     use rand::{rngs::StdRng, SeedableRng};
     use wasm_bindgen_test::*;
 
@@ -183,12 +220,12 @@ mod tests {
         let elg_pub = elg_secret.get_public_key();
 
         // Test encrypt().
-        let values = vec![0u32, 1u32, 255u32, u32::MAX];
-        let _ = values.iter().map(|v| {
+        let values: Vec<Balance> = vec![0, 1, 255, u32::MAX.into(), u64::MAX];
+        for v in values.iter() {
             let (_, cipher) = elg_pub.const_time_encrypt_value(Scalar::from(*v), &mut rng);
             let decrypted_v = elg_secret.const_time_decrypt(&cipher).unwrap();
             assert_eq!(decrypted_v, *v);
-        });
+        }
 
         // Negative test.
         // If the message is altered, it won't decrypt.
@@ -201,4 +238,25 @@ mod tests {
             ErrorKind::CipherTextDecryptionError
         );
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn decrypt_with_hint() {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+
+        let value: Balance = u64::from(u32::MAX) + 1;
+        let (witness, _) = elg_pub.const_time_encrypt_value(value.into(), &mut rng);
+        let cipher = elg_pub.encrypt(&witness);
+        let (_, hint) = elg_pub.const_time_encrypt_value(value.into(), &mut rng);
+        assert_eq!(elg_secret.decrypt_with_hint(&cipher, &hint).unwrap(), value);
+
+        // Negative test: a hint of a different value is rejected.
+        let (_, wrong_hint) = elg_pub.const_time_encrypt_value((value + 1).into(), &mut rng);
+        assert_err!(
+            elg_secret.decrypt_with_hint(&cipher, &wrong_hint),
+            ErrorKind::CipherTextDecryptionError
+        );
+    }
 }
@@ -1,15 +1,19 @@
 //! The proof of correct encryption of the given value.
 //! For more details see section 5.2 of the whitepaper.
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofSimulator},
+    CommitmentWitness,
+};
 use crate::{
     asset_proofs::{
         encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-            ZKProofResponse,
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
         },
         errors::{ErrorKind, Fallible},
         transcript::{TranscriptProtocol, UpdateTranscript},
-        CipherText, CommitmentWitness, ElgamalPublicKey,
+        CipherText, ElgamalPublicKey,
     },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
 };
@@ -17,14 +21,18 @@ use bulletproofs::PedersenGens;
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
 };
-use merlin::{Transcript, TranscriptRng};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroize;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
-use sp_std::convert::From;
+use sp_std::{convert::From, prelude::*};
 
 /// The domain label for the correctness proof.
 pub const CORRECTNESS_PROOF_FINAL_RESPONSE_LABEL: &[u8] = b"PolymathCorrectnessFinalResponse";
@@ -37,7 +45,9 @@ pub const CORRECTNESS_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathCorrectnessChalle
 
 #[derive(PartialEq, Copy, Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct CorrectnessFinalResponse(Scalar);
+pub struct CorrectnessFinalResponse(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] Scalar,
+);
 
 impl From<Scalar> for CorrectnessFinalResponse {
     fn from(response: Scalar) -> Self {
@@ -65,7 +75,9 @@ impl Decode for CorrectnessFinalResponse {
 #[derive(PartialEq, Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CorrectnessInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     b: RistrettoPoint,
 }
 
@@ -111,6 +123,7 @@ impl UpdateTranscript for CorrectnessInitialMessage {
 /// Holds the non-interactive proofs of correctness, equivalent of L_correct of MERCAT paper.
 pub type CorrectnessProof = ZKProofResponse<CorrectnessInitialMessage, CorrectnessFinalResponse>;
 
+#[cfg(not(feature = "verify-only"))]
 pub struct CorrectnessProverAwaitingChallenge<'a> {
     /// The public key used for the elgamal encryption.
     pub pub_key: ElgamalPublicKey,
@@ -122,6 +135,7 @@ pub struct CorrectnessProverAwaitingChallenge<'a> {
     pub pc_gens: &'a PedersenGens,
 }
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Zeroize)]
 #[zeroize(drop)]
 pub struct CorrectnessProver {
@@ -132,6 +146,7 @@ pub struct CorrectnessProver {
     u: Scalar,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for CorrectnessProverAwaitingChallenge<'a> {
     type ZKInitialMessage = CorrectnessInitialMessage;
     type ZKFinalResponse = CorrectnessFinalResponse;
@@ -164,6 +179,7 @@ impl<'a> AssetProofProverAwaitingChallenge for CorrectnessProverAwaitingChalleng
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<CorrectnessFinalResponse> for CorrectnessProver {
     fn apply_challenge(&self, c: &ZKPChallenge) -> CorrectnessFinalResponse {
         CorrectnessFinalResponse(self.u + c.x() * self.w.blinding())
@@ -207,6 +223,47 @@ impl<'a> AssetProofVerifier for CorrectnessVerifier<'a> {
         );
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        z: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let generators = self.pc_gens;
+        let c = *challenge.x();
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(z.0, self.pub_key.pub_key)
+                .term(-Scalar::one(), initial_message.a)
+                .term(-c, self.cipher.x),
+            VerificationEquation::new()
+                .term(z.0, generators.B_blinding)
+                .term(-Scalar::one(), initial_message.b)
+                .term(-c, self.cipher.y)
+                .term(c * self.value, generators.B),
+        ])
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofSimulator for CorrectnessVerifier<'a> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let generators = self.pc_gens;
+        let y_prime = self.cipher.y - (self.value * generators.B);
+        let z = Scalar::random(rng);
+
+        let initial_message = CorrectnessInitialMessage {
+            a: z * self.pub_key.pub_key - challenge.x() * self.cipher.x,
+            b: z * generators.B_blinding - challenge.x() * y_prime,
+        };
+        (initial_message, CorrectnessFinalResponse(z))
+    }
 }
 
 // ------------------------------------------------------------------------
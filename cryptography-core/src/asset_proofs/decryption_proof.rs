@@ -0,0 +1,421 @@
+//! The proof that a cipher text decrypts to a public value.
+//!
+//! The owner of the secret key proves that `(X, Y)` decrypts to `value`,
+//! without revealing the secret key. Since `X = r * secret_key * g` and
+//! `Y - value * h = r * g`, this is a proof that `X` and the public key have
+//! the same discrete log in the bases `Y - value * h` and `g`, respectively.
+
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofSimulator},
+    ElgamalSecretKey,
+};
+use crate::{
+    asset_proofs::{
+        encryption_proofs::{
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
+        },
+        errors::{ErrorKind, Fallible},
+        transcript::{TranscriptProtocol, UpdateTranscript},
+        CipherText, ElgamalPublicKey,
+    },
+    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+};
+use bulletproofs::PedersenGens;
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
+};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
+use zeroize::Zeroize;
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::prelude::*;
+
+/// The domain label for the decryption proof.
+pub const DECRYPTION_PROOF_FINAL_RESPONSE_LABEL: &[u8] = b"PolymathDecryptionFinalResponse";
+/// The domain label for the challenge.
+pub const DECRYPTION_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathDecryptionChallenge";
+
+// ------------------------------------------------------------------------
+// Proof of Decryption to the Given Value
+// ------------------------------------------------------------------------
+
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecryptionFinalResponse(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] Scalar,
+);
+
+impl Encode for DecryptionFinalResponse {
+    fn size_hint(&self) -> usize {
+        ScalarEncoder(&self.0).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        ScalarEncoder(&self.0).encode_to(dest)
+    }
+}
+
+impl Decode for DecryptionFinalResponse {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let scalar = <ScalarDecoder>::decode(input)?.0;
+        Ok(DecryptionFinalResponse(scalar))
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecryptionInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    b: RistrettoPoint,
+}
+
+impl Encode for DecryptionInitialMessage {
+    fn size_hint(&self) -> usize {
+        RistrettoPointEncoder(&self.a).size_hint() + RistrettoPointEncoder(&self.b).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        RistrettoPointEncoder(&self.a).encode_to(dest);
+        RistrettoPointEncoder(&self.b).encode_to(dest);
+    }
+}
+
+impl Decode for DecryptionInitialMessage {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let a = <RistrettoPointDecoder>::decode(input)?.0;
+        let b = <RistrettoPointDecoder>::decode(input)?.0;
+
+        Ok(DecryptionInitialMessage { a, b })
+    }
+}
+
+/// A default implementation used for testing.
+impl Default for DecryptionInitialMessage {
+    fn default() -> Self {
+        DecryptionInitialMessage {
+            a: RISTRETTO_BASEPOINT_POINT,
+            b: RISTRETTO_BASEPOINT_POINT,
+        }
+    }
+}
+
+impl UpdateTranscript for DecryptionInitialMessage {
+    fn update_transcript(&self, transcript: &mut Transcript) -> Fallible<()> {
+        transcript.append_domain_separator(DECRYPTION_PROOF_CHALLENGE_LABEL);
+        transcript.append_validated_point(b"A", &self.a.compress())?;
+        transcript.append_validated_point(b"B", &self.b.compress())?;
+        Ok(())
+    }
+}
+
+/// Holds the non-interactive proof that a cipher text decrypts to a public value.
+pub type DecryptionProof = ZKProofResponse<DecryptionInitialMessage, DecryptionFinalResponse>;
+
+#[cfg(not(feature = "verify-only"))]
+pub struct DecryptionProverAwaitingChallenge<'a> {
+    /// The secret key that the cipher text was encrypted to.
+    pub secret_key: ElgamalSecretKey,
+
+    /// The encryption cipher text.
+    pub cipher: CipherText,
+
+    /// The decrypted value.
+    pub value: Scalar,
+
+    /// Pedersen Generators
+    pub pc_gens: &'a PedersenGens,
+}
+
+#[cfg(not(feature = "verify-only"))]
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct DecryptionProver {
+    /// The secret key.
+    secret_key: ElgamalSecretKey,
+
+    /// The randomness generated in the first round.
+    u: Scalar,
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofProverAwaitingChallenge for DecryptionProverAwaitingChallenge<'a> {
+    type ZKInitialMessage = DecryptionInitialMessage;
+    type ZKFinalResponse = DecryptionFinalResponse;
+    type ZKProver = DecryptionProver;
+
+    fn create_transcript_rng<T: RngCore + CryptoRng>(
+        &self,
+        rng: &mut T,
+        transcript: &Transcript,
+    ) -> TranscriptRng {
+        transcript
+            .build_rng()
+            .rekey_with_witness_bytes(b"secret_key", self.secret_key.secret.as_bytes())
+            .finalize(rng)
+    }
+
+    fn generate_initial_message(
+        &self,
+        rng: &mut TranscriptRng,
+    ) -> (Self::ZKProver, Self::ZKInitialMessage) {
+        let rand_commitment = Scalar::random(rng);
+        let y_prime = self.cipher.y - self.value * self.pc_gens.B;
+
+        (
+            DecryptionProver {
+                secret_key: self.secret_key.clone(),
+                u: rand_commitment,
+            },
+            DecryptionInitialMessage {
+                a: rand_commitment * self.pc_gens.B_blinding,
+                b: rand_commitment * y_prime,
+            },
+        )
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl AssetProofProver<DecryptionFinalResponse> for DecryptionProver {
+    fn apply_challenge(&self, c: &ZKPChallenge) -> DecryptionFinalResponse {
+        DecryptionFinalResponse(self.u + c.x() * self.secret_key.secret)
+    }
+}
+
+pub struct DecryptionVerifier<'a> {
+    /// The value that the cipher text decrypts to.
+    pub value: Scalar,
+
+    /// The public key to which the `value` is encrypted.
+    pub pub_key: ElgamalPublicKey,
+
+    /// The encryption cipher text.
+    pub cipher: CipherText,
+
+    /// The Generator Points
+    pub pc_gens: &'a PedersenGens,
+}
+
+impl<'a> AssetProofVerifier for DecryptionVerifier<'a> {
+    type ZKInitialMessage = DecryptionInitialMessage;
+    type ZKFinalResponse = DecryptionFinalResponse;
+
+    fn verify(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        z: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let generators = self.pc_gens;
+        let y_prime = self.cipher.y - (self.value * generators.B);
+
+        ensure!(
+            z.0 * generators.B_blinding == initial_message.a + challenge.x() * self.pub_key.pub_key,
+            ErrorKind::DecryptionFinalResponseVerificationError { check: 1 }
+        );
+        ensure!(
+            z.0 * y_prime == initial_message.b + challenge.x() * self.cipher.x,
+            ErrorKind::DecryptionFinalResponseVerificationError { check: 2 }
+        );
+        Ok(())
+    }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        z: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let generators = self.pc_gens;
+        let c = *challenge.x();
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(z.0, generators.B_blinding)
+                .term(-Scalar::one(), initial_message.a)
+                .term(-c, self.pub_key.pub_key),
+            VerificationEquation::new()
+                .term(z.0, self.cipher.y)
+                .term(-z.0 * self.value, generators.B)
+                .term(-Scalar::one(), initial_message.b)
+                .term(-c, self.cipher.x),
+        ])
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofSimulator for DecryptionVerifier<'a> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let generators = self.pc_gens;
+        let y_prime = self.cipher.y - (self.value * generators.B);
+        let z = Scalar::random(rng);
+
+        let initial_message = DecryptionInitialMessage {
+            a: z * generators.B_blinding - challenge.x() * self.pub_key.pub_key,
+            b: z * y_prime - challenge.x() * self.cipher.x,
+        };
+        (initial_message, DecryptionFinalResponse(z))
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [17u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_decryption_proof() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_value = 13u32;
+
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (_, cipher) = elg_pub.encrypt_value(secret_value.into(), &mut rng);
+
+        let prover = DecryptionProverAwaitingChallenge {
+            secret_key: elg_secret,
+            cipher,
+            value: Scalar::from(secret_value),
+            pc_gens: &gens,
+        };
+        let verifier = DecryptionVerifier {
+            value: Scalar::from(secret_value),
+            pub_key: elg_pub,
+            cipher,
+            pc_gens: &gens,
+        };
+        let mut transcript = Transcript::new(DECRYPTION_PROOF_FINAL_RESPONSE_LABEL);
+
+        // Positive tests
+        let mut transcript_rng = prover.create_transcript_rng(&mut rng, &transcript);
+        let (prover, initial_message) = prover.generate_initial_message(&mut transcript_rng);
+        initial_message.update_transcript(&mut transcript).unwrap();
+        let challenge = transcript
+            .scalar_challenge(DECRYPTION_PROOF_CHALLENGE_LABEL)
+            .unwrap();
+        let final_response = prover.apply_challenge(&challenge);
+
+        let result = verifier.verify(&challenge, &initial_message, &final_response);
+        assert!(result.is_ok());
+
+        // Negative tests
+        let bad_initial_message = DecryptionInitialMessage::default();
+        let result = verifier.verify(&challenge, &bad_initial_message, &final_response);
+        assert_err!(
+            result,
+            ErrorKind::DecryptionFinalResponseVerificationError { check: 1 }
+        );
+
+        let bad_final_response = DecryptionFinalResponse(Scalar::default());
+        let result = verifier.verify(&challenge, &initial_message, &bad_final_response);
+        assert_err!(
+            result,
+            ErrorKind::DecryptionFinalResponseVerificationError { check: 1 }
+        );
+
+        // A different value does not verify.
+        let bad_verifier = DecryptionVerifier {
+            value: Scalar::from(secret_value + 1),
+            pub_key: elg_pub,
+            cipher,
+            pc_gens: &gens,
+        };
+        let result = bad_verifier.verify(&challenge, &initial_message, &final_response);
+        assert_err!(
+            result,
+            ErrorKind::DecryptionFinalResponseVerificationError { check: 2 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn nizkp_decryption_proof() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_value: Balance = u64::MAX;
+
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (_, cipher) = elg_pub.const_time_encrypt_value(secret_value.into(), &mut rng);
+        let value = elg_secret.const_time_decrypt(&cipher).unwrap();
+
+        let proof = encryption_proofs::single_property_prover(
+            DecryptionProverAwaitingChallenge {
+                secret_key: elg_secret,
+                cipher: cipher.elgamal_cipher,
+                value: value.into(),
+                pc_gens: &gens,
+            },
+            &mut rng,
+        )
+        .unwrap();
+
+        let verifier = DecryptionVerifier {
+            value: value.into(),
+            pub_key: elg_pub,
+            cipher: cipher.elgamal_cipher,
+            pc_gens: &gens,
+        };
+        assert!(encryption_proofs::single_property_verifier(&verifier, proof).is_ok());
+
+        let mut batch = BatchVerifier::new();
+        batch.add(&verifier, &proof);
+        assert!(batch.verify().is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn serialize_deserialize_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_value = 42u32;
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let (_, cipher) = pub_key.encrypt_value(secret_value.into(), &mut rng);
+        let gens = PedersenGens::default();
+        let prover = DecryptionProverAwaitingChallenge {
+            secret_key,
+            cipher,
+            value: secret_value.into(),
+            pc_gens: &gens,
+        };
+        let (initial_message, final_response) = encryption_proofs::single_property_prover::<
+            StdRng,
+            DecryptionProverAwaitingChallenge,
+        >(prover, &mut rng)
+        .unwrap();
+
+        let bytes = initial_message.encode();
+        let mut input = bytes.as_slice();
+        let recovered_initial_message = <DecryptionInitialMessage>::decode(&mut input).unwrap();
+        assert_eq!(recovered_initial_message, initial_message);
+
+        let bytes = final_response.encode();
+        let mut input = bytes.as_slice();
+        let recovered_final_response = <DecryptionFinalResponse>::decode(&mut input).unwrap();
+        assert_eq!(recovered_final_response, final_response);
+    }
+}
@@ -0,0 +1,276 @@
+//! The `discrete_log` library implements a baby-step giant-step
+//! solver for the small discrete logarithms that show up during
+//! twisted Elgamal decryption.
+//!
+//! Given a point `P = value * h`, where `value` is known to lie in
+//! `[0, 2^range_bits)`, the solver splits `value` into
+//! `value = i * m + j` where `m = 2^baby_steps_bits`, and:
+//! - precomputes the "baby steps" `j * h` for all `j` in `[0, m)`,
+//! - walks through the "giant steps" `P - i * m * h` until it finds
+//!   one of the precomputed baby steps.
+//!
+//! The precomputed table takes `O(m)` memory, and solving takes
+//! `O(2^range_bits / m)` point operations. Larger tables trade memory
+//! for faster decryption. The table can be built once, encoded with
+//! SCALE codec, and loaded back instead of recomputing it.
+
+use crate::asset_proofs::errors::{ErrorKind, Fallible};
+
+use bulletproofs::PedersenGens;
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar, traits::Identity};
+use lazy_static::lazy_static;
+use sp_std::prelude::*;
+
+/// The largest supported value range (in bits).
+pub const MAX_RANGE_BITS: u32 = 64;
+
+/// The largest supported number of baby steps (in bits).
+pub const MAX_BABY_STEPS_BITS: u32 = 32;
+
+/// The number of baby steps (in bits) of the default table.
+/// Decrypting any `u32` with this table takes at most `2^16` giant steps.
+pub const DEFAULT_BABY_STEPS_BITS: u32 = 16;
+
+lazy_static! {
+    /// A table for the `u32` range that is shared by all the default decryption calls.
+    /// It is built on first use.
+    static ref DEFAULT_DISCRETE_LOG_TABLE: DiscreteLogTable =
+        DiscreteLogTable::new(32, DEFAULT_BABY_STEPS_BITS)
+            .expect("The default table parameters are valid");
+}
+
+/// A precomputed table of baby steps.
+///
+/// The baby steps are indexed by the first 8 bytes of their compressed form.
+/// Since these keys are truncated, several baby steps can share a key. All of
+/// them are kept, and every match is double-checked before it is returned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscreteLogTable {
+    /// The solver finds values in `[0, 2^range_bits)`.
+    range_bits: u32,
+
+    /// The table holds `2^baby_steps_bits` baby steps.
+    baby_steps_bits: u32,
+
+    /// The `(key(j * h), j)` pairs, sorted by their keys and then by `j`.
+    baby_steps: Vec<(u64, u32)>,
+}
+
+fn point_key(point: &RistrettoPoint) -> u64 {
+    let mut key = [0u8; 8];
+    key.copy_from_slice(&point.compress().as_bytes()[..8]);
+    u64::from_le_bytes(key)
+}
+
+fn check_parameters(range_bits: u32, baby_steps_bits: u32) -> Fallible<()> {
+    ensure!(
+        range_bits <= MAX_RANGE_BITS
+            && baby_steps_bits > 0
+            && baby_steps_bits <= MAX_BABY_STEPS_BITS
+            && baby_steps_bits <= range_bits,
+        ErrorKind::InvalidDiscreteLogTableParameters {
+            range_bits,
+            baby_steps_bits
+        }
+    );
+    Ok(())
+}
+
+impl DiscreteLogTable {
+    /// Precomputes a table that can solve for any value in `[0, 2^range_bits)`
+    /// using `2^baby_steps_bits` baby steps.
+    pub fn new(range_bits: u32, baby_steps_bits: u32) -> Fallible<Self> {
+        check_parameters(range_bits, baby_steps_bits)?;
+
+        let gens = PedersenGens::default();
+        let baby_steps_count = 1u64 << baby_steps_bits;
+        let mut baby_steps = Vec::with_capacity(baby_steps_count as usize);
+        let mut point = RistrettoPoint::identity();
+        for j in 0..baby_steps_count {
+            baby_steps.push((point_key(&point), j as u32));
+            point += gens.B;
+        }
+        // Sort by key and then by index. The baby steps whose keys collide are all kept.
+        baby_steps.sort_unstable();
+
+        Ok(DiscreteLogTable {
+            range_bits,
+            baby_steps_bits,
+            baby_steps,
+        })
+    }
+
+    /// Returns the table that is used by `ElgamalSecretKey::decrypt`.
+    pub fn default_table() -> &'static DiscreteLogTable {
+        &DEFAULT_DISCRETE_LOG_TABLE
+    }
+
+    pub fn range_bits(&self) -> u32 {
+        self.range_bits
+    }
+
+    pub fn baby_steps_bits(&self) -> u32 {
+        self.baby_steps_bits
+    }
+
+    /// Finds `value` such that `value * h == target`.
+    /// Returns `None` if no such value exists in the table's range.
+    pub fn solve(&self, target: &RistrettoPoint) -> Option<u64> {
+        let gens = PedersenGens::default();
+        let giant_step = Scalar::from(1u64 << self.baby_steps_bits) * gens.B;
+        let giant_steps_count = 1u64 << (self.range_bits - self.baby_steps_bits);
+
+        let mut point = *target;
+        for i in 0..giant_steps_count {
+            let key = point_key(&point);
+            // Try all the baby steps with this key, and rule out the collisions of the
+            // truncated keys.
+            let first = self
+                .baby_steps
+                .binary_search(&(key, 0))
+                .unwrap_or_else(|index| index);
+            for (_, j) in self.baby_steps[first..]
+                .iter()
+                .take_while(|(other_key, _)| *other_key == key)
+            {
+                let value = (i << self.baby_steps_bits) + u64::from(*j);
+                if Scalar::from(value) * gens.B == *target {
+                    return Some(value);
+                }
+            }
+            point -= giant_step;
+        }
+
+        None
+    }
+}
+
+impl Encode for DiscreteLogTable {
+    fn size_hint(&self) -> usize {
+        self.range_bits.size_hint() + self.baby_steps_bits.size_hint() + self.baby_steps.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.range_bits.encode_to(dest);
+        self.baby_steps_bits.encode_to(dest);
+        self.baby_steps.encode_to(dest);
+    }
+}
+
+impl Decode for DiscreteLogTable {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let range_bits = <u32>::decode(input)?;
+        let baby_steps_bits = <u32>::decode(input)?;
+        check_parameters(range_bits, baby_steps_bits)
+            .map_err(|_| CodecError::from("Invalid `DiscreteLogTable` parameters"))?;
+
+        let baby_steps = <Vec<(u64, u32)>>::decode(input)?;
+        let is_sorted = baby_steps.windows(2).all(|pair| pair[0] < pair[1]);
+        if !is_sorted || baby_steps.len() as u64 != 1u64 << baby_steps_bits {
+            return Err(CodecError::from("Invalid `DiscreteLogTable` baby steps"));
+        }
+
+        Ok(DiscreteLogTable {
+            range_bits,
+            baby_steps_bits,
+            baby_steps,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn solve_discrete_log() {
+        let gens = PedersenGens::default();
+        let table = DiscreteLogTable::new(20, 10).unwrap();
+
+        for value in [0u64, 1, 1023, 1024, 1025, 54321, (1 << 20) - 1].iter() {
+            let target = Scalar::from(*value) * gens.B;
+            assert_eq!(table.solve(&target), Some(*value));
+        }
+
+        // Values outside of the range are not found.
+        let target = Scalar::from(1u64 << 20) * gens.B;
+        assert_eq!(table.solve(&target), None);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn invalid_parameters() {
+        assert_err!(
+            DiscreteLogTable::new(8, 10),
+            ErrorKind::InvalidDiscreteLogTableParameters {
+                range_bits: 8,
+                baby_steps_bits: 10
+            }
+        );
+        assert_err!(
+            DiscreteLogTable::new(65, 10),
+            ErrorKind::InvalidDiscreteLogTableParameters {
+                range_bits: 65,
+                baby_steps_bits: 10
+            }
+        );
+        assert_err!(
+            DiscreteLogTable::new(16, 0),
+            ErrorKind::InvalidDiscreteLogTableParameters {
+                range_bits: 16,
+                baby_steps_bits: 0
+            }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn serialize_deserialize_table() {
+        let gens = PedersenGens::default();
+        let table = DiscreteLogTable::new(16, 8).unwrap();
+
+        let bytes = table.encode();
+        let mut input = bytes.as_slice();
+        let recovered_table = <DiscreteLogTable>::decode(&mut input).unwrap();
+        assert_eq!(recovered_table, table);
+
+        let target = Scalar::from(4242u64) * gens.B;
+        assert_eq!(recovered_table.solve(&target), Some(4242));
+
+        // A table with unsorted or missing baby steps is rejected.
+        let mut bad_table = table.clone();
+        bad_table.baby_steps.swap(0, 1);
+        let bytes = bad_table.encode();
+        assert!(<DiscreteLogTable>::decode(&mut bytes.as_slice()).is_err());
+        let mut bad_table = table;
+        bad_table.baby_steps.pop();
+        let bytes = bad_table.encode();
+        assert!(<DiscreteLogTable>::decode(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn solve_with_colliding_keys() {
+        let gens = PedersenGens::default();
+        let mut table = DiscreteLogTable::new(16, 8).unwrap();
+
+        // 4242 is found at the baby step 146 of the giant step 16. Add a baby step that
+        // shares its key and comes first, as a collision of the truncated keys would.
+        let key = point_key(&(Scalar::from(146u64) * gens.B));
+        table.baby_steps.push((key, 0));
+        table.baby_steps.sort_unstable();
+        let colliding = table.baby_steps.binary_search(&(key, 0)).unwrap();
+        assert_eq!(table.baby_steps[colliding + 1], (key, 146));
+
+        let target = Scalar::from(4242u64) * gens.B;
+        assert_eq!(table.solve(&target), Some(4242));
+    }
+}
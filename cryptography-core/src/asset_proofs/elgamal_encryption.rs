@@ -4,22 +4,28 @@
 //! addition and subtraction API over the cipher texts.
 
 use crate::{
-    asset_proofs::errors::{ErrorKind, Fallible},
+    asset_proofs::{
+        discrete_log::DiscreteLogTable,
+        errors::{ErrorKind, Fallible},
+        Balance,
+    },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
 };
 
 use bulletproofs::PedersenGens;
 use core::ops::{Add, AddAssign, Sub, SubAssign};
 use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+#[cfg(not(feature = "verify-only"))]
 use rand::rngs::StdRng;
-use rand_core::{CryptoRng, RngCore};
 
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
-use sp_std::prelude::*;
+use sp_std::{fmt, prelude::*};
 
 /// Prover's representation of the commitment secret.
 #[derive(Clone, PartialEq, Zeroize, Debug)]
@@ -28,9 +34,11 @@ use sp_std::prelude::*;
 pub struct CommitmentWitness {
     /// Depending on how the witness was created this variable stores the
     /// balance value or the asset id in Scalar format.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     value: Scalar,
 
     /// A random blinding factor.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     blinding: Scalar,
 }
 
@@ -50,6 +58,7 @@ impl CommitmentWitness {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl From<(Scalar, &mut StdRng)> for CommitmentWitness {
     fn from(v: (Scalar, &mut StdRng)) -> Self {
         CommitmentWitness {
@@ -84,7 +93,9 @@ impl Decode for CommitmentWitness {
 #[derive(PartialEq, Copy, Clone, Default, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CipherText {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub x: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub y: RistrettoPoint,
 }
 
@@ -172,10 +183,11 @@ define_sub_assign_variants!(LHS = CipherText, RHS = CipherText);
 /// where g and h are 2 orthogonal generators.
 
 /// An Elgamal Secret Key is a random scalar.
-#[derive(Clone, Zeroize, Debug)]
+#[derive(Clone, Zeroize)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[zeroize(drop)]
 pub struct ElgamalSecretKey {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub secret: Scalar,
 }
 
@@ -198,10 +210,18 @@ impl Decode for ElgamalSecretKey {
     }
 }
 
+/// Does not print the secret.
+impl fmt::Debug for ElgamalSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ElgamalSecretKey { .. }")
+    }
+}
+
 /// The Elgamal Public Key is the secret key multiplied by the blinding generator (g).
 #[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ElgamalPublicKey {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub pub_key: RistrettoPoint,
 }
 
@@ -218,6 +238,7 @@ impl ElgamalPublicKey {
     }
 
     /// Generates a blinding factor, and encrypts the value.
+    #[cfg(not(feature = "verify-only"))]
     pub fn encrypt_value<R: RngCore + CryptoRng>(
         &self,
         value: Scalar,
@@ -261,21 +282,27 @@ impl ElgamalSecretKey {
         }
     }
 
-    /// Decrypt a cipher text that is known to encrypt a u32.
-    pub fn decrypt(&self, cipher_text: &CipherText) -> Fallible<u32> {
-        let gens = PedersenGens::default();
+    /// Decrypt a cipher text that is known to encrypt a balance.
+    /// Uses the lazily built default `DiscreteLogTable`, which covers the
+    /// `u32` range. Larger balances need either a wider table or the
+    /// constant time ciphertext, `CipherTextWithHint`.
+    pub fn decrypt(&self, cipher_text: &CipherText) -> Fallible<Balance> {
+        self.decrypt_with_table(cipher_text, DiscreteLogTable::default_table())
+    }
+
+    /// Decrypt a cipher text that is known to encrypt a balance within the
+    /// range of the precomputed table of baby steps.
+    pub fn decrypt_with_table(
+        &self,
+        cipher_text: &CipherText,
+        table: &DiscreteLogTable,
+    ) -> Fallible<Balance> {
         // value * h = Y - X / secret_key
         let value_h = cipher_text.y - self.secret.invert() * cipher_text.x;
-        // Brute force all possible values to find the one that matches value * h.
-        let mut result = Scalar::zero() * gens.B;
-        for v in 0..u32::max_value() {
-            if result == value_h {
-                return Ok(v);
-            }
-            result += gens.B;
-        }
-
-        Err(ErrorKind::CipherTextDecryptionError.into())
+        // Search for the value that matches value * h.
+        table
+            .solve(&value_h)
+            .ok_or_else(|| ErrorKind::CipherTextDecryptionError.into())
     }
 
     /// Verifies that a cipher text encrypts the given witness.
@@ -368,7 +395,7 @@ mod tests {
         let elg_pub = elg_secret.get_public_key();
 
         // Test encrypting balance.
-        let balance: Balance = 256u32;
+        let balance: Balance = 256;
         let blinding = Scalar::random(&mut rng);
         let balance_witness = CommitmentWitness {
             value: balance.into(),
@@ -400,6 +427,31 @@ mod tests {
         assert!(elg_secret.verify(&cipher, &asset_id.into()).is_ok());
     }
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn decrypt_with_table() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let table = DiscreteLogTable::new(32, 12).unwrap();
+
+        for value in [0u64, 1, 4095, 4096, 1_000_000].iter() {
+            let (_, cipher) = elg_pub.encrypt_value((*value).into(), &mut rng);
+            assert_eq!(
+                elg_secret.decrypt_with_table(&cipher, &table).unwrap(),
+                *value
+            );
+        }
+
+        // A table with a smaller range can not decrypt larger values.
+        let small_table = DiscreteLogTable::new(16, 8).unwrap();
+        let (_, cipher) = elg_pub.encrypt_value(70_000u64.into(), &mut rng);
+        assert_err!(
+            elg_secret.decrypt_with_table(&cipher, &small_table),
+            ErrorKind::CipherTextDecryptionError
+        );
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn homomorphic_encryption() {
@@ -441,7 +493,7 @@ mod tests {
     #[wasm_bindgen_test]
     fn test_two_encryptions() {
         let mut rng = StdRng::from_seed([17u8; 32]);
-        let value = 256u32;
+        let value: Balance = 256;
         let blinding = Scalar::random(&mut rng);
         let w = CommitmentWitness {
             value: value.into(),
@@ -2,15 +2,19 @@
 //! under different public keys.
 //! For more details see section 5.4 of the whitepaper.
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofSimulator},
+    CommitmentWitness,
+};
 use crate::{
     asset_proofs::{
         encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-            ZKProofResponse,
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
         },
         errors::{ErrorKind, Fallible},
         transcript::{TranscriptProtocol, UpdateTranscript},
-        CipherText, CommitmentWitness, ElgamalPublicKey,
+        CipherText, ElgamalPublicKey,
     },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
 };
@@ -19,13 +23,18 @@ use bulletproofs::PedersenGens;
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
 };
-use merlin::{Transcript, TranscriptRng};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
 use zeroize::{Zeroize, Zeroizing};
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::prelude::*;
 
 /// The domain label for the encrypting the same value proof.
 pub const ENCRYPTING_SAME_VALUE_PROOF_FINAL_RESPONSE_LABEL: &[u8] =
@@ -42,7 +51,9 @@ pub const ENCRYPTING_SAME_VALUE_PROOF_CHALLENGE_LABEL: &[u8] =
 #[derive(PartialEq, Copy, Clone, Default, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EncryptingSameValueFinalResponse {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z1: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z2: Scalar,
 }
 
@@ -70,8 +81,11 @@ impl Decode for EncryptingSameValueFinalResponse {
 #[derive(PartialEq, Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EncryptingSameValueInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     a1: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     a2: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     b: RistrettoPoint,
 }
 
@@ -126,6 +140,7 @@ impl UpdateTranscript for EncryptingSameValueInitialMessage {
 pub type CipherEqualDifferentPubKeyProof =
     ZKProofResponse<EncryptingSameValueInitialMessage, EncryptingSameValueFinalResponse>;
 
+#[cfg(not(feature = "verify-only"))]
 pub struct EncryptingSameValueProverAwaitingChallenge<'a> {
     /// The first public key used for the elgamal encryption.
     pub pub_key1: ElgamalPublicKey,
@@ -140,6 +155,7 @@ pub struct EncryptingSameValueProverAwaitingChallenge<'a> {
     pub pc_gens: &'a PedersenGens,
 }
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Zeroize)]
 #[zeroize(drop)]
 pub struct EncryptingSameValueProver {
@@ -153,6 +169,7 @@ pub struct EncryptingSameValueProver {
     u2: Scalar,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for EncryptingSameValueProverAwaitingChallenge<'a> {
     type ZKInitialMessage = EncryptingSameValueInitialMessage;
     type ZKFinalResponse = EncryptingSameValueFinalResponse;
@@ -188,6 +205,7 @@ impl<'a> AssetProofProverAwaitingChallenge for EncryptingSameValueProverAwaiting
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<EncryptingSameValueFinalResponse> for EncryptingSameValueProver {
     fn apply_challenge(&self, c: &ZKPChallenge) -> EncryptingSameValueFinalResponse {
         EncryptingSameValueFinalResponse {
@@ -247,6 +265,55 @@ impl<'a> AssetProofVerifier for EncryptingSameValueVerifier<'a> {
         );
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        // 2 ciphertexts that encrypt the same witness must have the same Y value.
+        ensure!(
+            self.cipher1.y == self.cipher2.y,
+            ErrorKind::VerificationError
+        );
+        let c = *challenge.x();
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(final_response.z1, self.pub_key1.pub_key)
+                .term(-Scalar::one(), initial_message.a1)
+                .term(-c, self.cipher1.x),
+            VerificationEquation::new()
+                .term(final_response.z1, self.pub_key2.pub_key)
+                .term(-Scalar::one(), initial_message.a2)
+                .term(-c, self.cipher2.x),
+            VerificationEquation::new()
+                .term(final_response.z1, self.pc_gens.B_blinding)
+                .term(final_response.z2, self.pc_gens.B)
+                .term(-Scalar::one(), initial_message.b)
+                .term(-c, self.cipher1.y),
+        ])
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofSimulator for EncryptingSameValueVerifier<'a> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let z1 = Scalar::random(rng);
+        let z2 = Scalar::random(rng);
+
+        let initial_message = EncryptingSameValueInitialMessage {
+            a1: z1 * self.pub_key1.pub_key - challenge.x() * self.cipher1.x,
+            a2: z1 * self.pub_key2.pub_key - challenge.x() * self.cipher2.x,
+            b: z1 * self.pc_gens.B_blinding + z2 * self.pc_gens.B - challenge.x() * self.cipher1.y,
+        };
+        (initial_message, EncryptingSameValueFinalResponse { z1, z2 })
+    }
 }
 
 // ------------------------------------------------------------------------
@@ -1,10 +1,14 @@
 //! Encryption proofs' interface definitions and
 //! Non-Interactive Zero Knowledge Proof API.
 
-use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::{
+    ristretto::RistrettoPoint,
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
 use merlin::{Transcript, TranscriptRng};
 use rand_core::{CryptoRng, RngCore};
-use sp_std::convert::TryFrom;
+use sp_std::{convert::TryFrom, prelude::*};
 
 use super::errors::{Error, ErrorKind, Fallible};
 use crate::asset_proofs::transcript::{TranscriptProtocol, UpdateTranscript};
@@ -27,6 +31,14 @@ impl ZKPChallenge {
     pub fn x(&self) -> &Scalar {
         &self.x
     }
+
+    /// Creates a challenge without checking that it is not zero.
+    /// Used by the composed provers to split a challenge. Their verifiers
+    /// reconstruct the split challenges with `try_from`, which does the check.
+    #[cfg(not(feature = "verify-only"))]
+    pub(crate) fn from_scalar_unchecked(x: Scalar) -> Self {
+        ZKPChallenge { x }
+    }
 }
 
 impl TryFrom<Scalar> for ZKPChallenge {
@@ -38,6 +50,44 @@ impl TryFrom<Scalar> for ZKPChallenge {
     }
 }
 
+/// A single check of a verifier, expressed as the terms of a multiscalar
+/// multiplication. The check holds if `sum(scalars[i] * points[i])` is the identity.
+#[derive(Clone, Debug, Default)]
+pub struct VerificationEquation {
+    pub scalars: Vec<Scalar>,
+    pub points: Vec<RistrettoPoint>,
+}
+
+impl VerificationEquation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `scalar * point` term to the equation.
+    pub fn term(mut self, scalar: Scalar, point: RistrettoPoint) -> Self {
+        self.scalars.push(scalar);
+        self.points.push(point);
+        self
+    }
+
+    /// Adds the `scalars[i] * points[i]` terms to the equation.
+    pub fn terms<S, P>(mut self, scalars: S, points: P) -> Self
+    where
+        S: IntoIterator<Item = Scalar>,
+        P: IntoIterator<Item = RistrettoPoint>,
+    {
+        self.scalars.extend(scalars);
+        self.points.extend(points);
+        self
+    }
+
+    /// Returns true if the check holds.
+    pub fn holds(&self) -> bool {
+        self.scalars.len() == self.points.len()
+            && RistrettoPoint::vartime_multiscalar_mul(&self.scalars, &self.points).is_identity()
+    }
+}
+
 /// The interface for a 3-Sigma protocol.
 /// Abstracting the prover and verifier roles.
 ///
@@ -117,6 +167,45 @@ pub trait AssetProofVerifier {
         initial_message: &Self::ZKInitialMessage,
         final_response: &Self::ZKFinalResponse,
     ) -> Fallible<()>;
+
+    /// Expresses the checks of `verify` as multiscalar multiplication terms, so that
+    /// they can be combined with the checks of other proofs by the `BatchVerifier`.
+    /// The proof is valid if all the returned equations hold.
+    ///
+    /// # Inputs
+    /// `challenge`       The scalar challenge, generated by the transcript.
+    /// `initial_message` The initial message, generated by the Prover.
+    /// `final_response`  The final response, generated by the Prover.
+    ///
+    /// # Output
+    /// The verification equations on success, or an error if the proof is malformed.
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>>;
+}
+
+/// A verifier that can simulate accepting proofs of its statement for any
+/// given challenge, without knowing the witness. This is the special
+/// honest-verifier zero-knowledge property of Sigma protocols, and it is
+/// required by the OR-composition of proofs.
+pub trait AssetProofSimulator: AssetProofVerifier {
+    /// Simulates an initial message and a final response which are accepted
+    /// by `verify` for the given `challenge`.
+    ///
+    /// # Inputs
+    /// `challenge` The scalar challenge that the simulated proof must answer.
+    /// `rng`       An RNG.
+    ///
+    /// # Output
+    /// A simulated initial message and final response.
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse);
 }
 
 // ------------------------------------------------------------------------
@@ -136,6 +225,7 @@ pub type ZKProofResponse<ZKInitialMessage, ZKFinalResponse> = (ZKInitialMessage,
 ///
 /// # Outputs
 /// An initial message and a final response as a tuple on success, or failure on an error.
+#[cfg(not(feature = "verify-only"))]
 pub fn single_property_prover<
     T: RngCore + CryptoRng,
     ProverAwaitingChallenge: AssetProofProverAwaitingChallenge,
@@ -177,17 +267,24 @@ pub fn single_property_verifier<Verifier: AssetProofVerifier>(
 ) -> Fallible<()> {
     let initial_message = proof.0;
     let final_response = proof.1;
-    let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
-
-    // Update the transcript with Prover's initial message
-    initial_message.update_transcript(&mut transcript)?;
-    let challenge = transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)?;
+    let challenge = non_interactive_challenge(&initial_message)?;
 
     verifier.verify(&challenge, &initial_message, &final_response)?;
 
     Ok(())
 }
 
+/// Recomputes the challenge of a non-interactive proof from the prover's initial message.
+pub(crate) fn non_interactive_challenge<InitialMessage: UpdateTranscript>(
+    initial_message: &InitialMessage,
+) -> Fallible<ZKPChallenge> {
+    let mut transcript = Transcript::new(ENCRYPTION_PROOFS_LABEL);
+
+    // Update the transcript with Prover's initial message
+    initial_message.update_transcript(&mut transcript)?;
+    transcript.scalar_challenge(ENCRYPTION_PROOFS_CHALLENGE_LABEL)
+}
+
 // ------------------------------------------------------------------------
 // Tests
 // ------------------------------------------------------------------------
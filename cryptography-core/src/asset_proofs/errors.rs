@@ -1,3 +1,4 @@
+use crate::asset_proofs::Balance;
 use bulletproofs::ProofError;
 use failure::{Backtrace, Context, Fail};
 
@@ -71,6 +72,13 @@ pub enum ErrorKind {
     )]
     CorrectnessFinalResponseVerificationError { check: u16 },
 
+    /// Failed to verify a decryption proof.
+    #[fail(
+        display = "Failed to verify the check number {} of the decryption proof",
+        check
+    )]
+    DecryptionFinalResponseVerificationError { check: u16 },
+
     /// Failed to verify a R1 proof.
     #[fail(
         display = "Failed to verify the check number {} of the R1 proof",
@@ -107,6 +115,13 @@ pub enum ErrorKind {
     )]
     CiphertextRefreshmentFinalResponseVerificationError { check: u16 },
 
+    /// Failed to verify a ciphertext re-encryption proof.
+    #[fail(
+        display = "Failed to verify the check number {} of the ciphertext re-encryption proof",
+        check
+    )]
+    CiphertextReencryptionFinalResponseVerificationError { check: u16 },
+
     /// Failed to verify an encrypting the same value proof.
     #[fail(
         display = "Failed to verify the check number {} of the encrypting the same value proof",
@@ -121,6 +136,17 @@ pub enum ErrorKind {
     )]
     MembershipProofVerificationError { check: u16 },
 
+    /// Failed to verify the non-membership proof.
+    #[fail(
+        display = "Failed to verify the check number {} of the non-membership proof",
+        check
+    )]
+    NonMembershipProofVerificationError { check: u16 },
+
+    /// The committed element belongs to the set of the non-membership proof.
+    #[fail(display = "The element belongs to the set of the non-membership proof")]
+    NonMembershipProofInvalidAssetError,
+
     /// Invalid asset type is provided.
     #[fail(display = "Invalid asset type ")]
     MembershipProofInvalidAssetError,
@@ -138,7 +164,7 @@ pub enum ErrorKind {
         display = "Expected to receive {:?} from the sender, got a different amount.",
         expected_amount
     )]
-    TransactionAmountMismatch { expected_amount: u32 },
+    TransactionAmountMismatch { expected_amount: Balance },
 
     /// The public key in the memo of the initial transaction does not match the public key
     /// in the memo.
@@ -151,8 +177,8 @@ pub enum ErrorKind {
         transaction_amount, balance
     )]
     NotEnoughFund {
-        balance: u32,
-        transaction_amount: u32,
+        balance: Balance,
+        transaction_amount: Balance,
     },
 
     /// The account Id in the transaction does not match the input account info.
@@ -163,6 +189,10 @@ pub enum ErrorKind {
     #[fail(display = "The mercat transaction id does not match the one supplied previously.")]
     TransactionIdMismatch,
 
+    /// The rejected transaction is not the pending transaction of the sender.
+    #[fail(display = "The rejected transaction does not match the pending transaction")]
+    PendingTransactionMismatch,
+
     /// Error while converting a transaction content to binary format.
     #[fail(display = "Error during the serialization to byte array.")]
     SerializationError,
@@ -171,6 +201,10 @@ pub enum ErrorKind {
     #[fail(display = "A range proof error occurred: {:?}", source)]
     ProvingError { source: ProofError },
 
+    /// The lower bound of an interval is greater than its upper bound.
+    #[fail(display = "Invalid interval [{}, {}]", min, max)]
+    InvalidInterval { min: Balance, max: Balance },
+
     /// The ticker id can be at most 12 characters long.
     #[fail(
         display = "Incorrect ticker length. The length can be at most {:?}, but got {:?}",
@@ -181,6 +215,112 @@ pub enum ErrorKind {
     /// The auditors' payload does not match the compliance rules.
     #[fail(display = "The auditors' payload does not match the compliance rules.")]
     AuditorPayloadError,
+
+    /// The mediators' payload or justifications do not match the mediators of the transaction.
+    #[fail(display = "The mediators' payload does not match the mediators of the transaction.")]
+    MediatorPayloadError,
+
+    /// Not enough distinct mediators have justified the transaction.
+    #[fail(
+        display = "Not enough mediator justifications: needed {}, got {}",
+        needed, given
+    )]
+    NotEnoughMediatorJustifications { needed: u32, given: u32 },
+
+    /// Not enough distinct mediators have rejected the transaction to keep it from
+    /// being justified.
+    #[fail(
+        display = "Not enough mediator rejections: needed {}, got {}",
+        needed, given
+    )]
+    NotEnoughMediatorRejections { needed: u32, given: u32 },
+
+    /// The legs or the receipts of a batched transaction do not match its receivers.
+    #[fail(display = "The legs of the batched transaction do not match its receivers.")]
+    BatchTransferError,
+
+    /// A batched transaction has more receivers than its range proof can aggregate.
+    #[fail(
+        display = "The batched transaction has {} receivers, but at most {} are supported",
+        given, max
+    )]
+    TooManyBatchReceivers { max: u32, given: u32 },
+
+    /// Invalid parameters were passed to the discrete log table.
+    #[fail(
+        display = "Invalid discrete log table parameters: range bits {}, baby steps bits {}",
+        range_bits, baby_steps_bits
+    )]
+    InvalidDiscreteLogTableParameters {
+        range_bits: u32,
+        baby_steps_bits: u32,
+    },
+
+    /// A proof in a batch failed verification.
+    #[fail(display = "Failed to verify the proof at index {} of the batch", index)]
+    BatchVerificationError { index: usize },
+
+    /// Invalid threshold parameters were passed to the distributed key generation.
+    #[fail(
+        display = "Invalid threshold parameters: threshold {}, parties {}",
+        threshold, parties
+    )]
+    InvalidThresholdParameters { threshold: u32, parties: u32 },
+
+    /// The participant index is not in the range of the key generation parties.
+    #[fail(display = "Invalid participant index {}", index)]
+    InvalidParticipantIndex { index: u32 },
+
+    /// The commitments of a dealer are missing, duplicated or malformed.
+    #[fail(
+        display = "The key generation commitments of the dealer {} are invalid",
+        dealer
+    )]
+    DkgCommitmentsError { dealer: u32 },
+
+    /// A secret share does not match the commitments of its dealer.
+    #[fail(
+        display = "Failed to verify the key generation share of the dealer {}",
+        dealer
+    )]
+    DkgShareVerificationError { dealer: u32 },
+
+    /// The masked products of the participants do not lie on the same polynomial.
+    #[fail(display = "The masked products of the key generation are inconsistent")]
+    DkgMaskedProductError,
+
+    /// Failed to verify a partial decryption proof.
+    #[fail(
+        display = "Failed to verify the check number {} of the partial decryption proof",
+        check
+    )]
+    PartialDecryptionFinalResponseVerificationError { check: u16 },
+
+    /// A decryption share failed verification.
+    #[fail(
+        display = "Failed to verify the decryption share of the participant {}",
+        index
+    )]
+    DecryptionShareVerificationError { index: u32 },
+
+    /// Not enough distinct decryption shares were given.
+    #[fail(
+        display = "Not enough decryption shares: needed {}, got {}",
+        needed, given
+    )]
+    NotEnoughDecryptionShares { needed: u32, given: u32 },
+
+    /// The seed of a key derivation is too short or too long.
+    #[fail(display = "Invalid key derivation seed length {}", length)]
+    InvalidSeedLength { length: usize },
+
+    /// A key derivation path could not be parsed.
+    #[fail(display = "Invalid key derivation path")]
+    InvalidDerivationPath,
+
+    /// The signature does not match the message or the public key.
+    #[fail(display = "Failed to verify the signature")]
+    SignatureVerificationError,
 }
 
 pub type Fallible<T, E = Error> = Result<T, E>;
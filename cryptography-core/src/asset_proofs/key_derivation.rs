@@ -0,0 +1,316 @@
+//! The `key_derivation` library derives a tree of Elgamal secret keys
+//! from a single master seed, along the lines of BIP32.
+//!
+//! Every node of the tree is an `ExtendedSecretKey`: a secret scalar and
+//! a 32 byte chain code. The master node is derived from the seed, and the
+//! child `i` of a node is derived from the node's chain code, its secret
+//! scalar and `i`. Since a child depends on the secret of its parent, all
+//! derivations are hardened, and knowing a child key or the public key of
+//! a node does not reveal anything about its siblings or its parent.
+//!
+//! Nodes are derived with Merlin transcripts instead of HMAC-SHA512:
+//! ```text
+//! master := H("seed", seed)
+//! child_i := H("chain_code", chain_code, "secret", secret, "index", i)
+//! ```
+//! where the first 64 bytes of the output are reduced to the secret scalar,
+//! and the next 32 bytes become the chain code.
+//!
+//! A wallet uses the following paths:
+//! - `m/0/a/b/c` for the account of the asset id `a || b || c`, where the
+//!   12 bytes of the asset id are split into three little-endian `u32`s.
+//! - `m/1/i` for the `i`-th mediator role.
+//! - `m/2/i` for the auditor role with the id `i`.
+//!
+//! Therefore, the seed and the list of tickers are enough to restore all
+//! the keys of a wallet.
+
+use crate::asset_proofs::{
+    errors::{Error, ErrorKind, Fallible},
+    AssetId, ElgamalSecretKey, ASSET_ID_LEN,
+};
+
+use curve25519_dalek::scalar::Scalar;
+use merlin::Transcript;
+use sp_std::{fmt, prelude::*, str::FromStr};
+use zeroize::Zeroize;
+
+/// The domain label for the master key derivation.
+const MASTER_KEY_LABEL: &[u8] = b"PolymathHDMasterKey";
+
+/// The domain label for the child key derivation.
+const CHILD_KEY_LABEL: &[u8] = b"PolymathHDChildKey";
+
+/// The smallest accepted seed length (in bytes).
+pub const MIN_SEED_LEN: usize = 16;
+
+/// The largest accepted seed length (in bytes).
+pub const MAX_SEED_LEN: usize = 64;
+
+/// The first index of the account paths.
+pub const ACCOUNT_PURPOSE: u32 = 0;
+
+/// The first index of the mediator paths.
+pub const MEDIATOR_PURPOSE: u32 = 1;
+
+/// The first index of the auditor paths.
+pub const AUDITOR_PURPOSE: u32 = 2;
+
+/// The child index that is used to derive the blinding factor of an
+/// account's asset id from the account's node.
+const ASSET_ID_BLINDING_INDEX: u32 = 0;
+
+// ------------------------------------------------------------------------
+// Derivation Path
+// ------------------------------------------------------------------------
+
+/// A list of child indices, written as `m/i_0/i_1/...`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    pub fn new(indices: Vec<u32>) -> Self {
+        DerivationPath(indices)
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// The path of the account that holds the asset `asset_id`.
+    pub fn account(asset_id: &AssetId) -> Self {
+        let mut indices = Vec::with_capacity(1 + ASSET_ID_LEN / 4);
+        indices.push(ACCOUNT_PURPOSE);
+        for chunk in asset_id.id.chunks(4) {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(chunk);
+            indices.push(u32::from_le_bytes(bytes));
+        }
+        DerivationPath(indices)
+    }
+
+    /// The path of the `index`-th mediator role.
+    pub fn mediator(index: u32) -> Self {
+        DerivationPath(vec![MEDIATOR_PURPOSE, index])
+    }
+
+    /// The path of the auditor role with the id `auditor_id`.
+    pub fn auditor(auditor_id: u32) -> Self {
+        DerivationPath(vec![AUDITOR_PURPOSE, auditor_id])
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let mut parts = path.split('/');
+        ensure!(parts.next() == Some("m"), ErrorKind::InvalidDerivationPath);
+        parts
+            .map(|part| -> Fallible<u32> {
+                // `u32::from_str` accepts a leading `+`, which is not part of the path format.
+                ensure!(
+                    !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()),
+                    ErrorKind::InvalidDerivationPath
+                );
+                part.parse::<u32>()
+                    .map_err(|_| ErrorKind::InvalidDerivationPath.into())
+            })
+            .collect::<Fallible<Vec<_>>>()
+            .map(DerivationPath)
+    }
+}
+
+// ------------------------------------------------------------------------
+// Extended Secret Key
+// ------------------------------------------------------------------------
+
+/// A node of the key tree.
+#[derive(Clone, Zeroize)]
+#[zeroize(drop)]
+pub struct ExtendedSecretKey {
+    secret: Scalar,
+    chain_code: [u8; 32],
+}
+
+/// Does not print the secret or the chain code.
+impl fmt::Debug for ExtendedSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ExtendedSecretKey { .. }")
+    }
+}
+
+impl ExtendedSecretKey {
+    /// Derives the master node from a seed of `MIN_SEED_LEN` to `MAX_SEED_LEN` bytes.
+    pub fn from_seed(seed: &[u8]) -> Fallible<Self> {
+        ensure!(
+            seed.len() >= MIN_SEED_LEN && seed.len() <= MAX_SEED_LEN,
+            ErrorKind::InvalidSeedLength { length: seed.len() }
+        );
+
+        let mut transcript = Transcript::new(MASTER_KEY_LABEL);
+        transcript.append_message(b"seed", seed);
+        Ok(Self::from_transcript(transcript))
+    }
+
+    /// Derives the child `index` of this node.
+    pub fn derive_child(&self, index: u32) -> Self {
+        let mut transcript = Transcript::new(CHILD_KEY_LABEL);
+        transcript.append_message(b"chain_code", &self.chain_code);
+        transcript.append_message(b"secret", self.secret.as_bytes());
+        transcript.append_u64(b"index", index as u64);
+        Self::from_transcript(transcript)
+    }
+
+    /// Derives the descendant of this node at `path`.
+    pub fn derive_path(&self, path: &DerivationPath) -> Self {
+        path.indices()
+            .iter()
+            .fold(self.clone(), |node, index| node.derive_child(*index))
+    }
+
+    /// Returns the Elgamal secret key of this node.
+    pub fn secret_key(&self) -> ElgamalSecretKey {
+        ElgamalSecretKey::new(self.secret)
+    }
+
+    /// Returns the blinding factor of the asset id of the account at this node,
+    /// so that the whole account, and not only its keys, can be restored.
+    pub fn asset_id_blinding(&self) -> Scalar {
+        self.derive_child(ASSET_ID_BLINDING_INDEX).secret
+    }
+
+    fn from_transcript(mut transcript: Transcript) -> Self {
+        let mut secret_bytes = [0u8; 64];
+        transcript.challenge_bytes(b"secret", &mut secret_bytes);
+        let secret = Scalar::from_bytes_mod_order_wide(&secret_bytes);
+        secret_bytes.zeroize();
+
+        let mut chain_code = [0u8; 32];
+        transcript.challenge_bytes(b"chain_code", &mut chain_code);
+
+        ExtendedSecretKey { secret, chain_code }
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::asset_id_from_ticker;
+    use wasm_bindgen_test::*;
+
+    const SEED: [u8; 32] = [42u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn key_derivation_is_deterministic() {
+        let master = ExtendedSecretKey::from_seed(&SEED).unwrap();
+        let path = DerivationPath::account(&asset_id_from_ticker("ACME").unwrap());
+
+        // Positive tests: the same seed and path always give the same key.
+        let key = master.derive_path(&path).secret_key();
+        let restored = ExtendedSecretKey::from_seed(&SEED)
+            .unwrap()
+            .derive_path(&path)
+            .secret_key();
+        assert_eq!(key.secret, restored.secret);
+        assert_eq!(
+            master.derive_child(ACCOUNT_PURPOSE).secret,
+            master.derive_path(&DerivationPath::new(vec![0])).secret
+        );
+        assert_eq!(
+            master.derive_path(&DerivationPath::default()).secret,
+            master.secret
+        );
+
+        // Negative tests: other seeds and paths give different keys.
+        let other_master = ExtendedSecretKey::from_seed(&[7u8; 32]).unwrap();
+        assert_ne!(other_master.derive_path(&path).secret, key.secret);
+        let other_paths = [
+            DerivationPath::account(&asset_id_from_ticker("ACMF").unwrap()),
+            DerivationPath::mediator(0),
+            DerivationPath::auditor(0),
+            DerivationPath::auditor(1),
+        ];
+        for other_path in other_paths.iter() {
+            assert_ne!(master.derive_path(other_path).secret, key.secret);
+        }
+        assert_ne!(master.derive_path(&path).asset_id_blinding(), key.secret);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn debug_does_not_print_secrets() {
+        let master = ExtendedSecretKey::from_seed(&SEED).unwrap();
+        let key = master.secret_key();
+        assert_eq!(format!("{:?}", master), "ExtendedSecretKey { .. }");
+        assert_eq!(format!("{:?}", key), "ElgamalSecretKey { .. }");
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn seed_length() {
+        assert!(ExtendedSecretKey::from_seed(&[1u8; MIN_SEED_LEN]).is_ok());
+        assert!(ExtendedSecretKey::from_seed(&[1u8; MAX_SEED_LEN]).is_ok());
+        assert_err!(
+            ExtendedSecretKey::from_seed(&[1u8; MIN_SEED_LEN - 1]),
+            ErrorKind::InvalidSeedLength {
+                length: MIN_SEED_LEN - 1
+            }
+        );
+        assert_err!(
+            ExtendedSecretKey::from_seed(&[1u8; MAX_SEED_LEN + 1]),
+            ErrorKind::InvalidSeedLength {
+                length: MAX_SEED_LEN + 1
+            }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn derivation_path_parsing() {
+        let path = DerivationPath::account(&asset_id_from_ticker("ACME").unwrap());
+        assert_eq!(path.to_string().parse::<DerivationPath>().unwrap(), path);
+        assert_eq!(
+            "m/1/4294967295".parse::<DerivationPath>().unwrap(),
+            DerivationPath::mediator(u32::max_value())
+        );
+        assert_eq!(
+            "m".parse::<DerivationPath>().unwrap(),
+            DerivationPath::default()
+        );
+
+        for invalid in [
+            "",
+            "n/1",
+            "m/",
+            "m//1",
+            "m/1/",
+            "m/+1",
+            "m/1'",
+            "m/4294967296",
+        ]
+        .iter()
+        {
+            assert_err!(
+                invalid.parse::<DerivationPath>(),
+                ErrorKind::InvalidDerivationPath
+            );
+        }
+    }
+}
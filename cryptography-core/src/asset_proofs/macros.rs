@@ -92,3 +92,16 @@ macro_rules! assert_err {
         assert_eq!($predicate.expect_err("Error expected").kind(), &$err);
     };
 }
+
+/// Turns the given collection into a parallel iterator when the `parallel` feature
+/// is enabled, and into a sequential one otherwise.
+#[allow(unused_macros)]
+macro_rules! cfg_into_iter {
+    ($e:expr) => {{
+        #[cfg(feature = "parallel")]
+        let it = $e.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let it = $e.into_iter();
+        it
+    }};
+}
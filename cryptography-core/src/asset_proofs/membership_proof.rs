@@ -4,27 +4,36 @@
 //! This implementation is based on one-out-of-many proof construction described in the following paper
 //! <https://eprint.iacr.org/2015/643.pdf>
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge},
+    one_out_of_many_proof::{
+        convert_to_matrix_rep, Matrix, OOONProver, Polynomial, R1ProverAwaitingChallenge,
+    },
+};
 use crate::{
     asset_proofs::{
         encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-            ZKProofResponse,
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
         },
         errors::{ErrorKind, Fallible},
         one_out_of_many_proof::{
-            convert_to_base, convert_to_matrix_rep, Matrix, OOONProofFinalResponse,
-            OOONProofInitialMessage, OOONProver, OooNProofGenerators, Polynomial, R1ProofVerifier,
-            R1ProverAwaitingChallenge,
+            convert_to_base, OOONProofFinalResponse, OOONProofInitialMessage, OooNProofGenerators,
+            R1ProofVerifier,
         },
         transcript::{TranscriptProtocol, UpdateTranscript},
     },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder},
 };
 use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
-use merlin::{Transcript, TranscriptRng};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroizing;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
@@ -37,6 +46,7 @@ const MEMBERSHIP_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathMembershipProofChallen
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MembershipProofInitialMessage {
     ooon_proof_initial_message: OOONProofInitialMessage,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     secret_element_comm: RistrettoPoint,
     elements_set_size: u32,
 }
@@ -92,6 +102,7 @@ pub struct MembershipProofFinalResponse {
 pub type MembershipProof =
     ZKProofResponse<MembershipProofInitialMessage, MembershipProofFinalResponse>;
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone, Debug)]
 pub struct MembershipProver {
     ooon_prover: OOONProver,
@@ -99,6 +110,7 @@ pub struct MembershipProver {
 /// The prover awaiting challenge will be initialized by the commitment witness data, which is the
 /// committed secret and the blinding factor, and will keep a reference to the public set of elements,
 /// to which the committed secret provably belongs to.
+#[cfg(not(feature = "verify-only"))]
 pub struct MembershipProverAwaitingChallenge<'a> {
     /// The committed secret element.
     pub secret_element: Zeroizing<Scalar>,
@@ -116,6 +128,7 @@ pub struct MembershipProverAwaitingChallenge<'a> {
     pub exp: u32,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> MembershipProverAwaitingChallenge<'a> {
     pub fn new(
         secret_element: Scalar,
@@ -145,6 +158,7 @@ impl<'a> MembershipProverAwaitingChallenge<'a> {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for MembershipProverAwaitingChallenge<'a> {
     type ZKInitialMessage = MembershipProofInitialMessage;
     type ZKFinalResponse = MembershipProofFinalResponse;
@@ -259,6 +273,7 @@ impl<'a> AssetProofProverAwaitingChallenge for MembershipProverAwaitingChallenge
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<MembershipProofFinalResponse> for MembershipProver {
     fn apply_challenge(&self, c: &ZKPChallenge) -> MembershipProofFinalResponse {
         let ooon_proof_final_response = self.ooon_prover.apply_challenge(c);
@@ -275,16 +290,15 @@ pub struct MembershipProofVerifier<'a> {
     pub generators: &'a OooNProofGenerators,
 }
 
-impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
-    type ZKInitialMessage = MembershipProofInitialMessage;
-    type ZKFinalResponse = MembershipProofFinalResponse;
-
-    fn verify(
+impl<'a> MembershipProofVerifier<'a> {
+    /// Returns the equation of the second check of the proof, after validating
+    /// the proof's set parameters against the verifier's elements set.
+    fn elements_equation(
         &self,
         c: &ZKPChallenge,
-        initial_message: &Self::ZKInitialMessage,
-        final_response: &Self::ZKFinalResponse,
-    ) -> Fallible<()> {
+        initial_message: &MembershipProofInitialMessage,
+        final_response: &MembershipProofFinalResponse,
+    ) -> Fallible<VerificationEquation> {
         let m = initial_message.ooon_proof_initial_message.m as usize;
         let n = initial_message.ooon_proof_initial_message.n as usize;
         let exp = u32::try_from(m).map_err(|_| ErrorKind::InvalidExponentParameter)?;
@@ -300,32 +314,16 @@ impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
 
         let initial_size = min(element_set_size, size);
         ensure!(initial_size != 0, ErrorKind::EmptyElementsSet);
-        let b_comm = initial_message
-            .ooon_proof_initial_message
-            .r1_proof_initial_message
-            .b();
-        let r1_verifier = R1ProofVerifier {
-            b: b_comm,
-            generators: self.generators,
-        };
-
-        r1_verifier
-            .verify(
-                c,
-                &initial_message
-                    .ooon_proof_initial_message
-                    .r1_proof_initial_message,
-                &final_response
-                    .ooon_proof_final_response
-                    .r1_proof_final_response(),
-            )
-            .map_err(|_| ErrorKind::MembershipProofVerificationError { check: 1 })?;
 
         let mut f_values = vec![*c.x(); m * n];
         let proof_f_elements = &final_response
             .ooon_proof_final_response
             .r1_proof_final_response()
             .f_elements();
+        ensure!(
+            n > 0 && proof_f_elements.len() == m * (n - 1),
+            ErrorKind::MembershipProofVerificationError { check: 1 }
+        );
 
         for i in 0..m {
             for j in 1..n {
@@ -336,8 +334,6 @@ impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
         }
 
         let mut p_i: Scalar;
-        let right =
-            final_response.ooon_proof_final_response.z() * self.generators.com_gens.B_blinding;
 
         let mut sum1 = Scalar::zero();
         let mut sum2 = Scalar::zero();
@@ -360,7 +356,7 @@ impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
         // This padding operation can be directly incorporated into the computation
         // of the aggregated value `sum2`.
 
-        // The code snippet within the lines 316-321 duplicates the snippet from 299-304 and obviously we
+        // The code snippet below duplicates the loop above and obviously we
         // could have avoided this by simply checking if `i > initial_size` during the `sum2` aggregation,
         // but that would require making the `if` checks for all `i in initial_size..size`
         // which would be more inefficient approach.
@@ -378,20 +374,89 @@ impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
             }
         }
 
-        let mut left = sum1 * self.secret_element_com - sum2 * self.generators.com_gens.B;
+        let mut equation = VerificationEquation::new()
+            .term(sum1, self.secret_element_com)
+            .term(-sum2, self.generators.com_gens.B)
+            .term(
+                -final_response.ooon_proof_final_response.z(),
+                self.generators.com_gens.B_blinding,
+            );
         let mut temp = Scalar::one();
         for k in 0..m {
-            left -= temp * initial_message.ooon_proof_initial_message.g_vec[k];
+            equation = equation.term(-temp, initial_message.ooon_proof_initial_message.g_vec[k]);
             temp *= c.x();
         }
 
+        Ok(equation)
+    }
+
+    fn r1_verifier(&self, initial_message: &MembershipProofInitialMessage) -> R1ProofVerifier {
+        R1ProofVerifier {
+            b: initial_message
+                .ooon_proof_initial_message
+                .r1_proof_initial_message
+                .b(),
+            generators: self.generators,
+        }
+    }
+}
+
+impl<'a> AssetProofVerifier for MembershipProofVerifier<'a> {
+    type ZKInitialMessage = MembershipProofInitialMessage;
+    type ZKFinalResponse = MembershipProofFinalResponse;
+
+    fn verify(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let equation = self.elements_equation(c, initial_message, final_response)?;
+
+        self.r1_verifier(initial_message)
+            .verify(
+                c,
+                &initial_message
+                    .ooon_proof_initial_message
+                    .r1_proof_initial_message,
+                &final_response
+                    .ooon_proof_final_response
+                    .r1_proof_final_response(),
+            )
+            .map_err(|_| ErrorKind::MembershipProofVerificationError { check: 1 })?;
+
         ensure!(
-            left == right,
+            equation.holds(),
             ErrorKind::MembershipProofVerificationError { check: 2 }
         );
 
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let equation = self.elements_equation(c, initial_message, final_response)?;
+
+        let mut equations = self
+            .r1_verifier(initial_message)
+            .verification_equations(
+                c,
+                &initial_message
+                    .ooon_proof_initial_message
+                    .r1_proof_initial_message,
+                &final_response
+                    .ooon_proof_final_response
+                    .r1_proof_final_response(),
+            )
+            .map_err(|_| ErrorKind::MembershipProofVerificationError { check: 1 })?;
+        equations.push(equation);
+
+        Ok(equations)
+    }
 }
 
 #[cfg(test)]
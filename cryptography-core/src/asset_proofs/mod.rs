@@ -210,46 +210,65 @@ pub mod elgamal_encryption;
 pub use elgamal_encryption::{CipherText, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey};
 pub mod const_time_elgamal_encryption;
 pub use const_time_elgamal_encryption::CipherTextWithHint;
+pub mod discrete_log;
+pub use discrete_log::DiscreteLogTable;
+pub mod threshold_elgamal;
+pub use threshold_elgamal::{ThresholdPublicKey, ThresholdSecretKeyShare};
 
+pub mod batch_verifier;
+pub use batch_verifier::BatchVerifier;
 pub mod encryption_proofs;
 
+pub mod ciphertext_reencryption_proof;
 pub mod ciphertext_refreshment_proof;
+pub mod composed_proofs;
 pub mod correctness_proof;
+pub mod decryption_proof;
 pub mod encrypting_same_value_proof;
+pub mod key_derivation;
+pub use key_derivation::{DerivationPath, ExtendedSecretKey};
 pub mod membership_proof;
+pub mod non_membership_proof;
 pub mod one_out_of_many_proof;
 pub mod range_proof;
+pub mod signature;
+pub use signature::Signature;
 pub mod transcript;
 pub mod wellformedness_proof;
 pub use bulletproofs;
 
 /// The balance value to keep confidential.
 ///
-/// Since Elgamal decryption involves searching the entire
-/// space of possible values, the decryption time doubles for
-/// every extra bit of the value size. We have limited
-/// the size of the balance to 32 bits, but even that is very costly.
-/// To experiment with runtimes for different ranges use the
+/// Since Elgamal decryption involves solving a discrete log over
+/// the space of possible values, the decryption time grows with
+/// the value size. We use a baby-step giant-step solver (see
+/// `discrete_log`) whose runtime grows with the square root of
+/// the range, for a memory cost of the same order. Applications
+/// that decrypt often can precompute a larger `DiscreteLogTable`
+/// and pass it to `ElgamalSecretKey::decrypt_with_table`.
+/// To experiment with runtimes for different table sizes use the
 /// benchmarking tool in this repo.
 ///
-/// Possible remedies are:
-/// #0 limit the range even further since confidential values
-///     in the context of Polymesh could be limited.
-/// #1 use AVX2 instruction sets if available on the target
-///    architectures. Our preliminary investigation using
-///    `curve25519_dalek`'s AVX2 features doesn't show a
-///    significant improvment.
-/// #2 Given the fact that encrypted Elgamal values are mostly used
-///    for zero-knowledge proof generations, it is very likely that
-///    we won't need to decrypt the encrypted values very often.
-///    We can recommend that applications use a different faster
-///    encryption mechanism to store the confidentional values on disk.
-pub type Balance = u32;
-pub const BALANCE_RANGE: u32 = 32;
+/// Given the fact that encrypted Elgamal values are mostly used
+/// for zero-knowledge proof generations, it is very likely that
+/// we won't need to decrypt the encrypted values very often.
+/// We can recommend that applications use a different faster
+/// encryption mechanism to store the confidentional values on disk.
+///
+/// Balances used to be 32 bits wide. Both widths share the same wire format:
+/// `CipherText` and `CipherTextWithHint` encrypt the full scalar value, and
+/// `InRangeProof` records the bit size it was created for in its `range`
+/// field. Proofs with `range == LEGACY_BALANCE_RANGE` therefore still decode
+/// and verify, and can be told apart from the 64-bit ones.
+pub type Balance = u64;
+pub const BALANCE_RANGE: u32 = 64;
+
+/// The bit size of the balances before they were widened to 64 bits.
+pub const LEGACY_BALANCE_RANGE: u32 = 32;
 
 /// Asset ID length.
 /// Note that MERCAT's asset id corresponds to PolyMesh's asset ticker.
-const ASSET_ID_LEN: usize = 12;
+pub(crate) const ASSET_ID_LEN: usize = 12;
 
 /// The AssetId to keep confidential.
 /// Note that since `id` is effectively an array of 12 bytes and
@@ -262,6 +281,7 @@ const ASSET_ID_LEN: usize = 12;
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[zeroize(drop)]
 pub struct AssetId {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub id: [u8; ASSET_ID_LEN],
 }
 
@@ -0,0 +1,576 @@
+//! Non-membership proofs are zero-knowledge proofs which show that a committed
+//! secret does NOT belong to a given set of public elements, without revealing
+//! any other information about the secret.
+//!
+//! The proof works on the same commitment `C = m * B + r * B_blinding` as the
+//! membership proof, using the commitment generators of `OooNProofGenerators`.
+//! Hence, the same committed asset id can be proven to be a member of the set
+//! of valid assets, and not a member of the set of frozen assets.
+//!
+//! For every element `m_i` of the set, the verifier computes `C_i = C - m_i * B`,
+//! which commits to `m - m_i`. The prover shows that none of the `C_i` commits
+//! to zero, by proving the knowledge of `a_i` and `b_i` such that
+//! ```text
+//! B = a_i * C_i + b_i * B_blinding
+//! ```
+//! where `a_i = 1 / (m - m_i)` and `b_i = -r / (m - m_i)`. If `m` were equal to `m_i`,
+//! then `C_i = r * B_blinding`, and finding such `a_i` and `b_i` would reveal the
+//! discrete log of `B` with respect to `B_blinding`.
+//!
+//! The size of the proof grows linearly with the size of the set.
+//! Unlike the membership proof, the proof must cover the whole set of the
+//! verifier, so elements appended to the set after a proof was generated
+//! invalidate that proof.
+
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge};
+use crate::{
+    asset_proofs::{
+        encryption_proofs::{
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
+        },
+        errors::{ErrorKind, Fallible},
+        one_out_of_many_proof::OooNProofGenerators,
+        transcript::{TranscriptProtocol, UpdateTranscript},
+    },
+    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+};
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
+use zeroize::{Zeroize, Zeroizing};
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::{mem, prelude::*};
+
+pub const NON_MEMBERSHIP_PROOF_LABEL: &[u8] = b"PolymathNonMembershipProofLabel";
+const NON_MEMBERSHIP_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathNonMembershipProofChallengeLabel";
+
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NonMembershipProofInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    a_vec: Vec<RistrettoPoint>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    secret_element_comm: RistrettoPoint,
+}
+
+impl Encode for NonMembershipProofInitialMessage {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        mem::size_of::<u32>() + 32 * self.a_vec.len() // a_vec
+            + RistrettoPointEncoder(&self.secret_element_comm).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let a_vec = self
+            .a_vec
+            .iter()
+            .map(RistrettoPointEncoder)
+            .collect::<Vec<_>>();
+
+        a_vec.encode_to(dest);
+        RistrettoPointEncoder(&self.secret_element_comm).encode_to(dest);
+    }
+}
+
+impl Decode for NonMembershipProofInitialMessage {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let a_vec = <Vec<RistrettoPointDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+        let secret_element_comm = <RistrettoPointDecoder>::decode(input)?.0;
+
+        Ok(NonMembershipProofInitialMessage {
+            a_vec,
+            secret_element_comm,
+        })
+    }
+}
+
+impl UpdateTranscript for NonMembershipProofInitialMessage {
+    fn update_transcript(&self, transcript: &mut Transcript) -> Fallible<()> {
+        transcript.append_domain_separator(NON_MEMBERSHIP_PROOF_CHALLENGE_LABEL);
+        for a in &self.a_vec {
+            transcript.append_validated_point(b"A", &a.compress())?;
+        }
+        transcript.append_validated_point(b"Comm", &self.secret_element_comm.compress())?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NonMembershipProofFinalResponse {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    z_vec: Vec<Scalar>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    y_vec: Vec<Scalar>,
+}
+
+impl Encode for NonMembershipProofFinalResponse {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        mem::size_of::<u32>() + 32 * self.z_vec.len() // z_vec
+            + mem::size_of::<u32>() + 32 * self.y_vec.len() // y_vec
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let z_vec = self.z_vec.iter().map(ScalarEncoder).collect::<Vec<_>>();
+        let y_vec = self.y_vec.iter().map(ScalarEncoder).collect::<Vec<_>>();
+
+        z_vec.encode_to(dest);
+        y_vec.encode_to(dest);
+    }
+}
+
+impl Decode for NonMembershipProofFinalResponse {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let z_vec = <Vec<ScalarDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+        let y_vec = <Vec<ScalarDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+
+        Ok(NonMembershipProofFinalResponse { z_vec, y_vec })
+    }
+}
+
+/// Holds the non-interactive proofs of non-membership.
+pub type NonMembershipProof =
+    ZKProofResponse<NonMembershipProofInitialMessage, NonMembershipProofFinalResponse>;
+
+#[cfg(not(feature = "verify-only"))]
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct NonMembershipProver {
+    /// The inverses of the differences between the secret and the elements.
+    a_values: Vec<Scalar>,
+    /// The blinding factors of the `C_i` commitments, multiplied by `-a_values`.
+    b_values: Vec<Scalar>,
+    /// The randomness generated in the first round.
+    u_values: Vec<Scalar>,
+    /// The randomness generated in the first round.
+    w_values: Vec<Scalar>,
+}
+
+/// The prover awaiting challenge is initialized by the commitment witness data, which is the
+/// committed secret and the blinding factor, and keeps a reference to the public set of elements,
+/// to which the committed secret provably does not belong to.
+#[cfg(not(feature = "verify-only"))]
+pub struct NonMembershipProverAwaitingChallenge<'a> {
+    /// The committed secret element.
+    pub secret_element: Zeroizing<Scalar>,
+    /// The blinding factor used to commit to the secret_message.
+    pub random: Zeroizing<Scalar>,
+    /// Generator points used to construct the commitments.
+    pub generators: &'a OooNProofGenerators,
+    /// The set of elements which the committed secret element does not belong to.
+    pub elements_set: &'a [Scalar],
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> NonMembershipProverAwaitingChallenge<'a> {
+    pub fn new(
+        secret_element: Scalar,
+        random: Scalar,
+        generators: &'a OooNProofGenerators,
+        elements_set: &'a [Scalar],
+    ) -> Fallible<Self> {
+        ensure!(!elements_set.is_empty(), ErrorKind::EmptyElementsSet);
+        ensure!(
+            !elements_set.contains(&secret_element),
+            ErrorKind::NonMembershipProofInvalidAssetError
+        );
+
+        Ok(NonMembershipProverAwaitingChallenge {
+            secret_element: Zeroizing::new(secret_element),
+            random: Zeroizing::new(random),
+            generators,
+            elements_set,
+        })
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofProverAwaitingChallenge for NonMembershipProverAwaitingChallenge<'a> {
+    type ZKInitialMessage = NonMembershipProofInitialMessage;
+    type ZKFinalResponse = NonMembershipProofFinalResponse;
+    type ZKProver = NonMembershipProver;
+
+    fn create_transcript_rng<T: RngCore + CryptoRng>(
+        &self,
+        rng: &mut T,
+        transcript: &Transcript,
+    ) -> TranscriptRng {
+        transcript
+            .build_rng()
+            .rekey_with_witness_bytes(b"secret_element", self.secret_element.as_bytes())
+            .rekey_with_witness_bytes(b"random", self.random.as_bytes())
+            .finalize(rng)
+    }
+
+    fn generate_initial_message(
+        &self,
+        rng: &mut TranscriptRng,
+    ) -> (Self::ZKProver, Self::ZKInitialMessage) {
+        let pc_gens = self.generators.com_gens;
+        let secret_commitment = pc_gens.commit(*self.secret_element, *self.random);
+
+        // a_i = 1 / (m - m_i), the constructor guarantees that m - m_i is not zero.
+        let mut a_values: Vec<Scalar> = self
+            .elements_set
+            .iter()
+            .map(|element| *self.secret_element - element)
+            .collect();
+        Scalar::batch_invert(&mut a_values);
+        let b_values: Vec<Scalar> = a_values.iter().map(|a| -(*self.random) * a).collect();
+
+        let u_values: Vec<Scalar> = (0..a_values.len()).map(|_| Scalar::random(rng)).collect();
+        let w_values: Vec<Scalar> = (0..a_values.len()).map(|_| Scalar::random(rng)).collect();
+
+        let a_vec = self
+            .elements_set
+            .iter()
+            .zip(u_values.iter().zip(w_values.iter()))
+            .map(|(element, (u, w))| {
+                let c_i = secret_commitment - element * pc_gens.B;
+                u * c_i + w * pc_gens.B_blinding
+            })
+            .collect();
+
+        (
+            NonMembershipProver {
+                a_values,
+                b_values,
+                u_values,
+                w_values,
+            },
+            NonMembershipProofInitialMessage {
+                a_vec,
+                secret_element_comm: secret_commitment,
+            },
+        )
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl AssetProofProver<NonMembershipProofFinalResponse> for NonMembershipProver {
+    fn apply_challenge(&self, c: &ZKPChallenge) -> NonMembershipProofFinalResponse {
+        let z_vec = self
+            .u_values
+            .iter()
+            .zip(self.a_values.iter())
+            .map(|(u, a)| u + c.x() * a)
+            .collect();
+        let y_vec = self
+            .w_values
+            .iter()
+            .zip(self.b_values.iter())
+            .map(|(w, b)| w + c.x() * b)
+            .collect();
+
+        NonMembershipProofFinalResponse { z_vec, y_vec }
+    }
+}
+
+pub struct NonMembershipProofVerifier<'a> {
+    pub secret_element_com: RistrettoPoint,
+    pub elements_set: &'a [Scalar],
+    pub generators: &'a OooNProofGenerators,
+}
+
+impl<'a> AssetProofVerifier for NonMembershipProofVerifier<'a> {
+    type ZKInitialMessage = NonMembershipProofInitialMessage;
+    type ZKFinalResponse = NonMembershipProofFinalResponse;
+
+    fn verify(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let equations = self.verification_equations(c, initial_message, final_response)?;
+
+        ensure!(
+            equations.iter().all(VerificationEquation::holds),
+            ErrorKind::NonMembershipProofVerificationError { check: 2 }
+        );
+
+        Ok(())
+    }
+
+    fn verification_equations(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        ensure!(!self.elements_set.is_empty(), ErrorKind::EmptyElementsSet);
+
+        // The proof must be about the verifier's commitment, and cover all the elements.
+        let size = self.elements_set.len();
+        ensure!(
+            initial_message.secret_element_comm == self.secret_element_com
+                && initial_message.a_vec.len() == size
+                && final_response.z_vec.len() == size
+                && final_response.y_vec.len() == size,
+            ErrorKind::NonMembershipProofVerificationError { check: 1 }
+        );
+
+        let pc_gens = self.generators.com_gens;
+        let c = *c.x();
+
+        // z_i * C_i + y_i * B_blinding == A_i + c * B, where C_i = C - m_i * B.
+        Ok(self
+            .elements_set
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let z = final_response.z_vec[i];
+                VerificationEquation::new()
+                    .term(z, self.secret_element_com)
+                    .term(-(z * element) - c, pc_gens.B)
+                    .term(final_response.y_vec[i], pc_gens.B_blinding)
+                    .term(-Scalar::one(), initial_message.a_vec[i])
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::{
+        encryption_proofs::{single_property_prover, single_property_verifier},
+        membership_proof::{MembershipProofVerifier, MembershipProverAwaitingChallenge},
+        BatchVerifier,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [42u8; 32];
+    const BASE: u32 = 4;
+    const EXPONENT: u32 = 3;
+
+    #[test]
+    #[wasm_bindgen_test]
+    /// Tests the whole workflow of non-membership proofs
+    fn test_non_membership_proofs() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let mut transcript = Transcript::new(NON_MEMBERSHIP_PROOF_LABEL);
+
+        let generators = OooNProofGenerators::new(EXPONENT, BASE);
+
+        let even_elements: Vec<Scalar> = (0..20u32).map(|m| Scalar::from(2 * m)).collect();
+        let blinding = Scalar::random(&mut rng);
+        let odd_member = generators.com_gens.commit(Scalar::from(7u32), blinding);
+        let even_member = generators.com_gens.commit(Scalar::from(8u32), blinding);
+
+        let prover = NonMembershipProverAwaitingChallenge::new(
+            Scalar::from(7u32),
+            blinding,
+            &generators,
+            even_elements.as_slice(),
+        )
+        .unwrap();
+
+        let mut transcript_rng = prover.create_transcript_rng(&mut rng, &transcript);
+        let (prover, initial_message) = prover.generate_initial_message(&mut transcript_rng);
+
+        initial_message.update_transcript(&mut transcript).unwrap();
+        let challenge = transcript
+            .scalar_challenge(NON_MEMBERSHIP_PROOF_CHALLENGE_LABEL)
+            .unwrap();
+
+        let final_response = prover.apply_challenge(&challenge);
+
+        // Positive test
+        let verifier = NonMembershipProofVerifier {
+            secret_element_com: odd_member,
+            elements_set: even_elements.as_slice(),
+            generators: &generators,
+        };
+        let result = verifier.verify(&challenge, &initial_message, &final_response);
+        assert!(result.is_ok());
+
+        // Negative tests
+        let verifier = NonMembershipProofVerifier {
+            secret_element_com: even_member,
+            elements_set: even_elements.as_slice(),
+            generators: &generators,
+        };
+        assert_err!(
+            verifier.verify(&challenge, &initial_message, &final_response),
+            ErrorKind::NonMembershipProofVerificationError { check: 1 }
+        );
+
+        let mut bad_initial_message = initial_message.clone();
+        bad_initial_message.secret_element_comm = even_member;
+        assert_err!(
+            verifier.verify(&challenge, &bad_initial_message, &final_response),
+            ErrorKind::NonMembershipProofVerificationError { check: 2 }
+        );
+
+        // A proof does not cover the elements that were appended to the set.
+        let more_elements: Vec<Scalar> = (0..21u32).map(|m| Scalar::from(2 * m)).collect();
+        let verifier = NonMembershipProofVerifier {
+            secret_element_com: odd_member,
+            elements_set: more_elements.as_slice(),
+            generators: &generators,
+        };
+        assert_err!(
+            verifier.verify(&challenge, &initial_message, &final_response),
+            ErrorKind::NonMembershipProofVerificationError { check: 1 }
+        );
+
+        // Testing the attempt of initializing the prover with a member of the set or an empty set.
+        assert_err!(
+            NonMembershipProverAwaitingChallenge::new(
+                Scalar::from(8u32),
+                blinding,
+                &generators,
+                even_elements.as_slice(),
+            ),
+            ErrorKind::NonMembershipProofInvalidAssetError
+        );
+        assert_err!(
+            NonMembershipProverAwaitingChallenge::new(
+                Scalar::from(7u32),
+                blinding,
+                &generators,
+                &[],
+            ),
+            ErrorKind::EmptyElementsSet
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_membership_and_non_membership_of_the_same_commitment() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let generators = OooNProofGenerators::new(EXPONENT, BASE);
+
+        let valid_assets: Vec<Scalar> = (0..50u32).map(Scalar::from).collect();
+        let frozen_assets: Vec<Scalar> = (10..20u32).map(Scalar::from).collect();
+        let blinding = Scalar::random(&mut rng);
+
+        // The asset is valid and not frozen.
+        let asset = Scalar::from(42u32);
+        let asset_commitment = generators.com_gens.commit(asset, blinding);
+
+        let membership_proof = single_property_prover(
+            MembershipProverAwaitingChallenge::new(
+                asset,
+                blinding,
+                &generators,
+                valid_assets.as_slice(),
+                BASE,
+                EXPONENT,
+            )
+            .unwrap(),
+            &mut rng,
+        )
+        .unwrap();
+        let non_membership_proof = single_property_prover(
+            NonMembershipProverAwaitingChallenge::new(
+                asset,
+                blinding,
+                &generators,
+                frozen_assets.as_slice(),
+            )
+            .unwrap(),
+            &mut rng,
+        )
+        .unwrap();
+
+        let membership_verifier = MembershipProofVerifier {
+            secret_element_com: asset_commitment,
+            elements_set: valid_assets.as_slice(),
+            generators: &generators,
+        };
+        let non_membership_verifier = NonMembershipProofVerifier {
+            secret_element_com: asset_commitment,
+            elements_set: frozen_assets.as_slice(),
+            generators: &generators,
+        };
+        assert!(
+            single_property_verifier(&non_membership_verifier, non_membership_proof.clone())
+                .is_ok()
+        );
+
+        let mut batch = BatchVerifier::new();
+        batch.add(&membership_verifier, &membership_proof);
+        batch.add(&non_membership_verifier, &non_membership_proof);
+        assert!(batch.verify().is_ok());
+
+        // The proof can not be claimed for the commitment to a frozen asset.
+        let frozen_commitment = generators.com_gens.commit(Scalar::from(15u32), blinding);
+        let (mut bad_initial_message, final_response) = non_membership_proof.clone();
+        bad_initial_message.secret_element_comm = frozen_commitment;
+        let bad_proof = (bad_initial_message, final_response);
+        let verifier = NonMembershipProofVerifier {
+            secret_element_com: frozen_commitment,
+            elements_set: frozen_assets.as_slice(),
+            generators: &generators,
+        };
+        assert_err!(
+            single_property_verifier(&verifier, bad_proof.clone()),
+            ErrorKind::NonMembershipProofVerificationError { check: 2 }
+        );
+        let mut batch = BatchVerifier::new();
+        batch.add(&non_membership_verifier, &non_membership_proof);
+        batch.add(&verifier, &bad_proof);
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 1 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn serialize_deserialize_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let generators = OooNProofGenerators::new(EXPONENT, BASE);
+        let even_elements: Vec<Scalar> = (0..64u32).map(|m| Scalar::from(2 * m)).collect();
+        let blinding = Scalar::random(&mut rng);
+
+        let prover = NonMembershipProverAwaitingChallenge::new(
+            Scalar::from(9u32),
+            blinding,
+            &generators,
+            even_elements.as_slice(),
+        )
+        .unwrap();
+
+        let (initial_message0, final_response0) = single_property_prover::<
+            StdRng,
+            NonMembershipProverAwaitingChallenge,
+        >(prover, &mut rng)
+        .unwrap();
+
+        let bytes = initial_message0.encode();
+        let mut input = bytes.as_slice();
+        let recovered_initial_message =
+            <NonMembershipProofInitialMessage>::decode(&mut input).unwrap();
+        assert_eq!(recovered_initial_message, initial_message0);
+
+        let bytes = final_response0.encode();
+        let mut input = bytes.as_slice();
+        let recovered_final_response =
+            <NonMembershipProofFinalResponse>::decode(&mut input).unwrap();
+        assert_eq!(recovered_final_response, final_response0);
+    }
+}
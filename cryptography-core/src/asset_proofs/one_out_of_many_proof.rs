@@ -6,11 +6,11 @@
 
 #![allow(non_snake_case)]
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge};
 use crate::{
     asset_proofs::{
-        encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-        },
+        encryption_proofs::{AssetProofVerifier, VerificationEquation, ZKPChallenge},
         errors::{ErrorKind, Fallible},
         transcript::{TranscriptProtocol, UpdateTranscript},
     },
@@ -22,24 +22,41 @@ use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_COMPRESSED, constants::RISTRETTO_BASEPOINT_POINT,
     ristretto::RistrettoPoint, scalar::Scalar, traits::MultiscalarMul,
 };
-use merlin::{Transcript, TranscriptRng};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_512;
-use zeroize::{Zeroize, Zeroizing};
+use zeroize::Zeroize;
+#[cfg(not(feature = "verify-only"))]
+use zeroize::Zeroizing;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use lazy_static::lazy_static;
 use sp_std::{
+    collections::btree_map::BTreeMap,
     mem,
     ops::{Add, Neg, Sub},
     prelude::*,
 };
+use spin::Mutex;
 
 const OOON_PROOF_LABEL: &[u8; 14] = b"PolymathMERCAT";
 const OOON_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathOOONProofChallengeLabel";
 const R1_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathR1ProofChallengeLabel";
 
+lazy_static! {
+    /// The generators that were derived so far, keyed by their `(base, exp)` parameters.
+    /// The generators are never dropped, so that they can be shared by all threads.
+    static ref GENERATORS_CACHE: Mutex<BTreeMap<(u32, u32), &'static OooNProofGenerators>> =
+        Mutex::new(BTreeMap::new());
+}
+
 /// One-out-of-Many Proofs are instantiated with a fixed value `N = n^m`,
 /// `n` and `m` are system parameters which choice has a significant impact on the final proof sizes and performance.
 /// `n` is the fixed base. Usually we will work with base `4`.
@@ -65,6 +82,7 @@ pub(crate) fn convert_to_base(number: usize, base: usize, exp: u32) -> Vec<usize
 /// The number is represented as the given base number `n = n0 *base^0 + n1 *base^1 +...+ n_exp *base^{exp-1}`
 /// The return value is a bit-matrix of size `exp x base` where
 /// in the  `j`-th row there is exactly one 1 at the cell matrix[j][n_j].
+#[cfg(not(feature = "verify-only"))]
 #[inline(always)]
 pub(crate) fn convert_to_matrix_rep(number: usize, base: usize, exp: u32) -> Vec<Scalar> {
     let mut rem: usize;
@@ -114,12 +132,74 @@ impl OooNProofGenerators {
         }
     }
 
+    /// Returns the generators for the given parameters from a process-wide cache.
+    /// The generators are derived on the first call for each `(base, exp)` pair.
+    pub fn cached(base: u32, exp: u32) -> &'static OooNProofGenerators {
+        let cached = GENERATORS_CACHE.lock().get(&(base, exp)).copied();
+        if let Some(generators) = cached {
+            return generators;
+        }
+
+        // Derive the generators without holding the lock. If another thread cached them
+        // in the meantime, its generators are kept and these ones are dropped.
+        let generators = OooNProofGenerators::new(base, exp);
+        *GENERATORS_CACHE
+            .lock()
+            .entry((base, exp))
+            .or_insert_with(|| Box::leak(Box::new(generators)))
+    }
+
     /// Commits to the given vector using the provided blinding randomness.
     pub fn vector_commit(&self, m_vec: &[Scalar], blinding: Scalar) -> RistrettoPoint {
         RistrettoPoint::multiscalar_mul(m_vec, &self.h_vec) + (blinding * self.com_gens.B_blinding)
     }
 }
 
+impl Encode for OooNProofGenerators {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        RistrettoPointEncoder(&self.com_gens.B).size_hint()
+            + RistrettoPointEncoder(&self.com_gens.B_blinding).size_hint()
+            + mem::size_of::<u32>()
+            + 32 * self.h_vec.len() // h_vec
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let h_vec = self
+            .h_vec
+            .iter()
+            .map(RistrettoPointEncoder)
+            .collect::<Vec<_>>();
+
+        RistrettoPointEncoder(&self.com_gens.B).encode_to(dest);
+        RistrettoPointEncoder(&self.com_gens.B_blinding).encode_to(dest);
+        h_vec.encode_to(dest);
+    }
+}
+
+/// Decoded generators are not checked against any `(base, exp)` parameters, so a
+/// tampered table can not be detected. They should only be loaded by provers from
+/// storage they trust. Verifiers must use `OooNProofGenerators::new` or
+/// `OooNProofGenerators::cached` instead.
+impl Decode for OooNProofGenerators {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let b = <RistrettoPointDecoder>::decode(input)?.0;
+        let b_blinding = <RistrettoPointDecoder>::decode(input)?.0;
+        let h_vec = <Vec<RistrettoPointDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+
+        Ok(OooNProofGenerators {
+            com_gens: PedersenGens {
+                B: b,
+                B_blinding: b_blinding,
+            },
+            h_vec,
+        })
+    }
+}
+
 impl Default for OooNProofGenerators {
     fn default() -> Self {
         Self::new(4, 3)
@@ -277,9 +357,13 @@ impl Polynomial {
 #[derive(PartialEq, Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct R1ProofInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     b: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     c: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     d: RistrettoPoint,
 }
 impl R1ProofInitialMessage {
@@ -338,8 +422,11 @@ impl UpdateTranscript for R1ProofInitialMessage {
 #[derive(PartialEq, Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct R1ProofFinalResponse {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     f_elements: Vec<Scalar>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z_a: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z_c: Scalar,
     m: u32,
     n: u32,
@@ -397,6 +484,7 @@ impl R1ProofFinalResponse {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone, Debug, Zeroize)]
 pub struct R1Prover {
     // Implement a getter instead of making this public
@@ -409,6 +497,7 @@ pub struct R1Prover {
     pub m: u32,
     pub n: u32,
 }
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone)]
 pub struct R1ProverAwaitingChallenge<'a> {
     /// The bit-value matrix, where each row contains only one 1
@@ -427,6 +516,7 @@ pub struct R1ProverAwaitingChallenge<'a> {
     pub n: u32,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for R1ProverAwaitingChallenge<'a> {
     type ZKInitialMessage = R1ProofInitialMessage;
     type ZKFinalResponse = R1ProofFinalResponse;
@@ -500,6 +590,7 @@ impl<'a> AssetProofProverAwaitingChallenge for R1ProverAwaitingChallenge<'a> {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<R1ProofFinalResponse> for R1Prover {
     fn apply_challenge(&self, c: &ZKPChallenge) -> R1ProofFinalResponse {
         let mut f_values: Vec<Scalar> = Vec::with_capacity((self.m * (self.n - 1)) as usize);
@@ -529,24 +620,19 @@ pub struct R1ProofVerifier<'a> {
     pub(crate) generators: &'a OooNProofGenerators,
 }
 
-impl<'a> AssetProofVerifier for R1ProofVerifier<'a> {
-    type ZKInitialMessage = R1ProofInitialMessage;
-    type ZKFinalResponse = R1ProofFinalResponse;
-
-    fn verify(
+impl<'a> R1ProofVerifier<'a> {
+    /// Returns the elements of the `f` matrix and of the `f * (x - f)` matrix.
+    fn f_matrices(
         &self,
         c: &ZKPChallenge,
-        initial_message: &Self::ZKInitialMessage,
-        final_response: &Self::ZKFinalResponse,
-    ) -> Fallible<()> {
+        final_response: &R1ProofFinalResponse,
+    ) -> (Vec<Scalar>, Vec<Scalar>) {
         let rows = final_response.m as usize;
         let columns = final_response.n as usize;
 
         let mut f_matrix = Matrix::new(rows, columns, *c.x());
         let x_matrix = Matrix::new(rows, columns, *c.x());
 
-        let generators = self.generators;
-
         // Here we set f[j][0] = x - (f[j][1]+ ... + f[j][columns - 1])
         for i in 0..rows {
             for j in 1..columns {
@@ -557,14 +643,29 @@ impl<'a> AssetProofVerifier for R1ProofVerifier<'a> {
             }
         }
 
-        let com_f = generators.vector_commit(&f_matrix.elements, final_response.z_a);
-        let com_fx = generators.vector_commit(
-            &f_matrix
-                .entrywise_product(&(&x_matrix - &f_matrix))
-                .unwrap()
-                .elements,
-            final_response.z_c,
-        );
+        let fx_matrix = f_matrix
+            .entrywise_product(&(&x_matrix - &f_matrix))
+            .unwrap();
+
+        (f_matrix.elements, fx_matrix.elements)
+    }
+}
+
+impl<'a> AssetProofVerifier for R1ProofVerifier<'a> {
+    type ZKInitialMessage = R1ProofInitialMessage;
+    type ZKFinalResponse = R1ProofFinalResponse;
+
+    fn verify(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let generators = self.generators;
+        let (f_elements, fx_elements) = self.f_matrices(c, final_response);
+
+        let com_f = generators.vector_commit(&f_elements, final_response.z_a);
+        let com_fx = generators.vector_commit(&fx_elements, final_response.z_c);
 
         ensure!(
             c.x() * self.b + initial_message.a == com_f,
@@ -578,12 +679,46 @@ impl<'a> AssetProofVerifier for R1ProofVerifier<'a> {
 
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let generators = self.generators;
+        let (f_elements, fx_elements) = self.f_matrices(c, final_response);
+        ensure!(
+            f_elements.len() == generators.h_vec.len(),
+            ErrorKind::R1FinalResponseVerificationError { check: 1 }
+        );
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(*c.x(), self.b)
+                .term(Scalar::one(), initial_message.a)
+                .terms(
+                    f_elements.into_iter().map(Neg::neg),
+                    generators.h_vec.iter().cloned(),
+                )
+                .term(-final_response.z_a, generators.com_gens.B_blinding),
+            VerificationEquation::new()
+                .term(*c.x(), initial_message.c)
+                .term(Scalar::one(), initial_message.d)
+                .terms(
+                    fx_elements.into_iter().map(Neg::neg),
+                    generators.h_vec.iter().cloned(),
+                )
+                .term(-final_response.z_c, generators.com_gens.B_blinding),
+        ])
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OOONProofInitialMessage {
     pub(crate) r1_proof_initial_message: R1ProofInitialMessage,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub(crate) g_vec: Vec<RistrettoPoint>,
     pub(crate) n: u32,
     pub(crate) m: u32,
@@ -666,6 +801,7 @@ impl UpdateTranscript for OOONProofInitialMessage {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OOONProofFinalResponse {
     r1_proof_final_response: R1ProofFinalResponse,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z: Scalar,
     m: u32,
     n: u32,
@@ -719,6 +855,7 @@ impl Decode for OOONProofFinalResponse {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone, Debug, Zeroize)]
 pub struct OOONProver {
     pub(crate) rho_values: Vec<Scalar>,
@@ -730,6 +867,7 @@ pub struct OOONProver {
 /// Given the public list of commitments `C_0, C_1, ..., C_{N-1} where N = base^exp, the prover wants to
 /// prove the knowledge of a secret commitment C_l  which is opening to 0.
 /// The prover witness is comprised of the secret_index `l` and the commitment's random factor `random`
+#[cfg(not(feature = "verify-only"))]
 pub struct OOONProverAwaitingChallenge<'a> {
     /// The index of the secret commitment in the given list, which is opening to zero and is blinded by "random"
     pub secret_index: u32,
@@ -742,6 +880,7 @@ pub struct OOONProverAwaitingChallenge<'a> {
     pub exp: u32,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for OOONProverAwaitingChallenge<'a> {
     type ZKInitialMessage = OOONProofInitialMessage;
     type ZKFinalResponse = OOONProofFinalResponse;
@@ -797,24 +936,28 @@ impl<'a> AssetProofProverAwaitingChallenge for OOONProverAwaitingChallenge<'a> {
         let (r1_prover, r1_initial_message) = r1_prover.generate_initial_message(rng);
 
         let one = Polynomial::new(self.exp as usize);
-        let mut polynomials: Vec<Polynomial> = Vec::with_capacity(n);
-
-        for i in 0..n {
-            polynomials.push(one.clone());
-            let i_rep = convert_to_base(i, self.base as usize, exp);
-            for (k, item) in i_rep.iter().enumerate().take(self.exp as usize) {
-                let t = k * self.base as usize + item;
-                polynomials[i].add_factor(l_bit_matrix[t], r1_prover.a_values[t]);
-            }
-        }
+        let a_values = &r1_prover.a_values;
+        let polynomials: Vec<Polynomial> = cfg_into_iter!(0..n)
+            .map(|i| {
+                let mut polynomial = one.clone();
+                let i_rep = convert_to_base(i, self.base as usize, exp);
+                for (k, item) in i_rep.iter().enumerate().take(self.exp as usize) {
+                    let t = k * self.base as usize + item;
+                    polynomial.add_factor(l_bit_matrix[t], a_values[t]);
+                }
+                polynomial
+            })
+            .collect();
 
-        let mut G_values: Vec<RistrettoPoint> = Vec::with_capacity(self.exp as usize);
-        for k in 0..self.exp as usize {
-            G_values.push(rho[k] * generators.com_gens.B_blinding);
-            for (i, polynomial) in polynomials.iter().enumerate().take(n) {
-                G_values[k] += (polynomial.coeffs[k]) * self.commitments[i];
-            }
-        }
+        let G_values: Vec<RistrettoPoint> = cfg_into_iter!(0..self.exp as usize)
+            .map(|k| {
+                rho[k] * generators.com_gens.B_blinding
+                    + RistrettoPoint::multiscalar_mul(
+                        polynomials.iter().map(|polynomial| polynomial.coeffs[k]),
+                        self.commitments,
+                    )
+            })
+            .collect();
 
         (
             OOONProver {
@@ -833,6 +976,7 @@ impl<'a> AssetProofProverAwaitingChallenge for OOONProverAwaitingChallenge<'a> {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<OOONProofFinalResponse> for OOONProver {
     fn apply_challenge(&self, c: &ZKPChallenge) -> OOONProofFinalResponse {
         let r1_final_response = self.r1_prover.apply_challenge(c);
@@ -861,36 +1005,18 @@ pub struct OOONProofVerifier<'a> {
     pub generators: &'a OooNProofGenerators,
 }
 
-impl<'a> AssetProofVerifier for OOONProofVerifier<'a> {
-    type ZKInitialMessage = OOONProofInitialMessage;
-    type ZKFinalResponse = OOONProofFinalResponse;
-
-    fn verify(
+impl<'a> OOONProofVerifier<'a> {
+    /// Returns the equation of the second check of the proof.
+    fn commitments_equation(
         &self,
         c: &ZKPChallenge,
-        initial_message: &Self::ZKInitialMessage,
-        final_response: &Self::ZKFinalResponse,
-    ) -> Fallible<()> {
+        initial_message: &OOONProofInitialMessage,
+        final_response: &OOONProofFinalResponse,
+    ) -> VerificationEquation {
         let size = final_response.n.pow(final_response.m as u32) as usize;
         let m = final_response.m as usize;
         let n = final_response.n as usize;
 
-        let b_comm = initial_message.r1_proof_initial_message.b;
-        let r1_verifier = R1ProofVerifier {
-            b: b_comm,
-            generators: self.generators,
-        };
-
-        let result_r1 = r1_verifier.verify(
-            c,
-            &initial_message.r1_proof_initial_message,
-            &final_response.r1_proof_final_response,
-        );
-        ensure!(
-            result_r1.is_ok(),
-            ErrorKind::OOONFinalResponseVerificationError { check: 1 }
-        );
-
         let mut f_values = vec![*c.x(); m * n];
         let proof_f_elements = &final_response.r1_proof_final_response.f_elements;
 
@@ -901,32 +1027,81 @@ impl<'a> AssetProofVerifier for OOONProofVerifier<'a> {
             }
         }
 
-        let mut p_i: Scalar;
-        let mut left: RistrettoPoint = RistrettoPoint::default();
-        let right = final_response.z * self.generators.com_gens.B_blinding;
+        let p_values: Vec<Scalar> = cfg_into_iter!(0..size)
+            .map(|i| {
+                let i_rep = convert_to_base(i, n, m as u32);
+                (0..m).fold(Scalar::one(), |p_i, j| p_i * f_values[j * n + i_rep[j]])
+            })
+            .collect();
 
-        for i in 0..size {
-            p_i = Scalar::one();
-            let i_rep = convert_to_base(i, n, m as u32);
-            for j in 0..m {
-                p_i *= f_values[j * n + i_rep[j]];
-            }
-            left += p_i * self.commitments[i];
-        }
+        let mut equation = VerificationEquation::new()
+            .term(-final_response.z, self.generators.com_gens.B_blinding)
+            .terms(p_values, self.commitments.iter().take(size).cloned());
 
         let mut temp = Scalar::one();
         for k in 0..m {
-            left -= temp * initial_message.g_vec[k];
+            equation = equation.term(-temp, initial_message.g_vec[k]);
             temp *= c.x();
         }
 
+        equation
+    }
+
+    fn r1_verifier(&self, initial_message: &OOONProofInitialMessage) -> R1ProofVerifier {
+        R1ProofVerifier {
+            b: initial_message.r1_proof_initial_message.b,
+            generators: self.generators,
+        }
+    }
+}
+
+impl<'a> AssetProofVerifier for OOONProofVerifier<'a> {
+    type ZKInitialMessage = OOONProofInitialMessage;
+    type ZKFinalResponse = OOONProofFinalResponse;
+
+    fn verify(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        let result_r1 = self.r1_verifier(initial_message).verify(
+            c,
+            &initial_message.r1_proof_initial_message,
+            &final_response.r1_proof_final_response,
+        );
         ensure!(
-            left == right,
+            result_r1.is_ok(),
+            ErrorKind::OOONFinalResponseVerificationError { check: 1 }
+        );
+
+        ensure!(
+            self.commitments_equation(c, initial_message, final_response)
+                .holds(),
             ErrorKind::OOONFinalResponseVerificationError { check: 2 }
         );
 
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        c: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        final_response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let mut equations = self
+            .r1_verifier(initial_message)
+            .verification_equations(
+                c,
+                &initial_message.r1_proof_initial_message,
+                &final_response.r1_proof_final_response,
+            )
+            .map_err(|_| ErrorKind::OOONFinalResponseVerificationError { check: 1 })?;
+        equations.push(self.commitments_equation(c, initial_message, final_response));
+
+        Ok(equations)
+    }
 }
 
 #[cfg(test)]
@@ -1193,6 +1368,35 @@ mod tests {
         );
     }
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_cached_and_serialized_generators() {
+        const BASE: u32 = 4;
+        const EXPONENT: u32 = 3;
+        let generators = OooNProofGenerators::new(BASE, EXPONENT);
+
+        // The cache derives the same generators, only once per parameters.
+        let cached = OooNProofGenerators::cached(BASE, EXPONENT);
+        assert_eq!(cached.h_vec, generators.h_vec);
+        assert!(core::ptr::eq(
+            cached,
+            OooNProofGenerators::cached(BASE, EXPONENT)
+        ));
+        let other = OooNProofGenerators::cached(BASE, EXPONENT + 1);
+        assert_eq!(other.h_vec.len(), (BASE * (EXPONENT + 1)) as usize);
+        assert!(!core::ptr::eq(cached, other));
+
+        // The generators survive the round trip through the codec.
+        let bytes = generators.encode();
+        let recovered = <OooNProofGenerators>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(recovered.h_vec, generators.h_vec);
+        assert_eq!(recovered.com_gens.B, generators.com_gens.B);
+        assert_eq!(
+            recovered.com_gens.B_blinding,
+            generators.com_gens.B_blinding
+        );
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn test_polynomials() {
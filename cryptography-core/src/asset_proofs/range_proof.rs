@@ -3,8 +3,14 @@
 //! plain text. For example proving that the value that was encrypted
 //! is within a range.
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::BALANCE_RANGE;
 use crate::{
-    asset_proofs::errors::{ErrorKind, Fallible},
+    asset_proofs::{
+        errors::{ErrorKind, Fallible},
+        transcript::TranscriptProtocol,
+        Balance, CipherText,
+    },
     codec_wrapper::{
         CompressedRistrettoDecoder, CompressedRistrettoEncoder, RangeProofDencoder,
         RangeProofEncoder,
@@ -13,13 +19,76 @@ use crate::{
 
 use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
-use curve25519_dalek::{ristretto::CompressedRistretto, scalar::Scalar};
-use merlin::Transcript;
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
+use lazy_static::lazy_static;
+use merlin::{Transcript, TranscriptRng};
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use sha3::{
+    digest::{ExtendableOutput, Input as DigestInput, XofReader},
+    Shake256,
+};
+use sp_std::{iter, mem, prelude::*};
 
 const RANGE_PROOF_LABEL: &[u8] = b"PolymathRangeProof";
+const INTERVAL_PROOF_LABEL: &[u8] = b"PolymathIntervalProof";
+
+/// The domain label for the transcript that seeds the verifier randomness.
+const RANGE_PROOF_VERIFIER_LABEL: &[u8] = b"PolymathRangeProofVerifier";
+
+/// The largest supported range (in bits).
+const MAX_RANGE: usize = 64;
+
+/// The largest number of values in an aggregated range proof.
+pub const MAX_AGGREGATION_SIZE: usize = 64;
+
+lazy_static! {
+    /// Generators for Pedersen commitments.
+    static ref PC_GENS: PedersenGens = PedersenGens::default();
+
+    /// Generators for Bulletproofs, valid for proofs up to bitsize 64
+    /// and aggregation size up to 1.
+    static ref BP_GENS: BulletproofGens = BulletproofGens::new(MAX_RANGE, 1);
+
+    /// Generators for Bulletproofs, valid for proofs up to bitsize 64
+    /// and aggregation size up to 2. Used by the interval proofs.
+    static ref BP_GENS_INTERVAL: BulletproofGens = BulletproofGens::new(MAX_RANGE, 2);
+
+    /// Generators for Bulletproofs, valid for proofs up to bitsize 64
+    /// and aggregation size up to `MAX_AGGREGATION_SIZE`. Used by the
+    /// aggregated proofs.
+    static ref BP_GENS_AGGREGATED: BulletproofGens =
+        BulletproofGens::new(MAX_RANGE, MAX_AGGREGATION_SIZE);
+
+    /// The `G` generators of `BP_GENS`, used by the batch verifier.
+    static ref BP_GENS_G: Vec<RistrettoPoint> = bulletproof_generators(b'G');
+
+    /// The `H` generators of `BP_GENS`, used by the batch verifier.
+    static ref BP_GENS_H: Vec<RistrettoPoint> = bulletproof_generators(b'H');
+}
+
+/// Derives the `label` generators of the first party of `BulletproofGens`,
+/// which are not exposed by the bulletproofs crate.
+/// The generators are read from `Shake256("GeneratorsChain" || label || party index)`.
+fn bulletproof_generators(label: u8) -> Vec<RistrettoPoint> {
+    let mut shake = Shake256::default();
+    shake.input(b"GeneratorsChain");
+    shake.input(&[label, 0, 0, 0, 0]);
+    let mut reader = shake.xof_result();
+
+    (0..MAX_RANGE)
+        .map(|_| {
+            let mut uniform_bytes = [0u8; 64];
+            reader.read(&mut uniform_bytes);
+            RistrettoPoint::from_uniform_bytes(&uniform_bytes)
+        })
+        .collect()
+}
 
 // ------------------------------------------------------------------------
 // Range Proof
@@ -33,7 +102,9 @@ pub type RangeProofFinalResponse = RangeProof;
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InRangeProof {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub init: RangeProofInitialMessage,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub response: RangeProofFinalResponse,
     pub range: u32,
 }
@@ -66,10 +137,11 @@ impl Decode for InRangeProof {
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl InRangeProof {
     #[allow(dead_code)]
     pub fn build<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
-        let range = 32;
+        let range = BALANCE_RANGE;
         prove_within_range(0, Scalar::one(), range, rng).expect("This shouldn't happen.")
     }
 }
@@ -77,28 +149,20 @@ impl InRangeProof {
 /// Generate a range proof for a commitment to a secret value.
 /// Range proof commitments are equevalant to the second term (Y)
 /// of the Elgamal encryption.
+#[cfg(not(feature = "verify-only"))]
 pub fn prove_within_range<Rng: RngCore + CryptoRng>(
     secret_value: u64,
     rand_blind: Scalar,
     range: u32,
     rng: &mut Rng,
 ) -> Fallible<InRangeProof> {
-    // Generators for Pedersen commitments.
-    let pc_gens = PedersenGens::default();
-
-    // Generators for Bulletproofs, valid for proofs up to bitsize 64
-    // and aggregation size up to 1.
-    // Note that we are not supporting aggregating more than one value
-    // from a single party into an aggretated proof yet.
-    let bp_gens = BulletproofGens::new(64, 1);
-
     // Transcripts eliminate the need for a dealer by employing
     // the Fiat-Shamir huristic.
     let mut prover_transcript = Transcript::new(RANGE_PROOF_LABEL);
 
     let (proof, commitment) = RangeProof::prove_single_with_rng(
-        &bp_gens,
-        &pc_gens,
+        &BP_GENS,
+        &PC_GENS,
         &mut prover_transcript,
         secret_value,
         &rand_blind,
@@ -114,17 +178,162 @@ pub fn prove_within_range<Rng: RngCore + CryptoRng>(
     })
 }
 
+/// Returns an rng that is seeded only from the proof and its commitments,
+/// so that every verifier of a proof draws the same randomness.
+fn verifier_rng(
+    label: &'static [u8],
+    proof: &RangeProof,
+    commitments: &[CompressedRistretto],
+) -> TranscriptRng {
+    let mut transcript = Transcript::new(RANGE_PROOF_VERIFIER_LABEL);
+    transcript.append_message(b"label", label);
+    transcript.append_message(b"proof", &proof.to_bytes());
+    for commitment in commitments {
+        transcript.append_message(b"V", commitment.as_bytes());
+    }
+    transcript.create_verifier_rng()
+}
+
 /// Verify that a range proof is valid given a commitment to a secret value.
-pub fn verify_within_range<Rng: RngCore + CryptoRng>(
-    proof: &InRangeProof,
+pub fn verify_within_range(proof: &InRangeProof) -> Fallible<()> {
+    let mut rng = verifier_rng(RANGE_PROOF_LABEL, &proof.response, &[proof.init]);
+
+    // Transcripts eliminate the need for a dealer by employing
+    // the Fiat-Shamir huristic.
+    let mut verifier_transcript = Transcript::new(RANGE_PROOF_LABEL);
+
+    proof
+        .response
+        .verify_single_with_rng(
+            &BP_GENS,
+            &PC_GENS,
+            &mut verifier_transcript,
+            &proof.init,
+            proof.range as usize,
+            &mut rng,
+        )
+        .map_err(|_| ErrorKind::VerificationError.into())
+}
+
+// ------------------------------------------------------------------------
+// Aggregated Range Proof
+// ------------------------------------------------------------------------
+
+/// Holds a single non-interactive range proof for several values that are
+/// all within the same range. The size of the proof grows logarithmically
+/// with the number of values, and it is verified in one go.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AggregatedInRangeProof {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub init: Vec<RangeProofInitialMessage>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub response: RangeProofFinalResponse,
+    pub range: u32,
+}
+
+impl Encode for AggregatedInRangeProof {
+    fn size_hint(&self) -> usize {
+        mem::size_of::<u32>() + 32 * self.init.len() // init
+            + RangeProofEncoder(&self.response).size_hint()
+            + self.range.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        let init = self
+            .init
+            .iter()
+            .map(CompressedRistrettoEncoder)
+            .collect::<Vec<_>>();
+
+        init.encode_to(dest);
+        RangeProofEncoder(&self.response).encode_to(dest);
+        self.range.encode_to(dest);
+    }
+}
+
+impl Decode for AggregatedInRangeProof {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let init = <Vec<CompressedRistrettoDecoder>>::decode(input)?
+            .into_iter()
+            .map(|decoder| decoder.0)
+            .collect::<Vec<_>>();
+        let response = <RangeProofDencoder>::decode(input)?.0;
+        let range = <u32>::decode(input)?;
+
+        Ok(AggregatedInRangeProof {
+            init,
+            response,
+            range,
+        })
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl AggregatedInRangeProof {
+    #[allow(dead_code)]
+    pub fn build<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let range = BALANCE_RANGE;
+        prove_multiple(&[0, 0], &[Scalar::one(), Scalar::one()], range, rng)
+            .expect("This shouldn't happen.")
+    }
+}
+
+/// Generate an aggregated range proof for the commitments to several secret
+/// values. The number of values must be a power of two, and at most
+/// `MAX_AGGREGATION_SIZE`.
+/// Similar to `prove_within_range`, each commitment is equivalent to the second
+/// term (Y) of the Elgamal encryption of the corresponding value.
+#[cfg(not(feature = "verify-only"))]
+pub fn prove_multiple<Rng: RngCore + CryptoRng>(
+    secret_values: &[u64],
+    rand_blinds: &[Scalar],
+    range: u32,
     rng: &mut Rng,
+) -> Fallible<AggregatedInRangeProof> {
+    // Transcripts eliminate the need for a dealer by employing
+    // the Fiat-Shamir huristic.
+    let mut prover_transcript = Transcript::new(RANGE_PROOF_LABEL);
+
+    let (proof, commitments) = RangeProof::prove_multiple_with_rng(
+        &BP_GENS_AGGREGATED,
+        &PC_GENS,
+        &mut prover_transcript,
+        secret_values,
+        rand_blinds,
+        range as usize,
+        rng,
+    )
+    .map_err(|source| ErrorKind::ProvingError { source })?;
+
+    Ok(AggregatedInRangeProof {
+        init: commitments,
+        response: proof,
+        range,
+    })
+}
+
+/// Verify that an aggregated range proof is valid for the given commitments to
+/// the secret values, and that these values are within `[0, 2^range)`.
+/// When the number of commitments is not a power of two, the proof is expected
+/// to be padded with extra commitments, which are not checked.
+pub fn verify_multiple(
+    proof: &AggregatedInRangeProof,
+    commitments: &[RistrettoPoint],
+    range: u32,
 ) -> Fallible<()> {
-    // Generators for Pedersen commitments.
-    let pc_gens = PedersenGens::default();
+    // Bind the proof to the commitments and the range it is expected to prove.
+    ensure!(
+        proof.range == range
+            && proof.init.len() == commitments.len().next_power_of_two()
+            && commitments
+                .iter()
+                .zip(&proof.init)
+                .all(|(commitment, init)| commitment.compress() == *init),
+        ErrorKind::VerificationError
+    );
 
-    // Generators for Bulletproofs, valid for proofs up to bitsize 64
-    // and aggregation size up to 1.
-    let bp_gens = BulletproofGens::new(64, 1);
+    let mut rng = verifier_rng(RANGE_PROOF_LABEL, &proof.response, &proof.init);
 
     // Transcripts eliminate the need for a dealer by employing
     // the Fiat-Shamir huristic.
@@ -132,17 +341,418 @@ pub fn verify_within_range<Rng: RngCore + CryptoRng>(
 
     proof
         .response
-        .verify_single_with_rng(
-            &bp_gens,
-            &pc_gens,
+        .verify_multiple_with_rng(
+            &BP_GENS_AGGREGATED,
+            &PC_GENS,
             &mut verifier_transcript,
             &proof.init,
             proof.range as usize,
-            rng,
+            &mut rng,
         )
         .map_err(|_| ErrorKind::VerificationError.into())
 }
 
+// ------------------------------------------------------------------------
+// Interval Range Proof
+// ------------------------------------------------------------------------
+
+/// Holds the non-interactive proof that an encrypted value lies within the
+/// `[min, max]` interval. The interval itself is not part of the proof, the
+/// verifier must supply it.
+///
+/// Given the commitment `Y = value * h + blinding * g` of the Elgamal encryption,
+/// the proof is an aggregated range proof that both `Y - min * h` and
+/// `max * h - Y` commit to values within `[0, 2^range)`, where `range` is the
+/// smallest supported bit size that covers `max - min`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InIntervalProof {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub response: RangeProofFinalResponse,
+    pub range: u32,
+}
+
+impl Encode for InIntervalProof {
+    fn size_hint(&self) -> usize {
+        RangeProofEncoder(&self.response).size_hint() + self.range.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        RangeProofEncoder(&self.response).encode_to(dest);
+        self.range.encode_to(dest);
+    }
+}
+
+impl Decode for InIntervalProof {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let response = <RangeProofDencoder>::decode(input)?.0;
+        let range = <u32>::decode(input)?;
+
+        Ok(InIntervalProof { response, range })
+    }
+}
+
+/// Returns the smallest range (in bits) supported by Bulletproofs that
+/// covers the width of the `[min, max]` interval.
+fn interval_range(min: Balance, max: Balance) -> Fallible<u32> {
+    ensure!(min <= max, ErrorKind::InvalidInterval { min, max });
+    let width_bits = 64 - (max - min).leading_zeros();
+
+    Ok(width_bits.next_power_of_two().max(8))
+}
+
+/// The commitments to `value - min` and `max - value`, derived from the
+/// commitment `y` to the value.
+fn interval_commitments(
+    y: RistrettoPoint,
+    min: Balance,
+    max: Balance,
+) -> Vec<RangeProofInitialMessage> {
+    vec![
+        (y - Scalar::from(min) * PC_GENS.B).compress(),
+        (Scalar::from(max) * PC_GENS.B - y).compress(),
+    ]
+}
+
+/// Generate a proof that a secret value lies within the `[min, max]` interval.
+/// As with `prove_within_range`, the commitment to the secret value is the
+/// second term (Y) of the Elgamal encryption.
+#[cfg(not(feature = "verify-only"))]
+pub fn prove_within_interval<Rng: RngCore + CryptoRng>(
+    secret_value: Balance,
+    rand_blind: Scalar,
+    min: Balance,
+    max: Balance,
+    rng: &mut Rng,
+) -> Fallible<InIntervalProof> {
+    let range = interval_range(min, max)?;
+    ensure!(
+        min <= secret_value && secret_value <= max,
+        ErrorKind::PlainTextRangeError
+    );
+
+    // Transcripts eliminate the need for a dealer by employing
+    // the Fiat-Shamir huristic.
+    let mut prover_transcript = Transcript::new(INTERVAL_PROOF_LABEL);
+
+    let (proof, _) = RangeProof::prove_multiple_with_rng(
+        &BP_GENS_INTERVAL,
+        &PC_GENS,
+        &mut prover_transcript,
+        &[secret_value - min, max - secret_value],
+        &[rand_blind, -rand_blind],
+        range as usize,
+        rng,
+    )
+    .map_err(|source| ErrorKind::ProvingError { source })?;
+
+    Ok(InIntervalProof {
+        response: proof,
+        range,
+    })
+}
+
+/// Verify that the value encrypted by `cipher` lies within the `[min, max]` interval.
+pub fn verify_within_interval(
+    proof: &InIntervalProof,
+    cipher: &CipherText,
+    min: Balance,
+    max: Balance,
+) -> Fallible<()> {
+    // The range must match the interval, or a wider range would let
+    // values outside of the interval through.
+    ensure!(
+        proof.range == interval_range(min, max)?,
+        ErrorKind::VerificationError
+    );
+
+    let commitments = interval_commitments(cipher.y, min, max);
+    let mut rng = verifier_rng(INTERVAL_PROOF_LABEL, &proof.response, &commitments);
+
+    // Transcripts eliminate the need for a dealer by employing
+    // the Fiat-Shamir huristic.
+    let mut verifier_transcript = Transcript::new(INTERVAL_PROOF_LABEL);
+
+    proof
+        .response
+        .verify_multiple_with_rng(
+            &BP_GENS_INTERVAL,
+            &PC_GENS,
+            &mut verifier_transcript,
+            &commitments,
+            proof.range as usize,
+            &mut rng,
+        )
+        .map_err(|_| ErrorKind::VerificationError.into())
+}
+
+// ------------------------------------------------------------------------
+// Batch Range Proof Verification
+// ------------------------------------------------------------------------
+
+/// The parts of a `RangeProof` that show up in its verification equation.
+/// See `RangeProof::to_bytes` for their layout.
+struct RangeProofParts {
+    a: CompressedRistretto,
+    s: CompressedRistretto,
+    t_1: CompressedRistretto,
+    t_2: CompressedRistretto,
+    t_x: Scalar,
+    t_x_blinding: Scalar,
+    e_blinding: Scalar,
+    l_vec: Vec<CompressedRistretto>,
+    r_vec: Vec<CompressedRistretto>,
+    ipp_a: Scalar,
+    ipp_b: Scalar,
+}
+
+impl RangeProofParts {
+    fn new(proof: &RangeProof) -> Fallible<Self> {
+        let bytes = proof.to_bytes();
+        let chunks = bytes
+            .chunks(32)
+            .map(|chunk| {
+                let mut buf = [0u8; 32];
+                buf.copy_from_slice(chunk);
+                buf
+            })
+            .collect::<Vec<_>>();
+        // 4 points and 3 scalars, followed by the `(L, R)` pairs and the 2 scalars
+        // of the inner product proof.
+        ensure!(
+            bytes.len() % 32 == 0 && chunks.len() >= 9 && chunks.len() % 2 == 1,
+            ErrorKind::VerificationError
+        );
+        let scalar = |chunk: [u8; 32]| -> Fallible<Scalar> {
+            Scalar::from_canonical_bytes(chunk).ok_or_else(|| ErrorKind::VerificationError.into())
+        };
+        let ipp_len = chunks.len() - 2;
+
+        Ok(RangeProofParts {
+            a: CompressedRistretto(chunks[0]),
+            s: CompressedRistretto(chunks[1]),
+            t_1: CompressedRistretto(chunks[2]),
+            t_2: CompressedRistretto(chunks[3]),
+            t_x: scalar(chunks[4])?,
+            t_x_blinding: scalar(chunks[5])?,
+            e_blinding: scalar(chunks[6])?,
+            l_vec: chunks[7..ipp_len]
+                .iter()
+                .step_by(2)
+                .map(|chunk| CompressedRistretto(*chunk))
+                .collect(),
+            r_vec: chunks[8..ipp_len]
+                .iter()
+                .step_by(2)
+                .map(|chunk| CompressedRistretto(*chunk))
+                .collect(),
+            ipp_a: scalar(chunks[ipp_len])?,
+            ipp_b: scalar(chunks[ipp_len + 1])?,
+        })
+    }
+}
+
+/// The terms of the multiscalar multiplication that checks a batch of range proofs.
+/// The generators are shared by all proofs, so their scalars are accumulated.
+struct RangeProofBatch {
+    scalars: Vec<Scalar>,
+    points: Vec<CompressedRistretto>,
+    b_scalar: Scalar,
+    b_blinding_scalar: Scalar,
+    g_scalars: Vec<Scalar>,
+    h_scalars: Vec<Scalar>,
+}
+
+impl RangeProofBatch {
+    fn new() -> Self {
+        RangeProofBatch {
+            scalars: Vec::new(),
+            points: Vec::new(),
+            b_scalar: Scalar::zero(),
+            b_blinding_scalar: Scalar::zero(),
+            g_scalars: vec![Scalar::zero(); MAX_RANGE],
+            h_scalars: vec![Scalar::zero(); MAX_RANGE],
+        }
+    }
+
+    /// Replays the transcript of the proof to recover its challenges, and adds
+    /// its verification equation, scaled by a random weight, to the batch.
+    /// This mirrors `RangeProof::verify_single_with_rng`, and is cross-checked
+    /// against it on tampered proofs of every supported range in the tests.
+    fn add<R: RngCore + CryptoRng>(&mut self, proof: &InRangeProof, rng: &mut R) -> Fallible<()> {
+        let n = proof.range as usize;
+        ensure!(
+            n == 8 || n == 16 || n == 32 || n == 64,
+            ErrorKind::VerificationError
+        );
+        let parts = RangeProofParts::new(&proof.response)?;
+        let lg_n = parts.l_vec.len();
+        ensure!(n == 1 << lg_n, ErrorKind::VerificationError);
+
+        let mut transcript = Transcript::new(RANGE_PROOF_LABEL);
+        transcript.append_domain_separator(b"rangeproof v1");
+        transcript.append_u64(b"n", n as u64);
+        transcript.append_u64(b"m", 1);
+        transcript.append_message(b"V", proof.init.as_bytes());
+        transcript.append_validated_point(b"A", &parts.a)?;
+        transcript.append_validated_point(b"S", &parts.s)?;
+        let y = *transcript.scalar_challenge(b"y")?.x();
+        let z = *transcript.scalar_challenge(b"z")?.x();
+        transcript.append_validated_point(b"T_1", &parts.t_1)?;
+        transcript.append_validated_point(b"T_2", &parts.t_2)?;
+        let x = *transcript.scalar_challenge(b"x")?.x();
+        transcript.append_message(b"t_x", parts.t_x.as_bytes());
+        transcript.append_message(b"t_x_blinding", parts.t_x_blinding.as_bytes());
+        transcript.append_message(b"e_blinding", parts.e_blinding.as_bytes());
+        let w = *transcript.scalar_challenge(b"w")?.x();
+
+        // The challenges of the inner product proof.
+        transcript.append_domain_separator(b"ipp v1");
+        transcript.append_u64(b"n", n as u64);
+        let mut u = Vec::with_capacity(lg_n);
+        for (l, r) in parts.l_vec.iter().zip(parts.r_vec.iter()) {
+            transcript.append_validated_point(b"L", l)?;
+            transcript.append_validated_point(b"R", r)?;
+            u.push(*transcript.scalar_challenge(b"u")?.x());
+        }
+        let mut u_inv = u.clone();
+        let all_inv = Scalar::batch_invert(&mut u_inv);
+        let u_sq = u.iter().map(|u_i| u_i * u_i).collect::<Vec<_>>();
+        let u_inv_sq = u_inv.iter().map(|u_i| u_i * u_i).collect::<Vec<_>>();
+        let mut s = Vec::with_capacity(n);
+        s.push(all_inv);
+        for i in 1..n {
+            let lg_i = (32 - 1 - (i as u32).leading_zeros()) as usize;
+            let k = 1 << lg_i;
+            s.push(s[i - k] * u_sq[(lg_n - 1) - lg_i]);
+        }
+
+        let weight = Scalar::random(rng);
+        let c = Scalar::random(rng);
+        let zz = z * z;
+        let a = parts.ipp_a;
+        let b = parts.ipp_b;
+
+        let y_inv = y.invert();
+        let mut exp_y = Scalar::one();
+        let mut exp_y_inv = Scalar::one();
+        let mut exp_2 = Scalar::one();
+        let mut sum_y = Scalar::zero();
+        for i in 0..n {
+            self.g_scalars[i] += weight * (-z - a * s[i]);
+            self.h_scalars[i] += weight * (z + exp_y_inv * (zz * exp_2 - b * s[n - 1 - i]));
+            sum_y += exp_y;
+            exp_y *= y;
+            exp_y_inv *= y_inv;
+            exp_2 += exp_2;
+        }
+        let sum_2 = exp_2 - Scalar::one();
+        let delta = (z - zz) * sum_y - z * zz * sum_2;
+
+        self.b_scalar += weight * (w * (parts.t_x - a * b) + c * (delta - parts.t_x));
+        self.b_blinding_scalar += weight * (-parts.e_blinding - c * parts.t_x_blinding);
+
+        self.scalars.extend_from_slice(&[
+            weight,
+            weight * x,
+            weight * c * x,
+            weight * c * x * x,
+            weight * c * zz,
+        ]);
+        self.points
+            .extend_from_slice(&[parts.a, parts.s, parts.t_1, parts.t_2, proof.init]);
+        self.scalars
+            .extend(u_sq.iter().chain(u_inv_sq.iter()).map(|u_i| weight * u_i));
+        self.points
+            .extend(parts.l_vec.iter().chain(parts.r_vec.iter()).cloned());
+
+        Ok(())
+    }
+
+    /// Returns true if the sum of all the verification equations holds.
+    fn check(self) -> bool {
+        RistrettoPoint::optional_multiscalar_mul(
+            self.scalars
+                .into_iter()
+                .chain(iter::once(self.b_scalar))
+                .chain(iter::once(self.b_blinding_scalar))
+                .chain(self.g_scalars.into_iter())
+                .chain(self.h_scalars.into_iter()),
+            self.points
+                .iter()
+                .map(|point| point.decompress())
+                .chain(iter::once(Some(PC_GENS.B)))
+                .chain(iter::once(Some(PC_GENS.B_blinding)))
+                .chain(BP_GENS_G.iter().map(|point| Some(*point)))
+                .chain(BP_GENS_H.iter().map(|point| Some(*point))),
+        )
+        .map_or(false, |sum| sum.is_identity())
+    }
+}
+
+/// Verifies many range proofs at once.
+///
+/// The verification equations of the proofs are scaled by random weights and
+/// summed up into a single multiscalar multiplication over the cached generators.
+/// The weights are drawn from a transcript of all the proofs of the batch.
+/// If the batch is rejected, the proofs are checked one by one to find the
+/// first invalid proof.
+#[derive(Clone, Debug, Default)]
+pub struct BatchRangeVerifier<'a> {
+    proofs: Vec<&'a InRangeProof>,
+}
+
+impl<'a> BatchRangeVerifier<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a proof to the batch.
+    pub fn push(&mut self, proof: &'a InRangeProof) {
+        self.proofs.push(proof);
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Verifies all the proofs of the batch.
+    /// Returns a `BatchVerificationError` with the index of the first invalid proof
+    /// if the batch is rejected.
+    pub fn verify(&self) -> Fallible<()> {
+        let mut transcript = Transcript::new(RANGE_PROOF_VERIFIER_LABEL);
+        transcript.append_message(b"label", RANGE_PROOF_LABEL);
+        for proof in &self.proofs {
+            transcript.append_u64(b"n", proof.range as u64);
+            transcript.append_message(b"proof", &proof.response.to_bytes());
+            transcript.append_message(b"V", proof.init.as_bytes());
+        }
+        let mut rng = transcript.create_verifier_rng();
+
+        let mut batch = RangeProofBatch::new();
+        for (index, proof) in self.proofs.iter().enumerate() {
+            batch
+                .add(proof, &mut rng)
+                .map_err(|_| ErrorKind::BatchVerificationError { index })?;
+        }
+        if batch.check() {
+            return Ok(());
+        }
+
+        for (index, proof) in self.proofs.iter().enumerate() {
+            ensure!(
+                verify_within_range(proof).is_ok(),
+                ErrorKind::BatchVerificationError { index }
+            );
+        }
+        Err(ErrorKind::VerificationError.into())
+    }
+}
+
 // ------------------------------------------------------------------------
 // Tests
 // ------------------------------------------------------------------------
@@ -171,7 +781,7 @@ mod tests {
         let proof = prove_within_range(secret_value as u64, witness.blinding(), 32, &mut rng)
             .expect("This shouldn't happen.");
         assert_eq!(proof.range, 32);
-        assert!(verify_within_range(&proof, &mut rng).is_ok());
+        assert!(verify_within_range(&proof).is_ok());
 
         // Make sure the second part of the elgamal encryption is the same as the commited value in the range proof.
         assert_eq!(proof.init, cipher.y.compress());
@@ -180,6 +790,272 @@ mod tests {
         let large_secret_value: u64 = u64::from(u32::max_value()) + 3;
         let bad_proof =
             prove_within_range(large_secret_value, witness.blinding(), 32, &mut rng).unwrap();
-        assert!(!verify_within_range(&bad_proof, &mut rng).is_ok());
+        assert!(!verify_within_range(&bad_proof).is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn balance_range_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_value: Balance = u64::from(u32::max_value()) + 3;
+
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (witness, cipher) = elg_pub.encrypt_value(secret_value.into(), &mut rng);
+
+        // Positive test: secret value within range [0, 2^64)
+        let proof =
+            prove_within_range(secret_value, witness.blinding(), BALANCE_RANGE, &mut rng).unwrap();
+        assert_eq!(proof.range, BALANCE_RANGE);
+        assert_eq!(proof.init, cipher.y.compress());
+        assert!(verify_within_range(&proof).is_ok());
+
+        // The range survives the round trip through the codec, so 32-bit and 64-bit
+        // proofs can be told apart.
+        let legacy_proof =
+            prove_within_range(42, witness.blinding(), LEGACY_BALANCE_RANGE, &mut rng).unwrap();
+        for proof in [proof, legacy_proof].iter() {
+            let bytes = proof.encode();
+            let recovered_proof = <InRangeProof>::decode(&mut bytes.as_slice()).unwrap();
+            assert_eq!(recovered_proof.range, proof.range);
+            assert!(verify_within_range(&recovered_proof).is_ok());
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn aggregated_range_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_values: Vec<Balance> = vec![42, u64::from(u32::max_value()) + 3];
+
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        let (witnesses, ciphers): (Vec<_>, Vec<_>) = secret_values
+            .iter()
+            .map(|value| elg_pub.encrypt_value((*value).into(), &mut rng))
+            .unzip();
+        let blindings: Vec<Scalar> = witnesses.iter().map(|w| w.blinding()).collect();
+
+        // Positive test: all secret values within range [0, 2^64)
+        let commitments: Vec<RistrettoPoint> = ciphers.iter().map(|cipher| cipher.y).collect();
+        let proof = prove_multiple(&secret_values, &blindings, BALANCE_RANGE, &mut rng).unwrap();
+        assert_eq!(proof.range, BALANCE_RANGE);
+        assert!(verify_multiple(&proof, &commitments, BALANCE_RANGE).is_ok());
+
+        // Make sure the commitments are the second parts of the elgamal encryptions.
+        for (init, cipher) in proof.init.iter().zip(ciphers.iter()) {
+            assert_eq!(*init, cipher.y.compress());
+        }
+
+        // The proof survives the round trip through the codec.
+        let bytes = proof.encode();
+        let recovered_proof = <AggregatedInRangeProof>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(recovered_proof.init, proof.init);
+        assert!(verify_multiple(&recovered_proof, &commitments, BALANCE_RANGE).is_ok());
+
+        // Negative test: one of the secret values is outside the allowed range.
+        let bad_proof = prove_multiple(&secret_values, &blindings, 32, &mut rng).unwrap();
+        assert!(verify_multiple(&bad_proof, &commitments, 32).is_err());
+
+        // Negative test: the proof is for a different range than the expected one.
+        assert!(verify_multiple(&proof, &commitments, 32).is_err());
+
+        // Negative test: the proof does not hold for other commitments.
+        let mut bad_proof = proof.clone();
+        bad_proof.init.swap(0, 1);
+        assert!(verify_multiple(&bad_proof, &commitments, BALANCE_RANGE).is_err());
+        let swapped_commitments = [commitments[1], commitments[0]];
+        assert!(verify_multiple(&bad_proof, &swapped_commitments, BALANCE_RANGE).is_err());
+
+        // Negative test: the proof does not match the expected commitments.
+        assert!(verify_multiple(&proof, &swapped_commitments, BALANCE_RANGE).is_err());
+        assert!(verify_multiple(&proof, &commitments[..1], BALANCE_RANGE).is_err());
+
+        // Negative test: the number of values must be a power of two.
+        assert!(prove_multiple(&[1, 2, 3], &[Scalar::one(); 3], BALANCE_RANGE, &mut rng).is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn interval_range_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (min, max): (Balance, Balance) = (0, 10_000);
+
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+
+        // Positive tests: values within the interval, including its bounds.
+        for value in [min, 9_999, max].iter() {
+            let (witness, cipher) = elg_pub.encrypt_value((*value).into(), &mut rng);
+            let proof =
+                prove_within_interval(*value, witness.blinding(), min, max, &mut rng).unwrap();
+            assert_eq!(proof.range, 16);
+            assert!(verify_within_interval(&proof, &cipher, min, max).is_ok());
+        }
+
+        // A single value interval and a 64 bit wide interval.
+        for (value, min, max) in [(7, 7, 7), (u64::MAX - 1, 1, u64::MAX)].iter() {
+            let (witness, cipher) = elg_pub.encrypt_value((*value).into(), &mut rng);
+            let proof =
+                prove_within_interval(*value, witness.blinding(), *min, *max, &mut rng).unwrap();
+            assert!(verify_within_interval(&proof, &cipher, *min, *max).is_ok());
+        }
+
+        let value = 5_000;
+        let (witness, cipher) = elg_pub.encrypt_value(value.into(), &mut rng);
+        let proof = prove_within_interval(value, witness.blinding(), min, max, &mut rng).unwrap();
+
+        // The proof survives the round trip through the codec.
+        let bytes = proof.encode();
+        let recovered_proof = <InIntervalProof>::decode(&mut bytes.as_slice()).unwrap();
+        assert!(verify_within_interval(&recovered_proof, &cipher, min, max).is_ok());
+
+        // Negative tests: the proof does not hold for other intervals or cipher texts.
+        assert!(verify_within_interval(&proof, &cipher, min, 4_999).is_err());
+        assert!(verify_within_interval(&proof, &cipher, 5_001, max).is_err());
+        assert!(verify_within_interval(&proof, &cipher, min, u64::MAX).is_err());
+        let (_, other_cipher) = elg_pub.encrypt_value(value.into(), &mut rng);
+        assert!(verify_within_interval(&proof, &other_cipher, min, max).is_err());
+
+        // Negative tests: invalid intervals and values outside of the interval.
+        assert_err!(
+            prove_within_interval(value, witness.blinding(), max, min, &mut rng),
+            ErrorKind::InvalidInterval { min: max, max: min }
+        );
+        assert_err!(
+            prove_within_interval(max + 1, witness.blinding(), min, max, &mut rng),
+            ErrorKind::PlainTextRangeError
+        );
+        assert_err!(
+            verify_within_interval(&proof, &cipher, max, min),
+            ErrorKind::InvalidInterval { min: max, max: min }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn batch_range_proof() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let ranges = [8, 16, 32, BALANCE_RANGE, BALANCE_RANGE];
+        let proofs: Vec<InRangeProof> = ranges
+            .iter()
+            .map(|range| {
+                let value = rng.next_u64() >> (64 - range);
+                prove_within_range(value, Scalar::random(&mut rng), *range, &mut rng).unwrap()
+            })
+            .collect();
+
+        // Positive tests: an empty batch and a batch of valid proofs.
+        assert!(BatchRangeVerifier::new().verify().is_ok());
+        let mut batch = BatchRangeVerifier::new();
+        for proof in proofs.iter() {
+            batch.push(proof);
+        }
+        assert_eq!(batch.len(), ranges.len());
+        assert!(batch.verify().is_ok());
+
+        // Negative test: a secret value outside of the allowed range.
+        let large_secret_value: u64 = u64::from(u32::max_value()) + 3;
+        let bad_proof =
+            prove_within_range(large_secret_value, Scalar::random(&mut rng), 32, &mut rng).unwrap();
+        let mut batch = BatchRangeVerifier::new();
+        for proof in proofs[..2].iter().chain(iter::once(&bad_proof)) {
+            batch.push(proof);
+        }
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 2 }
+        );
+
+        // Negative test: a proof that does not match its commitment.
+        let mut bad_proof = proofs[3].clone();
+        bad_proof.init = proofs[4].init;
+        let mut batch = BatchRangeVerifier::new();
+        batch.push(&proofs[0]);
+        batch.push(&bad_proof);
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 1 }
+        );
+
+        // Negative test: a proof with the wrong range.
+        let mut bad_proof = proofs[0].clone();
+        bad_proof.range = 12;
+        let mut batch = BatchRangeVerifier::new();
+        batch.push(&bad_proof);
+        assert_err!(
+            batch.verify(),
+            ErrorKind::BatchVerificationError { index: 0 }
+        );
+    }
+
+    /// Checks the batched verification equation on its own, without falling
+    /// back to verifying the proofs one by one.
+    fn batch_equation_holds(proofs: &[&InRangeProof]) -> bool {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let mut batch = RangeProofBatch::new();
+        proofs
+            .iter()
+            .all(|proof| batch.add(proof, &mut rng).is_ok())
+            && batch.check()
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn batch_range_proof_agrees_with_single_verification() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let ranges = [8u32, 16, 32, BALANCE_RANGE];
+
+        for range in ranges.iter() {
+            let value = rng.next_u64() >> (64 - range);
+            let proof =
+                prove_within_range(value, Scalar::random(&mut rng), *range, &mut rng).unwrap();
+            let other_proof =
+                prove_within_range(value, Scalar::random(&mut rng), *range, &mut rng).unwrap();
+
+            // The valid proof, and a copy of it with a flipped bit in every 32 byte chunk.
+            let mut candidates = vec![proof.clone()];
+            let bytes = proof.response.to_bytes();
+            for offset in (0..bytes.len()).step_by(32) {
+                let mut tampered = bytes.clone();
+                tampered[offset] ^= 1;
+                if let Ok(response) = RangeProof::from_bytes(&tampered) {
+                    candidates.push(InRangeProof {
+                        response,
+                        ..proof.clone()
+                    });
+                }
+            }
+
+            // The proof with another commitment and with the other supported ranges.
+            candidates.push(InRangeProof {
+                init: other_proof.init,
+                ..proof.clone()
+            });
+            for other_range in ranges.iter().filter(|other_range| *other_range != range) {
+                candidates.push(InRangeProof {
+                    range: *other_range,
+                    ..proof.clone()
+                });
+            }
+
+            // A proof of a value just outside of the range.
+            if *range < 64 {
+                let large_value = 1u64 << range;
+                candidates.push(
+                    prove_within_range(large_value, Scalar::random(&mut rng), *range, &mut rng)
+                        .unwrap(),
+                );
+            }
+
+            // The batch must accept exactly the proofs that verify on their own,
+            // whether alone or together with a valid proof.
+            for candidate in candidates.iter() {
+                let expected = verify_within_range(candidate).is_ok();
+                assert_eq!(batch_equation_holds(&[candidate]), expected);
+                assert_eq!(batch_equation_holds(&[&other_proof, candidate]), expected);
+                assert_eq!(batch_equation_holds(&[candidate, &other_proof]), expected);
+            }
+        }
     }
 }
@@ -0,0 +1,191 @@
+//! Schnorr signatures with keys derived from the Elgamal keys. They let the owner
+//! of a key, such as a mediator, sign a statement without publishing another key,
+//! and without ever signing with the secret key that decrypts their cipher texts.
+//!
+//! Given `pub_key = secret_key * g`, anyone can derive the verification key
+//! `signing_pub_key = t * pub_key`, where `t = H(pub_key)`, while only the owner
+//! knows the signing key `t * secret_key`. The signer picks a random `k` and computes:
+//! ```text
+//! R = k * g
+//! c = H(signing_pub_key, R, message)
+//! s = k + c * t * secret_key
+//! ```
+//! The verifier accepts `(R, s)` if `s * g == R + c * signing_pub_key`.
+
+use crate::{
+    asset_proofs::{
+        errors::{ErrorKind, Fallible},
+        transcript::TranscriptProtocol,
+        ElgamalPublicKey, ElgamalSecretKey,
+    },
+    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+};
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
+use zeroize::Zeroizing;
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+
+/// The domain label for the signatures.
+pub const SIGNATURE_LABEL: &[u8] = b"PolymathElgamalSignature";
+/// The domain label for the challenge.
+pub const SIGNATURE_CHALLENGE_LABEL: &[u8] = b"PolymathElgamalSignatureChallenge";
+/// The domain label for the derivation of the signing keys.
+pub const SIGNING_KEY_LABEL: &[u8] = b"PolymathElgamalSigningKey";
+/// The domain label for the derivation factor of the signing keys.
+pub const SIGNING_KEY_FACTOR_LABEL: &[u8] = b"PolymathElgamalSigningKeyFactor";
+
+/// A Schnorr signature of a message.
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Signature {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    r: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    s: Scalar,
+}
+
+impl Encode for Signature {
+    fn size_hint(&self) -> usize {
+        RistrettoPointEncoder(&self.r).size_hint() + ScalarEncoder(&self.s).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        RistrettoPointEncoder(&self.r).encode_to(dest);
+        ScalarEncoder(&self.s).encode_to(dest);
+    }
+}
+
+impl Decode for Signature {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let r = <RistrettoPointDecoder>::decode(input)?.0;
+        let s = <ScalarDecoder>::decode(input)?.0;
+
+        Ok(Signature { r, s })
+    }
+}
+
+/// Computes the factor `t = H(pub_key)` that derives the signing keys from `pub_key`.
+fn signing_key_factor(pub_key: &ElgamalPublicKey) -> Fallible<Scalar> {
+    let mut transcript = Transcript::new(SIGNING_KEY_LABEL);
+    transcript.append_validated_point(b"pub_key", &pub_key.pub_key.compress())?;
+    Ok(*transcript.scalar_challenge(SIGNING_KEY_FACTOR_LABEL)?.x())
+}
+
+/// Computes the challenge `c = H(signing_pub_key, R, message)`.
+fn signature_challenge(
+    signing_pub_key: &RistrettoPoint,
+    r: &RistrettoPoint,
+    message: &[u8],
+) -> Fallible<Scalar> {
+    let mut transcript = Transcript::new(SIGNATURE_LABEL);
+    transcript.append_validated_point(b"pub_key", &signing_pub_key.compress())?;
+    transcript.append_validated_point(b"R", &r.compress())?;
+    transcript.append_message(b"message", message);
+    Ok(*transcript.scalar_challenge(SIGNATURE_CHALLENGE_LABEL)?.x())
+}
+
+impl ElgamalSecretKey {
+    /// Signs `message` with the signing key derived from this key. The nonce is derived
+    /// from the signing key and the message, as well as from `rng`, so a weak `rng` does
+    /// not leak the key.
+    #[cfg(not(feature = "verify-only"))]
+    pub fn sign<T: RngCore + CryptoRng>(&self, message: &[u8], rng: &mut T) -> Fallible<Signature> {
+        let gens = PedersenGens::default();
+        let pub_key = self.get_public_key();
+        let signing_key = Zeroizing::new(signing_key_factor(&pub_key)? * self.secret);
+        let mut nonce_rng = Transcript::new(SIGNATURE_LABEL)
+            .build_rng()
+            .rekey_with_witness_bytes(b"signing_key", signing_key.as_bytes())
+            .rekey_with_witness_bytes(b"message", message)
+            .finalize(rng);
+        let k = Zeroizing::new(Scalar::random(&mut nonce_rng));
+
+        let r = *k * gens.B_blinding;
+        let c = signature_challenge(&(*signing_key * gens.B_blinding), &r, message)?;
+        Ok(Signature {
+            r,
+            s: *k + c * *signing_key,
+        })
+    }
+}
+
+impl ElgamalPublicKey {
+    /// Verifies that `signature` is a signature of `message` by the owner of this key.
+    pub fn verify_signature(&self, message: &[u8], signature: &Signature) -> Fallible<()> {
+        let gens = PedersenGens::default();
+        let signing_pub_key = signing_key_factor(self)? * self.pub_key;
+        let c = signature_challenge(&signing_pub_key, &signature.r, message)?;
+        ensure!(
+            signature.s * gens.B_blinding == signature.r + c * signing_pub_key,
+            ErrorKind::SignatureVerificationError
+        );
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [42u8; 32];
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_signature() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let pub_key = secret_key.get_public_key();
+        let message = b"reject tx-1";
+
+        let signature = secret_key.sign(message, &mut rng).unwrap();
+        assert!(pub_key.verify_signature(message, &signature).is_ok());
+
+        // The signature survives its encoding.
+        let decoded = Signature::decode(&mut &signature.encode()[..]).unwrap();
+        assert_eq!(decoded, signature);
+
+        // Negative tests.
+        assert_err!(
+            pub_key.verify_signature(b"reject tx-2", &signature),
+            ErrorKind::SignatureVerificationError
+        );
+
+        let other_pub_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+        assert_err!(
+            other_pub_key.verify_signature(message, &signature),
+            ErrorKind::SignatureVerificationError
+        );
+
+        let bad_signature = Signature {
+            r: signature.r,
+            s: signature.s + Scalar::one(),
+        };
+        assert_err!(
+            pub_key.verify_signature(message, &bad_signature),
+            ErrorKind::SignatureVerificationError
+        );
+
+        // The signature is not made with the decryption key itself.
+        let gens = PedersenGens::default();
+        let c = signature_challenge(&pub_key.pub_key, &signature.r, message).unwrap();
+        assert_ne!(
+            signature.s * gens.B_blinding,
+            signature.r + c * pub_key.pub_key
+        );
+    }
+}
@@ -0,0 +1,1421 @@
+//! The `threshold_elgamal` library splits the decryption capability of
+//! a twisted Elgamal key between `n` parties, so that any `t` of them can
+//! jointly decrypt a cipher text, while fewer than `t` learn nothing.
+//!
+//! Since the twisted Elgamal decryption computes `X / secret_key`, the
+//! parties share the inverse of the secret key, `d := 1 / secret_key`.
+//! Decrypting `(X, Y)` then only needs `d * X`, which is linear in `d`.
+//!
+//! # Distributed key generation
+//! The key is generated without a trusted dealer using joint Feldman
+//! secret sharing. Every party `j` acts as a dealer and picks three random
+//! polynomials:
+//! - `a_j` of degree `t - 1`, whose sum `a := sum(a_j)` shares `d = a(0)`,
+//! - `b_j` of degree `t - 1`, whose sum `b := sum(b_j)` shares a random
+//!   mask `k = b(0)`,
+//! - `c_j` of degree `2 * t - 2` with `c_j(0) = 0`, whose sum rerandomizes
+//!   the product below.
+//!
+//! The dealer broadcasts the commitments to the coefficients of these
+//! polynomials and privately sends `(a_j(i), b_j(i), c_j(i))` to the
+//! party `i`. Each party checks its shares against the commitments, sums
+//! them up, and broadcasts its masked product `u_i := a(i) * b(i) + c(i)`.
+//! The masked products lie on a polynomial of degree `2 * t - 2` whose
+//! constant term is `u = d * k`. Anyone can interpolate `u`, and compute the
+//! public key as:
+//! ```text
+//! public_key := (1 / u) * (k * g) = (1 / d) * g = secret_key * g
+//! ```
+//! where `k * g` is the sum of the dealers' commitments to `b_j(0)`.
+//! Interpolating `u` needs `2 * t - 1` masked products, therefore the
+//! number of parties must be at least `2 * t - 1`. When there are more
+//! parties, the additional masked products are checked to lie on the same
+//! polynomial.
+//!
+//! # Threshold decryption
+//! Party `i` holds the share `d_i := a(i)` and its public verification key
+//! is `d_i * g`. To help decrypting `(X, Y)` the party publishes the
+//! decryption share `d_i * X`, along with a proof of equality of the
+//! discrete logs of the verification key and the decryption share.
+//! Given `t` valid decryption shares, their Lagrange interpolation at `0`
+//! is `d * X = random * g`, which is all that is needed to decrypt both the
+//! `CipherText` and the `CipherTextWithHint`.
+
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::encryption_proofs::{
+    single_property_prover, AssetProofProver, AssetProofProverAwaitingChallenge,
+};
+use crate::{
+    asset_proofs::{
+        const_time_elgamal_encryption::{decrypt_with_blinding_point, CipherTextWithHint},
+        discrete_log::DiscreteLogTable,
+        encryption_proofs::{
+            single_property_verifier, AssetProofVerifier, VerificationEquation, ZKPChallenge,
+            ZKProofResponse,
+        },
+        errors::{ErrorKind, Fallible},
+        transcript::{TranscriptProtocol, UpdateTranscript},
+        Balance, CipherText, ElgamalPublicKey,
+    },
+    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+};
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::RistrettoPoint,
+    scalar::Scalar,
+    traits::{Identity, IsIdentity},
+};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::{fmt, mem, prelude::*};
+
+/// The domain label for the partial decryption proof.
+pub const PARTIAL_DECRYPTION_PROOF_FINAL_RESPONSE_LABEL: &[u8] =
+    b"PolymathPartialDecryptionFinalResponse";
+/// The domain label for the challenge.
+pub const PARTIAL_DECRYPTION_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathPartialDecryptionChallenge";
+
+// ------------------------------------------------------------------------
+// Polynomial Helpers
+// ------------------------------------------------------------------------
+
+fn check_parameters(threshold: u32, parties: u32) -> Fallible<()> {
+    ensure!(
+        threshold > 0 && 2 * u64::from(threshold) - 1 <= u64::from(parties),
+        ErrorKind::InvalidThresholdParameters { threshold, parties }
+    );
+    Ok(())
+}
+
+fn check_index(index: u32, parties: u32) -> Fallible<()> {
+    ensure!(
+        index > 0 && index <= parties,
+        ErrorKind::InvalidParticipantIndex { index }
+    );
+    Ok(())
+}
+
+/// Evaluates `sum(coefficients[k] * x^k)`.
+fn evaluate_polynomial(coefficients: &[Scalar], x: Scalar) -> Scalar {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |acc, coefficient| acc * x + coefficient)
+}
+
+/// Evaluates `sum(commitments[k] * x^k)`.
+fn evaluate_commitments(commitments: &[RistrettoPoint], x: Scalar) -> RistrettoPoint {
+    commitments
+        .iter()
+        .rev()
+        .fold(RistrettoPoint::identity(), |acc, commitment| {
+            acc * x + commitment
+        })
+}
+
+/// Returns the Lagrange basis polynomial of `index` over the points `indices`,
+/// evaluated at `at`.
+fn lagrange_coefficient(indices: &[u32], index: u32, at: Scalar) -> Scalar {
+    let x_i = Scalar::from(index);
+    indices
+        .iter()
+        .filter(|&&j| j != index)
+        .fold(Scalar::one(), |acc, &j| {
+            let x_j = Scalar::from(j);
+            acc * (at - x_j) * (x_i - x_j).invert()
+        })
+}
+
+// ------------------------------------------------------------------------
+// Distributed Key Generation
+// ------------------------------------------------------------------------
+
+/// The public commitments of a dealer to its polynomials.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DkgCommitments {
+    pub dealer: u32,
+
+    /// The commitments to the coefficients of the key polynomial.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub secret: Vec<RistrettoPoint>,
+
+    /// The commitments to the coefficients of the mask polynomial.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub mask: Vec<RistrettoPoint>,
+
+    /// The commitments to the coefficients of the zero polynomial, starting
+    /// from the coefficient of degree 1.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub zero: Vec<RistrettoPoint>,
+}
+
+impl DkgCommitments {
+    fn is_well_formed(&self, threshold: u32) -> bool {
+        let degree = threshold as usize;
+        self.secret.len() == degree
+            && self.mask.len() == degree
+            && self.zero.len() == 2 * degree - 2
+    }
+
+    /// Verifies that the shares of a recipient match the dealer's commitments.
+    pub fn verify_share(&self, share: &DkgShare) -> Fallible<()> {
+        let gens = PedersenGens::default();
+        let x = Scalar::from(share.recipient);
+        ensure!(
+            share.dealer == self.dealer
+                && share.secret * gens.B_blinding == evaluate_commitments(&self.secret, x)
+                && share.mask * gens.B_blinding == evaluate_commitments(&self.mask, x)
+                && share.zero * gens.B_blinding == x * evaluate_commitments(&self.zero, x),
+            ErrorKind::DkgShareVerificationError {
+                dealer: share.dealer
+            }
+        );
+        Ok(())
+    }
+}
+
+fn encode_points<W: Output>(points: &[RistrettoPoint], dest: &mut W) {
+    points
+        .iter()
+        .map(RistrettoPointEncoder)
+        .collect::<Vec<_>>()
+        .encode_to(dest);
+}
+
+fn decode_points<I: Input>(input: &mut I) -> Result<Vec<RistrettoPoint>, CodecError> {
+    Ok(<Vec<RistrettoPointDecoder>>::decode(input)?
+        .into_iter()
+        .map(|decoder| decoder.0)
+        .collect())
+}
+
+impl Encode for DkgCommitments {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.dealer.size_hint()
+            + mem::size_of::<u32>() + 32 * self.secret.len() // secret
+            + mem::size_of::<u32>() + 32 * self.mask.len() // mask
+            + mem::size_of::<u32>() + 32 * self.zero.len() // zero
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.dealer.encode_to(dest);
+        encode_points(&self.secret, dest);
+        encode_points(&self.mask, dest);
+        encode_points(&self.zero, dest);
+    }
+}
+
+impl Decode for DkgCommitments {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let dealer = <u32>::decode(input)?;
+        let secret = decode_points(input)?;
+        let mask = decode_points(input)?;
+        let zero = decode_points(input)?;
+
+        Ok(DkgCommitments {
+            dealer,
+            secret,
+            mask,
+            zero,
+        })
+    }
+}
+
+/// The secret shares that a dealer sends privately to a recipient.
+#[derive(Clone, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DkgShare {
+    pub dealer: u32,
+    pub recipient: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    secret: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    mask: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    zero: Scalar,
+}
+
+impl Encode for DkgShare {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.dealer.size_hint()
+            + self.recipient.size_hint()
+            + ScalarEncoder(&self.secret).size_hint()
+            + ScalarEncoder(&self.mask).size_hint()
+            + ScalarEncoder(&self.zero).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.dealer.encode_to(dest);
+        self.recipient.encode_to(dest);
+        ScalarEncoder(&self.secret).encode_to(dest);
+        ScalarEncoder(&self.mask).encode_to(dest);
+        ScalarEncoder(&self.zero).encode_to(dest);
+    }
+}
+
+impl Decode for DkgShare {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let dealer = <u32>::decode(input)?;
+        let recipient = <u32>::decode(input)?;
+        let secret = <ScalarDecoder>::decode(input)?.0;
+        let mask = <ScalarDecoder>::decode(input)?.0;
+        let zero = <ScalarDecoder>::decode(input)?.0;
+
+        Ok(DkgShare {
+            dealer,
+            recipient,
+            secret,
+            mask,
+            zero,
+        })
+    }
+}
+
+/// Prints the dealer and the recipient, but not the shares.
+impl fmt::Debug for DkgShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DkgShare")
+            .field("dealer", &self.dealer)
+            .field("recipient", &self.recipient)
+            .finish()
+    }
+}
+
+/// The dealer role of a party in the first round of the key generation.
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct DkgDealer {
+    index: u32,
+    parties: u32,
+    secret_coefficients: Vec<Scalar>,
+    mask_coefficients: Vec<Scalar>,
+    zero_coefficients: Vec<Scalar>,
+}
+
+impl DkgDealer {
+    /// Picks the random polynomials of the dealer.
+    ///
+    /// # Inputs
+    /// `index`     The index of the dealer, in `[1, parties]`.
+    /// `threshold` The number of parties that are needed to decrypt.
+    /// `parties`   The number of parties, at least `2 * threshold - 1`.
+    /// `rng`       An RNG.
+    #[cfg(not(feature = "verify-only"))]
+    pub fn new<R: RngCore + CryptoRng>(
+        index: u32,
+        threshold: u32,
+        parties: u32,
+        rng: &mut R,
+    ) -> Fallible<Self> {
+        check_parameters(threshold, parties)?;
+        check_index(index, parties)?;
+
+        let degree = threshold as usize;
+        let mut random_coefficients =
+            |count: usize| -> Vec<Scalar> { (0..count).map(|_| Scalar::random(rng)).collect() };
+
+        Ok(DkgDealer {
+            index,
+            parties,
+            secret_coefficients: random_coefficients(degree),
+            mask_coefficients: random_coefficients(degree),
+            zero_coefficients: random_coefficients(2 * degree - 2),
+        })
+    }
+
+    /// Returns the commitments that the dealer broadcasts to all the parties.
+    pub fn commitments(&self) -> DkgCommitments {
+        let gens = PedersenGens::default();
+        let commit = |coefficients: &[Scalar]| -> Vec<RistrettoPoint> {
+            coefficients
+                .iter()
+                .map(|coefficient| coefficient * gens.B_blinding)
+                .collect()
+        };
+
+        DkgCommitments {
+            dealer: self.index,
+            secret: commit(&self.secret_coefficients),
+            mask: commit(&self.mask_coefficients),
+            zero: commit(&self.zero_coefficients),
+        }
+    }
+
+    /// Returns the shares that the dealer sends privately to the `recipient`.
+    pub fn share_for(&self, recipient: u32) -> Fallible<DkgShare> {
+        check_index(recipient, self.parties)?;
+        let x = Scalar::from(recipient);
+
+        Ok(DkgShare {
+            dealer: self.index,
+            recipient,
+            secret: evaluate_polynomial(&self.secret_coefficients, x),
+            mask: evaluate_polynomial(&self.mask_coefficients, x),
+            zero: x * evaluate_polynomial(&self.zero_coefficients, x),
+        })
+    }
+}
+
+/// Checks that there is exactly one well formed set of commitments from
+/// each dealer, and returns them sorted by their dealers.
+fn sorted_commitments(
+    threshold: u32,
+    parties: u32,
+    commitments: &[DkgCommitments],
+) -> Fallible<Vec<&DkgCommitments>> {
+    let mut sorted: Vec<&DkgCommitments> = commitments.iter().collect();
+    sorted.sort_by_key(|commitment| commitment.dealer);
+
+    for dealer in 1..=parties {
+        let commitment = sorted.get(dealer as usize - 1);
+        ensure!(
+            commitment.map_or(false, |commitment| commitment.dealer == dealer
+                && commitment.is_well_formed(threshold)),
+            ErrorKind::DkgCommitmentsError { dealer }
+        );
+    }
+    if let Some(extra) = sorted.get(parties as usize) {
+        return Err(ErrorKind::DkgCommitmentsError {
+            dealer: extra.dealer,
+        }
+        .into());
+    }
+
+    Ok(sorted)
+}
+
+/// The masked product that a party broadcasts in the second round of the
+/// key generation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MaskedProduct {
+    pub index: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub value: Scalar,
+}
+
+impl Encode for MaskedProduct {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.index.size_hint() + ScalarEncoder(&self.value).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.index.encode_to(dest);
+        ScalarEncoder(&self.value).encode_to(dest);
+    }
+}
+
+impl Decode for MaskedProduct {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let index = <u32>::decode(input)?;
+        let value = <ScalarDecoder>::decode(input)?.0;
+
+        Ok(MaskedProduct { index, value })
+    }
+}
+
+/// A party of the key generation, after it has received and verified the
+/// shares of all the dealers.
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct DkgParticipant {
+    index: u32,
+    secret: Scalar,
+    mask: Scalar,
+    zero: Scalar,
+}
+
+impl DkgParticipant {
+    /// Verifies and combines the shares that the party has received.
+    ///
+    /// # Inputs
+    /// `index`       The index of the party, in `[1, parties]`.
+    /// `threshold`   The number of parties that are needed to decrypt.
+    /// `parties`     The number of parties.
+    /// `commitments` The commitments that were broadcast by all the dealers.
+    /// `shares`      The shares that all the dealers sent to this party.
+    pub fn new(
+        index: u32,
+        threshold: u32,
+        parties: u32,
+        commitments: &[DkgCommitments],
+        shares: &[DkgShare],
+    ) -> Fallible<Self> {
+        check_parameters(threshold, parties)?;
+        check_index(index, parties)?;
+        let commitments = sorted_commitments(threshold, parties, commitments)?;
+
+        let mut participant = DkgParticipant {
+            index,
+            secret: Scalar::zero(),
+            mask: Scalar::zero(),
+            zero: Scalar::zero(),
+        };
+        for commitment in commitments {
+            let dealer = commitment.dealer;
+            let mut dealer_shares = shares
+                .iter()
+                .filter(|share| share.dealer == dealer && share.recipient == index);
+            let share = dealer_shares
+                .next()
+                .ok_or(ErrorKind::DkgShareVerificationError { dealer })?;
+            ensure!(
+                dealer_shares.next().is_none(),
+                ErrorKind::DkgShareVerificationError { dealer }
+            );
+            commitment.verify_share(share)?;
+
+            participant.secret += share.secret;
+            participant.mask += share.mask;
+            participant.zero += share.zero;
+        }
+
+        Ok(participant)
+    }
+
+    /// Returns the masked product that the party broadcasts to all the parties.
+    pub fn masked_product(&self) -> MaskedProduct {
+        MaskedProduct {
+            index: self.index,
+            value: self.secret * self.mask + self.zero,
+        }
+    }
+
+    /// Returns the party's share of the decryption key.
+    pub fn key_share(&self) -> ThresholdSecretKeyShare {
+        ThresholdSecretKeyShare {
+            index: self.index,
+            secret: self.secret,
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// Threshold Keys
+// ------------------------------------------------------------------------
+
+/// A party's share of the decryption key.
+#[derive(Clone, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ThresholdSecretKeyShare {
+    index: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    secret: Scalar,
+}
+
+impl Encode for ThresholdSecretKeyShare {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.index.size_hint() + ScalarEncoder(&self.secret).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.index.encode_to(dest);
+        ScalarEncoder(&self.secret).encode_to(dest);
+    }
+}
+
+impl Decode for ThresholdSecretKeyShare {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let index = <u32>::decode(input)?;
+        let secret = <ScalarDecoder>::decode(input)?.0;
+
+        Ok(ThresholdSecretKeyShare { index, secret })
+    }
+}
+
+/// Prints the index, but not the share.
+impl fmt::Debug for ThresholdSecretKeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThresholdSecretKeyShare")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl ThresholdSecretKeyShare {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn verification_key(&self) -> RistrettoPoint {
+        let gens = PedersenGens::default();
+        self.secret * gens.B_blinding
+    }
+
+    /// Computes the party's decryption share of a cipher text, and proves that
+    /// it was computed with the party's share of the decryption key.
+    #[cfg(not(feature = "verify-only"))]
+    pub fn decryption_share<R: RngCore + CryptoRng>(
+        &self,
+        cipher_text: &CipherText,
+        rng: &mut R,
+    ) -> Fallible<DecryptionShare> {
+        let gens = PedersenGens::default();
+        let proof = single_property_prover(
+            PartialDecryptionProverAwaitingChallenge {
+                secret: self.secret,
+                x: cipher_text.x,
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
+
+        Ok(DecryptionShare {
+            index: self.index,
+            share: self.secret * cipher_text.x,
+            proof,
+        })
+    }
+
+    /// Computes the party's decryption share of a constant time cipher text.
+    #[cfg(not(feature = "verify-only"))]
+    pub fn const_time_decryption_share<R: RngCore + CryptoRng>(
+        &self,
+        cipher_text: &CipherTextWithHint,
+        rng: &mut R,
+    ) -> Fallible<DecryptionShare> {
+        self.decryption_share(&cipher_text.elgamal_cipher, rng)
+    }
+}
+
+/// The public key of a threshold key, along with the verification keys of
+/// the parties' decryption shares.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ThresholdPublicKey {
+    pub threshold: u32,
+    pub parties: u32,
+
+    /// The public key that is used to encrypt to the parties.
+    pub pub_key: ElgamalPublicKey,
+
+    /// The verification key of the party `i` is at index `i - 1`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub verification_keys: Vec<RistrettoPoint>,
+}
+
+impl Encode for ThresholdPublicKey {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.threshold.size_hint()
+            + self.parties.size_hint()
+            + self.pub_key.size_hint()
+            + mem::size_of::<u32>()
+            + 32 * self.verification_keys.len() // verification_keys
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.threshold.encode_to(dest);
+        self.parties.encode_to(dest);
+        self.pub_key.encode_to(dest);
+        encode_points(&self.verification_keys, dest);
+    }
+}
+
+impl Decode for ThresholdPublicKey {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let threshold = <u32>::decode(input)?;
+        let parties = <u32>::decode(input)?;
+        check_parameters(threshold, parties)
+            .map_err(|_| CodecError::from("Invalid `ThresholdPublicKey` parameters"))?;
+        let pub_key = <ElgamalPublicKey>::decode(input)?;
+        let verification_keys = decode_points(input)?;
+        if verification_keys.len() != parties as usize {
+            return Err(CodecError::from(
+                "Invalid `ThresholdPublicKey` verification keys",
+            ));
+        }
+
+        Ok(ThresholdPublicKey {
+            threshold,
+            parties,
+            pub_key,
+            verification_keys,
+        })
+    }
+}
+
+impl ThresholdPublicKey {
+    /// Computes the threshold public key from the public messages of the key generation.
+    ///
+    /// # Inputs
+    /// `threshold`   The number of parties that are needed to decrypt.
+    /// `parties`     The number of parties.
+    /// `commitments` The commitments that were broadcast by all the dealers.
+    /// `products`    The masked products that were broadcast by all the parties.
+    pub fn new(
+        threshold: u32,
+        parties: u32,
+        commitments: &[DkgCommitments],
+        products: &[MaskedProduct],
+    ) -> Fallible<Self> {
+        check_parameters(threshold, parties)?;
+        let commitments = sorted_commitments(threshold, parties, commitments)?;
+
+        let mut products: Vec<&MaskedProduct> = products.iter().collect();
+        products.sort_by_key(|product| product.index);
+        ensure!(
+            products.len() == parties as usize
+                && products
+                    .iter()
+                    .zip(1..=parties)
+                    .all(|(product, index)| product.index == index),
+            ErrorKind::DkgMaskedProductError
+        );
+
+        // Interpolate `u = d * k` from the first `2 * threshold - 1` masked products
+        // and check that the other ones lie on the same polynomial.
+        let (interpolated, rest) = products.split_at(2 * threshold as usize - 1);
+        let indices: Vec<u32> = interpolated.iter().map(|product| product.index).collect();
+        let interpolate = |at: Scalar| -> Scalar {
+            interpolated
+                .iter()
+                .map(|product| lagrange_coefficient(&indices, product.index, at) * product.value)
+                .sum()
+        };
+        for product in rest {
+            ensure!(
+                interpolate(Scalar::from(product.index)) == product.value,
+                ErrorKind::DkgMaskedProductError
+            );
+        }
+        let masked_secret = interpolate(Scalar::zero());
+
+        let mask_commitment: RistrettoPoint = commitments
+            .iter()
+            .map(|commitment| commitment.mask[0])
+            .sum();
+        let secret_commitment: RistrettoPoint = commitments
+            .iter()
+            .map(|commitment| commitment.secret[0])
+            .sum();
+        ensure!(
+            masked_secret != Scalar::zero()
+                && !mask_commitment.is_identity()
+                && !secret_commitment.is_identity(),
+            ErrorKind::DkgMaskedProductError
+        );
+
+        let verification_keys = (1..=parties)
+            .map(|index| {
+                let x = Scalar::from(index);
+                commitments
+                    .iter()
+                    .map(|commitment| evaluate_commitments(&commitment.secret, x))
+                    .sum::<RistrettoPoint>()
+            })
+            .collect();
+
+        Ok(ThresholdPublicKey {
+            threshold,
+            parties,
+            pub_key: ElgamalPublicKey {
+                pub_key: masked_secret.invert() * mask_commitment,
+            },
+            verification_keys,
+        })
+    }
+
+    pub fn verification_key(&self, index: u32) -> Fallible<RistrettoPoint> {
+        check_index(index, self.parties)?;
+        Ok(self.verification_keys[index as usize - 1])
+    }
+
+    fn verify_share_of(&self, x: RistrettoPoint, share: &DecryptionShare) -> Fallible<()> {
+        let index = share.index;
+        let verification_key = self
+            .verification_key(index)
+            .map_err(|_| ErrorKind::DecryptionShareVerificationError { index })?;
+        let gens = PedersenGens::default();
+        single_property_verifier(
+            &PartialDecryptionVerifier {
+                verification_key,
+                x,
+                share: share.share,
+                pc_gens: &gens,
+            },
+            share.proof,
+        )
+        .map_err(|_| ErrorKind::DecryptionShareVerificationError { index })?;
+
+        Ok(())
+    }
+
+    /// Verifies that a decryption share was computed correctly by its party.
+    pub fn verify_share(&self, cipher_text: &CipherText, share: &DecryptionShare) -> Fallible<()> {
+        self.verify_share_of(cipher_text.x, share)
+    }
+
+    /// Combines `threshold` valid decryption shares with distinct indices into `d * x`.
+    /// Shares that fail verification are skipped, so that a misbehaving party cannot
+    /// prevent the decryption as long as `threshold` honest parties have contributed.
+    fn combine(&self, x: RistrettoPoint, shares: &[DecryptionShare]) -> Fallible<RistrettoPoint> {
+        let threshold = self.threshold as usize;
+        let mut selected: Vec<&DecryptionShare> = Vec::with_capacity(threshold);
+        for share in shares {
+            if selected.len() == threshold {
+                break;
+            }
+            if selected.iter().any(|other| other.index == share.index)
+                || self.verify_share_of(x, share).is_err()
+            {
+                continue;
+            }
+            selected.push(share);
+        }
+        ensure!(
+            selected.len() == threshold,
+            ErrorKind::NotEnoughDecryptionShares {
+                needed: self.threshold,
+                given: selected.len() as u32,
+            }
+        );
+
+        let indices: Vec<u32> = selected.iter().map(|share| share.index).collect();
+        Ok(selected
+            .iter()
+            .map(|share| lagrange_coefficient(&indices, share.index, Scalar::zero()) * share.share)
+            .sum())
+    }
+
+    /// Decrypts a cipher text from the decryption shares of at least `threshold` parties.
+    /// Uses the lazily built default `DiscreteLogTable`, which covers the `u32` range.
+    pub fn combine_shares(
+        &self,
+        cipher_text: &CipherText,
+        shares: &[DecryptionShare],
+    ) -> Fallible<Balance> {
+        self.combine_shares_with_table(cipher_text, shares, DiscreteLogTable::default_table())
+    }
+
+    /// Decrypts a cipher text from the decryption shares of at least `threshold` parties,
+    /// for a balance within the range of the precomputed table of baby steps.
+    pub fn combine_shares_with_table(
+        &self,
+        cipher_text: &CipherText,
+        shares: &[DecryptionShare],
+        table: &DiscreteLogTable,
+    ) -> Fallible<Balance> {
+        // value * h = Y - d * X
+        let value_h = cipher_text.y - self.combine(cipher_text.x, shares)?;
+        table
+            .solve(&value_h)
+            .ok_or_else(|| ErrorKind::CipherTextDecryptionError.into())
+    }
+
+    /// Decrypts a constant time cipher text from the decryption shares of at least
+    /// `threshold` parties.
+    pub fn const_time_combine_shares(
+        &self,
+        cipher_text: &CipherTextWithHint,
+        shares: &[DecryptionShare],
+    ) -> Fallible<Balance> {
+        let random_1_g = self.combine(cipher_text.elgamal_cipher.x, shares)?;
+        decrypt_with_blinding_point(cipher_text, random_1_g)
+    }
+}
+
+// ------------------------------------------------------------------------
+// Decryption Shares
+// ------------------------------------------------------------------------
+
+/// A party's contribution to the decryption of a cipher text `(X, Y)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecryptionShare {
+    pub index: u32,
+
+    /// The decryption share `d_i * X`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    pub share: RistrettoPoint,
+
+    /// The proof that `share` and the party's verification key use the same secret.
+    pub proof: PartialDecryptionProof,
+}
+
+impl Encode for DecryptionShare {
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.index.size_hint()
+            + RistrettoPointEncoder(&self.share).size_hint()
+            + self.proof.0.size_hint()
+            + self.proof.1.size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        self.index.encode_to(dest);
+        RistrettoPointEncoder(&self.share).encode_to(dest);
+        self.proof.0.encode_to(dest);
+        self.proof.1.encode_to(dest);
+    }
+}
+
+impl Decode for DecryptionShare {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let index = <u32>::decode(input)?;
+        let share = <RistrettoPointDecoder>::decode(input)?.0;
+        let initial_message = <PartialDecryptionInitialMessage>::decode(input)?;
+        let final_response = <PartialDecryptionFinalResponse>::decode(input)?;
+
+        Ok(DecryptionShare {
+            index,
+            share,
+            proof: (initial_message, final_response),
+        })
+    }
+}
+
+// ------------------------------------------------------------------------
+// Proof of Correct Partial Decryption
+// ------------------------------------------------------------------------
+
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PartialDecryptionFinalResponse(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] Scalar,
+);
+
+impl Encode for PartialDecryptionFinalResponse {
+    fn size_hint(&self) -> usize {
+        ScalarEncoder(&self.0).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        ScalarEncoder(&self.0).encode_to(dest)
+    }
+}
+
+impl Decode for PartialDecryptionFinalResponse {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let scalar = <ScalarDecoder>::decode(input)?.0;
+        Ok(PartialDecryptionFinalResponse(scalar))
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PartialDecryptionInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
+    b: RistrettoPoint,
+}
+
+impl Encode for PartialDecryptionInitialMessage {
+    fn size_hint(&self) -> usize {
+        RistrettoPointEncoder(&self.a).size_hint() + RistrettoPointEncoder(&self.b).size_hint()
+    }
+
+    fn encode_to<W: Output>(&self, dest: &mut W) {
+        RistrettoPointEncoder(&self.a).encode_to(dest);
+        RistrettoPointEncoder(&self.b).encode_to(dest);
+    }
+}
+
+impl Decode for PartialDecryptionInitialMessage {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let a = <RistrettoPointDecoder>::decode(input)?.0;
+        let b = <RistrettoPointDecoder>::decode(input)?.0;
+
+        Ok(PartialDecryptionInitialMessage { a, b })
+    }
+}
+
+/// A default implementation used for testing.
+impl Default for PartialDecryptionInitialMessage {
+    fn default() -> Self {
+        PartialDecryptionInitialMessage {
+            a: RISTRETTO_BASEPOINT_POINT,
+            b: RISTRETTO_BASEPOINT_POINT,
+        }
+    }
+}
+
+impl UpdateTranscript for PartialDecryptionInitialMessage {
+    fn update_transcript(&self, transcript: &mut Transcript) -> Fallible<()> {
+        transcript.append_domain_separator(PARTIAL_DECRYPTION_PROOF_CHALLENGE_LABEL);
+        transcript.append_validated_point(b"A", &self.a.compress())?;
+        transcript.append_validated_point(b"B", &self.b.compress())?;
+        Ok(())
+    }
+}
+
+/// Holds the non-interactive proof that a decryption share `d_i * X` and the
+/// verification key `d_i * g` have the same discrete log.
+pub type PartialDecryptionProof =
+    ZKProofResponse<PartialDecryptionInitialMessage, PartialDecryptionFinalResponse>;
+
+#[cfg(not(feature = "verify-only"))]
+pub struct PartialDecryptionProverAwaitingChallenge<'a> {
+    /// The party's share of the decryption key.
+    pub secret: Scalar,
+
+    /// The `X` part of the cipher text.
+    pub x: RistrettoPoint,
+
+    /// The Pedersen generators.
+    pub pc_gens: &'a PedersenGens,
+}
+
+#[cfg(not(feature = "verify-only"))]
+#[derive(Zeroize)]
+#[zeroize(drop)]
+pub struct PartialDecryptionProver {
+    /// The party's share of the decryption key.
+    secret: Scalar,
+
+    /// The randomness generated in the first round.
+    u: Scalar,
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofProverAwaitingChallenge for PartialDecryptionProverAwaitingChallenge<'a> {
+    type ZKInitialMessage = PartialDecryptionInitialMessage;
+    type ZKFinalResponse = PartialDecryptionFinalResponse;
+    type ZKProver = PartialDecryptionProver;
+
+    fn create_transcript_rng<T: RngCore + CryptoRng>(
+        &self,
+        rng: &mut T,
+        transcript: &Transcript,
+    ) -> TranscriptRng {
+        transcript
+            .build_rng()
+            .rekey_with_witness_bytes(b"secret", self.secret.as_bytes())
+            .finalize(rng)
+    }
+
+    fn generate_initial_message(
+        &self,
+        rng: &mut TranscriptRng,
+    ) -> (Self::ZKProver, Self::ZKInitialMessage) {
+        let rand_commitment = Scalar::random(rng);
+
+        (
+            PartialDecryptionProver {
+                secret: self.secret,
+                u: rand_commitment,
+            },
+            PartialDecryptionInitialMessage {
+                a: rand_commitment * self.pc_gens.B_blinding,
+                b: rand_commitment * self.x,
+            },
+        )
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl AssetProofProver<PartialDecryptionFinalResponse> for PartialDecryptionProver {
+    fn apply_challenge(&self, c: &ZKPChallenge) -> PartialDecryptionFinalResponse {
+        PartialDecryptionFinalResponse(self.u + c.x() * self.secret)
+    }
+}
+
+pub struct PartialDecryptionVerifier<'a> {
+    /// The verification key of the party.
+    pub verification_key: RistrettoPoint,
+
+    /// The `X` part of the cipher text.
+    pub x: RistrettoPoint,
+
+    /// The decryption share of the party.
+    pub share: RistrettoPoint,
+
+    /// The Pedersen generators.
+    pub pc_gens: &'a PedersenGens,
+}
+
+impl<'a> AssetProofVerifier for PartialDecryptionVerifier<'a> {
+    type ZKInitialMessage = PartialDecryptionInitialMessage;
+    type ZKFinalResponse = PartialDecryptionFinalResponse;
+
+    fn verify(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        z: &Self::ZKFinalResponse,
+    ) -> Fallible<()> {
+        // 2 conditions need to hold:
+        // z * g == A + c * verification_key
+        // z * X == B + c * share
+        ensure!(
+            z.0 * self.pc_gens.B_blinding
+                == initial_message.a + challenge.x() * self.verification_key,
+            ErrorKind::PartialDecryptionFinalResponseVerificationError { check: 1 }
+        );
+        ensure!(
+            z.0 * self.x == initial_message.b + challenge.x() * self.share,
+            ErrorKind::PartialDecryptionFinalResponseVerificationError { check: 2 }
+        );
+        Ok(())
+    }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        z: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let c = *challenge.x();
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(z.0, self.pc_gens.B_blinding)
+                .term(-Scalar::one(), initial_message.a)
+                .term(-c, self.verification_key),
+            VerificationEquation::new()
+                .term(z.0, self.x)
+                .term(-Scalar::one(), initial_message.b)
+                .term(-c, self.share),
+        ])
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::ElgamalSecretKey;
+    use rand::{rngs::StdRng, SeedableRng};
+    use wasm_bindgen_test::*;
+
+    const SEED_1: [u8; 32] = [42u8; 32];
+
+    fn run_key_generation<R: RngCore + CryptoRng>(
+        threshold: u32,
+        parties: u32,
+        rng: &mut R,
+    ) -> (ThresholdPublicKey, Vec<ThresholdSecretKeyShare>) {
+        let dealers: Vec<DkgDealer> = (1..=parties)
+            .map(|index| DkgDealer::new(index, threshold, parties, rng).unwrap())
+            .collect();
+        let commitments: Vec<DkgCommitments> =
+            dealers.iter().map(|dealer| dealer.commitments()).collect();
+
+        let participants: Vec<DkgParticipant> = (1..=parties)
+            .map(|index| {
+                let shares: Vec<DkgShare> = dealers
+                    .iter()
+                    .map(|dealer| dealer.share_for(index).unwrap())
+                    .collect();
+                DkgParticipant::new(index, threshold, parties, &commitments, &shares).unwrap()
+            })
+            .collect();
+        let products: Vec<MaskedProduct> = participants
+            .iter()
+            .map(|participant| participant.masked_product())
+            .collect();
+
+        let pub_key = ThresholdPublicKey::new(threshold, parties, &commitments, &products).unwrap();
+        let key_shares = participants
+            .iter()
+            .map(|participant| participant.key_share())
+            .collect();
+
+        (pub_key, key_shares)
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn threshold_key_generation() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (pub_key, key_shares) = run_key_generation(3, 5, &mut rng);
+
+        for key_share in key_shares.iter() {
+            assert_eq!(
+                pub_key.verification_key(key_share.index()).unwrap(),
+                key_share.verification_key()
+            );
+        }
+
+        // Any 3 shares interpolate the inverse of the secret key.
+        let indices = [2u32, 4, 5];
+        let inverse_secret: Scalar = indices
+            .iter()
+            .map(|&index| {
+                lagrange_coefficient(&indices, index, Scalar::zero())
+                    * key_shares[index as usize - 1].secret
+            })
+            .sum();
+        let secret_key = ElgamalSecretKey::new(inverse_secret.invert());
+        assert_eq!(secret_key.get_public_key(), pub_key.pub_key);
+
+        // Negative tests.
+        assert_err!(
+            DkgDealer::new(1, 3, 4, &mut rng),
+            ErrorKind::InvalidThresholdParameters {
+                threshold: 3,
+                parties: 4
+            }
+        );
+        assert_err!(
+            DkgDealer::new(6, 3, 5, &mut rng),
+            ErrorKind::InvalidParticipantIndex { index: 6 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn key_generation_rejects_bad_messages() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (threshold, parties) = (2, 4);
+        let dealers: Vec<DkgDealer> = (1..=parties)
+            .map(|index| DkgDealer::new(index, threshold, parties, &mut rng).unwrap())
+            .collect();
+        let commitments: Vec<DkgCommitments> =
+            dealers.iter().map(|dealer| dealer.commitments()).collect();
+        let shares: Vec<DkgShare> = dealers
+            .iter()
+            .map(|dealer| dealer.share_for(1).unwrap())
+            .collect();
+
+        // A share that does not match the commitments of its dealer.
+        let mut bad_shares = shares.clone();
+        bad_shares[2].secret += Scalar::one();
+        assert_err!(
+            DkgParticipant::new(1, threshold, parties, &commitments, &bad_shares),
+            ErrorKind::DkgShareVerificationError { dealer: 3 }
+        );
+
+        // A missing share.
+        assert_err!(
+            DkgParticipant::new(1, threshold, parties, &commitments, &shares[..3]),
+            ErrorKind::DkgShareVerificationError { dealer: 4 }
+        );
+
+        // Malformed and missing commitments.
+        let mut bad_commitments = commitments.clone();
+        bad_commitments[1].zero.clear();
+        assert_err!(
+            DkgParticipant::new(1, threshold, parties, &bad_commitments, &shares),
+            ErrorKind::DkgCommitmentsError { dealer: 2 }
+        );
+        assert_err!(
+            DkgParticipant::new(1, threshold, parties, &commitments[1..], &shares),
+            ErrorKind::DkgCommitmentsError { dealer: 1 }
+        );
+
+        // A masked product that is inconsistent with the other ones.
+        let mut products: Vec<MaskedProduct> = (1..=parties)
+            .map(|index| {
+                let shares: Vec<DkgShare> = dealers
+                    .iter()
+                    .map(|dealer| dealer.share_for(index).unwrap())
+                    .collect();
+                DkgParticipant::new(index, threshold, parties, &commitments, &shares)
+                    .unwrap()
+                    .masked_product()
+            })
+            .collect();
+        assert!(ThresholdPublicKey::new(threshold, parties, &commitments, &products).is_ok());
+        products[3].value += Scalar::one();
+        assert_err!(
+            ThresholdPublicKey::new(threshold, parties, &commitments, &products),
+            ErrorKind::DkgMaskedProductError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn threshold_decryption() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (pub_key, key_shares) = run_key_generation(2, 3, &mut rng);
+        let value: Balance = 123_456;
+
+        let (_, cipher) = pub_key.pub_key.encrypt_value(Scalar::from(value), &mut rng);
+        let shares: Vec<DecryptionShare> = key_shares
+            .iter()
+            .map(|key_share| key_share.decryption_share(&cipher, &mut rng).unwrap())
+            .collect();
+        for share in shares.iter() {
+            assert!(pub_key.verify_share(&cipher, share).is_ok());
+        }
+
+        // Any 2 of the 3 shares decrypt the cipher text.
+        assert_eq!(
+            pub_key.combine_shares(&cipher, &shares[..2]).unwrap(),
+            value
+        );
+        assert_eq!(
+            pub_key.combine_shares(&cipher, &shares[1..]).unwrap(),
+            value
+        );
+        assert_eq!(
+            pub_key
+                .combine_shares(&cipher, &[shares[2], shares[0]])
+                .unwrap(),
+            value
+        );
+
+        // Constant time cipher texts can hold the whole 64-bit range.
+        let large_value = u64::MAX - 1;
+        let (_, hinted_cipher) = pub_key
+            .pub_key
+            .const_time_encrypt_value(Scalar::from(large_value), &mut rng);
+        let hinted_shares: Vec<DecryptionShare> = key_shares[1..]
+            .iter()
+            .map(|key_share| {
+                key_share
+                    .const_time_decryption_share(&hinted_cipher, &mut rng)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            pub_key
+                .const_time_combine_shares(&hinted_cipher, &hinted_shares)
+                .unwrap(),
+            large_value
+        );
+
+        // Negative test: repeating a share does not count twice.
+        assert_err!(
+            pub_key.combine_shares(&cipher, &[shares[0], shares[0]]),
+            ErrorKind::NotEnoughDecryptionShares {
+                needed: 2,
+                given: 1
+            }
+        );
+
+        // Negative test: shares of another cipher text.
+        assert_err!(
+            pub_key.const_time_combine_shares(&hinted_cipher, &shares[..2]),
+            ErrorKind::NotEnoughDecryptionShares {
+                needed: 2,
+                given: 0
+            }
+        );
+
+        // Negative test: a share that was tampered with.
+        let mut bad_share = shares[1];
+        bad_share.share += RISTRETTO_BASEPOINT_POINT;
+        assert_err!(
+            pub_key.verify_share(&cipher, &bad_share),
+            ErrorKind::DecryptionShareVerificationError { index: 2 }
+        );
+        assert_err!(
+            pub_key.combine_shares(&cipher, &[shares[0], bad_share]),
+            ErrorKind::NotEnoughDecryptionShares {
+                needed: 2,
+                given: 1
+            }
+        );
+
+        // An invalid share is skipped, even if it comes first and claims the
+        // index of a valid share.
+        assert_eq!(
+            pub_key
+                .combine_shares(&cipher, &[bad_share, shares[0], shares[2]])
+                .unwrap(),
+            value
+        );
+        assert_eq!(
+            pub_key
+                .combine_shares(&cipher, &[bad_share, shares[1], shares[0]])
+                .unwrap(),
+            value
+        );
+
+        // Negative test: a share that claims the index of another party.
+        let mut bad_share = shares[1];
+        bad_share.index = 3;
+        assert_err!(
+            pub_key.verify_share(&cipher, &bad_share),
+            ErrorKind::DecryptionShareVerificationError { index: 3 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn partial_decryption_proof() {
+        let gens = PedersenGens::default();
+        let mut rng = StdRng::from_seed(SEED_1);
+        let secret = Scalar::random(&mut rng);
+        let x = Scalar::random(&mut rng) * RISTRETTO_BASEPOINT_POINT;
+
+        let prover = PartialDecryptionProverAwaitingChallenge {
+            secret,
+            x,
+            pc_gens: &gens,
+        };
+        let verifier = PartialDecryptionVerifier {
+            verification_key: secret * gens.B_blinding,
+            x,
+            share: secret * x,
+            pc_gens: &gens,
+        };
+        let mut transcript = Transcript::new(PARTIAL_DECRYPTION_PROOF_FINAL_RESPONSE_LABEL);
+
+        // Positive tests
+        let mut transcript_rng = prover.create_transcript_rng(&mut rng, &transcript);
+        let (prover, initial_message) = prover.generate_initial_message(&mut transcript_rng);
+        initial_message.update_transcript(&mut transcript).unwrap();
+        let challenge = transcript
+            .scalar_challenge(PARTIAL_DECRYPTION_PROOF_CHALLENGE_LABEL)
+            .unwrap();
+        let final_response = prover.apply_challenge(&challenge);
+
+        let result = verifier.verify(&challenge, &initial_message, &final_response);
+        assert!(result.is_ok());
+
+        // Negative tests
+        let bad_initial_message = PartialDecryptionInitialMessage::default();
+        assert_err!(
+            verifier.verify(&challenge, &bad_initial_message, &final_response),
+            ErrorKind::PartialDecryptionFinalResponseVerificationError { check: 1 }
+        );
+
+        let bad_verifier = PartialDecryptionVerifier {
+            share: secret * x + RISTRETTO_BASEPOINT_POINT,
+            ..verifier
+        };
+        assert_err!(
+            bad_verifier.verify(&challenge, &initial_message, &final_response),
+            ErrorKind::PartialDecryptionFinalResponseVerificationError { check: 2 }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn serialize_deserialize_threshold_messages() {
+        let mut rng = StdRng::from_seed(SEED_1);
+        let (pub_key, key_shares) = run_key_generation(2, 3, &mut rng);
+
+        let bytes = pub_key.encode();
+        let recovered_pub_key = <ThresholdPublicKey>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(recovered_pub_key, pub_key);
+
+        let (_, cipher) = pub_key.pub_key.encrypt_value(Scalar::from(7u32), &mut rng);
+        let share = key_shares[0].decryption_share(&cipher, &mut rng).unwrap();
+        let bytes = share.encode();
+        let recovered_share = <DecryptionShare>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(recovered_share, share);
+
+        let bytes = key_shares[1].encode();
+        let recovered_key_share = <ThresholdSecretKeyShare>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(
+            recovered_key_share.verification_key(),
+            key_shares[1].verification_key()
+        );
+    }
+}
@@ -62,6 +62,14 @@ pub trait TranscriptProtocol {
         rng: &mut T,
         witness: &CommitmentWitness,
     ) -> TranscriptRng;
+
+    /// Create an RNG seeded only from the transcript's cloned state.
+    /// Verifiers use it to draw their random weights, so that every
+    /// verifier of the same transcript gets the same result.
+    ///
+    /// # Output
+    /// A new RNG.
+    fn create_verifier_rng(&self) -> TranscriptRng;
 }
 
 impl TranscriptProtocol for Transcript {
@@ -98,8 +106,37 @@ impl TranscriptProtocol for Transcript {
             .rekey_with_witness_bytes(b"w_blinding", witness.blinding().as_bytes())
             .finalize(rng)
     }
+
+    fn create_verifier_rng(&self) -> TranscriptRng {
+        self.build_rng().finalize(&mut ZeroRng)
+    }
+}
+
+/// An RNG that only returns zeros. Finalizing a transcript RNG with it leaves
+/// the transcript's state as the only source of the RNG's randomness.
+struct ZeroRng;
+
+impl RngCore for ZeroRng {
+    fn next_u32(&mut self) -> u32 {
+        0
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.iter_mut().for_each(|byte| *byte = 0);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
 }
 
+impl CryptoRng for ZeroRng {}
+
 /// A trait that is used to update the transcript with the initial message
 /// that results from the first round of the protocol.
 pub trait UpdateTranscript {
@@ -119,4 +156,20 @@ mod tests {
             ErrorKind::VerificationError
         );
     }
+
+    #[test]
+    fn verifier_rng_is_deterministic() {
+        let mut transcript = Transcript::new(b"unit test");
+        transcript.append_message(b"message", b"first");
+        let mut other_transcript = transcript.clone();
+        other_transcript.append_message(b"message", b"second");
+
+        let draw = |transcript: &Transcript| {
+            let mut bytes = [0u8; 32];
+            transcript.create_verifier_rng().fill_bytes(&mut bytes);
+            bytes
+        };
+        assert_eq!(draw(&transcript), draw(&transcript.clone()));
+        assert_ne!(draw(&transcript), draw(&other_transcript));
+    }
 }
@@ -2,15 +2,19 @@
 //! This proofs the knoweledge about the encrypted value.
 //! For more details see section 5.1 of the whitepaper.
 
+#[cfg(not(feature = "verify-only"))]
+use crate::asset_proofs::{
+    encryption_proofs::{AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofSimulator},
+    CommitmentWitness,
+};
 use crate::{
     asset_proofs::{
         encryption_proofs::{
-            AssetProofProver, AssetProofProverAwaitingChallenge, AssetProofVerifier, ZKPChallenge,
-            ZKProofResponse,
+            AssetProofVerifier, VerificationEquation, ZKPChallenge, ZKProofResponse,
         },
         errors::{ErrorKind, Fallible},
         transcript::{TranscriptProtocol, UpdateTranscript},
-        CipherText, CommitmentWitness, ElgamalPublicKey,
+        CipherText, ElgamalPublicKey,
     },
     codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
 };
@@ -19,13 +23,18 @@ use bulletproofs::PedersenGens;
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar,
 };
-use merlin::{Transcript, TranscriptRng};
+use merlin::Transcript;
+#[cfg(not(feature = "verify-only"))]
+use merlin::TranscriptRng;
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroizing;
 
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
+use sp_std::prelude::*;
 
 /// The domain label for the wellformedness proof.
 pub const WELLFORMEDNESS_PROOF_FINAL_RESPONSE_LABEL: &[u8] = b"PolymathWellformednessFinalResponse";
@@ -35,7 +44,9 @@ pub const WELLFORMEDNESS_PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathWellformedness
 #[derive(PartialEq, Copy, Clone, Default, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WellformednessFinalResponse {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z1: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     z2: Scalar,
 }
 
@@ -62,7 +73,9 @@ impl Decode for WellformednessFinalResponse {
 #[derive(PartialEq, Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WellformednessInitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     b: RistrettoPoint,
 }
 
@@ -110,6 +123,7 @@ impl UpdateTranscript for WellformednessInitialMessage {
 pub type WellformednessProof =
     ZKProofResponse<WellformednessInitialMessage, WellformednessFinalResponse>;
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone, Debug)]
 pub struct WellformednessProver {
     /// The secret commitment witness.
@@ -119,6 +133,7 @@ pub struct WellformednessProver {
     rand_b: Scalar,
 }
 
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone)]
 pub struct WellformednessProverAwaitingChallenge<'a> {
     /// The public key used for the elgamal encryption.
@@ -131,6 +146,7 @@ pub struct WellformednessProverAwaitingChallenge<'a> {
     pub pc_gens: &'a PedersenGens,
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl<'a> AssetProofProverAwaitingChallenge for WellformednessProverAwaitingChallenge<'a> {
     type ZKInitialMessage = WellformednessInitialMessage;
     type ZKFinalResponse = WellformednessFinalResponse;
@@ -164,6 +180,7 @@ impl<'a> AssetProofProverAwaitingChallenge for WellformednessProverAwaitingChall
     }
 }
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetProofProver<WellformednessFinalResponse> for WellformednessProver {
     fn apply_challenge(&self, c: &ZKPChallenge) -> WellformednessFinalResponse {
         WellformednessFinalResponse {
@@ -201,6 +218,45 @@ impl<'a> AssetProofVerifier for WellformednessVerifier<'a> {
         );
         Ok(())
     }
+
+    fn verification_equations(
+        &self,
+        challenge: &ZKPChallenge,
+        initial_message: &Self::ZKInitialMessage,
+        response: &Self::ZKFinalResponse,
+    ) -> Fallible<Vec<VerificationEquation>> {
+        let c = *challenge.x();
+
+        Ok(vec![
+            VerificationEquation::new()
+                .term(response.z1, self.pub_key.pub_key)
+                .term(-Scalar::one(), initial_message.a)
+                .term(-c, self.cipher.x),
+            VerificationEquation::new()
+                .term(response.z1, self.pc_gens.B_blinding)
+                .term(response.z2, self.pc_gens.B)
+                .term(-Scalar::one(), initial_message.b)
+                .term(-c, self.cipher.y),
+        ])
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl<'a> AssetProofSimulator for WellformednessVerifier<'a> {
+    fn simulate<T: RngCore + CryptoRng>(
+        &self,
+        challenge: &ZKPChallenge,
+        rng: &mut T,
+    ) -> (Self::ZKInitialMessage, Self::ZKFinalResponse) {
+        let z1 = Scalar::random(rng);
+        let z2 = Scalar::random(rng);
+
+        let initial_message = WellformednessInitialMessage {
+            a: z1 * self.pub_key.pub_key - challenge.x() * self.cipher.x,
+            b: z1 * self.pc_gens.B_blinding + z2 * self.pc_gens.B - challenge.x() * self.cipher.y,
+        };
+        (initial_message, WellformednessFinalResponse { z1, z2 })
+    }
 }
 
 #[cfg(test)]
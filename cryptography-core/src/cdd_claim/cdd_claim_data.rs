@@ -1,6 +1,9 @@
 use crate::{
     cdd_claim::pedersen_commitments::{generate_blinding_factor, generate_pedersen_commit},
-    codec_wrapper::{RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder},
+    codec_wrapper::{
+        RistrettoPointDecoder, RistrettoPointEncoder, ScalarDecoder, ScalarEncoder, TypeTag,
+        Version, Versioned,
+    },
 };
 use codec::{Decode, Encode, Error as CodecError, Input, Output};
 use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
@@ -19,7 +22,9 @@ pub fn slice_to_scalar(data: &[u8]) -> Scalar {
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CddClaimData {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub investor_did: Scalar,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))]
     pub investor_unique_id: Scalar,
 }
 
@@ -58,9 +63,16 @@ impl Decode for CddClaimData {
     }
 }
 
+impl Versioned for CddClaimData {
+    const TYPE_TAG: TypeTag = *b"CCLM";
+    const VERSION: Version = 1;
+}
+
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct CddId(pub RistrettoPoint);
+pub struct CddId(
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_hex"))] pub RistrettoPoint,
+);
 
 impl Encode for CddId {
     #[inline]
@@ -80,6 +92,11 @@ impl Decode for CddId {
     }
 }
 
+impl Versioned for CddId {
+    const TYPE_TAG: TypeTag = *b"CDID";
+    const VERSION: Version = 1;
+}
+
 /// Compute the CDD_ID. \
 /// CDD_ID = PedersenCommitment(INVESTOR_DID, INVESTOR_UNIQUE_ID, [INVESTOR_DID | INVESTOR_UNIQUE_ID]) \
 ///
@@ -6,6 +6,9 @@ use curve25519_dalek::{
 };
 use sp_std::vec::Vec;
 
+mod versioned;
+pub use versioned::{Envelope, TypeTag, Version, Versioned};
+
 /// Constants:
 /// A serialized Ristretto point size.
 pub const RISTRETTO_POINT_SIZE: usize = 32;
@@ -0,0 +1,203 @@
+//! A versioned, self-describing envelope for the wire format of the top-level types.
+//!
+//! The SCALE encoding of a proof or a transaction does not say which type it holds or
+//! which layout it uses, so changing a layout silently breaks all the stored values of
+//! the type. Top-level types implement `Versioned` and are exchanged as an `Envelope`:
+//! ```text
+//! type_tag (4 bytes) || version (u16) || SCALE(payload)
+//! ```
+//! Decoding an envelope rejects other types, versions newer than the current version,
+//! and trailing bytes. Envelopes of older versions are passed to `Versioned::decode_older`,
+//! which upgrades them to the current layout.
+
+use codec::{Decode, Encode, Error as CodecError, Input};
+use sp_std::prelude::*;
+
+/// The tag that identifies the type of an envelope's payload.
+pub type TypeTag = [u8; 4];
+
+/// The version of the layout of an envelope's payload.
+pub type Version = u16;
+
+/// A top-level type with a versioned wire format.
+///
+/// Whenever the layout of the type changes, `VERSION` must be increased, and
+/// `decode_older` must learn to decode and upgrade the previous layouts.
+pub trait Versioned: Encode + Decode {
+    /// The unique tag of the type.
+    const TYPE_TAG: TypeTag;
+
+    /// The current version of the layout.
+    const VERSION: Version;
+
+    /// Decodes the payload of an older `version` and upgrades it to the current layout.
+    /// By default, all older versions are rejected.
+    fn decode_older<I: Input>(_version: Version, _input: &mut I) -> Result<Self, CodecError> {
+        Err(CodecError::from("Unsupported version"))
+    }
+
+    /// Encodes this value inside an envelope with the current version.
+    fn encode_versioned(&self) -> Vec<u8> {
+        Envelope::wrap(self).encode()
+    }
+
+    /// Decodes a value from an envelope.
+    fn decode_versioned<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        Envelope::decode(input)?.open()
+    }
+}
+
+/// Holds the SCALE encoding of a `Versioned` value, along with its type tag and version.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct Envelope {
+    pub type_tag: TypeTag,
+    pub version: Version,
+    pub payload: Vec<u8>,
+}
+
+impl Envelope {
+    /// Wraps `value` in an envelope with the current version of its type.
+    pub fn wrap<T: Versioned>(value: &T) -> Self {
+        Envelope {
+            type_tag: T::TYPE_TAG,
+            version: T::VERSION,
+            payload: value.encode(),
+        }
+    }
+
+    /// Decodes the payload as a `T`, upgrading it if it has an older version.
+    pub fn open<T: Versioned>(&self) -> Result<T, CodecError> {
+        if self.type_tag != T::TYPE_TAG {
+            return Err(CodecError::from("Unexpected type tag"));
+        }
+
+        let mut payload = &self.payload[..];
+        let value = if self.version == T::VERSION {
+            T::decode(&mut payload)?
+        } else if self.version < T::VERSION {
+            T::decode_older(self.version, &mut payload)?
+        } else {
+            return Err(CodecError::from("Unknown version"));
+        };
+
+        if !payload.is_empty() {
+            return Err(CodecError::from("Trailing bytes in the payload"));
+        }
+        Ok(value)
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first layout only had an amount.
+    #[derive(Encode, Decode)]
+    struct PaymentV1 {
+        amount: u32,
+    }
+
+    /// The second layout added a memo.
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Payment {
+        amount: u32,
+        memo: Vec<u8>,
+    }
+
+    impl Versioned for Payment {
+        const TYPE_TAG: TypeTag = *b"TPAY";
+        const VERSION: Version = 2;
+
+        fn decode_older<I: Input>(version: Version, input: &mut I) -> Result<Self, CodecError> {
+            match version {
+                1 => {
+                    let old = PaymentV1::decode(input)?;
+                    Ok(Payment {
+                        amount: old.amount,
+                        memo: Vec::new(),
+                    })
+                }
+                _ => Err(CodecError::from("Unsupported version")),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Receipt(u32);
+
+    impl Versioned for Receipt {
+        const TYPE_TAG: TypeTag = *b"TRCP";
+        const VERSION: Version = 1;
+    }
+
+    fn envelope(type_tag: TypeTag, version: Version, payload: Vec<u8>) -> Vec<u8> {
+        Envelope {
+            type_tag,
+            version,
+            payload,
+        }
+        .encode()
+    }
+
+    #[test]
+    fn versioned_round_trip() {
+        let payment = Payment {
+            amount: 42,
+            memo: b"rent".to_vec(),
+        };
+        let encoded = payment.encode_versioned();
+        assert_eq!(&encoded[..4], b"TPAY");
+        assert_eq!(&encoded[4..6], &2u16.to_le_bytes());
+        assert_eq!(
+            Payment::decode_versioned(&mut &encoded[..]).unwrap(),
+            payment
+        );
+    }
+
+    #[test]
+    fn versioned_upgrade() {
+        let old = envelope(*b"TPAY", 1, PaymentV1 { amount: 7 }.encode());
+        assert_eq!(
+            Payment::decode_versioned(&mut &old[..]).unwrap(),
+            Payment {
+                amount: 7,
+                memo: Vec::new()
+            }
+        );
+
+        // Versions without an upgrade path are rejected.
+        let older = envelope(*b"TPAY", 0, 7u32.encode());
+        assert!(Payment::decode_versioned(&mut &older[..]).is_err());
+        let receipt = envelope(*b"TRCP", 0, Receipt(1).encode());
+        assert!(Receipt::decode_versioned(&mut &receipt[..]).is_err());
+    }
+
+    #[test]
+    fn versioned_rejects_invalid_envelopes() {
+        let payment = Payment {
+            amount: 42,
+            memo: Vec::new(),
+        };
+
+        // Unknown version.
+        let newer = envelope(*b"TPAY", 3, payment.encode());
+        assert!(Payment::decode_versioned(&mut &newer[..]).is_err());
+
+        // Wrong type tag.
+        let receipt = Receipt(42).encode_versioned();
+        assert!(Payment::decode_versioned(&mut &receipt[..]).is_err());
+
+        // Trailing bytes inside the payload.
+        let mut payload = payment.encode();
+        payload.push(0);
+        let trailing = envelope(*b"TPAY", 2, payload);
+        assert!(Payment::decode_versioned(&mut &trailing[..]).is_err());
+
+        // An unversioned encoding.
+        assert!(Payment::decode_versioned(&mut &payment.encode()[..]).is_err());
+    }
+}
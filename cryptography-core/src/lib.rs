@@ -15,3 +15,6 @@ pub mod cdd_claim;
 pub mod codec_wrapper;
 
 pub mod asset_proofs;
+
+#[cfg(feature = "serde")]
+pub mod serde_hex;
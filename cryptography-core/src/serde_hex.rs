@@ -0,0 +1,342 @@
+//! Human-readable serde representations of the cryptographic primitives.
+//!
+//! With the `serde` feature of `curve25519_dalek` and `bulletproofs`, points, scalars and
+//! range proofs serialize as sequences of bytes, which are hard to read and to process
+//! outside of Rust. The fields of these types are annotated with
+//! `#[serde(with = "cryptography_core::serde_hex")]` instead, which serializes them as
+//! lowercase hex strings:
+//! - a `Scalar` as its 32 canonical bytes,
+//! - a `RistrettoPoint` or a `CompressedRistretto` as its 32 compressed bytes,
+//! - a `RangeProof` as the output of `RangeProof::to_bytes`,
+//! - a byte array as its bytes,
+//! - vectors and pairs of the above as arrays of hex strings.
+//!
+//! Deserialization accepts both lowercase and uppercase digits, and rejects
+//! non-canonical scalars, invalid points, and invalid range proofs.
+//! The formats are described by the JSON schema in the `schemas` directory.
+
+use crate::asset_proofs::ASSET_ID_LEN;
+
+use bulletproofs::RangeProof;
+use curve25519_dalek::{
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use sp_std::{fmt, prelude::*, str};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// A type with a hex string representation.
+pub trait HexSerde: Sized {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+/// Serializes `value` with its hex representation. Used by `#[serde(with = "...")]`.
+pub fn serialize<T: HexSerde, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize_hex(serializer)
+}
+
+/// Deserializes a value from its hex representation. Used by `#[serde(with = "...")]`.
+pub fn deserialize<'de, T: HexSerde, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_hex(deserializer)
+}
+
+// ------------------------------------------------------------------------
+// Hex Strings
+// ------------------------------------------------------------------------
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut hex = Vec::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        hex.push(HEX_DIGITS[(byte >> 4) as usize]);
+        hex.push(HEX_DIGITS[(byte & 0x0f) as usize]);
+    }
+    serializer.serialize_str(str::from_utf8(&hex).map_err(ser::Error::custom)?)
+}
+
+fn hex_digit(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+struct HexVisitor;
+
+impl<'de> de::Visitor<'de> for HexVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        if value.len() % 2 != 0 {
+            return Err(E::invalid_length(value.len(), &self));
+        }
+        value
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| Some(hex_digit(pair[0])? << 4 | hex_digit(pair[1])?))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_str(HexVisitor)
+}
+
+fn deserialize_array_32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+    let bytes = deserialize_bytes(deserializer)?;
+    if bytes.len() != 32 {
+        return Err(de::Error::invalid_length(bytes.len(), &"32 bytes"));
+    }
+    let mut array = [0u8; 32];
+    array.copy_from_slice(&bytes);
+    Ok(array)
+}
+
+// ------------------------------------------------------------------------
+// Primitives
+// ------------------------------------------------------------------------
+
+impl HexSerde for Scalar {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_bytes(), serializer)
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Scalar::from_canonical_bytes(deserialize_array_32(deserializer)?)
+            .ok_or_else(|| de::Error::custom("non-canonical scalar"))
+    }
+}
+
+impl HexSerde for RistrettoPoint {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.compress().as_bytes(), serializer)
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CompressedRistretto(deserialize_array_32(deserializer)?)
+            .decompress()
+            .ok_or_else(|| de::Error::custom("invalid compressed Ristretto point"))
+    }
+}
+
+impl HexSerde for CompressedRistretto {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_bytes(), serializer)
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let point = CompressedRistretto(deserialize_array_32(deserializer)?);
+        point
+            .decompress()
+            .ok_or_else(|| de::Error::custom("invalid compressed Ristretto point"))?;
+        Ok(point)
+    }
+}
+
+impl HexSerde for RangeProof {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self.to_bytes(), serializer)
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RangeProof::from_bytes(&deserialize_bytes(deserializer)?)
+            .map_err(|_| de::Error::custom("invalid range proof"))
+    }
+}
+
+macro_rules! impl_hex_serde_for_byte_array {
+    ($($len:expr),*) => {
+        $(
+            impl HexSerde for [u8; $len] {
+                fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serialize_bytes(self, serializer)
+                }
+
+                fn deserialize_hex<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Self, D::Error> {
+                    let bytes = deserialize_bytes(deserializer)?;
+                    if bytes.len() != $len {
+                        return Err(de::Error::invalid_length(bytes.len(), &stringify!($len bytes)));
+                    }
+                    let mut array = [0u8; $len];
+                    array.copy_from_slice(&bytes);
+                    Ok(array)
+                }
+            }
+        )*
+    };
+}
+
+impl_hex_serde_for_byte_array!(ASSET_ID_LEN, 32);
+
+// ------------------------------------------------------------------------
+// Collections
+// ------------------------------------------------------------------------
+
+/// Serializes a reference with its hex representation, as an element of a collection.
+struct Hex<'a, T>(&'a T);
+
+impl<'a, T: HexSerde> Serialize for Hex<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_hex(serializer)
+    }
+}
+
+/// Deserializes an element of a collection from its hex representation.
+struct HexOwned<T>(T);
+
+impl<'de, T: HexSerde> Deserialize<'de> for HexOwned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_hex(deserializer).map(HexOwned)
+    }
+}
+
+impl<T: HexSerde> HexSerde for Vec<T> {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Hex))
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = Vec::<HexOwned<T>>::deserialize(deserializer)?;
+        Ok(elements.into_iter().map(|element| element.0).collect())
+    }
+}
+
+impl<T: HexSerde> HexSerde for [T; 2] {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Hex))
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let elements = Vec::<HexOwned<T>>::deserialize(deserializer)?;
+        let length = elements.len();
+        let mut elements = elements.into_iter();
+        match (elements.next(), elements.next(), elements.next()) {
+            (Some(first), Some(second), None) => Ok([first.0, second.0]),
+            _ => Err(de::Error::invalid_length(length, &"2 elements")),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::asset_proofs::range_proof::InRangeProof;
+    use rand::{rngs::StdRng, SeedableRng};
+    use serde_json::json;
+    use wasm_bindgen_test::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        #[serde(with = "crate::serde_hex")]
+        scalar: Scalar,
+        #[serde(with = "crate::serde_hex")]
+        point: RistrettoPoint,
+        #[serde(with = "crate::serde_hex")]
+        compressed: CompressedRistretto,
+        #[serde(with = "crate::serde_hex")]
+        scalars: Vec<Scalar>,
+        #[serde(with = "crate::serde_hex")]
+        pair: [RistrettoPoint; 2],
+        #[serde(with = "crate::serde_hex")]
+        id: [u8; ASSET_ID_LEN],
+    }
+
+    fn sample() -> Sample {
+        let mut rng = StdRng::from_seed([42u8; 32]);
+        let point = RistrettoPoint::random(&mut rng);
+        Sample {
+            scalar: Scalar::from(1u8),
+            point,
+            compressed: point.compress(),
+            scalars: vec![Scalar::from(2u8), Scalar::from(3u8)],
+            pair: [point, point + point],
+            id: [0xab; ASSET_ID_LEN],
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn hex_round_trip() {
+        let sample = sample();
+        let value = serde_json::to_value(&sample).unwrap();
+
+        let point_hex = hex::encode(sample.point.compress().as_bytes());
+        assert_eq!(
+            value["scalar"],
+            json!("0100000000000000000000000000000000000000000000000000000000000000")
+        );
+        assert_eq!(value["point"], json!(point_hex));
+        assert_eq!(value["compressed"], json!(point_hex));
+        assert_eq!(value["scalars"].as_array().unwrap().len(), 2);
+        assert_eq!(value["id"], json!("abababababababababababab"));
+
+        let decoded: Sample = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(decoded, sample);
+
+        // Uppercase digits are accepted.
+        let mut upper = value;
+        upper["point"] = json!(point_hex.to_uppercase());
+        assert_eq!(serde_json::from_value::<Sample>(upper).unwrap(), sample);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn hex_rejects_invalid_values() {
+        let value = serde_json::to_value(&sample()).unwrap();
+        let invalid = |field: &str, field_value: serde_json::Value| {
+            let mut value = value.clone();
+            value[field] = field_value;
+            serde_json::from_value::<Sample>(value).is_err()
+        };
+
+        // Odd lengths, wrong lengths, and non-hex digits.
+        assert!(invalid("scalar", json!("010")));
+        assert!(invalid("scalar", json!("01")));
+        assert!(invalid("id", json!("ab")));
+        assert!(invalid("scalar", json!("zz".repeat(32))));
+        // A non-canonical scalar: the group order plus one.
+        assert!(invalid(
+            "scalar",
+            json!("eed3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010")
+        ));
+        // An invalid point.
+        assert!(invalid("point", json!("ff".repeat(32))));
+        assert!(invalid("compressed", json!("ff".repeat(32))));
+        // A pair with three elements.
+        let point = value["point"].clone();
+        assert!(invalid("pair", json!([point, point, point])));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn hex_range_proof() {
+        #[derive(Serialize, Deserialize)]
+        struct Proof(#[serde(with = "crate::serde_hex")] RangeProof);
+
+        let mut rng = StdRng::from_seed([7u8; 32]);
+        let proof = InRangeProof::build(&mut rng).response;
+        let json = serde_json::to_string(&Proof(proof.clone())).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(proof.to_bytes())));
+
+        let decoded: Proof = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.0.to_bytes(), proof.to_bytes());
+        assert!(serde_json::from_str::<Proof>("\"00\"").is_err());
+    }
+}
@@ -66,14 +66,14 @@ fn bench_transaction_validator(
 ) {
     let label = "MERCAT Transaction: Validator".to_string();
 
-    let indexed_transaction: Vec<((String, u32), InitializedAssetTx)> = (MIN_ISSUED_AMOUNT_ORDER
-        ..MAX_ISSUED_AMOUNT_ORDER)
-        .map(|i| {
-            let amount = 10u32.pow(i);
-            (format!("issued_amount ({:?})", amount), amount)
-        })
-        .zip(transactions)
-        .collect();
+    let indexed_transaction: Vec<((String, Balance), InitializedAssetTx)> =
+        (MIN_ISSUED_AMOUNT_ORDER..MAX_ISSUED_AMOUNT_ORDER)
+            .map(|i| {
+                let amount = 10u64.pow(i);
+                (format!("issued_amount ({:?})", amount), amount)
+            })
+            .zip(transactions)
+            .collect();
 
     c.bench_function_over_inputs(
         &label,
@@ -104,8 +104,8 @@ fn bench_asset_transaction(c: &mut Criterion) {
     let (issuer_account, issuer_init_balance) =
         utility::create_account_with_amount(&mut rng, &asset_id, &valid_asset_ids, 0);
 
-    let issued_amounts: Vec<u32> = (MIN_ISSUED_AMOUNT_ORDER..MAX_ISSUED_AMOUNT_ORDER)
-        .map(|i| 10u32.pow(i))
+    let issued_amounts: Vec<Balance> = (MIN_ISSUED_AMOUNT_ORDER..MAX_ISSUED_AMOUNT_ORDER)
+        .map(|i| 10u64.pow(i))
         .collect();
 
     // Initialization
@@ -1,6 +1,6 @@
 mod utility;
 use criterion::{criterion_group, criterion_main, Criterion};
-use cryptography_core::asset_proofs::AssetId;
+use cryptography_core::asset_proofs::{AssetId, Balance};
 use mercat::{
     account::convert_asset_ids,
     transaction::{CtxMediator, CtxReceiver, CtxSender, TransactionValidator},
@@ -17,7 +17,7 @@ const MIN_SENDER_BALANCE_ORDER: u32 = 1;
 const MAX_SENDER_BALANCE_ORDER: u32 = 7;
 
 // The receiver's initial balance.
-const RECEIVER_INIT_BALANCE: u32 = 10000;
+const RECEIVER_INIT_BALANCE: Balance = 10000;
 
 // The size of the valid asset id set.
 const MAX_ASSET_ID_INDEX: u32 = 1000000;
@@ -38,9 +38,9 @@ fn bench_transaction_sender(
     let rcvr_pub_account_cloned = rcvr_pub_account.clone();
     let sender_account_cloned = sender_account.clone();
 
-    let indexed_transaction: Vec<(u32, EncryptedAmount)> = (MIN_SENDER_BALANCE_ORDER
+    let indexed_transaction: Vec<(Balance, EncryptedAmount)> = (MIN_SENDER_BALANCE_ORDER
         ..MAX_SENDER_BALANCE_ORDER)
-        .map(|i| 10u32.pow(i))
+        .map(|i| 10u64.pow(i))
         .zip(sender_balances)
         .collect();
 
@@ -53,8 +53,9 @@ fn bench_transaction_sender(
                     .create_transaction(
                         &sender_account_cloned,
                         sender_balance,
+                        *amount,
                         &rcvr_pub_account_cloned,
-                        &mediator_pub_key.clone(),
+                        &[mediator_pub_key],
                         &[],
                         *amount,
                         &mut rng,
@@ -73,8 +74,9 @@ fn bench_transaction_sender(
                 .create_transaction(
                     &sender_account.clone(),
                     sender_balance,
+                    *amount,
                     &rcvr_pub_account,
-                    &mediator_pub_key.clone(),
+                    &[mediator_pub_key],
                     &[],
                     *amount,
                     &mut rng,
@@ -93,9 +95,9 @@ fn bench_transaction_receiver(
     let mut rng = thread_rng();
     let receiver_account_cloned = receiver_account.clone();
 
-    let indexed_transaction: Vec<(u32, InitializedTransferTx)> = (MIN_SENDER_BALANCE_ORDER
+    let indexed_transaction: Vec<(Balance, InitializedTransferTx)> = (MIN_SENDER_BALANCE_ORDER
         ..MAX_SENDER_BALANCE_ORDER)
-        .map(|i| 10u32.pow(i))
+        .map(|i| 10u64.pow(i))
         .zip(transactions)
         .collect();
 
@@ -146,7 +148,7 @@ fn bench_transaction_mediator(
 
     let indexed_transaction: Vec<((String, EncryptedAmount), FinalizedTransferTx)> =
         (MIN_SENDER_BALANCE_ORDER..MAX_SENDER_BALANCE_ORDER)
-            .map(|i| format!("initial_balance ({:?})", 10u32.pow(i)))
+            .map(|i| format!("initial_balance ({:?})", 10u64.pow(i)))
             .zip(sender_pub_balances)
             .zip(transactions)
             .collect();
@@ -159,7 +161,7 @@ fn bench_transaction_mediator(
                 let mediator = CtxMediator;
                 mediator
                     .justify_transaction(
-                        tx.clone(),
+                        tx.clone().into(),
                         &mediator_account_cloned.encryption_key,
                         &sender_pub_account_cloned,
                         sender_balance,
@@ -180,7 +182,7 @@ fn bench_transaction_mediator(
             let mediator = CtxMediator;
             mediator
                 .justify_transaction(
-                    tx.clone(),
+                    tx.clone().into(),
                     &mediator_account.encryption_key,
                     &sender_pub_account,
                     sender_balance,
@@ -200,13 +202,13 @@ fn bench_transaction_validator(
     sender_pub_balances: Vec<EncryptedAmount>,
     receiver_pub_account: PubAccount,
     transactions: Vec<JustifiedTransferTx>,
+    mediator_pub_key: EncryptionPubKey,
 ) {
     let label = "MERCAT Transaction: Validator".to_string();
-    let mut rng = thread_rng();
 
     let indexed_transaction: Vec<((String, EncryptedAmount), JustifiedTransferTx)> =
         (MIN_SENDER_BALANCE_ORDER..MAX_SENDER_BALANCE_ORDER)
-            .map(|i| format!("initial_balance ({:?})", 10u32.pow(i)))
+            .map(|i| format!("initial_balance ({:?})", 10u64.pow(i)))
             .zip(sender_pub_balances)
             .zip(transactions)
             .collect();
@@ -223,7 +225,8 @@ fn bench_transaction_validator(
                         sender_balance,
                         &receiver_pub_account,
                         &[],
-                        &mut rng,
+                        &[mediator_pub_key],
+                        1,
                     )
                     .unwrap();
             })
@@ -255,7 +258,7 @@ fn bench_transaction(c: &mut Criterion) {
     let sender_balances: Vec<EncryptedAmount> = (MIN_SENDER_BALANCE_ORDER
         ..MAX_SENDER_BALANCE_ORDER)
         .map(|i| {
-            let value = 10u32.pow(i);
+            let value = 10u64.pow(i);
             utility::issue_assets(&mut rng, &sender_pub_account, &sender_init_balance, value)
         })
         .collect();
@@ -291,6 +294,7 @@ fn bench_transaction(c: &mut Criterion) {
         sender_balances,
         receiver_account.public,
         justified_transaction,
+        enc_pub_key,
     );
 }
 
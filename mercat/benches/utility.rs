@@ -1,5 +1,5 @@
 use cryptography_core::{
-    asset_proofs::{AssetId, CommitmentWitness, ElgamalSecretKey},
+    asset_proofs::{AssetId, Balance, CommitmentWitness, ElgamalSecretKey},
     Scalar,
 };
 use mercat::{
@@ -13,7 +13,7 @@ pub fn issue_assets<R: RngCore + CryptoRng>(
     rng: &mut R,
     pub_account: &PubAccount,
     init_balance: &EncryptedAmount,
-    amount: u32,
+    amount: Balance,
 ) -> EncryptedAmount {
     let (_, encrypted_amount) = pub_account
         .owner_enc_pub_key
@@ -44,7 +44,7 @@ pub fn create_account_with_amount<R: RngCore + CryptoRng>(
     rng: &mut R,
     asset_id: &AssetId,
     valid_asset_ids: &Vec<Scalar>,
-    initial_amount: u32,
+    initial_amount: Balance,
 ) -> (Account, EncryptedAmount) {
     let secret_account = gen_keys(rng, asset_id);
 
@@ -1,5 +1,6 @@
+use cryptography_core::asset_proofs::Balance;
 use log::info;
-use mercat_common::{gen_seed, save_config};
+use mercat_common::{gen_seed, keystore::PasswordSource, save_config};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -23,6 +24,13 @@ pub struct CreateAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// An asset ticker name which is a string of at most 12 characters.
     /// In these test CLIs, the unique account id is created from the pair of username and ticker.
     #[structopt(
@@ -40,6 +48,15 @@ pub struct CreateAccountInfo {
     )]
     pub seed: Option<String>,
 
+    /// An optional path to a file that holds the BIP39 mnemonic of the user's wallet.
+    /// When it is given, the keys are derived from the mnemonic, and can be restored from it later.
+    #[structopt(
+        parse(from_os_str),
+        long,
+        help = "Path to a file that holds the BIP39 mnemonic to derive the keys from. If not provided, the keys will be chosen at random."
+    )]
+    pub mnemonic_file: Option<PathBuf>,
+
     /// An optional path to save the config used for this experiment.
     #[structopt(
         parse(from_os_str),
@@ -82,6 +99,13 @@ pub struct DecryptAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// An asset ticker name which is a string of at most 12 characters.
     /// In these test CLIs, the unique account id is created from the pair of username and ticker.
     #[structopt(
@@ -117,7 +141,7 @@ pub struct IssueAssetInfo {
 
     /// Amount to issue.
     #[structopt(short, long, help = "The amount of assets to issue.")]
-    pub amount: u32,
+    pub amount: Balance,
 
     /// The directory that will serve as the database of the on/off-chain data and will be used
     /// to save and load the data that in a real execution would be written to the on/off the
@@ -131,6 +155,13 @@ pub struct IssueAssetInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The issuer's name. An account must have already been created for this user.
     #[structopt(short, long, help = "The name of the issuer.")]
     pub issuer: String,
@@ -184,7 +215,7 @@ pub struct CreateTransactionInfo {
 
     /// Amount to transfer.
     #[structopt(short, long, help = "The amount of assets to transfer.")]
-    pub amount: u32,
+    pub amount: Balance,
 
     /// The directory that will serve as the database of the on/off-chain data and will be used
     /// to save and load the data that in a real execution would be written to the on/off the
@@ -198,6 +229,13 @@ pub struct CreateTransactionInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The sender's name. An account must have already been created for this user.
     #[structopt(long, help = "The sender's name.")]
     pub sender: String,
@@ -260,7 +298,7 @@ pub struct FinalizeTransactionInfo {
 
     /// The expected amount to receive.
     #[structopt(short, long, help = "The expected amount to receive.")]
-    pub amount: u32,
+    pub amount: Balance,
 
     /// The directory that will serve as the database of the on/off-chain data and will be used
     /// to save and load the data that in a real execution would be written to the on/off the
@@ -274,6 +312,13 @@ pub struct FinalizeTransactionInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     // TODO(CRYP-110)
     // Depending on how we decide to name transaction files, we may or may not need the sender's name.
     /// The sender's name. An account must have already been created for this user.
@@ -304,6 +349,39 @@ pub struct FinalizeTransactionInfo {
     pub cheat: bool,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
+pub struct MigrateKeystoreInfo {
+    /// The directory that will serve as the database of the on/off-chain data and will be used
+    /// to save and load the data that in a real execution would be written to the on/off the
+    /// blockchain. Defaults to the current directory. This directory will have two main
+    /// sub-directories: `on-chain` and `off-chain`.
+    #[structopt(
+        parse(from_os_str),
+        help = "The directory to load and save the input and output files. Defaults to current directory.",
+        short,
+        long
+    )]
+    pub db_dir: Option<PathBuf>,
+
+    /// The source of the password that encrypts the secret accounts.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`."
+    )]
+    pub password_source: PasswordSource,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
+pub struct GenerateMnemonicInfo {
+    /// The path of the file to save the new mnemonic to. The file must not exist yet.
+    #[structopt(
+        parse(from_os_str),
+        long,
+        help = "Path to the file to save the new BIP39 mnemonic to. An existing file is never overwritten."
+    )]
+    pub mnemonic_file: PathBuf,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
 pub enum CLI {
     /// Create a MERCAT account using command line arguments.
@@ -326,6 +404,12 @@ pub enum CLI {
 
     /// Decrypt the account balance.
     Decrypt(DecryptAccountInfo),
+
+    /// Encrypt the plain text secret accounts of the `off-chain` directory with a password.
+    MigrateKeystore(MigrateKeystoreInfo),
+
+    /// Generate the BIP39 mnemonic of a new wallet, to be used with `--mnemonic-file`.
+    GenerateMnemonic(GenerateMnemonicInfo),
 }
 
 pub fn parse_input() -> CLI {
@@ -342,8 +426,10 @@ pub fn parse_input() -> CLI {
             let cfg = CreateAccountInfo {
                 save_config: cfg.save_config.clone(),
                 seed,
+                mnemonic_file: cfg.mnemonic_file,
                 ticker: cfg.ticker,
                 db_dir,
+                password_source: cfg.password_source,
                 user: cfg.user.clone(),
                 cheat: cfg.cheat,
                 tx_id: cfg.tx_id,
@@ -380,6 +466,7 @@ pub fn parse_input() -> CLI {
             let cfg = DecryptAccountInfo {
                 ticker: cfg.ticker,
                 db_dir,
+                password_source: cfg.password_source,
                 user: cfg.user,
             };
 
@@ -403,6 +490,7 @@ pub fn parse_input() -> CLI {
                 seed,
                 amount: cfg.amount,
                 db_dir,
+                password_source: cfg.password_source,
                 issuer: cfg.issuer,
                 auditors: cfg.auditors,
                 save_config: cfg.save_config.clone(),
@@ -433,6 +521,7 @@ pub fn parse_input() -> CLI {
                 seed,
                 amount: cfg.amount,
                 db_dir,
+                password_source: cfg.password_source,
                 sender: cfg.sender,
                 receiver: cfg.receiver,
                 mediator: cfg.mediator,
@@ -465,6 +554,7 @@ pub fn parse_input() -> CLI {
                 seed,
                 amount: cfg.amount,
                 db_dir,
+                password_source: cfg.password_source,
                 sender: cfg.sender,
                 receiver: cfg.receiver,
                 save_config: cfg.save_config.clone(),
@@ -482,5 +572,30 @@ pub fn parse_input() -> CLI {
 
             CLI::FinalizeTransaction(cfg)
         }
+
+        CLI::MigrateKeystore(cfg) => {
+            let db_dir = cfg.db_dir.clone().or_else(|| std::env::current_dir().ok());
+
+            let cfg = MigrateKeystoreInfo {
+                db_dir,
+                password_source: cfg.password_source,
+            };
+
+            info!(
+                "Parsed the following config from the command line:\n{:#?}",
+                cfg
+            );
+
+            CLI::MigrateKeystore(cfg)
+        }
+
+        CLI::GenerateMnemonic(cfg) => {
+            info!(
+                "Parsed the following config from the command line:\n{:#?}",
+                cfg
+            );
+
+            CLI::GenerateMnemonic(cfg)
+        }
     }
 }
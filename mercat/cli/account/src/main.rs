@@ -12,6 +12,8 @@ use mercat_common::{
     debug_decrypt_account_balance,
     errors::Error,
     init_print_logger,
+    keystore::process_migrate_keystore,
+    process_generate_mnemonic,
 };
 use metrics::timing;
 use std::time::Instant;
@@ -29,7 +31,15 @@ fn main() {
         CLI::Create(cfg) => {
             let db_dir = cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap();
             process_create_account(
-                cfg.seed, db_dir, cfg.ticker, cfg.user, cfg.stdout, cfg.tx_id, cfg.cheat,
+                cfg.seed,
+                cfg.mnemonic_file,
+                cfg.password_source,
+                db_dir,
+                cfg.ticker,
+                cfg.user,
+                cfg.stdout,
+                cfg.tx_id,
+                cfg.cheat,
             )
             .unwrap()
         }
@@ -39,12 +49,14 @@ fn main() {
             debug_decrypt_account_balance(
                 cfg.user,
                 cfg.ticker,
-                cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap()
+                cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+                cfg.password_source,
             )
             .unwrap()
         ),
         CLI::Issue(cfg) => process_issue_asset(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.issuer,
             &cfg.auditors,
@@ -57,6 +69,7 @@ fn main() {
         .unwrap(),
         CLI::CreateTransaction(cfg) => process_create_tx(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.sender,
             cfg.receiver,
@@ -71,6 +84,7 @@ fn main() {
         .unwrap(),
         CLI::FinalizeTransaction(cfg) => process_finalize_tx(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.sender,
             cfg.receiver,
@@ -81,6 +95,12 @@ fn main() {
             cfg.cheat,
         )
         .unwrap(),
+        CLI::MigrateKeystore(cfg) => process_migrate_keystore(
+            cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+            cfg.password_source,
+        )
+        .unwrap(),
+        CLI::GenerateMnemonic(cfg) => process_generate_mnemonic(cfg.mnemonic_file).unwrap(),
     };
     info!("The program finished successfully.");
 }
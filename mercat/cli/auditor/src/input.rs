@@ -1,5 +1,5 @@
 use log::info;
-use mercat_common::{gen_seed, save_config};
+use mercat_common::{gen_seed, keystore::PasswordSource, save_config};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -33,6 +33,13 @@ pub struct CreateAuditorAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
     /// The seed can be found inside the logs.
     #[structopt(
@@ -42,6 +49,15 @@ pub struct CreateAuditorAccountInfo {
     )]
     pub seed: Option<String>,
 
+    /// An optional path to a file that holds the BIP39 mnemonic of the user's wallet.
+    /// When it is given, the keys are derived from the mnemonic, and can be restored from it later.
+    #[structopt(
+        parse(from_os_str),
+        long,
+        help = "Path to a file that holds the BIP39 mnemonic to derive the keys from. If not provided, the keys will be chosen at random."
+    )]
+    pub mnemonic_file: Option<PathBuf>,
+
     /// An optional path to save the config used for this experiment.
     #[structopt(
         parse(from_os_str),
@@ -65,6 +81,13 @@ pub struct AuditTransactionInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The transaction name.
     /// This name must be the same as the one used in either the initialize the asset issuance,
     /// or the tranfer init.
@@ -112,7 +135,9 @@ pub fn parse_input() -> Result<CLI, confy::ConfyError> {
             let cfg = CreateAuditorAccountInfo {
                 save_config: cfg.save_config.clone(),
                 seed,
+                mnemonic_file: cfg.mnemonic_file,
                 db_dir,
+                password_source: cfg.password_source,
                 user: cfg.user,
                 user_id: cfg.user_id,
             };
@@ -134,6 +159,7 @@ pub fn parse_input() -> Result<CLI, confy::ConfyError> {
 
             let cfg = AuditTransactionInfo {
                 db_dir,
+                password_source: cfg.password_source,
                 tx_name: cfg.tx_name,
                 auditor: cfg.auditor,
                 save_config: cfg.save_config.clone(),
@@ -26,6 +26,8 @@ fn main() {
     match args {
         CLI::Create(cfg) => process_create_auditor(
             cfg.seed.expect("Empty seed!"),
+            cfg.mnemonic_file,
+            cfg.password_source,
             cfg.db_dir.expect("Empty database directory"),
             cfg.user,
             cfg.user_id,
@@ -35,6 +37,7 @@ fn main() {
             cfg.auditor,
             cfg.tx_name,
             cfg.db_dir.expect("Empty database directory"),
+            cfg.password_source,
         )
         .unwrap(),
     };
@@ -1,11 +1,16 @@
 use crate::{
-    account_create_transaction_file, create_rng_from_seed, errors::Error, get_asset_ids,
-    non_empty_account_id, save_object, update_account_map, user_secret_account_file,
-    OrderedPubAccountTx, OrderingState, PrintableAccountId, COMMON_OBJECTS_DIR, OFF_CHAIN_DIR,
-    ON_CHAIN_DIR,
+    account_create_transaction_file, create_rng_from_seed,
+    errors::Error,
+    get_asset_ids,
+    keystore::{read_password, save_secret_object, PasswordSource},
+    load_master_key, non_empty_account_id, save_object, update_account_map,
+    user_secret_account_file, OrderedPubAccountTx, OrderingState, PrintableAccountId,
+    COMMON_OBJECTS_DIR, ON_CHAIN_DIR,
 };
 use codec::Encode;
-use cryptography_core::asset_proofs::{asset_id_from_ticker, CommitmentWitness, ElgamalSecretKey};
+use cryptography_core::asset_proofs::{
+    asset_id_from_ticker, CommitmentWitness, DerivationPath, ElgamalSecretKey, ExtendedSecretKey,
+};
 use curve25519_dalek::scalar::Scalar;
 use log::{error, info};
 use mercat::{account::AccountCreator, AccountCreatorInitializer, EncryptionKeys, SecAccount};
@@ -15,6 +20,8 @@ use std::{path::PathBuf, time::Instant};
 
 pub fn process_create_account(
     seed: Option<String>,
+    mnemonic_file: Option<PathBuf>,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     ticker: String,
     user: String,
@@ -24,9 +31,13 @@ pub fn process_create_account(
 ) -> Result<(), Error> {
     // Setup the rng.
     let mut rng = create_rng_from_seed(seed)?;
+    let master_key = mnemonic_file
+        .map(|path| load_master_key(&path))
+        .transpose()?;
+    let password = read_password(password_source)?;
 
     // Create the account.
-    let secret_account = create_secret_account(&mut rng, ticker.clone())?;
+    let secret_account = create_secret_account(&mut rng, ticker.clone(), master_key.as_ref())?;
     let valid_asset_ids = get_asset_ids(db_dir.clone())?;
 
     let create_account_timer = Instant::now();
@@ -64,12 +75,12 @@ pub fn process_create_account(
 
     // Save the artifacts to file.
     let save_to_file_timer = Instant::now();
-    save_object(
+    save_secret_object(
         db_dir.clone(),
-        OFF_CHAIN_DIR,
         &user,
         &user_secret_account_file(&ticker),
         &secret_account,
+        password.as_ref(),
     )?;
 
     let account_id = account_tx.pub_account.enc_asset_id;
@@ -104,17 +115,30 @@ pub fn process_create_account(
 fn create_secret_account<R: RngCore + CryptoRng>(
     rng: &mut R,
     ticker_id: String,
+    master_key: Option<&ExtendedSecretKey>,
 ) -> Result<SecAccount, Error> {
-    let elg_secret = ElgamalSecretKey::new(Scalar::random(rng));
+    let asset_id =
+        asset_id_from_ticker(&ticker_id).map_err(|error| Error::LibraryError { error })?;
+
+    // When the account belongs to a wallet, both the keys and the asset id blinding
+    // are derived from the wallet's master key, so that the account can be restored.
+    let (elg_secret, asset_id_blinding) = match master_key {
+        Some(master_key) => {
+            let account_key = master_key.derive_path(&DerivationPath::account(&asset_id));
+            (account_key.secret_key(), account_key.asset_id_blinding())
+        }
+        None => (
+            ElgamalSecretKey::new(Scalar::random(rng)),
+            Scalar::random(rng),
+        ),
+    };
     let elg_pub = elg_secret.get_public_key();
     let enc_keys = EncryptionKeys {
         public: elg_pub,
         secret: elg_secret,
     };
 
-    let asset_id =
-        asset_id_from_ticker(&ticker_id).map_err(|error| Error::LibraryError { error })?;
-    let asset_id_witness = CommitmentWitness::new(asset_id.into(), Scalar::random(rng));
+    let asset_id_witness = CommitmentWitness::new(asset_id.into(), asset_id_blinding);
 
     Ok(SecAccount {
         enc_keys,
@@ -1,25 +1,34 @@
 use crate::{
-    asset_transaction_file, create_rng_from_seed, errors::Error, last_ordering_state, load_object,
-    retrieve_auditors_by_names, save_issue_transaction_name, save_object, user_public_account_file,
-    user_secret_account_file, OrderedAssetInstruction, OrderedPubAccount, OrderingState,
-    COMMON_OBJECTS_DIR, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+    asset_transaction_file, create_rng_from_seed,
+    errors::Error,
+    keystore::{load_secret_object, read_password, PasswordSource},
+    last_ordering_state, load_object, refresh_balance_hint, retrieve_auditors_by_names,
+    save_issue_transaction_name, save_object, save_pending_amount_hint,
+    user_public_account_balance_file, user_public_account_file, user_secret_account_file,
+    OrderedAssetInstruction, OrderedPubAccount, OrderingState, COMMON_OBJECTS_DIR, ON_CHAIN_DIR,
 };
 use codec::Encode;
-use cryptography_core::asset_proofs::{asset_id_from_ticker, CommitmentWitness};
+use cryptography_core::{
+    asset_proofs::{asset_id_from_ticker, Balance, CommitmentWitness},
+    codec_wrapper::Versioned,
+};
 use curve25519_dalek::scalar::Scalar;
 use log::info;
-use mercat::{asset::AssetIssuer, Account, AssetTransactionIssuer, AssetTxState, TxSubstate};
+use mercat::{
+    asset::AssetIssuer, Account, AssetTransactionIssuer, AssetTxState, EncryptedAmount, TxSubstate,
+};
 use metrics::timing;
 use rand::Rng;
 use std::{path::PathBuf, time::Instant};
 
 pub fn process_issue_asset_with_tx_name(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     issuer: String,
     auditors: &[String],
     ticker: String,
-    amount: u32,
+    amount: Balance,
     stdout: bool,
     tx_id: u32,
     tx_name: Option<String>,
@@ -29,22 +38,33 @@ pub fn process_issue_asset_with_tx_name(
         save_issue_transaction_name(tx_id, name, issuer.clone(), ticker.clone(), db_dir.clone())?;
     }
     process_issue_asset(
-        seed, db_dir, issuer, auditors, ticker, amount, stdout, tx_id, cheat,
+        seed,
+        password_source,
+        db_dir,
+        issuer,
+        auditors,
+        ticker,
+        amount,
+        stdout,
+        tx_id,
+        cheat,
     )
 }
 
 pub fn process_issue_asset(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     issuer: String,
     auditors: &[String],
     ticker: String,
-    amount: u32,
+    amount: Balance,
     stdout: bool,
     tx_id: u32,
     cheat: bool,
 ) -> Result<(), Error> {
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
 
     let load_from_file_timer = Instant::now();
     let issuer_ordered_pub_account: OrderedPubAccount = load_object(
@@ -55,11 +75,11 @@ pub fn process_issue_asset(
     )?;
     let issuer_account = Account {
         public: issuer_ordered_pub_account.pub_account,
-        secret: load_object(
+        secret: load_secret_object(
             db_dir.clone(),
-            OFF_CHAIN_DIR,
             &issuer,
             &user_secret_account_file(&ticker),
+            password.as_ref(),
         )?,
     };
     let auditors_accounts = retrieve_auditors_by_names(auditors, db_dir.clone())?;
@@ -114,6 +134,32 @@ pub fn process_issue_asset(
         .initialize_asset_transaction(&issuer_account, &auditors_accounts, amount, &mut rng)
         .map_err(|error| Error::LibraryError { error })?;
 
+    // Record the issued amount on top of the issuer's validated balance, so that the balance
+    // can be decrypted even if it is too large for the default discrete log table.
+    let issuer_account_balance: EncryptedAmount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        &issuer,
+        &user_public_account_balance_file(&ticker),
+    )?;
+    refresh_balance_hint(
+        &issuer,
+        &ticker,
+        &issuer_account.secret.enc_keys,
+        &issuer_account_balance,
+        db_dir.clone(),
+        &mut rng,
+    )?;
+    save_pending_amount_hint(
+        &issuer,
+        &ticker,
+        &issuer_account.public.owner_enc_pub_key,
+        amount,
+        true,
+        db_dir.clone(),
+        &mut rng,
+    )?;
+
     let ordering_state = OrderingState {
         last_processed_tx_counter: issuer_ordered_pub_account.last_processed_tx_counter,
         last_pending_tx_counter: next_pending_tx_counter,
@@ -147,7 +193,7 @@ pub fn process_issue_asset(
     let instruction = OrderedAssetInstruction {
         state,
         ordering_state,
-        data: asset_tx.encode().to_vec(),
+        data: asset_tx.encode_versioned(),
         amount,
         auditors: auditors.to_vec(),
     };
@@ -1,12 +1,16 @@
 use crate::{
     compute_enc_pending_balance, confidential_transaction_file, construct_path,
-    create_rng_from_seed, debug_decrypt, errors::Error, last_ordering_state, load_object,
-    non_empty_account_id, retrieve_auditors_by_names, save_object, save_transfer_transaction_name,
-    user_public_account_balance_file, user_public_account_file, user_secret_account_file,
-    OrderedPubAccount, OrderedTransferInstruction, OrderingState, PrintableAccountId,
-    COMMON_OBJECTS_DIR, MEDIATOR_PUBLIC_ACCOUNT_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+    create_rng_from_seed, decrypt_balance,
+    errors::Error,
+    keystore::{load_secret_object, read_password, PasswordSource},
+    last_ordering_state, load_object, non_empty_account_id, refresh_balance_hint,
+    retrieve_auditors_by_names, save_object, save_pending_amount_hint,
+    save_transfer_transaction_name, user_public_account_balance_file, user_public_account_file,
+    user_secret_account_file, OrderedPubAccount, OrderedTransferInstruction, OrderingState,
+    PrintableAccountId, COMMON_OBJECTS_DIR, MEDIATOR_PUBLIC_ACCOUNT_FILE, ON_CHAIN_DIR,
 };
-use codec::{Decode, Encode};
+use codec::Encode;
+use cryptography_core::{asset_proofs::Balance, codec_wrapper::Versioned};
 use log::{debug, info};
 use mercat::{
     transaction::{CtxReceiver, CtxSender},
@@ -19,13 +23,14 @@ use std::{path::PathBuf, time::Instant};
 
 pub fn process_create_tx_with_tx_name(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     sender: String,
     receiver: String,
     mediator: String,
     auditors: &[String],
     ticker: String,
-    amount: u32,
+    amount: Balance,
     stdout: bool,
     tx_id: u32,
     cheat: bool,
@@ -42,24 +47,37 @@ pub fn process_create_tx_with_tx_name(
         )?;
     }
     process_create_tx(
-        seed, db_dir, sender, receiver, mediator, auditors, ticker, amount, stdout, tx_id, cheat,
+        seed,
+        password_source,
+        db_dir,
+        sender,
+        receiver,
+        mediator,
+        auditors,
+        ticker,
+        amount,
+        stdout,
+        tx_id,
+        cheat,
     )
 }
 
 pub fn process_create_tx(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     sender: String,
     receiver: String,
     mediator: String,
     auditors: &[String],
     ticker: String,
-    amount: u32,
+    amount: Balance,
     stdout: bool,
     tx_id: u32,
     cheat: bool,
 ) -> Result<(), Error> {
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
     let load_from_file_timer = Instant::now();
 
     let sender_ordered_pub_account: OrderedPubAccount = load_object(
@@ -75,11 +93,11 @@ pub fn process_create_tx(
         &user_public_account_balance_file(&ticker),
     )?;
     let sender_account = Account {
-        secret: load_object(
+        secret: load_secret_object(
             db_dir.clone(),
-            OFF_CHAIN_DIR,
             &sender,
             &user_secret_account_file(&ticker),
+            password.as_ref(),
         )?,
         public: sender_ordered_pub_account.pub_account,
     };
@@ -109,6 +127,14 @@ pub fn process_create_tx(
 
     // Calculate the pending
     let calc_pending_state_timer = Instant::now();
+    refresh_balance_hint(
+        &sender,
+        &ticker,
+        &sender_account.secret.enc_keys,
+        &sender_account_balance,
+        db_dir.clone(),
+        &mut rng,
+    )?;
     let last_processed_tx_counter = sender_ordered_pub_account.last_processed_tx_counter;
     let last_processed_account_balance = sender_account_balance;
     let ordering_state = last_ordering_state(
@@ -125,14 +151,16 @@ pub fn process_create_tx(
         last_processed_account_balance,
         db_dir.clone(),
     )?;
+    let sender_balance = decrypt_balance(
+        &sender,
+        &ticker,
+        &sender_account.secret.enc_keys.secret,
+        &pending_balance,
+        db_dir.clone(),
+    )?;
     debug!(
         "------------> initiating transfer tx: {}, pending_balance: {}",
-        tx_id,
-        debug_decrypt(
-            sender_account.public.enc_asset_id,
-            pending_balance,
-            db_dir.clone()
-        )?
+        tx_id, sender_balance
     );
     let next_pending_tx_counter = ordering_state.last_pending_tx_counter + 1;
 
@@ -172,13 +200,23 @@ pub fn process_create_tx(
         .create_transaction(
             &pending_account,
             &pending_balance,
+            sender_balance,
             &receiver_account.pub_account,
-            &mediator_account,
+            &[mediator_account],
             &auditors_accounts,
             amount,
             &mut rng,
         )
         .map_err(|error| Error::LibraryError { error })?;
+    save_pending_amount_hint(
+        &sender,
+        &ticker,
+        &pending_account.public.owner_enc_pub_key,
+        amount,
+        false,
+        db_dir.clone(),
+        &mut rng,
+    )?;
 
     let ordering_state = OrderingState {
         last_processed_tx_counter: sender_ordered_pub_account.last_processed_tx_counter,
@@ -202,7 +240,7 @@ pub fn process_create_tx(
     let instruction = OrderedTransferInstruction {
         state: new_state,
         ordering_state,
-        data: asset_tx.encode().to_vec(),
+        data: asset_tx.encode_versioned(),
         auditors: auditors.to_vec(),
     };
 
@@ -233,16 +271,18 @@ pub fn process_create_tx(
 
 pub fn process_finalize_tx(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     sender: String,
     receiver: String,
     ticker: String,
-    amount: u32,
+    amount: Balance,
     stdout: bool,
     tx_id: u32,
     cheat: bool,
 ) -> Result<(), Error> {
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
     let load_from_file_timer = Instant::now();
     let state = TransferTxState::Initialization(TxSubstate::Started);
 
@@ -253,11 +293,11 @@ pub fn process_finalize_tx(
         &user_public_account_file(&ticker),
     )?;
     let receiver_account = Account {
-        secret: load_object(
+        secret: load_secret_object(
             db_dir.clone(),
-            OFF_CHAIN_DIR,
             &receiver,
             &user_secret_account_file(&ticker),
+            password.as_ref(),
         )?,
         public: receiver_ordered_pub_account.pub_account,
     };
@@ -269,17 +309,18 @@ pub fn process_finalize_tx(
         &confidential_transaction_file(tx_id, &sender, state),
     )?;
 
-    let tx = InitializedTransferTx::decode(&mut &instruction.data[..]).map_err(|error| {
-        Error::ObjectLoadError {
-            error,
-            path: construct_path(
-                db_dir.clone(),
-                ON_CHAIN_DIR,
-                &sender.clone(),
-                &confidential_transaction_file(tx_id, &sender, state),
-            ),
-        }
-    })?;
+    let tx =
+        InitializedTransferTx::decode_versioned(&mut &instruction.data[..]).map_err(|error| {
+            Error::ObjectLoadError {
+                error,
+                path: construct_path(
+                    db_dir.clone(),
+                    ON_CHAIN_DIR,
+                    &sender.clone(),
+                    &confidential_transaction_file(tx_id, &sender, state),
+                ),
+            }
+        })?;
 
     timing!(
         "account.finalize_tx.load_from_file",
@@ -291,7 +332,7 @@ pub fn process_finalize_tx(
     // Calculate the pending
     let calc_pending_state_timer = Instant::now();
     let ordering_state = last_ordering_state(
-        receiver,
+        receiver.clone(),
         receiver_ordered_pub_account.last_processed_tx_counter,
         tx_id,
         db_dir.clone(),
@@ -320,6 +361,32 @@ pub fn process_finalize_tx(
         amount += 1
     }
 
+    // Record the received amount on top of the receiver's validated balance, so that the
+    // balance can be decrypted even if it is too large for the default discrete log table.
+    let receiver_account_balance: EncryptedAmount = load_object(
+        db_dir.clone(),
+        ON_CHAIN_DIR,
+        &receiver,
+        &user_public_account_balance_file(&ticker),
+    )?;
+    refresh_balance_hint(
+        &receiver,
+        &ticker,
+        &receiver_account.secret.enc_keys,
+        &receiver_account_balance,
+        db_dir.clone(),
+        &mut rng,
+    )?;
+    save_pending_amount_hint(
+        &receiver,
+        &ticker,
+        &receiver_account.public.owner_enc_pub_key,
+        amount,
+        true,
+        db_dir.clone(),
+        &mut rng,
+    )?;
+
     // Finalize the transaction.
     let finalize_by_receiver_timer = Instant::now();
     let receiver = CtxReceiver {};
@@ -354,7 +421,7 @@ pub fn process_finalize_tx(
     let instruction = OrderedTransferInstruction {
         state,
         ordering_state,
-        data: asset_tx.encode().to_vec(),
+        data: asset_tx.encode_versioned(),
         auditors: vec![], // TODO
     };
 
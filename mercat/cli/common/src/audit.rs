@@ -1,14 +1,19 @@
 use crate::{
     asset_transaction_audit_result_file, asset_transaction_file,
     confidential_transaction_audit_result_file, confidential_transaction_file, construct_path,
-    create_rng_from_seed, errors::Error, load_object, load_transaction_names, save_object,
-    user_public_account_file, AuditResult, InitializedAssetTx, OrderedAssetInstruction,
-    OrderedPubAccount, TransferInstruction, TxAssetNameIdInfo, TxNameIdInfo, TxTransferNameIdInfo,
-    AUDITOR_PUBLIC_ACCOUNT_FILE, COMMON_OBJECTS_DIR, OFF_CHAIN_DIR, ON_CHAIN_DIR,
-    SECRET_ACCOUNT_FILE,
+    create_rng_from_seed,
+    errors::Error,
+    keystore::{load_secret_object, read_password, save_secret_object, Password, PasswordSource},
+    load_master_key, load_object, load_transaction_names, save_object, user_public_account_file,
+    AuditResult, InitializedAssetTx, OrderedAssetInstruction, OrderedPubAccount,
+    TransferInstruction, TxAssetNameIdInfo, TxNameIdInfo, TxTransferNameIdInfo,
+    AUDITOR_PUBLIC_ACCOUNT_FILE, COMMON_OBJECTS_DIR, ON_CHAIN_DIR, SECRET_ACCOUNT_FILE,
+};
+use codec::Encode;
+use cryptography_core::{
+    asset_proofs::{DerivationPath, ElgamalSecretKey, ExtendedSecretKey},
+    codec_wrapper::Versioned,
 };
-use codec::{Decode, Encode};
-use cryptography_core::asset_proofs::ElgamalSecretKey;
 use curve25519_dalek::scalar::Scalar;
 use log::info;
 use mercat::{
@@ -22,9 +27,15 @@ use std::{path::PathBuf, time::Instant};
 
 fn generate_auditors_keys<R: RngCore + CryptoRng>(
     rng: &mut R,
+    master_key: Option<&ExtendedSecretKey>,
     auditor_id: u8,
 ) -> (AuditorPubAccount, AuditorAccount) {
-    let auditor_elg_secret_key = ElgamalSecretKey::new(Scalar::random(rng));
+    let auditor_elg_secret_key = match master_key {
+        Some(master_key) => master_key
+            .derive_path(&DerivationPath::auditor(auditor_id.into()))
+            .secret_key(),
+        None => ElgamalSecretKey::new(Scalar::random(rng)),
+    };
     let auditor_enc_key = EncryptionKeys {
         public: auditor_elg_secret_key.get_public_key(),
         secret: auditor_elg_secret_key,
@@ -44,16 +55,23 @@ fn generate_auditors_keys<R: RngCore + CryptoRng>(
 
 pub fn process_create_auditor(
     seed: String,
+    mnemonic_file: Option<PathBuf>,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     user: String,
     auditor_id: u8,
 ) -> Result<(), Error> {
     // Setup the rng.
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let master_key = mnemonic_file
+        .map(|path| load_master_key(&path))
+        .transpose()?;
+    let password = read_password(password_source)?;
 
     // Generate keys for the auditor.
     let auditor_key_gen_timer = Instant::now();
-    let (public_account, private_account) = generate_auditors_keys(&mut rng, auditor_id);
+    let (public_account, private_account) =
+        generate_auditors_keys(&mut rng, master_key.as_ref(), auditor_id);
     timing!(
         "auditor.key_gen",
         auditor_key_gen_timer,
@@ -70,12 +88,12 @@ pub fn process_create_auditor(
         &public_account,
     )?;
 
-    save_object(
+    save_secret_object(
         db_dir,
-        OFF_CHAIN_DIR,
         &user,
         SECRET_ACCOUNT_FILE,
         &private_account,
+        password.as_ref(),
     )?;
     info!(
         "CLI log: auditor keys as base64:\n{}\n",
@@ -96,6 +114,7 @@ pub fn process_asset_audit(
     auditor: &String,
     tx_name: &String,
     db_dir: PathBuf,
+    password: Option<&Password>,
 ) -> Result<(), Error> {
     let instruction_path = asset_transaction_file(
         tx_asset_info.tx_id,
@@ -117,19 +136,20 @@ pub fn process_asset_audit(
     )?;
 
     let auditor_account: AuditorAccount =
-        load_object(db_dir.clone(), OFF_CHAIN_DIR, &auditor, SECRET_ACCOUNT_FILE)?;
-
-    let asset_tx = InitializedAssetTx::decode(&mut &instruction.data[..]).map_err(|error| {
-        Error::ObjectLoadError {
-            error,
-            path: construct_path(
-                db_dir.clone(),
-                ON_CHAIN_DIR,
-                COMMON_OBJECTS_DIR,
-                &instruction_path,
-            ),
-        }
-    })?;
+        load_secret_object(db_dir.clone(), &auditor, SECRET_ACCOUNT_FILE, password)?;
+
+    let asset_tx =
+        InitializedAssetTx::decode_versioned(&mut &instruction.data[..]).map_err(|error| {
+            Error::ObjectLoadError {
+                error,
+                path: construct_path(
+                    db_dir.clone(),
+                    ON_CHAIN_DIR,
+                    COMMON_OBJECTS_DIR,
+                    &instruction_path,
+                ),
+            }
+        })?;
 
     let result = AssetAuditor {}.audit_asset_transaction(
         &asset_tx,
@@ -159,6 +179,7 @@ fn process_transfer_audit(
     auditor: &String,
     tx_name: &String,
     db_dir: PathBuf,
+    password: Option<&Password>,
 ) -> Result<(), Error> {
     let instruction_path = confidential_transaction_file(
         tx_transfer_info.tx_id,
@@ -186,19 +207,20 @@ fn process_transfer_audit(
     )?;
 
     let auditor_account: AuditorAccount =
-        load_object(db_dir.clone(), OFF_CHAIN_DIR, &auditor, SECRET_ACCOUNT_FILE)?;
-
-    let asset_tx = JustifiedTransferTx::decode(&mut &instruction.data[..]).map_err(|error| {
-        Error::ObjectLoadError {
-            error,
-            path: construct_path(
-                db_dir.clone(),
-                ON_CHAIN_DIR,
-                COMMON_OBJECTS_DIR,
-                &instruction_path,
-            ),
-        }
-    })?;
+        load_secret_object(db_dir.clone(), &auditor, SECRET_ACCOUNT_FILE, password)?;
+
+    let asset_tx =
+        JustifiedTransferTx::decode_versioned(&mut &instruction.data[..]).map_err(|error| {
+            Error::ObjectLoadError {
+                error,
+                path: construct_path(
+                    db_dir.clone(),
+                    ON_CHAIN_DIR,
+                    COMMON_OBJECTS_DIR,
+                    &instruction_path,
+                ),
+            }
+        })?;
 
     let result = CtxAuditor {}.audit_transaction(
         &asset_tx,
@@ -224,14 +246,28 @@ fn process_transfer_audit(
     result.map_err(|error| Error::LibraryError { error })
 }
 
-pub fn process_audit(auditor: String, tx_name: String, db_dir: PathBuf) -> Result<(), Error> {
+pub fn process_audit(
+    auditor: String,
+    tx_name: String,
+    db_dir: PathBuf,
+    password_source: Option<PasswordSource>,
+) -> Result<(), Error> {
+    let password = read_password(password_source)?;
     let tx_info = load_transaction_names(db_dir.clone())[&tx_name].clone();
     match tx_info {
-        TxNameIdInfo::Asset(tx_asset_info) => {
-            process_asset_audit(tx_asset_info, &auditor, &tx_name, db_dir.clone())
-        }
-        TxNameIdInfo::Transfer(tx_transfer_info) => {
-            process_transfer_audit(tx_transfer_info, &auditor, &tx_name, db_dir.clone())
-        }
+        TxNameIdInfo::Asset(tx_asset_info) => process_asset_audit(
+            tx_asset_info,
+            &auditor,
+            &tx_name,
+            db_dir.clone(),
+            password.as_ref(),
+        ),
+        TxNameIdInfo::Transfer(tx_transfer_info) => process_transfer_audit(
+            tx_transfer_info,
+            &auditor,
+            &tx_name,
+            db_dir.clone(),
+            password.as_ref(),
+        ),
     }
 }
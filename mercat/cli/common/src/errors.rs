@@ -97,8 +97,8 @@ pub enum Error {
     #[fail(display = "Failed to parse the regex: {:?}", reason)]
     RegexError { reason: String },
 
-    /// Balance great than u32
-    #[fail(display = "balance does not fit u32")]
+    /// Balance does not fit the `Balance` type
+    #[fail(display = "balance does not fit u64")]
     BalanceTooBig,
 
     /// There can be only one top level transaction
@@ -161,4 +161,36 @@ pub enum Error {
 
     #[fail(display = "Not implemented, story: {}", story)]
     NotImplemented { story: String },
+
+    /// The password source is not one of `env:<VARIABLE>`, `file:<PATH>`, or `prompt`.
+    #[fail(display = "Invalid password source: {:?}", value)]
+    InvalidPasswordSource { value: String },
+
+    /// The environment variable that should hold the password is not set.
+    #[fail(display = "The password variable {} is not set.", name)]
+    PasswordEnvError { name: String },
+
+    /// An error occurred while reading the password from the terminal.
+    #[fail(display = "Failed to read the password: {:?}", error)]
+    PasswordPromptError { error: std::io::Error },
+
+    /// The secret object is encrypted, but no password was provided.
+    #[fail(display = "The file {:?} is encrypted, a password is required.", path)]
+    PasswordRequired { path: PathBuf },
+
+    /// An error occurred while encrypting a secret object.
+    #[fail(display = "Failed to encrypt the secret object.")]
+    KeystoreEncryptionError,
+
+    /// The password is wrong, or the keystore is corrupted.
+    #[fail(display = "Failed to decrypt the keystore, the password may be wrong.")]
+    KeystoreDecryptionError,
+
+    /// The keystore was created by an unsupported version.
+    #[fail(display = "Unsupported keystore version {}", version)]
+    UnsupportedKeystoreVersion { version: u32 },
+
+    /// The file does not hold a valid BIP39 mnemonic.
+    #[fail(display = "The file {:?} does not hold a valid BIP39 mnemonic.", path)]
+    InvalidMnemonic { path: PathBuf },
 }
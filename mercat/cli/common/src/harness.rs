@@ -12,8 +12,10 @@ use crate::{
     validate::validate_all_pending,
     AuditResult, COMMON_OBJECTS_DIR, ON_CHAIN_DIR,
 };
+use cryptography_core::asset_proofs::Balance;
 use linked_hash_map::LinkedHashMap;
 use log::{error, info, warn};
+use mercat::RejectionReason;
 use rand::Rng;
 use rand::{rngs::StdRng, SeedableRng};
 use rand::{CryptoRng, RngCore};
@@ -96,7 +98,7 @@ pub struct Transfer {
     pub receiver_approves: bool,
     pub mediator: Party,
     pub mediator_approves: bool,
-    pub amount: u32,
+    pub amount: Balance,
     pub ticker: String,
     pub auditors: Vec<Party>,
     pub tx_name: Option<String>,
@@ -141,9 +143,9 @@ impl TryFrom<(u32, String)> for Transfer {
             mediator_approves: caps[7].to_string() == "approve",
             amount: caps[2]
                 .to_string()
-                .parse::<u32>()
+                .parse::<Balance>()
                 .map_err(|_| Error::RegexError {
-                    reason: String::from("failed to convert amount to u32."),
+                    reason: String::from("failed to convert amount to Balance."),
                 })?,
             ticker,
             auditors,
@@ -167,7 +169,7 @@ pub struct Issue {
     pub tx_id: u32,
     pub issuer: Party,
     pub ticker: String,
-    pub amount: u32,
+    pub amount: Balance,
     pub auditors: Vec<Party>,
     pub tx_name: Option<String>,
 }
@@ -209,9 +211,9 @@ impl TryFrom<(u32, String)> for Issue {
             ticker,
             amount: caps[2]
                 .to_string()
-                .parse::<u32>()
+                .parse::<Balance>()
                 .map_err(|_| Error::RegexError {
-                    reason: String::from("failed to convert amount to u32."),
+                    reason: String::from("failed to convert amount to Balance."),
                 })?,
             auditors,
             tx_name,
@@ -272,7 +274,7 @@ impl TryFrom<(u32, String)> for Audit {
 pub struct InputAccount {
     owner: Party,
     ticker: String,
-    balance: u32,
+    balance: Balance,
 }
 
 /// The expected result of an audit.
@@ -379,6 +381,7 @@ impl Transfer {
             info!("Running: {}", value.clone());
             process_create_tx_with_tx_name(
                 seed.clone(),
+                None, // The secret accounts are stored in plain text.
                 chain_db_dir.clone(),
                 sender.clone(),
                 receiver.clone(),
@@ -421,6 +424,7 @@ impl Transfer {
             info!("Running: {}", value.clone());
             process_finalize_tx(
                 seed.clone(),
+                None, // The secret accounts are stored in plain text.
                 chain_db_dir.clone(),
                 sender.clone(),
                 receiver.clone(),
@@ -442,7 +446,7 @@ impl Transfer {
             .map(|auditor| auditor.name.clone())
             .collect();
         let value = format!(
-            "tx-{}: $ mercat-mediator justify-transaction --sender {} --receiver {} --mediator {} --auditors {} --ticker {} --tx-id {} --seed {} --db-dir {} {}",
+            "tx-{}: $ mercat-mediator justify-transaction --sender {} --receiver {} --mediator {} --auditors {} --ticker {} --tx-id {} --seed {} --db-dir {} {} {}",
             self.tx_id,
             self.sender.name,
             self.receiver.name,
@@ -452,6 +456,7 @@ impl Transfer {
             self.tx_id,
             seed,
             path_to_string(&chain_db_dir),
+            reject_flag(!self.mediator_approves),
             cheater_flag(self.mediator.cheater)
         );
         let ticker = self.ticker.clone();
@@ -459,19 +464,24 @@ impl Transfer {
         let receiver = self.receiver.name.clone();
         let mediator = self.mediator.name.clone();
         let tx_id = self.tx_id;
-        let reject = !self.mediator_approves;
+        let reject = if self.mediator_approves {
+            None
+        } else {
+            Some(RejectionReason::PolicyViolation)
+        };
         let cheat = self.mediator.cheater;
 
         Box::new(move || {
             info!("Running: {}", value.clone());
             justify_asset_transfer_transaction(
                 chain_db_dir.clone(),
+                None, // The secret accounts are stored in plain text.
+                seed.clone(),
                 sender.clone(),
                 receiver.clone(),
                 mediator.clone(),
                 &auditors,
                 ticker.clone(),
-                seed.clone(),
                 false, // Do not print the transaction data to stdout.
                 tx_id,
                 reject,
@@ -522,6 +532,8 @@ impl Create {
                         info!("Running: {}", value.clone());
                         process_create_account(
                             Some(seed.clone()),
+                            None, // The keys are chosen at random.
+                            None, // The secret accounts are stored in plain text.
                             chain_db_dir.clone(),
                             ticker.clone(),
                             owner.clone(),
@@ -548,7 +560,14 @@ impl Create {
 
                 Box::new(move || {
                     info!("Running: {}", value.clone());
-                    process_create_mediator(seed.clone(), chain_db_dir.clone(), owner.clone())?;
+                    process_create_mediator(
+                        seed.clone(),
+                        None, // The keys are chosen at random.
+                        None, // The secret accounts are stored in plain text.
+                        chain_db_dir.clone(),
+                        owner.clone(),
+                        0,
+                    )?;
                     Ok(value.clone())
                 })
             }
@@ -569,6 +588,8 @@ impl Create {
                         info!("Running: {}", value.clone());
                         process_create_auditor(
                             seed.clone(),
+                            None, // The keys are chosen at random.
+                            None, // The secret accounts are stored in plain text.
                             chain_db_dir.clone(),
                             owner.clone(),
                             owner_id,
@@ -622,6 +643,7 @@ impl Issue {
             info!("Running: {}", value.clone());
             process_issue_asset_with_tx_name(
                 seed.clone(),
+                None, // The secret accounts are stored in plain text.
                 chain_db_dir.clone(),
                 issuer.clone(),
                 &auditors,
@@ -678,7 +700,12 @@ impl Audit {
 
         Box::new(move || {
             info!("Running: {}", value.clone());
-            process_audit(auditor.clone(), tx_name.clone(), chain_db_dir.clone())?;
+            process_audit(
+                auditor.clone(),
+                tx_name.clone(),
+                chain_db_dir.clone(),
+                None, // The secret accounts are stored in plain text.
+            )?;
             Ok(value.clone())
         })
     }
@@ -796,6 +823,7 @@ impl TestCase {
                             String::from(user),
                             ticker.clone(),
                             self.chain_db_dir.clone(),
+                            None, // The secret accounts are stored in plain text.
                         )?;
                         accounts.insert(InputAccount {
                             owner: Party::try_from((user, PartyKind::Normal))?,
@@ -845,6 +873,14 @@ impl TestCase {
 // ------------------------------------------------------------------------------------------
 // -                                  Utility functions                                     -
 // ------------------------------------------------------------------------------------------
+fn reject_flag(reject: bool) -> String {
+    if reject {
+        String::from("--reject")
+    } else {
+        String::from("")
+    }
+}
+
 fn cheater_flag(is_cheater: bool) -> String {
     if is_cheater {
         String::from("--cheat")
@@ -905,11 +941,10 @@ fn make_empty_normal_accounts(
     }
     Ok((
         transaction_counter,
-        seq
-        //TransactionMode::Sequence {
-        //    repeat: 1,
-        //    steps: seq,
-        //},
+        seq, //TransactionMode::Sequence {
+             //    repeat: 1,
+             //    steps: seq,
+             //},
     ))
 }
 
@@ -1164,7 +1199,7 @@ fn parse_config(path: PathBuf, chain_db_dir: PathBuf) -> Result<TestCase, Error>
                                 ticker.clone()
                             ),
                         })?;
-                    let balance = u32::try_from(balance).map_err(|_| Error::BalanceTooBig)?;
+                    let balance = Balance::try_from(balance).map_err(|_| Error::BalanceTooBig)?;
                     if ticker != "NONE" {
                         accounts_outcome.insert(InputAccount {
                             owner: Party::try_from((owner.as_str(), PartyKind::Normal))?,
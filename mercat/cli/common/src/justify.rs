@@ -1,18 +1,24 @@
 use crate::{
     compute_enc_pending_balance, confidential_transaction_file, construct_path,
-    create_rng_from_seed, errors::Error, last_ordering_state, load_object, non_empty_account_id,
+    create_rng_from_seed,
+    errors::Error,
+    keystore::{load_secret_object, read_password, save_secret_object, PasswordSource},
+    last_ordering_state, load_master_key, load_object, non_empty_account_id,
     retrieve_auditors_by_names, save_object, user_public_account_balance_file,
     user_public_account_file, OrderedPubAccount, OrderedTransferInstruction, TransferInstruction,
-    COMMON_OBJECTS_DIR, MEDIATOR_PUBLIC_ACCOUNT_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
-    SECRET_ACCOUNT_FILE,
+    COMMON_OBJECTS_DIR, MEDIATOR_PUBLIC_ACCOUNT_FILE, ON_CHAIN_DIR, SECRET_ACCOUNT_FILE,
+};
+use codec::Encode;
+use cryptography_core::{
+    asset_proofs::{asset_id_from_ticker, DerivationPath, ElgamalSecretKey, ExtendedSecretKey},
+    codec_wrapper::Versioned,
 };
-use codec::{Decode, Encode};
-use cryptography_core::asset_proofs::{asset_id_from_ticker, ElgamalSecretKey};
 use curve25519_dalek::scalar::Scalar;
 use log::info;
 use mercat::{
     transaction::CtxMediator, EncryptedAmount, EncryptionKeys, EncryptionPubKey,
-    FinalizedTransferTx, MediatorAccount, TransferTransactionMediator, TransferTxState, TxSubstate,
+    FinalizedTransferTx, MediatorAccount, RejectedTransferTransactionMediator, RejectionReason,
+    TransferTransactionMediator, TransferTxState, TxSubstate,
 };
 use metrics::timing;
 use rand::{CryptoRng, RngCore};
@@ -20,8 +26,15 @@ use std::{path::PathBuf, time::Instant};
 
 fn generate_mediator_keys<R: RngCore + CryptoRng>(
     rng: &mut R,
+    master_key: Option<&ExtendedSecretKey>,
+    mediator_index: u32,
 ) -> (EncryptionPubKey, MediatorAccount) {
-    let mediator_elg_secret_key = ElgamalSecretKey::new(Scalar::random(rng));
+    let mediator_elg_secret_key = match master_key {
+        Some(master_key) => master_key
+            .derive_path(&DerivationPath::mediator(mediator_index))
+            .secret_key(),
+        None => ElgamalSecretKey::new(Scalar::random(rng)),
+    };
     let mediator_enc_key = EncryptionKeys {
         public: mediator_elg_secret_key.get_public_key(),
         secret: mediator_elg_secret_key,
@@ -35,13 +48,25 @@ fn generate_mediator_keys<R: RngCore + CryptoRng>(
     )
 }
 
-pub fn process_create_mediator(seed: String, db_dir: PathBuf, user: String) -> Result<(), Error> {
+pub fn process_create_mediator(
+    seed: String,
+    mnemonic_file: Option<PathBuf>,
+    password_source: Option<PasswordSource>,
+    db_dir: PathBuf,
+    user: String,
+    mediator_index: u32,
+) -> Result<(), Error> {
     // Setup the rng.
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let master_key = mnemonic_file
+        .map(|path| load_master_key(&path))
+        .transpose()?;
+    let password = read_password(password_source)?;
 
     // Generate keys for the mediator.
     let mediator_key_gen_timer = Instant::now();
-    let (public_account, private_account) = generate_mediator_keys(&mut rng);
+    let (public_account, private_account) =
+        generate_mediator_keys(&mut rng, master_key.as_ref(), mediator_index);
     timing!(
         "mediator.key_gen",
         mediator_key_gen_timer,
@@ -58,12 +83,12 @@ pub fn process_create_mediator(seed: String, db_dir: PathBuf, user: String) -> R
         &public_account,
     )?;
 
-    save_object(
+    save_secret_object(
         db_dir,
-        OFF_CHAIN_DIR,
         &user,
         SECRET_ACCOUNT_FILE,
         &private_account,
+        password.as_ref(),
     )?;
     info!(
         "CLI log: Mediator keys as base64:\n{}\n",
@@ -81,20 +106,24 @@ pub fn process_create_mediator(seed: String, db_dir: PathBuf, user: String) -> R
 
 pub fn justify_asset_transfer_transaction(
     db_dir: PathBuf,
+    password_source: Option<PasswordSource>,
+    seed: String,
     sender: String,
     receiver: String,
     mediator: String,
     auditors: &[String],
     ticker: String,
-    seed: String,
     stdout: bool,
     tx_id: u32,
-    reject: bool,
+    reject: Option<RejectionReason>,
     cheat: bool,
 ) -> Result<(), Error> {
+    // Setup the rng.
+    let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
+
     // Load the transaction, mediator's credentials, and issuer's public account.
     let justify_load_objects_timer = Instant::now();
-    let mut rng = create_rng_from_seed(Some(seed))?;
 
     let instruction_path = confidential_transaction_file(
         tx_id,
@@ -108,24 +137,43 @@ pub fn justify_asset_transfer_transaction(
         &instruction_path,
     )?;
 
-    let asset_tx = FinalizedTransferTx::decode(&mut &instruction.data[..]).map_err(|error| {
-        Error::ObjectLoadError {
-            error,
-            path: construct_path(
-                db_dir.clone(),
-                ON_CHAIN_DIR,
-                COMMON_OBJECTS_DIR,
-                &instruction_path,
-            ),
-        }
-    })?;
-
-    let mediator_account: MediatorAccount = load_object(
+    let asset_tx =
+        FinalizedTransferTx::decode_versioned(&mut &instruction.data[..]).map_err(|error| {
+            Error::ObjectLoadError {
+                error,
+                path: construct_path(
+                    db_dir.clone(),
+                    ON_CHAIN_DIR,
+                    COMMON_OBJECTS_DIR,
+                    &instruction_path,
+                ),
+            }
+        })?;
+
+    let mediator_account: MediatorAccount = load_secret_object(
         db_dir.clone(),
-        OFF_CHAIN_DIR,
         &mediator,
         SECRET_ACCOUNT_FILE,
+        password.as_ref(),
     )?;
+
+    // If a rejection reason is given, sign the rejection of the transaction. The mediator
+    // does not need to verify a transaction to reject it.
+    if let Some(reason) = reject {
+        return reject_asset_transfer_transaction(
+            db_dir,
+            asset_tx,
+            &mediator_account,
+            sender,
+            auditors,
+            stdout,
+            tx_id,
+            reason,
+            cheat,
+            &mut rng,
+        );
+    }
+
     let auditors_accounts = retrieve_auditors_by_names(auditors, db_dir.clone())?;
 
     let sender_ordered_pub_account: OrderedPubAccount = load_object(
@@ -179,7 +227,7 @@ pub fn justify_asset_transfer_transaction(
     let asset_id = asset_id_from_ticker(&ticker).map_err(|error| Error::LibraryError { error })?;
     let mut justified_tx = CtxMediator
         .justify_transaction(
-            asset_tx.clone(),
+            asset_tx.into(),
             &mediator_account.encryption_key,
             &sender_ordered_pub_account.pub_account,
             &pending_balance,
@@ -206,54 +254,28 @@ pub fn justify_asset_transfer_transaction(
         "tx_id" => tx_id.to_string()
     );
 
-    let next_instruction;
     let justify_save_objects_timer = Instant::now();
-    // If the `reject` flag is set, save the transaction as rejected.
-    if reject {
-        let rejected_state = TransferTxState::Justification(TxSubstate::Rejected);
-        next_instruction = TransferInstruction {
-            data: asset_tx.encode().to_vec(),
-            state: rejected_state,
-            auditors: auditors.to_vec(),
-        };
-
-        save_object(
-            db_dir,
-            ON_CHAIN_DIR,
-            COMMON_OBJECTS_DIR,
-            &confidential_transaction_file(tx_id, &sender, rejected_state),
-            &next_instruction,
-        )?;
-        if stdout {
-            info!(
-                "CLI log: tx-{}: Transaction as base64:\n{}\n",
-                tx_id,
-                base64::encode(asset_tx.encode())
-            );
-        }
-    } else {
-        let new_state = TransferTxState::Justification(TxSubstate::Started);
-        // Save the updated_issuer_account, and the justified transaction.
-        next_instruction = TransferInstruction {
-            data: justified_tx.encode().to_vec(),
-            state: new_state,
-            auditors: auditors.to_vec(),
-        };
-
-        save_object(
-            db_dir,
-            ON_CHAIN_DIR,
-            COMMON_OBJECTS_DIR,
-            &confidential_transaction_file(tx_id, &mediator, new_state),
-            &next_instruction,
-        )?;
-        if stdout {
-            info!(
-                "CLI log: tx-{}: Transaction as base64:\n{}\n",
-                tx_id,
-                base64::encode(justified_tx.encode())
-            );
-        }
+    let new_state = TransferTxState::Justification(TxSubstate::Started);
+    // Save the updated_issuer_account, and the justified transaction.
+    let next_instruction = TransferInstruction {
+        data: justified_tx.encode_versioned(),
+        state: new_state,
+        auditors: auditors.to_vec(),
+    };
+
+    save_object(
+        db_dir,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &confidential_transaction_file(tx_id, &mediator, new_state),
+        &next_instruction,
+    )?;
+    if stdout {
+        info!(
+            "CLI log: tx-{}: Transaction as base64:\n{}\n",
+            tx_id,
+            base64::encode(justified_tx.encode())
+        );
     }
 
     timing!(
@@ -265,3 +287,78 @@ pub fn justify_asset_transfer_transaction(
 
     Ok(())
 }
+
+fn reject_asset_transfer_transaction<R: RngCore + CryptoRng>(
+    db_dir: PathBuf,
+    asset_tx: FinalizedTransferTx,
+    mediator_account: &MediatorAccount,
+    sender: String,
+    auditors: &[String],
+    stdout: bool,
+    tx_id: u32,
+    reason: RejectionReason,
+    cheat: bool,
+    rng: &mut R,
+) -> Result<(), Error> {
+    let reject_library_timer = Instant::now();
+    let mut rejected_tx = CtxMediator
+        .reject_transaction(
+            asset_tx.into(),
+            &mediator_account.encryption_key,
+            reason,
+            rng,
+        )
+        .map_err(|error| Error::LibraryError { error })?;
+
+    if cheat {
+        info!(
+            "CLI log: tx-{}: Cheating by overwriting the sender's account id.",
+            tx_id
+        );
+
+        rejected_tx.finalized_data.init_data.memo.sender_account_id += non_empty_account_id();
+    }
+
+    timing!(
+        "mediator.reject_tx.library",
+        reject_library_timer,
+        Instant::now(),
+        "tx_id" => tx_id.to_string()
+    );
+
+    let reject_save_objects_timer = Instant::now();
+    let rejected_state = TransferTxState::Justification(TxSubstate::Rejected);
+    let next_instruction = TransferInstruction {
+        data: rejected_tx.encode_versioned(),
+        state: rejected_state,
+        auditors: auditors.to_vec(),
+    };
+
+    save_object(
+        db_dir,
+        ON_CHAIN_DIR,
+        COMMON_OBJECTS_DIR,
+        &confidential_transaction_file(tx_id, &sender, rejected_state),
+        &next_instruction,
+    )?;
+    info!(
+        "CLI log: tx-{}: Rejected the transaction with reason: {}.",
+        tx_id, reason
+    );
+    if stdout {
+        info!(
+            "CLI log: tx-{}: Transaction as base64:\n{}\n",
+            tx_id,
+            base64::encode(rejected_tx.encode())
+        );
+    }
+
+    timing!(
+        "mediator.reject_tx.save_objects",
+        reject_save_objects_timer,
+        Instant::now(),
+        "tx_id" => tx_id.to_string()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,473 @@
+//! The keystore encrypts the secret objects of the off-chain directory with a password.
+//!
+//! A keystore file is a JSON object that holds the SCALE encoding of the secret object,
+//! encrypted with ChaCha20-Poly1305. The encryption key is derived from the password
+//! with scrypt, using a random salt. Since the encryption is authenticated, a wrong
+//! password or a tampered file fails the decryption instead of producing garbage.
+//!
+//! Secret objects that were saved without a password are plain SCALE encoded files.
+//! They can still be loaded, and `process_migrate_keystore` encrypts them in place.
+
+use crate::{construct_path, errors::Error, OFF_CHAIN_DIR, SECRET_ACCOUNT_FILE};
+use chacha20poly1305::{
+    aead::{generic_array::GenericArray, Aead, NewAead, Payload},
+    ChaCha20Poly1305,
+};
+use codec::{Decode, Encode};
+use log::info;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{create_dir_all, read_dir, remove_file, rename, File},
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use zeroize::Zeroizing;
+
+/// The version of the keystore format.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// The associated data of the encryption, which binds the ciphertext to the format.
+const KEYSTORE_AAD: &[u8] = b"MERCAT keystore v1";
+
+/// The default scrypt parameters, as recommended for interactive logins.
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// A keystore password. It is wiped from memory when dropped.
+pub type Password = Zeroizing<String>;
+
+// ------------------------------------------------------------------------------------------------
+// -                                       Password Source                                        -
+// ------------------------------------------------------------------------------------------------
+
+/// Where the keystore password is read from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PasswordSource {
+    /// The password is the value of an environment variable.
+    Env(String),
+
+    /// The password is the first line of a file.
+    File(PathBuf),
+
+    /// The password is typed in the terminal.
+    Prompt,
+}
+
+impl FromStr for PasswordSource {
+    type Err = Error;
+
+    /// Parses `env:<VARIABLE>`, `file:<PATH>`, or `prompt`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "prompt" {
+            return Ok(PasswordSource::Prompt);
+        }
+        if let Some(name) = value.strip_prefix("env:") {
+            if !name.is_empty() {
+                return Ok(PasswordSource::Env(name.to_string()));
+            }
+        }
+        if let Some(path) = value.strip_prefix("file:") {
+            if !path.is_empty() {
+                return Ok(PasswordSource::File(PathBuf::from(path)));
+            }
+        }
+        Err(Error::InvalidPasswordSource {
+            value: value.to_string(),
+        })
+    }
+}
+
+impl PasswordSource {
+    /// Reads the password from this source.
+    pub fn read(&self) -> Result<Password, Error> {
+        let password = match self {
+            PasswordSource::Env(name) => {
+                std::env::var(name).map_err(|_| Error::PasswordEnvError { name: name.clone() })?
+            }
+            PasswordSource::File(path) => {
+                let content = Zeroizing::new(std::fs::read_to_string(path).map_err(|error| {
+                    Error::FileReadError {
+                        error,
+                        path: path.clone(),
+                    }
+                })?);
+                content.lines().next().unwrap_or_default().to_string()
+            }
+            PasswordSource::Prompt => {
+                rpassword::read_password_from_tty(Some("Keystore password: "))
+                    .map_err(|error| Error::PasswordPromptError { error })?
+            }
+        };
+        Ok(Zeroizing::new(password))
+    }
+}
+
+/// Helper function to read the password from an optional source.
+/// Secret objects are saved in plain text when there is no password.
+pub fn read_password(password_source: Option<PasswordSource>) -> Result<Option<Password>, Error> {
+    password_source.map(|source| source.read()).transpose()
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                           Keystore                                           -
+// ------------------------------------------------------------------------------------------------
+
+/// The scrypt parameters that derive the encryption key from the password.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    /// Base64 encoded salt.
+    pub salt: String,
+}
+
+/// An encrypted secret object.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub kdf: KdfParams,
+    /// Base64 encoded nonce of the ChaCha20-Poly1305 encryption.
+    pub nonce: String,
+    /// Base64 encoded ciphertext, including the authentication tag.
+    pub ciphertext: String,
+}
+
+fn derive_key(password: &Password, kdf: &KdfParams) -> Result<Zeroizing<[u8; KEY_LEN]>, Error> {
+    let salt = base64::decode(&kdf.salt).map_err(|_| Error::KeystoreDecryptionError)?;
+    let params = scrypt::ScryptParams::new(kdf.log_n, kdf.r, kdf.p)
+        .map_err(|_| Error::KeystoreDecryptionError)?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key[..])
+        .map_err(|_| Error::KeystoreDecryptionError)?;
+    Ok(key)
+}
+
+impl Keystore {
+    /// Encrypts `plaintext` with a key derived from `password`.
+    pub fn encrypt(plaintext: &[u8], password: &Password) -> Result<Self, Error> {
+        let mut rng = rand::thread_rng();
+        let mut salt = [0u8; SALT_LEN];
+        rng.fill(&mut salt);
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut nonce);
+
+        let kdf = KdfParams {
+            log_n: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt: base64::encode(salt),
+        };
+        let key = derive_key(password, &kdf)?;
+        let ciphertext = ChaCha20Poly1305::new(GenericArray::from_slice(&key[..]))
+            .encrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: KEYSTORE_AAD,
+                },
+            )
+            .map_err(|_| Error::KeystoreEncryptionError)?;
+
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            kdf,
+            nonce: base64::encode(nonce),
+            ciphertext: base64::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the keystore with a key derived from `password`.
+    pub fn decrypt(&self, password: &Password) -> Result<Zeroizing<Vec<u8>>, Error> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(Error::UnsupportedKeystoreVersion {
+                version: self.version,
+            });
+        }
+        let nonce = base64::decode(&self.nonce).map_err(|_| Error::KeystoreDecryptionError)?;
+        let ciphertext =
+            base64::decode(&self.ciphertext).map_err(|_| Error::KeystoreDecryptionError)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(Error::KeystoreDecryptionError);
+        }
+
+        let key = derive_key(password, &self.kdf)?;
+        ChaCha20Poly1305::new(GenericArray::from_slice(&key[..]))
+            .decrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: KEYSTORE_AAD,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| Error::KeystoreDecryptionError)
+    }
+
+    /// Parses a keystore. Returns `None` if `data` is not a keystore, e.g. a plain
+    /// text secret object.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        serde_json::from_slice(data).ok()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                        Load and Save                                         -
+// ------------------------------------------------------------------------------------------------
+
+/// Utility function to save a secret object to the off-chain directory of `user`.
+/// The object is encrypted if a password is given, and saved in plain text otherwise.
+pub fn save_secret_object<T: Encode>(
+    db_dir: PathBuf,
+    user: &str,
+    file_name: &str,
+    data: &T,
+    password: Option<&Password>,
+) -> Result<(), Error> {
+    let file_path = construct_path(db_dir, OFF_CHAIN_DIR, user, file_name);
+
+    // Create the user directory if it does not exist.
+    if let Some(user_dir) = file_path.parent() {
+        create_dir_all(user_dir).map_err(|error| Error::FileCreationError {
+            error,
+            path: user_dir.to_path_buf(),
+        })?;
+    }
+
+    write_secret(&file_path, &Zeroizing::new(data.encode()), password)
+}
+
+/// Utility function to load a secret object from the off-chain directory of `user`.
+/// Both keystores and plain text objects are accepted, but keystores need a password.
+pub fn load_secret_object<T: Decode>(
+    db_dir: PathBuf,
+    user: &str,
+    file_name: &str,
+    password: Option<&Password>,
+) -> Result<T, Error> {
+    let file_path = construct_path(db_dir, OFF_CHAIN_DIR, user, file_name);
+    let data = read_secret(&file_path, password)?;
+
+    T::decode(&mut &data[..]).map_err(|error| Error::ObjectLoadError {
+        error,
+        path: file_path,
+    })
+}
+
+/// Writes the secret object to a temporary file in the same directory, and then renames it over
+/// `file_path`. An interrupted write, e.g. during a migration, never leaves a truncated secret behind.
+fn write_secret(file_path: &Path, data: &[u8], password: Option<&Password>) -> Result<(), Error> {
+    let content = match password {
+        Some(password) => {
+            let keystore = Keystore::encrypt(data, password)?;
+            serde_json::to_vec_pretty(&keystore).map_err(|error| Error::FileWriteError {
+                error,
+                path: file_path.to_path_buf(),
+            })?
+        }
+        None => data.to_vec(),
+    };
+
+    let mut tmp_file_name = file_path.file_name().unwrap_or_default().to_os_string();
+    tmp_file_name.push(".tmp");
+    let tmp_file_path = file_path.with_file_name(tmp_file_name);
+    let written = File::create(&tmp_file_path)
+        .and_then(|mut file| {
+            file.write_all(&content)?;
+            file.sync_all()
+        })
+        .and_then(|_| rename(&tmp_file_path, file_path));
+    if let Err(error) = written {
+        let _ = remove_file(&tmp_file_path);
+        return Err(Error::ObjectSaveError {
+            error,
+            path: file_path.to_path_buf(),
+        });
+    }
+
+    Ok(())
+}
+
+fn read_secret(file_path: &Path, password: Option<&Password>) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let data = Zeroizing::new(
+        std::fs::read(file_path).map_err(|error| Error::FileReadError {
+            error,
+            path: file_path.to_path_buf(),
+        })?,
+    );
+
+    match Keystore::parse(&data) {
+        Some(keystore) => {
+            let password = password.ok_or_else(|| Error::PasswordRequired {
+                path: file_path.to_path_buf(),
+            })?;
+            keystore.decrypt(password)
+        }
+        None => Ok(data),
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                          Migration                                           -
+// ------------------------------------------------------------------------------------------------
+
+/// Encrypts all the plain text secret objects of the off-chain directory with the given password.
+/// Secret objects that are already encrypted are left as they are.
+pub fn process_migrate_keystore(
+    db_dir: PathBuf,
+    password_source: PasswordSource,
+) -> Result<(), Error> {
+    let password = password_source.read()?;
+
+    let mut off_chain_dir = db_dir;
+    off_chain_dir.push(OFF_CHAIN_DIR);
+    let user_dirs = read_dir(&off_chain_dir).map_err(|error| Error::FileReadError {
+        error,
+        path: off_chain_dir.clone(),
+    })?;
+
+    let mut migrated = 0;
+    for user_dir in user_dirs {
+        let user_dir = user_dir
+            .map_err(|error| Error::FileReadError {
+                error,
+                path: off_chain_dir.clone(),
+            })?
+            .path();
+        if !user_dir.is_dir() {
+            continue;
+        }
+
+        let files = read_dir(&user_dir).map_err(|error| Error::FileReadError {
+            error,
+            path: user_dir.clone(),
+        })?;
+        for file in files {
+            let file_path = file
+                .map_err(|error| Error::FileReadError {
+                    error,
+                    path: user_dir.clone(),
+                })?
+                .path();
+            let is_secret_object = file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.ends_with(SECRET_ACCOUNT_FILE));
+            if !file_path.is_file() || !is_secret_object {
+                continue;
+            }
+
+            let data = Zeroizing::new(std::fs::read(&file_path).map_err(|error| {
+                Error::FileReadError {
+                    error,
+                    path: file_path.clone(),
+                }
+            })?);
+            if Keystore::parse(&data).is_some() {
+                continue;
+            }
+
+            write_secret(&file_path, &data, Some(&password))?;
+            info!("CLI log: Encrypted the secret object {:?}.", file_path);
+            migrated += 1;
+        }
+    }
+
+    info!("CLI log: Encrypted {} secret objects.", migrated);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cryptography_core::{asset_proofs::ElgamalSecretKey, Scalar};
+    use mercat::{EncryptionKeys, MediatorAccount};
+
+    fn mediator_account() -> MediatorAccount {
+        let secret = ElgamalSecretKey::new(Scalar::from(42u32));
+        MediatorAccount {
+            encryption_key: EncryptionKeys {
+                public: secret.get_public_key(),
+                secret,
+            },
+        }
+    }
+
+    #[test]
+    fn keystore_round_trip() {
+        let password = Zeroizing::new("correct horse battery staple".to_string());
+        let data = mediator_account().encode();
+
+        let keystore = Keystore::encrypt(&data, &password).unwrap();
+        let parsed = Keystore::parse(&serde_json::to_vec(&keystore).unwrap()).unwrap();
+        assert_eq!(&parsed.decrypt(&password).unwrap()[..], &data[..]);
+
+        // Negative tests: a wrong password and a tampered ciphertext.
+        let wrong_password = Zeroizing::new("wrong password".to_string());
+        assert!(matches!(
+            parsed.decrypt(&wrong_password),
+            Err(Error::KeystoreDecryptionError)
+        ));
+        let mut tampered = parsed.clone();
+        let mut ciphertext = base64::decode(&tampered.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        tampered.ciphertext = base64::encode(ciphertext);
+        assert!(matches!(
+            tampered.decrypt(&password),
+            Err(Error::KeystoreDecryptionError)
+        ));
+
+        // A plain text object is not a keystore.
+        assert!(Keystore::parse(&data).is_none());
+    }
+
+    #[test]
+    fn password_source_parsing() {
+        assert_eq!(
+            "prompt".parse::<PasswordSource>().unwrap(),
+            PasswordSource::Prompt
+        );
+        assert_eq!(
+            "env:MERCAT_PASSWORD".parse::<PasswordSource>().unwrap(),
+            PasswordSource::Env("MERCAT_PASSWORD".to_string())
+        );
+        assert_eq!(
+            "file:/tmp/password".parse::<PasswordSource>().unwrap(),
+            PasswordSource::File(PathBuf::from("/tmp/password"))
+        );
+        for invalid in ["", "env:", "file:", "password"].iter() {
+            assert!(invalid.parse::<PasswordSource>().is_err());
+        }
+    }
+
+    #[test]
+    fn write_secret_replaces_the_file() {
+        let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        dir.push("chain_dir/unittest/keystore");
+        let _ = std::fs::remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        let file_path = dir.join(SECRET_ACCOUNT_FILE);
+        let password = Zeroizing::new("correct horse battery staple".to_string());
+        let data = mediator_account().encode();
+
+        // Encrypting a plain text secret in place, as the migration does.
+        write_secret(&file_path, &data, None).unwrap();
+        write_secret(&file_path, &data, Some(&password)).unwrap();
+        assert!(Keystore::parse(&std::fs::read(&file_path).unwrap()).is_some());
+        assert_eq!(
+            &read_secret(&file_path, Some(&password)).unwrap()[..],
+            &data[..]
+        );
+
+        // No temporary file is left behind.
+        let files = read_dir(&dir).unwrap().count();
+        assert_eq!(files, 1);
+    }
+}
@@ -8,17 +8,24 @@ pub mod chain_setup;
 pub mod errors;
 mod harness;
 pub mod justify;
+pub mod keystore;
 pub mod validate;
 
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
 use codec::{Decode, Encode};
-use cryptography_core::asset_proofs::CipherText;
+use cryptography_core::{
+    asset_proofs::{Balance, CipherText, CipherTextWithHint, ExtendedSecretKey},
+    codec_wrapper::Versioned,
+};
 use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT, scalar::Scalar};
 use errors::Error;
+use keystore::{load_secret_object, read_password, PasswordSource};
 use log::{debug, error, info};
 use mercat::{
-    Account, AssetTxState, AuditorPubAccount, EncryptedAmount, EncryptedAssetId,
-    FinalizedTransferTx, InitializedAssetTx, InitializedTransferTx, JustifiedTransferTx,
-    PubAccount, PubAccountTx, SecAccount, TransferTxState, TxSubstate,
+    AssetTxState, AuditorPubAccount, EncryptedAmount, EncryptedAssetId, EncryptionKeys,
+    EncryptionPubKey, EncryptionSecKey, FinalizedTransferTx, InitializedAssetTx,
+    InitializedTransferTx, JustifiedTransferTx, PubAccount, PubAccountTx, SecAccount,
+    TransferTxState, TxSubstate,
 };
 use metrics::Recorder;
 use metrics_core::Key;
@@ -30,9 +37,9 @@ use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
     fmt,
-    fs::{create_dir_all, File},
+    fs::{create_dir_all, File, OpenOptions},
     hash::Hash,
-    io::BufReader,
+    io::{BufReader, Write},
     path::{Path, PathBuf},
 };
 
@@ -43,12 +50,17 @@ pub const AUDITOR_PUBLIC_ACCOUNT_FILE: &str = "auditor_public_account";
 pub const VALIDATED_PUBLIC_ACCOUNT_FILE: &str = "validated_public_account";
 pub const VALIDATED_PUBLIC_ACCOUNT_BALANCE_FILE: &str = "validated_public_account_balance";
 pub const SECRET_ACCOUNT_FILE: &str = "secret_account";
+pub const BALANCE_HINTS_FILE: &str = "balance_hints";
 pub const ASSET_ID_LIST_FILE: &str = "valid_asset_ids.json";
 pub const COMMON_OBJECTS_DIR: &str = "common";
 pub const USER_ACCOUNT_MAP: &str = "user_ticker_to_account_id.json";
 pub const TRANSACTION_NAME_ID_MAP: &str = "transaction_name_to_id.json";
 pub const LAST_VALIDATED_TX_ID_FILE: &str = "last_validated_tx_id_file.json";
 
+/// The number of the most recent unsettled amounts that are kept in the balance hints of
+/// each account. Decrypting a balance tries every subset of them.
+pub const MAX_BALANCE_HINTS: usize = 8;
+
 /// A wrapper around MERCAT api which holds the transaction data, the transaction id,
 /// and the user who initiated the transaction. Some transactions also hold the
 /// ordering state.
@@ -64,7 +76,7 @@ pub enum CoreTransaction {
         issuer: String,
         ordering_state: OrderingState,
         tx_id: u32,
-        amount: u32,
+        amount: Balance,
         auditors: Vec<String>,
     },
     TransferInit {
@@ -191,6 +203,23 @@ pub struct OrderedPubAccount {
     pub pub_account: PubAccount,
 }
 
+/// The off-chain hints that let the owner of an account decrypt balances that do not fit in
+/// the default discrete log table. They hold the last balance decrypted from the validated
+/// account, and the amounts of the transactions that it did not include yet, all encrypted
+/// with constant time hints.
+#[derive(Debug, Default, Encode, Decode, Clone)]
+pub struct BalanceHints {
+    pub validated_balance: Option<CipherTextWithHint>,
+    pub pending_amounts: Vec<PendingAmountHint>,
+}
+
+/// The amount of a transaction that was not validated when it was recorded.
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct PendingAmountHint {
+    pub incoming: bool,
+    pub amount: CipherTextWithHint,
+}
+
 /// A wrapper around the MERCAT PubAccount that stores the ordering state of this transaction.
 #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
 pub struct OrderedPubAccountTx {
@@ -202,7 +231,7 @@ pub struct OrderedPubAccountTx {
 #[derive(Debug, Serialize, Deserialize, Encode, Decode, Clone)]
 pub struct OrderedAssetInstruction {
     pub state: AssetTxState,
-    pub amount: u32,
+    pub amount: Balance,
     pub ordering_state: OrderingState,
     pub auditors: Vec<String>,
     #[serde(with = "serde_bytes")]
@@ -326,6 +355,11 @@ pub fn user_secret_account_file(ticker: &str) -> String {
     format!("{}_{}", ticker, SECRET_ACCOUNT_FILE)
 }
 
+#[inline]
+pub fn user_balance_hints_file(ticker: &str) -> String {
+    format!("{}_{}", ticker, BALANCE_HINTS_FILE)
+}
+
 /// This is used for simulating cheating by increasing the account id.
 #[inline]
 pub fn non_empty_account_id() -> EncryptedAssetId {
@@ -600,6 +634,44 @@ pub fn gen_seed_from<T: RngCore + CryptoRng>(rng: &mut T) -> String {
     base64::encode(seed)
 }
 
+/// Helper function to load the master key of a wallet from a file that holds its BIP39 mnemonic.
+/// All the keys of the wallet are derived from this key, see `cryptography_core::asset_proofs::key_derivation`.
+pub fn load_master_key(mnemonic_file: &Path) -> Result<ExtendedSecretKey, Error> {
+    let phrase = std::fs::read_to_string(mnemonic_file).map_err(|error| Error::FileReadError {
+        error,
+        path: mnemonic_file.to_path_buf(),
+    })?;
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic =
+        Mnemonic::from_phrase(&phrase, Language::English).map_err(|_| Error::InvalidMnemonic {
+            path: mnemonic_file.to_path_buf(),
+        })?;
+    let seed = Seed::new(&mnemonic, "");
+
+    ExtendedSecretKey::from_seed(seed.as_bytes()).map_err(|error| Error::LibraryError { error })
+}
+
+/// Generates the 24 words BIP39 mnemonic of a new wallet, and saves it to `mnemonic_file`.
+/// An existing file is never overwritten, since it may hold the only backup of another wallet.
+pub fn process_generate_mnemonic(mnemonic_file: PathBuf) -> Result<(), Error> {
+    let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&mnemonic_file)
+        .map_err(|error| Error::FileCreationError {
+            error,
+            path: mnemonic_file.clone(),
+        })?;
+    writeln!(file, "{}", mnemonic.phrase()).map_err(|error| Error::ObjectSaveError {
+        error,
+        path: mnemonic_file.clone(),
+    })?;
+    info!("Saved a new mnemonic to {:?}.", mnemonic_file);
+
+    Ok(())
+}
+
 /// Helper function to create an RNG from seed.
 #[inline]
 pub fn create_rng_from_seed(seed: Option<String>) -> Result<StdRng, Error> {
@@ -1011,7 +1083,7 @@ pub fn load_tx_file(
     let tx = if state == AssetTxState::Initialization(TxSubstate::Started).to_string() {
         let instruction: OrderedAssetInstruction = load_object_from(PathBuf::from(tx_file_path))?;
         CoreTransaction::IssueInit {
-            issue_tx: InitializedAssetTx::decode(&mut &instruction.data[..])
+            issue_tx: InitializedAssetTx::decode_versioned(&mut &instruction.data[..])
                 .map_err(|_| Error::DecodeError)?,
             issuer: user,
             ordering_state: instruction.ordering_state,
@@ -1023,7 +1095,7 @@ pub fn load_tx_file(
         let instruction: OrderedTransferInstruction =
             load_object_from(PathBuf::from(tx_file_path))?;
         CoreTransaction::TransferInit {
-            tx: InitializedTransferTx::decode(&mut &instruction.data[..])
+            tx: InitializedTransferTx::decode_versioned(&mut &instruction.data[..])
                 .map_err(|_| Error::DecodeError)?,
             sender: user,
             ordering_state: instruction.ordering_state,
@@ -1033,7 +1105,7 @@ pub fn load_tx_file(
         let instruction: OrderedTransferInstruction =
             load_object_from(PathBuf::from(tx_file_path))?;
         CoreTransaction::TransferFinalize {
-            tx: FinalizedTransferTx::decode(&mut &instruction.data[..])
+            tx: FinalizedTransferTx::decode_versioned(&mut &instruction.data[..])
                 .map_err(|_| Error::DecodeError)?,
             receiver: user,
             ordering_state: instruction.ordering_state,
@@ -1042,7 +1114,7 @@ pub fn load_tx_file(
     } else if state == TransferTxState::Justification(TxSubstate::Started).to_string() {
         let instruction: TransferInstruction = load_object_from(PathBuf::from(tx_file_path))?;
         CoreTransaction::TransferJustify {
-            tx: JustifiedTransferTx::decode(&mut &instruction.data[..])
+            tx: JustifiedTransferTx::decode_versioned(&mut &instruction.data[..])
                 .map_err(|_| Error::DecodeError)?,
             mediator: user,
             tx_id,
@@ -1080,34 +1152,33 @@ pub fn retrieve_auditors_by_names(
 }
 
 /// Use only for debugging purposes.
+/// Since the validator does not know the passwords of the users, the balances of the
+/// accounts that are stored in an encrypted keystore are not decrypted.
 #[inline]
 fn debug_decrypt(
     account_id: EncryptedAssetId,
     enc_balance: EncryptedAmount,
     db_dir: PathBuf,
-) -> Result<u32, Error> {
+) -> Result<String, Error> {
     let (user, ticker, _) = get_user_ticker_from(account_id, db_dir.clone())?;
-    let ordered_pub_account: OrderedPubAccount = load_object(
+    let secret: SecAccount = match load_secret_object(
         db_dir.clone(),
-        ON_CHAIN_DIR,
         &user,
-        &user_public_account_file(&ticker),
-    )?;
-    let account = Account {
-        secret: load_object(
-            db_dir,
-            OFF_CHAIN_DIR,
-            &user,
-            &user_secret_account_file(&ticker),
-        )?,
-        public: ordered_pub_account.pub_account,
+        &user_secret_account_file(&ticker),
+        None,
+    ) {
+        Ok(secret) => secret,
+        Err(Error::PasswordRequired { .. }) => return Ok(String::from("<encrypted>")),
+        Err(error) => return Err(error),
     };
-    account
-        .secret
-        .enc_keys
-        .secret
-        .decrypt(&enc_balance)
-        .map_err(|error| Error::LibraryError { error })
+    decrypt_balance(
+        &user,
+        &ticker,
+        &secret.enc_keys.secret,
+        &enc_balance,
+        db_dir,
+    )
+    .map(|balance| balance.to_string())
 }
 
 /// Use only for debugging purposes.
@@ -1116,24 +1187,28 @@ pub fn debug_decrypt_account_balance(
     user: String,
     ticker: String,
     db_dir: PathBuf,
-) -> Result<u32, Error> {
+    password_source: Option<PasswordSource>,
+) -> Result<Balance, Error> {
     let enc_balance: EncryptedAmount = load_object(
         db_dir.clone(),
         ON_CHAIN_DIR,
         &user,
         &user_public_account_balance_file(&ticker),
     )?;
-    let secret: SecAccount = load_object(
-        db_dir,
-        OFF_CHAIN_DIR,
+    let password = read_password(password_source)?;
+    let secret: SecAccount = load_secret_object(
+        db_dir.clone(),
         &user,
         &user_secret_account_file(&ticker),
+        password.as_ref(),
     )?;
-    secret
-        .enc_keys
-        .secret
-        .decrypt(&enc_balance)
-        .map_err(|error| Error::LibraryError { error })
+    decrypt_balance(
+        &user,
+        &ticker,
+        &secret.enc_keys.secret,
+        &enc_balance,
+        db_dir,
+    )
 }
 
 /// Use only for debugging purposes.
@@ -1143,17 +1218,171 @@ pub fn debug_decrypt_base64_account_balance(
     encrypted_value: String,
     ticker: String,
     db_dir: PathBuf,
-) -> Result<u32, Error> {
+    password_source: Option<PasswordSource>,
+) -> Result<Balance, Error> {
     let mut data: &[u8] = &base64::decode(encrypted_value).unwrap();
     let enc_balance = EncryptedAmount::decode(&mut data).unwrap();
-    let scrt: SecAccount = load_object(
-        db_dir,
-        OFF_CHAIN_DIR,
+    let password = read_password(password_source)?;
+    let scrt: SecAccount = load_secret_object(
+        db_dir.clone(),
         &user,
         &user_secret_account_file(&ticker),
+        password.as_ref(),
     )?;
-    scrt.enc_keys
-        .secret
-        .decrypt(&enc_balance)
+    decrypt_balance(&user, &ticker, &scrt.enc_keys.secret, &enc_balance, db_dir)
+}
+
+/// Records the amount of a transaction of the account of the `user` that is not validated
+/// yet. This allows `decrypt_balance` to decrypt the balances that the account reaches
+/// whether the transaction is validated or rejected, even if they do not fit in the default
+/// discrete log table.
+pub fn save_pending_amount_hint<T: RngCore + CryptoRng>(
+    user: &str,
+    ticker: &str,
+    enc_pub_key: &EncryptionPubKey,
+    amount: Balance,
+    incoming: bool,
+    db_dir: PathBuf,
+    rng: &mut T,
+) -> Result<(), Error> {
+    let mut hints = load_balance_hints(user, ticker, db_dir.clone());
+    let (_, amount) = enc_pub_key.const_time_encrypt_value(amount.into(), rng);
+    hints
+        .pending_amounts
+        .push(PendingAmountHint { incoming, amount });
+    if hints.pending_amounts.len() > MAX_BALANCE_HINTS {
+        let excess = hints.pending_amounts.len() - MAX_BALANCE_HINTS;
+        hints.pending_amounts.drain(..excess);
+    }
+    save_balance_hints(user, ticker, &hints, db_dir)
+}
+
+/// Decrypts the validated balance of the account of the `user`, and records it as the new
+/// base of its balance hints. The amounts of the transactions that the validated balance
+/// includes are settled, and are removed from the hints.
+pub fn refresh_balance_hint<T: RngCore + CryptoRng>(
+    user: &str,
+    ticker: &str,
+    enc_keys: &EncryptionKeys,
+    validated_balance: &EncryptedAmount,
+    db_dir: PathBuf,
+    rng: &mut T,
+) -> Result<Balance, Error> {
+    let hints = load_balance_hints(user, ticker, db_dir.clone());
+    let (balance, settled) = decrypt_with_hints(&hints, &enc_keys.secret, validated_balance)?;
+
+    let (_, validated_balance) = enc_keys
+        .public
+        .const_time_encrypt_value(balance.into(), rng);
+    let pending_amounts = hints
+        .pending_amounts
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| settled & (1 << index) == 0)
+        .map(|(_, pending_amount)| pending_amount)
+        .collect();
+    save_balance_hints(
+        user,
+        ticker,
+        &BalanceHints {
+            validated_balance: Some(validated_balance),
+            pending_amounts,
+        },
+        db_dir,
+    )?;
+    Ok(balance)
+}
+
+#[inline]
+fn load_balance_hints(user: &str, ticker: &str, db_dir: PathBuf) -> BalanceHints {
+    load_object(
+        db_dir,
+        OFF_CHAIN_DIR,
+        user,
+        &user_balance_hints_file(ticker),
+    )
+    .unwrap_or_default()
+}
+
+#[inline]
+fn save_balance_hints(
+    user: &str,
+    ticker: &str,
+    hints: &BalanceHints,
+    db_dir: PathBuf,
+) -> Result<(), Error> {
+    save_object(
+        db_dir,
+        OFF_CHAIN_DIR,
+        user,
+        &user_balance_hints_file(ticker),
+        hints,
+    )
+}
+
+/// Decrypts `enc_balance` as the validated balance of the `hints` changed by some of their
+/// pending amounts, since each of the pending transactions may have been validated or
+/// rejected since. Returns the balance and the pending amounts that it includes, as a bit
+/// mask. The default discrete log table is only used if none of the hints match.
+fn decrypt_with_hints(
+    hints: &BalanceHints,
+    enc_sec_key: &EncryptionSecKey,
+    enc_balance: &EncryptedAmount,
+) -> Result<(Balance, u32), Error> {
+    let validated_balance = match &hints.validated_balance {
+        Some(validated_balance) => enc_sec_key
+            .const_time_decrypt(validated_balance)
+            .map_err(|error| Error::LibraryError { error })?,
+        None => 0,
+    };
+    let pending_amounts = hints
+        .pending_amounts
+        .iter()
+        .map(|pending_amount| {
+            let amount = i128::from(
+                enc_sec_key
+                    .const_time_decrypt(&pending_amount.amount)
+                    .map_err(|error| Error::LibraryError { error })?,
+            );
+            Ok(if pending_amount.incoming {
+                amount
+            } else {
+                -amount
+            })
+        })
+        .collect::<Result<Vec<i128>, Error>>()?;
+
+    // Most pending transactions end up validated, so the subsets that include the most
+    // amounts are tried first.
+    for included in (0..1u32 << pending_amounts.len()).rev() {
+        let balance = pending_amounts
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| included & (1 << index) != 0)
+            .fold(i128::from(validated_balance), |balance, (_, amount)| {
+                balance + amount
+            });
+        if let Ok(balance) = Balance::try_from(balance) {
+            if enc_sec_key.verify(enc_balance, &balance.into()).is_ok() {
+                return Ok((balance, included));
+            }
+        }
+    }
+
+    enc_sec_key
+        .decrypt(enc_balance)
+        .map(|balance| (balance, 0))
         .map_err(|error| Error::LibraryError { error })
 }
+
+/// Decrypts the balance of the account of the `user`, using its balance hints first.
+pub fn decrypt_balance(
+    user: &str,
+    ticker: &str,
+    enc_sec_key: &EncryptionSecKey,
+    enc_balance: &EncryptedAmount,
+    db_dir: PathBuf,
+) -> Result<Balance, Error> {
+    let hints = load_balance_hints(user, ticker, db_dir);
+    decrypt_with_hints(&hints, enc_sec_key, enc_balance).map(|(balance, _)| balance)
+}
@@ -5,18 +5,19 @@ use crate::{
     parse_tx_name, retrieve_auditors_by_names, save_object, save_to_file,
     user_public_account_balance_file, user_public_account_file, AssetInstruction, CoreTransaction,
     Direction, OrderedPubAccount, OrderedPubAccountTx, PrintableAccountId, TransferInstruction,
-    ValidationResult, COMMON_OBJECTS_DIR, LAST_VALIDATED_TX_ID_FILE, OFF_CHAIN_DIR, ON_CHAIN_DIR,
+    ValidationResult, COMMON_OBJECTS_DIR, LAST_VALIDATED_TX_ID_FILE, MEDIATOR_PUBLIC_ACCOUNT_FILE,
+    OFF_CHAIN_DIR, ON_CHAIN_DIR,
 };
-use codec::{Decode, Encode};
+use codec::Encode;
+use cryptography_core::{asset_proofs::Balance, codec_wrapper::Versioned};
 use log::{debug, error, info};
 use mercat::{
     account::AccountValidator, asset::AssetValidator, transaction::TransactionValidator,
     AccountCreatorVerifier, AssetTransactionVerifier, AssetTxState, EncryptedAmount,
-    EncryptedAssetId, InitializedAssetTx, JustifiedTransferTx, PubAccount,
+    EncryptedAssetId, EncryptionPubKey, InitializedAssetTx, JustifiedTransferTx, PubAccount,
     TransferTransactionVerifier, TransferTxState, TxSubstate,
 };
 use metrics::timing;
-use rand::rngs::OsRng;
 use std::{collections::HashSet, path::PathBuf, time::Instant};
 
 fn load_all_unverified_and_ready(db_dir: PathBuf) -> Result<Vec<CoreTransaction>, Error> {
@@ -247,7 +248,7 @@ pub fn validate_all_pending(db_dir: PathBuf) -> Result<(), Error> {
 
 pub fn validate_asset_issuance(
     db_dir: PathBuf,
-    amount: u32,
+    amount: Balance,
     asset_tx: InitializedAssetTx,
     tx_id: u32,
     auditors: &[String],
@@ -337,7 +338,7 @@ pub fn validate_asset_issuance(
     let new_state = AssetTxState::Justification(TxSubstate::Validated);
     let instruction = AssetInstruction {
         state: new_state,
-        data: asset_tx.encode().to_vec(),
+        data: asset_tx.encode_versioned(),
     };
     if let Err(error) = save_object(
         db_dir,
@@ -442,12 +443,14 @@ fn process_transaction(
     sender_pub_account: PubAccount,
     receiver_pub_account: PubAccount,
     pending_balance: EncryptedAmount,
+    mediator: &str,
     auditors: &[String],
     db_dir: PathBuf,
 ) -> Result<(), Error> {
-    let mut rng = OsRng::default();
-    let tx = JustifiedTransferTx::decode(&mut &instruction.data[..]).unwrap();
+    let tx = JustifiedTransferTx::decode_versioned(&mut &instruction.data[..]).unwrap();
     let auditors_accounts = retrieve_auditors_by_names(auditors, db_dir.clone())?;
+    let mediator_pub_key: EncryptionPubKey =
+        load_object(db_dir, ON_CHAIN_DIR, mediator, MEDIATOR_PUBLIC_ACCOUNT_FILE)?;
     let validator = TransactionValidator;
     validator
         .verify_transaction(
@@ -456,7 +459,8 @@ fn process_transaction(
             &pending_balance,
             &receiver_pub_account,
             &auditors_accounts,
-            &mut rng,
+            &[mediator_pub_key],
+            1,
         )
         .map_err(|error| Error::LibraryError { error })
 }
@@ -567,6 +571,7 @@ pub fn validate_transaction(
         sender_ordered_pub_account.pub_account,
         receiver_ordered_pub_account.pub_account,
         pending_balance,
+        &mediator,
         auditors,
         db_dir.clone(),
     ) {
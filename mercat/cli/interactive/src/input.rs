@@ -1,5 +1,6 @@
+use cryptography_core::asset_proofs::Balance;
 use log::info;
-use mercat_common::gen_seed;
+use mercat_common::{gen_seed, keystore::PasswordSource};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -18,6 +19,13 @@ pub struct CreateUserAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The name of the user. The name can be any valid string that can be used as a file name.
     /// It is the responsibility of the caller to ensure the uniqueness of the name.
     #[structopt(short, long, help = "The name of the user. This name must be unique.")]
@@ -40,6 +48,15 @@ pub struct CreateUserAccountInfo {
     )]
     pub seed: Option<String>,
 
+    /// An optional path to a file that holds the BIP39 mnemonic of the user's wallet.
+    /// When it is given, the keys are derived from the mnemonic, and can be restored from it later.
+    #[structopt(
+        parse(from_os_str),
+        long,
+        help = "Path to a file that holds the BIP39 mnemonic to derive the keys from. If not provided, the keys will be chosen at random."
+    )]
+    pub mnemonic_file: Option<PathBuf>,
+
     /// Space separated list of ticker names.
     #[structopt(short, long, help = "Space separated list of a ticker names.")]
     pub valid_ticker_names: Vec<String>,
@@ -69,6 +86,13 @@ pub struct CreateMediatorAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
     /// The seed can be found inside the logs.
     #[structopt(
@@ -77,6 +101,25 @@ pub struct CreateMediatorAccountInfo {
         help = "Base64 encoding of an initial seed for the RNG. If not provided, the seed will be chosen at random."
     )]
     pub seed: Option<String>,
+
+    /// An optional path to a file that holds the BIP39 mnemonic of the user's wallet.
+    /// When it is given, the keys are derived from the mnemonic, and can be restored from it later.
+    #[structopt(
+        parse(from_os_str),
+        long,
+        help = "Path to a file that holds the BIP39 mnemonic to derive the keys from. If not provided, the keys will be chosen at random."
+    )]
+    pub mnemonic_file: Option<PathBuf>,
+
+    /// The index of the mediator keys in the wallet of the mnemonic.
+    /// A wallet can hold the keys of several mediators, each under its own index.
+    #[structopt(
+        long,
+        default_value = "0",
+        help = "The index of the mediator keys to derive from the mnemonic. Only used with `--mnemonic-file`."
+    )]
+    #[serde(default)]
+    pub mediator_index: u32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
@@ -98,7 +141,7 @@ pub struct IssueAssetInfo {
 
     /// Amount to issue.
     #[structopt(short, long, help = "The amount of assets to issue.")]
-    pub amount: u32,
+    pub amount: Balance,
 
     /// The directory that will serve as the database of the on/off-chain data and will be used
     /// to save and load the data that in a real execution would be written to the on/off the
@@ -112,6 +155,13 @@ pub struct IssueAssetInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The issuer's name. An account must have already been created for this user.
     #[structopt(short, long, help = "The name of the issuer.")]
     pub issuer: String,
@@ -135,7 +185,7 @@ pub struct CreateTransactionInfo {
 
     /// Amount to transfer.
     #[structopt(short, long, help = "The amount of assets to transfer.")]
-    pub amount: u32,
+    pub amount: Balance,
 
     /// The directory that will serve as the database of the on/off-chain data and will be used
     /// to save and load the data that in a real execution would be written to the on/off the
@@ -149,6 +199,13 @@ pub struct CreateTransactionInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The sender's name. An account must have already been created for this user.
     #[structopt(long, help = "The sender's name.")]
     pub sender: String,
@@ -189,7 +246,7 @@ pub struct FinalizeTransactionInfo {
 
     /// The expected amount to receive.
     #[structopt(short, long, help = "The expected amount to receive.")]
-    pub amount: u32,
+    pub amount: Balance,
 
     /// The directory that will serve as the database of the on/off-chain data and will be used
     /// to save and load the data that in a real execution would be written to the on/off the
@@ -203,6 +260,13 @@ pub struct FinalizeTransactionInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The receiver's name. An account must have already been created for this user.
     #[structopt(short, long, help = "The sender's name.")]
     pub receiver: String,
@@ -230,6 +294,13 @@ pub struct JustifyTransferInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// Asset id that is transferred.
     /// An asset ticker name which is a string of at most 12 characters.
     #[structopt(
@@ -261,18 +332,17 @@ pub struct JustifyTransferInfo {
     #[structopt(short, long, help = "The name of the mediator.")]
     pub mediator: String,
 
+    /// Finalized tx as base64.
+    #[structopt(short, long, help = "Finalized tx as base64.")]
+    pub finalized_tx: String,
+
     /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
     /// The seed can be found inside the logs.
     #[structopt(
-        short,
         long,
         help = "Base64 encoding of an initial seed for the RNG. If not provided, the seed will be chosen at random."
     )]
     pub seed: Option<String>,
-
-    /// Finalized tx as base64.
-    #[structopt(short, long, help = "Finalized tx as base64.")]
-    pub finalized_tx: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, StructOpt)]
@@ -293,6 +363,13 @@ pub struct DecryptAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// An asset ticker name which is a string of at most 12 characters.
     /// In these test CLIs, the unique account id is created from the pair of username and ticker.
     #[structopt(
@@ -342,8 +419,10 @@ pub fn parse_input() -> CLI {
 
             let cfg = CreateUserAccountInfo {
                 seed,
+                mnemonic_file: cfg.mnemonic_file,
                 user: cfg.user,
                 db_dir: cfg.db_dir,
+                password_source: cfg.password_source,
                 ticker: cfg.ticker,
                 valid_ticker_names: cfg.valid_ticker_names,
             };
@@ -365,7 +444,10 @@ pub fn parse_input() -> CLI {
 
             let cfg = CreateMediatorAccountInfo {
                 seed,
+                mnemonic_file: cfg.mnemonic_file,
+                mediator_index: cfg.mediator_index,
                 db_dir,
+                password_source: cfg.password_source,
                 user: cfg.user,
             };
 
@@ -388,6 +470,7 @@ pub fn parse_input() -> CLI {
                 seed,
                 amount: cfg.amount,
                 db_dir,
+                password_source: cfg.password_source,
                 issuer: cfg.issuer,
             };
 
@@ -409,6 +492,7 @@ pub fn parse_input() -> CLI {
                 seed,
                 amount: cfg.amount,
                 db_dir,
+                password_source: cfg.password_source,
                 sender: cfg.sender,
                 receiver: cfg.receiver,
                 mediator: cfg.mediator,
@@ -433,6 +517,7 @@ pub fn parse_input() -> CLI {
                 seed,
                 amount: cfg.amount,
                 db_dir,
+                password_source: cfg.password_source,
                 receiver: cfg.receiver,
                 init_tx: cfg.init_tx,
             };
@@ -449,16 +534,18 @@ pub fn parse_input() -> CLI {
             let db_dir = cfg.db_dir.clone().or_else(|| std::env::current_dir().ok());
 
             let seed: Option<String> = cfg.seed.clone().or_else(|| Some(gen_seed()));
-            info!("Seed: {:?}", seed.clone().unwrap());
+            info!("Seed: {:?}", seed.clone().unwrap()); // unwrap won't panic
+
             let cfg = JustifyTransferInfo {
                 db_dir,
+                password_source: cfg.password_source,
                 ticker: cfg.ticker,
                 sender: cfg.sender,
                 sender_balance: cfg.sender_balance,
                 receiver: cfg.receiver,
                 mediator: cfg.mediator,
-                seed,
                 finalized_tx: cfg.finalized_tx,
+                seed,
             };
 
             info!(
@@ -474,6 +561,7 @@ pub fn parse_input() -> CLI {
             let cfg = DecryptAccountInfo {
                 ticker: cfg.ticker,
                 db_dir,
+                password_source: cfg.password_source,
                 user: cfg.user.clone(),
                 encrypted_value: cfg.encrypted_value,
             };
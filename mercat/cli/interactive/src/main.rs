@@ -4,7 +4,9 @@
 mod input;
 
 use codec::{Decode, Encode};
-use cryptography_core::asset_proofs::{AssetId, CommitmentWitness, ElgamalSecretKey};
+use cryptography_core::asset_proofs::{
+    AssetId, Balance, CommitmentWitness, DerivationPath, ElgamalSecretKey, ExtendedSecretKey,
+};
 use curve25519_dalek::scalar::Scalar;
 
 use input::{parse_input, CLI};
@@ -18,10 +20,14 @@ use mercat::{
     TransferTransactionSender,
 };
 use mercat_common::{
-    account_issue::process_issue_asset, create_rng_from_seed, debug_decrypt_base64_account_balance,
-    errors::Error, init_print_logger, justify::process_create_mediator, load_object, save_object,
-    user_public_account_file, user_secret_account_file, OrderedPubAccount, OFF_CHAIN_DIR,
-    ON_CHAIN_DIR, SECRET_ACCOUNT_FILE,
+    account_issue::process_issue_asset,
+    create_rng_from_seed, debug_decrypt_base64_account_balance, decrypt_balance,
+    errors::Error,
+    init_print_logger,
+    justify::process_create_mediator,
+    keystore::{load_secret_object, read_password, save_secret_object, PasswordSource},
+    load_master_key, load_object, save_object, user_public_account_file, user_secret_account_file,
+    OrderedPubAccount, ON_CHAIN_DIR, SECRET_ACCOUNT_FILE,
 };
 use rand::{CryptoRng, RngCore};
 use std::path::PathBuf;
@@ -45,6 +51,8 @@ fn main() {
             let db_dir = cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap();
             process_create_account(
                 cfg.seed,
+                cfg.mnemonic_file,
+                cfg.password_source,
                 db_dir,
                 cfg.user,
                 cfg.ticker,
@@ -54,12 +62,16 @@ fn main() {
         }
         CLI::CreateMediatorAccount(cfg) => process_create_mediator(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.mnemonic_file,
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.user,
+            cfg.mediator_index,
         )
         .unwrap(),
         CLI::Mint(cfg) => process_issue_asset(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.issuer,
             &[], // TODO
@@ -72,6 +84,7 @@ fn main() {
         .unwrap(),
         CLI::CreateTransaction(cfg) => process_create_tx(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.sender,
             cfg.receiver,
@@ -83,6 +96,7 @@ fn main() {
         .unwrap(),
         CLI::FinalizeTransaction(cfg) => process_finalize_tx(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.receiver,
             cfg.account_id_from_ticker,
@@ -91,14 +105,15 @@ fn main() {
         )
         .unwrap(),
         CLI::JustifyTransaction(cfg) => justify_asset_transfer_transaction(
-            cfg.seed.ok_or(Error::EmptySeed).unwrap(),
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+            cfg.password_source,
             cfg.sender,
             cfg.sender_balance,
             cfg.receiver,
             cfg.mediator,
             cfg.ticker,
             cfg.finalized_tx,
+            cfg.seed.ok_or(Error::EmptySeed).unwrap(),
         )
         .unwrap(),
         CLI::Decrypt(cfg) => info!(
@@ -107,7 +122,8 @@ fn main() {
                 cfg.user,
                 cfg.encrypted_value,
                 cfg.ticker,
-                cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap()
+                cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+                cfg.password_source,
             )
             .unwrap()
         ),
@@ -122,12 +138,18 @@ fn main() {
 
 fn process_create_account(
     seed: Option<String>,
+    mnemonic_file: Option<PathBuf>,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     user: String,
     ticker: String,
     ticker_names: Vec<String>,
 ) -> Result<(), Error> {
     let mut rng = create_rng_from_seed(seed)?;
+    let master_key = mnemonic_file
+        .map(|path| load_master_key(&path))
+        .transpose()?;
+    let password = read_password(password_source)?;
 
     let valid_asset_ids: Vec<AssetId> = ticker_names
         .into_iter()
@@ -141,19 +163,19 @@ fn process_create_account(
     let valid_asset_ids = convert_asset_ids(valid_asset_ids);
 
     // Create the account.
-    let secret_account = create_secret_account(&mut rng, ticker.clone())?;
+    let secret_account = create_secret_account(&mut rng, ticker.clone(), master_key.as_ref())?;
 
     let account_tx = AccountCreator
         .create(&secret_account, &valid_asset_ids, &mut rng)
         .map_err(|error| Error::LibraryError { error })?;
 
     // Save the artifacts to file.
-    save_object(
+    save_secret_object(
         db_dir.clone(),
-        OFF_CHAIN_DIR,
         &user,
         &user_secret_account_file(&ticker),
         &secret_account,
+        password.as_ref(),
     )?;
 
     let account_id = account_tx.pub_account.enc_asset_id;
@@ -183,20 +205,32 @@ fn process_create_account(
 fn create_secret_account<R: RngCore + CryptoRng>(
     rng: &mut R,
     ticker_id: String,
+    master_key: Option<&ExtendedSecretKey>,
 ) -> Result<SecAccount, Error> {
-    let elg_secret = ElgamalSecretKey::new(Scalar::random(rng));
+    let mut asset_id = [0u8; 12];
+    let decoded = hex::decode(ticker_id).unwrap();
+    asset_id[..decoded.len()].copy_from_slice(&decoded);
+    let asset_id = AssetId { id: asset_id };
+
+    // When the account belongs to a wallet, both the keys and the asset id blinding
+    // are derived from the wallet's master key, so that the account can be restored.
+    let (elg_secret, asset_id_blinding) = match master_key {
+        Some(master_key) => {
+            let account_key = master_key.derive_path(&DerivationPath::account(&asset_id));
+            (account_key.secret_key(), account_key.asset_id_blinding())
+        }
+        None => (
+            ElgamalSecretKey::new(Scalar::random(rng)),
+            Scalar::random(rng),
+        ),
+    };
     let elg_pub = elg_secret.get_public_key();
     let enc_keys = EncryptionKeys {
         public: elg_pub,
         secret: elg_secret,
     };
 
-    let mut asset_id = [0u8; 12];
-    let decoded = hex::decode(ticker_id).unwrap();
-    asset_id[..decoded.len()].copy_from_slice(&decoded);
-
-    let asset_id = AssetId { id: asset_id };
-    let asset_id_witness = CommitmentWitness::new(asset_id.into(), Scalar::random(rng));
+    let asset_id_witness = CommitmentWitness::new(asset_id.into(), asset_id_blinding);
 
     Ok(SecAccount {
         enc_keys,
@@ -206,15 +240,17 @@ fn create_secret_account<R: RngCore + CryptoRng>(
 
 pub fn process_create_tx(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     sender: String,
     receiver: Vec<String>,
     mediator: String,
     ticker: String,
-    amount: u32,
+    amount: Balance,
     pending_balance: String,
 ) -> Result<(), Error> {
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
 
     let sender_ordered_pub_account: OrderedPubAccount = load_object(
         db_dir.clone(),
@@ -223,11 +259,11 @@ pub fn process_create_tx(
         &user_public_account_file(&ticker),
     )?;
     let sender_account = Account {
-        secret: load_object(
-            db_dir,
-            OFF_CHAIN_DIR,
+        secret: load_secret_object(
+            db_dir.clone(),
             &sender,
             &user_secret_account_file(&ticker),
+            password.as_ref(),
         )?,
         public: sender_ordered_pub_account.pub_account,
     };
@@ -235,6 +271,13 @@ pub fn process_create_tx(
     // Calculate the pending
     let mut data: &[u8] = &base64::decode(pending_balance).unwrap();
     let pending_balance = EncryptedAmount::decode(&mut data).unwrap(); // For now the same as initial balance
+    let sender_balance = decrypt_balance(
+        &sender,
+        &ticker,
+        &sender_account.secret.enc_keys.secret,
+        &pending_balance,
+        db_dir,
+    )?;
 
     let mut data0: &[u8] = &base64::decode(&receiver[0]).unwrap();
     let mut data1: &[u8] = &base64::decode(&receiver[1]).unwrap();
@@ -259,8 +302,9 @@ pub fn process_create_tx(
         .create_transaction(
             &pending_account,
             &pending_balance,
+            sender_balance,
             &receiver_pub_account,
-            &mediator_account,
+            &[mediator_account],
             &[], // TODO
             amount,
             &mut rng,
@@ -277,13 +321,15 @@ pub fn process_create_tx(
 
 pub fn process_finalize_tx(
     seed: String,
+    password_source: Option<PasswordSource>,
     db_dir: PathBuf,
     receiver: String,
     ticker: String,
-    amount: u32,
+    amount: Balance,
     init_tx: String,
 ) -> Result<(), Error> {
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
 
     let receiver_ordered_pub_account: OrderedPubAccount = load_object(
         db_dir.clone(),
@@ -293,11 +339,11 @@ pub fn process_finalize_tx(
     )?;
 
     let receiver_account = Account {
-        secret: load_object(
+        secret: load_secret_object(
             db_dir,
-            OFF_CHAIN_DIR,
             &receiver,
             &user_secret_account_file(&ticker),
+            password.as_ref(),
         )?,
         public: receiver_ordered_pub_account.pub_account,
     };
@@ -321,23 +367,25 @@ pub fn process_finalize_tx(
 }
 
 pub fn justify_asset_transfer_transaction(
-    seed: String,
     db_dir: PathBuf,
+    password_source: Option<PasswordSource>,
     sender: Vec<String>,
     sender_balance: String,
     receiver: Vec<String>,
     mediator: String,
     ticker: String,
     finalized_tx: String,
+    seed: String,
 ) -> Result<(), Error> {
-    // Load the transaction, mediator's credentials, and issuer's public account.
     let mut rng = create_rng_from_seed(Some(seed))?;
+    let password = read_password(password_source)?;
 
+    // Load the transaction, mediator's credentials, and issuer's public account.
     let mut data: &[u8] = &base64::decode(&finalized_tx).unwrap();
     let asset_tx = FinalizedTransferTx::decode(&mut data).unwrap();
 
     let mediator_account: MediatorAccount =
-        load_object(db_dir, OFF_CHAIN_DIR, &mediator, SECRET_ACCOUNT_FILE)?;
+        load_secret_object(db_dir, &mediator, SECRET_ACCOUNT_FILE, password.as_ref())?;
 
     let mut data0: &[u8] = &base64::decode(&sender[0]).unwrap();
     let mut data1: &[u8] = &base64::decode(&sender[1]).unwrap();
@@ -365,7 +413,7 @@ pub fn justify_asset_transfer_transaction(
 
     let justified_tx = CtxMediator {}
         .justify_transaction(
-            asset_tx,
+            asset_tx.into(),
             &mediator_account.encryption_key,
             &sender_pub_account,
             &sender_balance,
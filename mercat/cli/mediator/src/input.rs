@@ -1,5 +1,6 @@
 use log::info;
-use mercat_common::{gen_seed, save_config};
+use mercat::RejectionReason;
+use mercat_common::{gen_seed, keystore::PasswordSource, save_config};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use structopt::StructOpt;
@@ -28,6 +29,13 @@ pub struct CreateMediatorAccountInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
     /// The seed can be found inside the logs.
     #[structopt(
@@ -37,6 +45,25 @@ pub struct CreateMediatorAccountInfo {
     )]
     pub seed: Option<String>,
 
+    /// An optional path to a file that holds the BIP39 mnemonic of the user's wallet.
+    /// When it is given, the keys are derived from the mnemonic, and can be restored from it later.
+    #[structopt(
+        parse(from_os_str),
+        long,
+        help = "Path to a file that holds the BIP39 mnemonic to derive the keys from. If not provided, the keys will be chosen at random."
+    )]
+    pub mnemonic_file: Option<PathBuf>,
+
+    /// The index of the mediator keys in the wallet of the mnemonic.
+    /// A wallet can hold the keys of several mediators, each under its own index.
+    #[structopt(
+        long,
+        default_value = "0",
+        help = "The index of the mediator keys to derive from the mnemonic. Only used with `--mnemonic-file`."
+    )]
+    #[serde(default)]
+    pub mediator_index: u32,
+
     /// An optional path to save the config used for this experiment.
     #[structopt(
         parse(from_os_str),
@@ -126,6 +153,13 @@ pub struct JustifyTransferInfo {
     )]
     pub db_dir: Option<PathBuf>,
 
+    /// An optional source of the password that encrypts the secret account in the keystore.
+    #[structopt(
+        long,
+        help = "Where to read the keystore password from: `env:<VARIABLE>`, `file:<PATH>`, or `prompt`. If not provided, the secret account is stored in plain text."
+    )]
+    pub password_source: Option<PasswordSource>,
+
     /// The transaction ID for the asset transaction.
     /// This ID must be the same as the one used to create the transaction,
     /// using the `mercat-account` CLI.
@@ -160,22 +194,30 @@ pub struct JustifyTransferInfo {
     #[structopt(short, long, help = "The name of the issuer.")]
     pub auditors: Vec<String>,
 
-    /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
-    /// The seed can be found inside the logs.
+    /// Whether to reject a transaction.
     #[structopt(
         short,
         long,
-        help = "Base64 encoding of an initial seed for the RNG. If not provided, the seed will be chosen at random."
+        help = "If present the mediator will reject the transaction."
     )]
-    pub seed: Option<String>,
+    pub reject: bool,
 
-    /// Whether to reject a transaction.
+    /// The reason that the mediator signs along with the rejection of a transaction.
     #[structopt(
-        short,
         long,
-        help = "If present the mediator will reject the transaction."
+        default_value = "policy-violation",
+        parse(try_from_str = parse_rejection_reason),
+        help = "The reason of the rejection: `invalid-amount`, `asset-mismatch`, or `policy-violation`. Defaults to `policy-violation`."
     )]
-    pub reject: bool,
+    pub reason: RejectionReason,
+
+    /// An optional seed, to feed to the RNG, that can be passed to reproduce a previous run of this CLI.
+    /// The seed can be found inside the logs.
+    #[structopt(
+        long,
+        help = "Base64 encoding of an initial seed for the RNG. If not provided, the seed will be chosen at random."
+    )]
+    pub seed: Option<String>,
 
     /// An optional path to save the config used for this experiment.
     #[structopt(
@@ -206,6 +248,15 @@ pub enum CLI {
     JustifyTransferTransaction(JustifyTransferInfo),
 }
 
+fn parse_rejection_reason(reason: &str) -> Result<RejectionReason, String> {
+    match reason {
+        "invalid-amount" => Ok(RejectionReason::InvalidAmount),
+        "asset-mismatch" => Ok(RejectionReason::AssetMismatch),
+        "policy-violation" => Ok(RejectionReason::PolicyViolation),
+        _ => Err(format!("Unknown rejection reason: {}", reason)),
+    }
+}
+
 pub fn parse_input() -> Result<CLI, confy::ConfyError> {
     info!("Parsing input configuration.");
     let args: CLI = CLI::from_args();
@@ -221,7 +272,10 @@ pub fn parse_input() -> Result<CLI, confy::ConfyError> {
             let cfg = CreateMediatorAccountInfo {
                 save_config: cfg.save_config.clone(),
                 seed,
+                mnemonic_file: cfg.mnemonic_file,
+                mediator_index: cfg.mediator_index,
                 db_dir,
+                password_source: cfg.password_source,
                 user: cfg.user,
             };
 
@@ -242,16 +296,19 @@ pub fn parse_input() -> Result<CLI, confy::ConfyError> {
 
             let seed: Option<String> = cfg.seed.clone().or_else(|| Some(gen_seed()));
             info!("Seed: {:?}", seed.clone().unwrap());
+
             let cfg = JustifyTransferInfo {
                 db_dir,
+                password_source: cfg.password_source,
                 tx_id: cfg.tx_id,
                 ticker: cfg.ticker,
                 sender: cfg.sender,
                 receiver: cfg.receiver,
                 mediator: cfg.mediator,
                 auditors: cfg.auditors,
-                seed,
                 reject: cfg.reject,
+                reason: cfg.reason,
+                seed,
                 save_config: cfg.save_config.clone(),
                 cheat: cfg.cheat,
                 stdout: cfg.stdout,
@@ -27,21 +27,25 @@ fn main() {
     match args {
         CLI::Create(cfg) => process_create_mediator(
             cfg.seed.ok_or(Error::EmptySeed).unwrap(),
+            cfg.mnemonic_file,
+            cfg.password_source,
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
             cfg.user,
+            cfg.mediator_index,
         )
         .unwrap(),
         CLI::JustifyTransferTransaction(cfg) => justify_asset_transfer_transaction(
             cfg.db_dir.ok_or(Error::EmptyDatabaseDir).unwrap(),
+            cfg.password_source,
+            cfg.seed.ok_or(Error::EmptySeed).unwrap(),
             cfg.sender,
             cfg.receiver,
             cfg.mediator,
             &cfg.auditors,
             cfg.ticker,
-            cfg.seed.ok_or(Error::EmptySeed).unwrap(),
             cfg.stdout,
             cfg.tx_id,
-            cfg.reject,
+            if cfg.reject { Some(cfg.reason) } else { None },
             cfg.cheat,
         )
         .unwrap(),
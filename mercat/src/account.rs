@@ -1,24 +1,26 @@
-use crate::{
-    AccountCreatorInitializer, AccountCreatorVerifier, EncryptedAmount, PubAccount, PubAccountTx,
-    SecAccount, BASE, EXPONENT,
+#[cfg(not(feature = "verify-only"))]
+use crate::{AccountCreatorInitializer, PubAccount, SecAccount};
+use crate::{AccountCreatorVerifier, EncryptedAmount, PubAccountTx, BASE, EXPONENT};
+#[cfg(not(feature = "verify-only"))]
+use cryptography_core::asset_proofs::{
+    correctness_proof::CorrectnessProverAwaitingChallenge,
+    encryption_proofs::single_property_prover, membership_proof::MembershipProverAwaitingChallenge,
+    wellformedness_proof::WellformednessProverAwaitingChallenge, CommitmentWitness,
 };
 use cryptography_core::{
     asset_proofs::{
-        bulletproofs::PedersenGens,
-        correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
-        encryption_proofs::single_property_prover,
-        encryption_proofs::single_property_verifier,
-        errors::Fallible,
-        membership_proof::{MembershipProofVerifier, MembershipProverAwaitingChallenge},
-        one_out_of_many_proof::OooNProofGenerators,
-        wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
-        AssetId, Balance, CommitmentWitness,
+        bulletproofs::PedersenGens, correctness_proof::CorrectnessVerifier,
+        encryption_proofs::single_property_verifier, errors::Fallible,
+        membership_proof::MembershipProofVerifier, one_out_of_many_proof::OooNProofGenerators,
+        wellformedness_proof::WellformednessVerifier, AssetId, Balance,
     },
     curve25519_dalek::scalar::Scalar,
 };
-use rand_core::{CryptoRng, RngCore};
 
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
 use sp_std::vec::Vec;
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroizing;
 
 // ------------------------------------------------------------------------------------------------
@@ -32,8 +34,10 @@ pub fn convert_asset_ids(valid_asset_ids: Vec<AssetId>) -> Vec<Scalar> {
         .collect::<Vec<_>>()
 }
 
+#[cfg(not(feature = "verify-only"))]
 pub struct AccountCreator;
 
+#[cfg(not(feature = "verify-only"))]
 impl AccountCreatorInitializer for AccountCreator {
     fn create<T: RngCore + CryptoRng>(
         &self,
@@ -71,7 +75,7 @@ impl AccountCreatorInitializer for AccountCreator {
         )?;
 
         // Prove that the encrypted asset id that is stored as `enc_asset_id.y` is among the list of publicly known asset ids.
-        let generators = &OooNProofGenerators::new(BASE, EXPONENT);
+        let generators = OooNProofGenerators::cached(BASE, EXPONENT);
         let asset_id = secret.asset_id_witness.value();
         let asset_membership_proof = single_property_prover(
             MembershipProverAwaitingChallenge::new(
@@ -143,7 +147,7 @@ impl AccountCreatorVerifier for AccountValidator {
 
         // Verify that the asset is from the proper asset list
         let membership_proof = account.asset_membership_proof.clone();
-        let generators = &OooNProofGenerators::new(BASE, EXPONENT);
+        let generators = OooNProofGenerators::cached(BASE, EXPONENT);
         single_property_verifier(
             &MembershipProofVerifier {
                 secret_element_com: account.pub_account.enc_asset_id.y,
@@ -1,25 +1,34 @@
 //! The MERCAT's asset issuance implementation.
 
 use crate::{
-    account::deposit, Account, AssetMemo, AssetTransactionAuditor, AssetTransactionIssuer,
-    AssetTransactionVerifier, AuditorAccount, AuditorPayload, AuditorPubAccount, EncryptedAmount,
-    EncryptionPubKey, InitializedAssetTx, PubAccount,
+    account::deposit, AssetTransactionAuditor, AssetTransactionVerifier, AuditorAccount,
+    AuditorPayload, AuditorPubAccount, EncryptedAmount, EncryptionPubKey, InitializedAssetTx,
+    PubAccount,
 };
+#[cfg(not(feature = "verify-only"))]
+use crate::{Account, AssetMemo, AssetTransactionIssuer};
 use cryptography_core::asset_proofs::{
     bulletproofs::PedersenGens,
-    correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
-    encrypting_same_value_proof::{
-        EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
-    },
-    encryption_proofs::single_property_prover,
+    correctness_proof::CorrectnessVerifier,
+    encrypting_same_value_proof::EncryptingSameValueVerifier,
     encryption_proofs::single_property_verifier,
     errors::{ErrorKind, Fallible},
-    wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
-    Balance, CommitmentWitness,
+    wellformedness_proof::WellformednessVerifier,
+    Balance,
+};
+#[cfg(not(feature = "verify-only"))]
+use cryptography_core::asset_proofs::{
+    correctness_proof::CorrectnessProverAwaitingChallenge,
+    encrypting_same_value_proof::EncryptingSameValueProverAwaitingChallenge,
+    encryption_proofs::single_property_prover,
+    wellformedness_proof::WellformednessProverAwaitingChallenge, CommitmentWitness,
 };
 
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
+#[cfg(not(feature = "verify-only"))]
 use sp_std::vec::Vec;
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroizing;
 
 /// Helper function to verify the proofs on an asset initialization transaction.
@@ -58,11 +67,14 @@ fn asset_issuance_init_verify(
     )
 }
 
-fn verify_auditor_payload(
+/// Verifies that the payload of each auditor is included, and that the auditors'
+/// cipher texts encrypt the same amount as `enc_amount`, which is encrypted using
+/// `enc_pub_key`. Returns the first error.
+pub(crate) fn verify_auditor_payload(
     auditors_payload: &[AuditorPayload],
     auditors_enc_pub_keys: &[AuditorPubAccount],
-    issuer_enc_pub_key: EncryptionPubKey,
-    issuer_enc_amount: EncryptedAmount,
+    enc_pub_key: EncryptionPubKey,
+    enc_amount: EncryptedAmount,
 ) -> Fallible<()> {
     ensure!(
         auditors_payload.len() == auditors_enc_pub_keys.len(),
@@ -70,20 +82,20 @@ fn verify_auditor_payload(
     );
 
     let gens = &PedersenGens::default();
-    let _: Fallible<()> = auditors_enc_pub_keys
+    auditors_enc_pub_keys
         .iter()
         .map(|auditor| {
             let mut found_auditor = false;
-            let _: Fallible<()> = auditors_payload
+            auditors_payload
                 .iter()
                 .map(|payload| {
                     if auditor.auditor_id == payload.auditor_id {
                         // Verify that the encrypted amounts are equal.
                         single_property_verifier(
                             &EncryptingSameValueVerifier {
-                                pub_key1: issuer_enc_pub_key,
+                                pub_key1: enc_pub_key,
                                 pub_key2: auditor.encryption_public_key,
-                                cipher1: issuer_enc_amount,
+                                cipher1: enc_amount,
                                 cipher2: payload.encrypted_amount.elgamal_cipher,
                                 pc_gens: &gens,
                             },
@@ -93,11 +105,11 @@ fn verify_auditor_payload(
                     }
                     Ok(())
                 })
-                .collect();
+                .collect::<Fallible<()>>()?;
             ensure!(found_auditor, ErrorKind::AuditorPayloadError);
             Ok(())
         })
-        .collect();
+        .collect::<Fallible<()>>()?;
 
     Ok(())
 }
@@ -108,8 +120,10 @@ fn verify_auditor_payload(
 
 /// The confidential transaction issuer issues an asset for an issuer account, and
 /// encrypts the metadata to the mediator's public key.
+#[cfg(not(feature = "verify-only"))]
 pub struct AssetIssuer;
 
+#[cfg(not(feature = "verify-only"))]
 impl AssetTransactionIssuer for AssetIssuer {
     fn initialize_asset_transaction<T: RngCore + CryptoRng>(
         &self,
@@ -151,7 +165,7 @@ impl AssetTransactionIssuer for AssetIssuer {
         )?;
 
         // Add the necessary payload for auditors.
-        let auditors_payload = add_asset_transaction_auditor(
+        let auditors_payload = add_auditor_payload(
             auditors_enc_pub_keys,
             &issr_account.secret.enc_keys.public,
             &issr_amount_witness,
@@ -169,9 +183,12 @@ impl AssetTransactionIssuer for AssetIssuer {
     }
 }
 
-fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
+/// Encrypts the amount of `amount_witness` for each of the auditors, and proves that
+/// it is the amount that is encrypted using `enc_pub_key`.
+#[cfg(not(feature = "verify-only"))]
+pub(crate) fn add_auditor_payload<T: RngCore + CryptoRng>(
     auditors_enc_pub_keys: &[AuditorPubAccount],
-    issuer_enc_pub_key: &EncryptionPubKey,
+    enc_pub_key: &EncryptionPubKey,
     amount_witness: &CommitmentWitness,
     rng: &mut T,
 ) -> Fallible<Vec<AuditorPayload>> {
@@ -179,7 +196,7 @@ fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
 
     let mut payload_vec: Vec<AuditorPayload> = Vec::with_capacity(auditors_enc_pub_keys.len());
     // Add the required payload for the auditors.
-    let _: Fallible<()> = auditors_enc_pub_keys
+    auditors_enc_pub_keys
         .iter()
         .map(|auditor| {
             let encrypted_amount = auditor
@@ -190,7 +207,7 @@ fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
             // commitment witness.
             let amount_equal_cipher_proof = single_property_prover(
                 EncryptingSameValueProverAwaitingChallenge {
-                    pub_key1: *issuer_enc_pub_key,
+                    pub_key1: *enc_pub_key,
                     pub_key2: auditor.encryption_public_key,
                     w: Zeroizing::new(amount_witness.clone()),
                     pc_gens: &gens,
@@ -207,7 +224,7 @@ fn add_asset_transaction_auditor<T: RngCore + CryptoRng>(
             payload_vec.push(payload);
             Ok(())
         })
-        .collect();
+        .collect::<Fallible<()>>()?;
 
     Ok(payload_vec)
 }
@@ -235,7 +252,7 @@ impl AssetTransactionVerifier for AssetValidator {
     /// Called by validators to verify the justification and processing of the transaction.
     fn verify_asset_transaction(
         &self,
-        amount: u32,
+        amount: Balance,
         initialized_asset_tx: &InitializedAssetTx,
         issr_account: &PubAccount,
         issr_init_balance: &EncryptedAmount,
@@ -339,7 +356,7 @@ mod tests {
     fn asset_issuance_and_validation() {
         // ----------------------- Setup
         let mut rng = StdRng::from_seed([10u8; 32]);
-        let issued_amount: Balance = 20u32;
+        let issued_amount: Balance = 20;
 
         // Generate keys for the issuer.
         let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
@@ -406,7 +423,7 @@ mod tests {
     ) {
         // ----------------------- Setup
         let mut rng = StdRng::from_seed([10u8; 32]);
-        let issued_amount: Balance = 20u32;
+        let issued_amount: Balance = 20;
 
         // Generate keys for the issuer.
         let issuer_elg_secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
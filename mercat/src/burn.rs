@@ -0,0 +1,594 @@
+//! The MERCAT's asset burn implementation.
+
+use crate::{
+    account::withdraw, asset::verify_auditor_payload, AuditorAccount, AuditorPubAccount,
+    BurnTransactionAuditor, BurnTransactionVerifier, BurnTx, EncryptedAmount, PubAccount,
+};
+#[cfg(not(feature = "verify-only"))]
+use crate::{asset::add_auditor_payload, Account, BurnMemo, BurnTransactionIssuer};
+use cryptography_core::asset_proofs::{
+    bulletproofs::PedersenGens,
+    ciphertext_refreshment_proof::CipherTextRefreshmentVerifier,
+    correctness_proof::CorrectnessVerifier,
+    encrypting_same_value_proof::EncryptingSameValueVerifier,
+    encryption_proofs::single_property_verifier,
+    errors::{ErrorKind, Fallible},
+    range_proof::verify_multiple,
+    wellformedness_proof::WellformednessVerifier,
+    BatchVerifier, BALANCE_RANGE,
+};
+#[cfg(not(feature = "verify-only"))]
+use cryptography_core::{
+    asset_proofs::{
+        ciphertext_refreshment_proof::CipherTextRefreshmentProverAwaitingChallenge,
+        correctness_proof::CorrectnessProverAwaitingChallenge,
+        encryption_proofs::single_property_prover, range_proof::prove_multiple,
+        wellformedness_proof::WellformednessProverAwaitingChallenge, Balance,
+    },
+    curve25519_dalek::scalar::Scalar,
+};
+
+#[cfg(not(feature = "verify-only"))]
+use rand_core::{CryptoRng, RngCore};
+#[cfg(not(feature = "verify-only"))]
+use zeroize::Zeroizing;
+
+/// Helper function to verify the proofs on a burn transaction.
+fn burn_verify_proofs(
+    burn_tx: &BurnTx,
+    holder_pub_account: &PubAccount,
+    holder_init_balance: &EncryptedAmount,
+) -> Fallible<()> {
+    ensure!(
+        holder_pub_account.enc_asset_id == burn_tx.account_id,
+        ErrorKind::AccountIdMismatch
+    );
+
+    let gens = &PedersenGens::default();
+    let memo = &burn_tx.memo;
+    let mut batch = BatchVerifier::new();
+
+    // Verify the proof of the burned amount's wellformedness.
+    batch.add(
+        &WellformednessVerifier {
+            pub_key: holder_pub_account.owner_enc_pub_key,
+            cipher: memo.enc_burned_amount,
+            pc_gens: &gens,
+        },
+        &burn_tx.amount_wellformedness_proof,
+    );
+
+    // Verify that the balance refreshment was done correctly.
+    batch.add(
+        &CipherTextRefreshmentVerifier::new(
+            holder_pub_account.owner_enc_pub_key,
+            *holder_init_balance,
+            memo.refreshed_enc_balance,
+            &gens,
+        ),
+        &burn_tx.balance_refreshed_same_proof,
+    );
+
+    batch.verify()?;
+
+    // Verify that the amount is not negative and that the balance has enough fund.
+    // The range proof must be over the burned amount and over the refreshed balance
+    // minus the burned amount.
+    let remaining_balance = withdraw(&memo.refreshed_enc_balance, &memo.enc_burned_amount);
+    verify_multiple(
+        &burn_tx.non_neg_amount_and_enough_fund_proof,
+        &[memo.enc_burned_amount.y, remaining_balance.y],
+        BALANCE_RANGE,
+    )
+}
+
+// -------------------------------------------------------------------------------------
+// -                                    Holder                                         -
+// -------------------------------------------------------------------------------------
+
+/// The holder of an account burns some of its units.
+#[cfg(not(feature = "verify-only"))]
+#[derive(Clone, Debug)]
+pub struct BurnIssuer;
+
+#[cfg(not(feature = "verify-only"))]
+impl BurnTransactionIssuer for BurnIssuer {
+    fn initialize_burn_transaction<T: RngCore + CryptoRng>(
+        &self,
+        holder_account: &Account,
+        holder_init_balance: &EncryptedAmount,
+        holder_balance: Balance,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        amount: Balance,
+        public_amount: bool,
+        rng: &mut T,
+    ) -> Fallible<BurnTx> {
+        let gens = PedersenGens::default();
+        let holder_enc_keys = &holder_account.secret.enc_keys;
+
+        // Check that the given balance is the one that is encrypted in the account.
+        let balance = holder_balance;
+        holder_enc_keys
+            .secret
+            .verify(holder_init_balance, &balance.into())?;
+        ensure!(
+            balance >= amount,
+            ErrorKind::NotEnoughFund {
+                balance,
+                transaction_amount: amount
+            }
+        );
+
+        // Encrypt the burned amount to the holder's public key (memo).
+        let (amount_witness, enc_burned_amount) =
+            holder_enc_keys.public.encrypt_value(amount.into(), rng);
+
+        // Refresh the encrypted balance, so that the holder knows its blinding.
+        let balance_refresh_enc_blinding = Scalar::random(rng);
+        let refreshed_enc_balance = holder_init_balance.refresh_with_hint(
+            &holder_enc_keys.secret,
+            balance_refresh_enc_blinding,
+            &balance.into(),
+        )?;
+
+        // Proof of the burned amount's wellformedness.
+        let amount_wellformedness_proof = single_property_prover(
+            WellformednessProverAwaitingChallenge {
+                pub_key: holder_enc_keys.public,
+                w: Zeroizing::new(amount_witness.clone()),
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
+
+        // Proof of the burned amount's correctness. It is only produced for a public
+        // amount, since it would let anyone brute-force a confidential one.
+        let amount_correctness_proof = if public_amount {
+            Some(single_property_prover(
+                CorrectnessProverAwaitingChallenge {
+                    pub_key: holder_enc_keys.public,
+                    w: amount_witness.clone(),
+                    pc_gens: &gens,
+                },
+                rng,
+            )?)
+        } else {
+            None
+        };
+
+        // Proof that the balance refreshment was done correctly.
+        let balance_refreshed_same_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                holder_enc_keys.secret.clone(),
+                *holder_init_balance,
+                refreshed_enc_balance,
+                &gens,
+            ),
+            rng,
+        )?;
+
+        // Prove that the amount is not negative and that the holder has enough funds,
+        // using a single aggregated range proof.
+        let amount_enc_blinding = amount_witness.blinding();
+        let non_neg_amount_and_enough_fund_proof = prove_multiple(
+            &[amount, balance - amount],
+            &[
+                amount_enc_blinding,
+                balance_refresh_enc_blinding - amount_enc_blinding,
+            ],
+            BALANCE_RANGE,
+            rng,
+        )?;
+
+        // Add the necessary payload for auditors.
+        let auditors_payload = add_auditor_payload(
+            auditors_enc_pub_keys,
+            &holder_enc_keys.public,
+            &amount_witness,
+            rng,
+        )?;
+
+        Ok(BurnTx {
+            account_id: holder_account.public.enc_asset_id,
+            memo: BurnMemo {
+                enc_burned_amount,
+                refreshed_enc_balance,
+            },
+            public_amount: if public_amount { Some(amount) } else { None },
+            amount_wellformedness_proof,
+            amount_correctness_proof,
+            balance_refreshed_same_proof,
+            non_neg_amount_and_enough_fund_proof,
+            auditors_payload,
+        })
+    }
+}
+
+// -------------------------------------------------------------------------------------
+// -                                    Validator                                      -
+// -------------------------------------------------------------------------------------
+
+pub struct BurnValidator;
+
+impl BurnTransactionVerifier for BurnValidator {
+    fn verify_burn_transaction(
+        &self,
+        burn_tx: &BurnTx,
+        holder_account: &PubAccount,
+        holder_init_balance: &EncryptedAmount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+    ) -> Fallible<EncryptedAmount> {
+        // Verify holder's proofs.
+        burn_verify_proofs(burn_tx, holder_account, holder_init_balance)?;
+
+        // Verify that a public amount is the one that is burned. A confidential burn
+        // must not carry a correctness proof.
+        match (burn_tx.public_amount, burn_tx.amount_correctness_proof) {
+            (Some(amount), Some(amount_correctness_proof)) => single_property_verifier(
+                &CorrectnessVerifier {
+                    value: amount.into(),
+                    pub_key: holder_account.owner_enc_pub_key,
+                    cipher: burn_tx.memo.enc_burned_amount,
+                    pc_gens: &PedersenGens::default(),
+                },
+                amount_correctness_proof,
+            )?,
+            (None, None) => {}
+            _ => return Err(ErrorKind::VerificationError.into()),
+        }
+
+        // Verify auditors payload.
+        verify_auditor_payload(
+            &burn_tx.auditors_payload,
+            auditors_enc_pub_keys,
+            holder_account.owner_enc_pub_key,
+            burn_tx.memo.enc_burned_amount,
+        )?;
+
+        // After successfully verifying the transaction, validator withdraws the amount
+        // from holder's account (aka processing phase).
+        Ok(withdraw(
+            holder_init_balance,
+            &burn_tx.memo.enc_burned_amount,
+        ))
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                          Auditor                                           -
+// ------------------------------------------------------------------------------------------------
+
+/// Burn transaction auditor.
+#[derive(Clone, Debug)]
+pub struct BurnAuditor;
+
+impl BurnTransactionAuditor for BurnAuditor {
+    fn audit_burn_transaction(
+        &self,
+        burn_tx: &BurnTx,
+        holder_account: &PubAccount,
+        auditor_enc_key: &AuditorAccount,
+    ) -> Fallible<()> {
+        ensure!(
+            holder_account.enc_asset_id == burn_tx.account_id,
+            ErrorKind::AccountIdMismatch
+        );
+        let gens = PedersenGens::default();
+
+        // If the payload of the auditor is there, verify that it encrypts the burned
+        // amount and decrypt it.
+        burn_tx
+            .auditors_payload
+            .iter()
+            .filter(|payload| payload.auditor_id == auditor_enc_key.auditor_id)
+            .map(|payload| {
+                single_property_verifier(
+                    &EncryptingSameValueVerifier {
+                        pub_key1: holder_account.owner_enc_pub_key,
+                        pub_key2: auditor_enc_key.encryption_key.public,
+                        cipher1: burn_tx.memo.enc_burned_amount,
+                        cipher2: payload.encrypted_amount.elgamal_cipher,
+                        pc_gens: &gens,
+                    },
+                    payload.amount_equal_cipher_proof,
+                )?;
+
+                auditor_enc_key
+                    .encryption_key
+                    .secret
+                    .const_time_decrypt(&payload.encrypted_amount)?;
+                Ok(())
+            })
+            .collect()
+    }
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    extern crate wasm_bindgen_test;
+    use super::*;
+    use crate::{
+        asset::{AssetIssuer, AssetValidator},
+        AssetTransactionIssuer, AssetTransactionVerifier, EncryptionKeys, SecAccount,
+    };
+    use cryptography_core::asset_proofs::{AssetId, CommitmentWitness, ElgamalSecretKey};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use wasm_bindgen_test::*;
+
+    fn gen_enc_key_pair(seed: u8) -> EncryptionKeys {
+        let mut rng = StdRng::from_seed([seed; 32]);
+        let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
+        let elg_pub = elg_secret.get_public_key();
+        EncryptionKeys {
+            public: elg_pub,
+            secret: elg_secret,
+        }
+    }
+
+    /// Creates an account and issues `balance` to it.
+    fn holder_account_helper(seed: u8, balance: Balance) -> (Account, EncryptedAmount) {
+        let mut rng = StdRng::from_seed([seed; 32]);
+        let enc_keys = gen_enc_key_pair(seed);
+        let asset_id_witness = CommitmentWitness::from((AssetId::from(1).into(), &mut rng));
+        let account = Account {
+            public: PubAccount {
+                enc_asset_id: enc_keys.public.encrypt(&asset_id_witness),
+                owner_enc_pub_key: enc_keys.public,
+            },
+            secret: SecAccount {
+                enc_keys,
+                asset_id_witness,
+            },
+        };
+
+        let asset_tx = AssetIssuer
+            .initialize_asset_transaction(&account, &[], balance, &mut rng)
+            .unwrap();
+        let init_balance = AssetValidator
+            .verify_asset_transaction(
+                balance,
+                &asset_tx,
+                &account.public,
+                &EncryptedAmount::default(),
+                &[],
+            )
+            .unwrap();
+
+        (account, init_balance)
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_burn_public_amount() {
+        let mut rng = StdRng::from_seed([40u8; 32]);
+        let (holder_account, holder_init_balance) = holder_account_helper(41u8, 100);
+
+        let burn_tx = BurnIssuer
+            .initialize_burn_transaction(
+                &holder_account,
+                &holder_init_balance,
+                100,
+                &[],
+                30,
+                true,
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(burn_tx.public_amount, Some(30));
+
+        let updated_holder_balance = BurnValidator
+            .verify_burn_transaction(&burn_tx, &holder_account.public, &holder_init_balance, &[])
+            .unwrap();
+
+        // ----------------------- Processing
+        // Check that the burned amount is subtracted from the account balance.
+        assert!(holder_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_holder_balance, &70u64.into())
+            .is_ok());
+
+        // Negative tests.
+        // The public amount must be the burned one.
+        let mut forged_tx = burn_tx.clone();
+        forged_tx.public_amount = Some(3);
+        assert!(BurnValidator
+            .verify_burn_transaction(
+                &forged_tx,
+                &holder_account.public,
+                &holder_init_balance,
+                &[]
+            )
+            .is_err());
+
+        // A public amount must come with its correctness proof.
+        let mut forged_tx = burn_tx.clone();
+        forged_tx.amount_correctness_proof = None;
+        assert_err!(
+            BurnValidator.verify_burn_transaction(
+                &forged_tx,
+                &holder_account.public,
+                &holder_init_balance,
+                &[]
+            ),
+            ErrorKind::VerificationError
+        );
+
+        // The holder can not burn more than their balance.
+        let result = BurnIssuer.initialize_burn_transaction(
+            &holder_account,
+            &holder_init_balance,
+            100,
+            &[],
+            101,
+            true,
+            &mut rng,
+        );
+        assert_err!(
+            result,
+            ErrorKind::NotEnoughFund {
+                balance: 100,
+                transaction_amount: 101
+            }
+        );
+
+        // The transaction burns from the holder's account only.
+        let (other_account, other_init_balance) = holder_account_helper(42u8, 100);
+        assert_err!(
+            BurnValidator.verify_burn_transaction(
+                &burn_tx,
+                &other_account.public,
+                &other_init_balance,
+                &[]
+            ),
+            ErrorKind::AccountIdMismatch
+        );
+
+        // The range proof must be over the burned amount.
+        let other_burn_tx = BurnIssuer
+            .initialize_burn_transaction(
+                &holder_account,
+                &holder_init_balance,
+                100,
+                &[],
+                40,
+                true,
+                &mut rng,
+            )
+            .unwrap();
+        let mut forged_tx = burn_tx.clone();
+        forged_tx.non_neg_amount_and_enough_fund_proof =
+            other_burn_tx.non_neg_amount_and_enough_fund_proof;
+        assert_err!(
+            BurnValidator.verify_burn_transaction(
+                &forged_tx,
+                &holder_account.public,
+                &holder_init_balance,
+                &[]
+            ),
+            ErrorKind::VerificationError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_burn_confidential_amount() {
+        let mut rng = StdRng::from_seed([43u8; 32]);
+        let (holder_account, holder_init_balance) = holder_account_helper(44u8, 100);
+
+        // Make imaginary auditors.
+        let auditors_secret_vec: Vec<AuditorAccount> = (0..3u8)
+            .map(|index| AuditorAccount {
+                auditor_id: [index; 32],
+                encryption_key: gen_enc_key_pair(50 + index),
+            })
+            .collect();
+        let auditors_vec: Vec<AuditorPubAccount> = auditors_secret_vec
+            .iter()
+            .map(|a| AuditorPubAccount {
+                auditor_id: a.auditor_id,
+                encryption_public_key: a.encryption_key.public,
+            })
+            .collect();
+
+        let burn_tx = BurnIssuer
+            .initialize_burn_transaction(
+                &holder_account,
+                &holder_init_balance,
+                100,
+                &auditors_vec,
+                30,
+                false,
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(burn_tx.public_amount, None);
+        assert!(burn_tx.amount_correctness_proof.is_none());
+
+        let updated_holder_balance = BurnValidator
+            .verify_burn_transaction(
+                &burn_tx,
+                &holder_account.public,
+                &holder_init_balance,
+                &auditors_vec,
+            )
+            .unwrap();
+
+        // ----------------------- Processing
+        // Check that the burned amount is subtracted from the account balance.
+        assert!(holder_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_holder_balance, &70u64.into())
+            .is_ok());
+
+        // ----------------------- Auditing
+        let result = auditors_secret_vec
+            .iter()
+            .map(|auditor| {
+                BurnAuditor.audit_burn_transaction(&burn_tx, &holder_account.public, auditor)
+            })
+            .collect::<Result<(), _>>();
+        assert!(result.is_ok());
+
+        // Negative tests.
+        // The holder misses an auditor. Validator catches it.
+        let result = BurnValidator.verify_burn_transaction(
+            &burn_tx,
+            &holder_account.public,
+            &holder_init_balance,
+            &auditors_vec[..2],
+        );
+        assert_err!(result, ErrorKind::AuditorPayloadError);
+
+        let verify = |burn_tx: &BurnTx| {
+            BurnValidator.verify_burn_transaction(
+                burn_tx,
+                &holder_account.public,
+                &holder_init_balance,
+                &auditors_vec,
+            )
+        };
+
+        // The auditors' payload must encrypt the burned amount.
+        let other_burn_tx = BurnIssuer
+            .initialize_burn_transaction(
+                &holder_account,
+                &holder_init_balance,
+                100,
+                &auditors_vec,
+                40,
+                false,
+                &mut rng,
+            )
+            .unwrap();
+        let mut tampered_tx = burn_tx.clone();
+        tampered_tx.auditors_payload[0].encrypted_amount =
+            other_burn_tx.auditors_payload[0].encrypted_amount;
+        assert_err!(verify(&tampered_tx), ErrorKind::VerificationError);
+        assert!(BurnAuditor
+            .audit_burn_transaction(
+                &tampered_tx,
+                &holder_account.public,
+                &auditors_secret_vec[0]
+            )
+            .is_err());
+
+        // A confidential burn can not be turned into a public one.
+        let mut forged_tx = burn_tx.clone();
+        forged_tx.public_amount = Some(30);
+        assert_err!(verify(&forged_tx), ErrorKind::VerificationError);
+
+        // Each auditor must have its own payload.
+        let mut tampered_tx = burn_tx;
+        tampered_tx.auditors_payload[1] = tampered_tx.auditors_payload[0].clone();
+        assert_err!(verify(&tampered_tx), ErrorKind::AuditorPayloadError);
+    }
+}
@@ -9,17 +9,25 @@ extern crate alloc;
 
 use codec::{Decode, Encode};
 pub use cryptography_core;
+#[cfg(not(feature = "verify-only"))]
+use cryptography_core::asset_proofs::AssetId;
 use cryptography_core::{
     asset_proofs::{
+        ciphertext_reencryption_proof::CipherEqualRotatedPubKeyProof,
         ciphertext_refreshment_proof::CipherEqualSamePubKeyProof,
         correctness_proof::CorrectnessProof,
-        encrypting_same_value_proof::CipherEqualDifferentPubKeyProof, errors::Fallible,
-        membership_proof::MembershipProof, range_proof::InRangeProof,
-        wellformedness_proof::WellformednessProof, AssetId, Balance, CipherText,
-        CipherTextWithHint, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+        encrypting_same_value_proof::CipherEqualDifferentPubKeyProof,
+        errors::Fallible,
+        membership_proof::MembershipProof,
+        range_proof::{AggregatedInRangeProof, MAX_AGGREGATION_SIZE},
+        wellformedness_proof::WellformednessProof,
+        Balance, CipherText, CipherTextWithHint, CommitmentWitness, ElgamalPublicKey,
+        ElgamalSecretKey, Signature,
     },
+    codec_wrapper::{TypeTag, Version, Versioned},
     curve25519_dalek::scalar::Scalar,
 };
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 
 #[cfg(feature = "serde")]
@@ -51,6 +59,10 @@ macro_rules! assert_err {
 const EXPONENT: u32 = 8;
 const BASE: u32 = 4;
 
+/// The largest number of receivers in a batched transaction. The amounts of the legs
+/// and the sender's remaining balance share a single aggregated range proof.
+pub const MAX_BATCH_RECEIVERS: usize = MAX_AGGREGATION_SIZE - 1;
+
 // -------------------------------------------------------------------------------------
 // -                                 New Type Def                                      -
 // -------------------------------------------------------------------------------------
@@ -92,6 +104,7 @@ pub struct MediatorAccount {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AuditorAccount {
     pub encryption_key: EncryptionKeys,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub auditor_id: [u8; 32],
 }
 
@@ -99,6 +112,7 @@ pub struct AuditorAccount {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AuditorPubAccount {
     pub encryption_public_key: EncryptionPubKey,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub auditor_id: [u8; 32],
 }
 
@@ -121,6 +135,11 @@ pub struct PubAccountTx {
     pub initial_balance_correctness_proof: CorrectnessProof,
 }
 
+impl Versioned for PubAccountTx {
+    const TYPE_TAG: TypeTag = *b"MACC";
+    const VERSION: Version = 1;
+}
+
 /// Holds the secret keys and asset id of an account. This cannot be put on the change.
 #[derive(Clone, Encode, Decode, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -131,12 +150,14 @@ pub struct SecAccount {
 
 /// Wrapper for both the secret and public account info
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Account {
     pub public: PubAccount,
     pub secret: SecAccount,
 }
 
 /// The interface for the account creation.
+#[cfg(not(feature = "verify-only"))]
 pub trait AccountCreatorInitializer {
     /// Creates a public account for a user and initializes the balance to zero.
     /// Corresponds to `CreateAccount` method of the MERCAT paper.
@@ -276,7 +297,13 @@ pub struct InitializedAssetTx {
     pub auditors_payload: Vec<AuditorPayload>,
 }
 
+impl Versioned for InitializedAssetTx {
+    const TYPE_TAG: TypeTag = *b"MIAS";
+    const VERSION: Version = 1;
+}
+
 /// The interface for the confidential asset issuance transaction.
+#[cfg(not(feature = "verify-only"))]
 pub trait AssetTransactionIssuer {
     /// Initializes a confidential asset issue transaction. Note that the returning
     /// values of this function contain sensitive information. Corresponds
@@ -294,7 +321,7 @@ pub trait AssetTransactionVerifier {
     /// Called by validators to verify the justification and processing of the transaction.
     fn verify_asset_transaction(
         &self,
-        amount: u32,
+        amount: Balance,
         justified_asset_tx: &InitializedAssetTx,
         issr_account: &PubAccount,
         issr_init_balance: &EncryptedAmount,
@@ -313,6 +340,83 @@ pub trait AssetTransactionAuditor {
     ) -> Fallible<()>;
 }
 
+// -------------------------------------------------------------------------------------
+// -                                   Asset Burn                                      -
+// -------------------------------------------------------------------------------------
+
+/// Burn memo holds the contents of a burn transaction.
+#[derive(Clone, Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BurnMemo {
+    pub enc_burned_amount: EncryptedAmount,
+    pub refreshed_enc_balance: EncryptedAmount,
+}
+
+/// Holds the public portion of a burn transaction, in which the holder of an account
+/// destroys some of its units. The burned amount is either public, or only revealed
+/// to the auditors.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BurnTx {
+    pub account_id: EncryptedAssetId,
+    pub memo: BurnMemo,
+    pub public_amount: Option<Balance>,
+    pub amount_wellformedness_proof: WellformednessProof,
+    /// Only set when the amount is public.
+    pub amount_correctness_proof: Option<CorrectnessProof>,
+    pub balance_refreshed_same_proof: CipherEqualSamePubKeyProof,
+    pub non_neg_amount_and_enough_fund_proof: AggregatedInRangeProof,
+    pub auditors_payload: Vec<AuditorPayload>,
+}
+
+impl Versioned for BurnTx {
+    const TYPE_TAG: TypeTag = *b"MBRN";
+    const VERSION: Version = 1;
+}
+
+/// The interface for the confidential burn transaction.
+#[cfg(not(feature = "verify-only"))]
+pub trait BurnTransactionIssuer {
+    /// Initializes a transaction that burns `amount` from the holder's account.
+    /// The amount is revealed to the validators if `public_amount` is set, and only
+    /// to the auditors otherwise.
+    ///
+    /// The holder passes in the plain text of `holder_init_balance` as
+    /// `holder_balance`. It is checked against the encrypted balance before use.
+    fn initialize_burn_transaction<T: RngCore + CryptoRng>(
+        &self,
+        holder_account: &Account,
+        holder_init_balance: &EncryptedAmount,
+        holder_balance: Balance,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        amount: Balance,
+        public_amount: bool,
+        rng: &mut T,
+    ) -> Fallible<BurnTx>;
+}
+
+pub trait BurnTransactionVerifier {
+    /// Called by validators to verify and process the transaction. It returns the
+    /// holder's balance with the burned amount withdrawn.
+    fn verify_burn_transaction(
+        &self,
+        burn_tx: &BurnTx,
+        holder_account: &PubAccount,
+        holder_init_balance: &EncryptedAmount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+    ) -> Fallible<EncryptedAmount>;
+}
+
+pub trait BurnTransactionAuditor {
+    /// Verify the transaction and audit the holder's encrypted amount.
+    fn audit_burn_transaction(
+        &self,
+        burn_tx: &BurnTx,
+        holder_account: &PubAccount,
+        auditor_enc_keys: &AuditorAccount,
+    ) -> Fallible<()>;
+}
+
 // -------------------------------------------------------------------------------------
 // -                       Confidential Transfer Transaction                           -
 // -------------------------------------------------------------------------------------
@@ -320,11 +424,21 @@ pub trait AssetTransactionAuditor {
 #[derive(Clone, Encode, Decode, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AuditorPayload {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub auditor_id: [u8; 32],
     pub encrypted_amount: EncryptedAmountWithHint,
     pub amount_equal_cipher_proof: CipherEqualDifferentPubKeyProof,
 }
 
+/// Holds the asset id and the amount of a transaction encrypted for one of its mediators.
+#[derive(Clone, Copy, Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MediatorPayload {
+    pub mediator_pub_key: EncryptionPubKey,
+    pub enc_asset_id: EncryptedAssetId,
+    pub enc_amount: EncryptedAmountWithHint,
+}
+
 /// Holds the memo for confidential transaction sent by the sender.
 #[derive(Default, Clone, Copy, Encode, Decode, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -336,8 +450,6 @@ pub struct TransferTxMemo {
     pub refreshed_enc_balance: EncryptedAmount,
     pub refreshed_enc_asset_id: EncryptedAssetId,
     pub enc_asset_id_using_receiver: EncryptedAssetId,
-    pub enc_asset_id_for_mediator: EncryptedAssetId,
-    pub enc_amount_for_mediator: EncryptedAmountWithHint,
 }
 
 /// Holds the proofs and memo of the confidential transaction sent by the sender.
@@ -345,8 +457,7 @@ pub struct TransferTxMemo {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InitializedTransferTx {
     pub amount_equal_cipher_proof: CipherEqualDifferentPubKeyProof,
-    pub non_neg_amount_proof: InRangeProof,
-    pub enough_fund_proof: InRangeProof,
+    pub non_neg_amount_and_enough_fund_proof: AggregatedInRangeProof,
     pub memo: TransferTxMemo,
     pub asset_id_equal_cipher_with_sender_receiver_keys_proof: CipherEqualDifferentPubKeyProof,
     pub balance_refreshed_same_proof: CipherEqualSamePubKeyProof,
@@ -354,6 +465,24 @@ pub struct InitializedTransferTx {
     pub asset_id_correctness_proof: CorrectnessProof,
     pub amount_correctness_proof: CorrectnessProof,
     pub auditors_payload: Vec<AuditorPayload>,
+    pub mediators_payload: Vec<MediatorPayload>,
+}
+
+impl InitializedTransferTx {
+    /// Returns the payload of the mediator with the given key.
+    pub fn mediator_payload(
+        &self,
+        mediator_pub_key: &EncryptionPubKey,
+    ) -> Option<&MediatorPayload> {
+        self.mediators_payload
+            .iter()
+            .find(|payload| payload.mediator_pub_key == *mediator_pub_key)
+    }
+}
+
+impl Versioned for InitializedTransferTx {
+    const TYPE_TAG: TypeTag = *b"MITX";
+    const VERSION: Version = 1;
 }
 
 /// Holds the initial transaction data and the proof of equality of asset ids
@@ -365,29 +494,66 @@ pub struct FinalizedTransferTx {
     pub asset_id_from_sender_equal_to_receiver_proof: CipherEqualSamePubKeyProof,
 }
 
-/// Wrapper for the contents and auditors' payload.
+impl Versioned for FinalizedTransferTx {
+    const TYPE_TAG: TypeTag = *b"MFTX";
+    const VERSION: Version = 1;
+}
+
+/// The signature of a mediator that approves a transaction.
+#[derive(Clone, Copy, Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MediatorJustification {
+    pub mediator_pub_key: EncryptionPubKey,
+    pub signature: Signature,
+}
+
+/// Wrapper for the contents and the justifications of the mediators.
 #[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct JustifiedTransferTx {
     pub finalized_data: FinalizedTransferTx,
+    pub justifications: Vec<MediatorJustification>,
+}
+
+impl From<FinalizedTransferTx> for JustifiedTransferTx {
+    /// Wraps a finalized transaction that none of the mediators has justified yet.
+    fn from(finalized_data: FinalizedTransferTx) -> Self {
+        JustifiedTransferTx {
+            finalized_data,
+            justifications: Vec::new(),
+        }
+    }
+}
+
+impl Versioned for JustifiedTransferTx {
+    const TYPE_TAG: TypeTag = *b"MJTX";
+    const VERSION: Version = 1;
 }
 
 /// The interface for confidential transaction.
+#[cfg(not(feature = "verify-only"))]
 pub trait TransferTransactionSender {
     /// This is called by the sender of a confidential transaction. The outputs
     /// can be safely placed on the chain. It corresponds to `CreateCTX` function of
     /// MERCAT paper.
+    ///
+    /// The sender passes in the plain text of `sender_init_balance` as
+    /// `sender_balance`, since decrypting a 64-bit balance is not practical.
+    /// It is checked against the encrypted balance before use.
     fn create_transaction<T: RngCore + CryptoRng>(
         &self,
         sender_account: &Account,
         sender_init_balance: &EncryptedAmount,
+        sender_balance: Balance,
         receiver_pub_account: &PubAccount,
-        mediator_pub_key: &EncryptionPubKey,
+        mediators_pub_keys: &[EncryptionPubKey],
         auditors_enc_pub_keys: &[AuditorPubAccount],
         amount: Balance,
         rng: &mut T,
     ) -> Fallible<InitializedTransferTx>;
 }
 
+#[cfg(not(feature = "verify-only"))]
 pub trait TransferTransactionReceiver {
     /// This function is called the receiver of the transaction to finalize and process
     /// the transaction. It corresponds to `FinalizeCTX` and `ProcessCTX` functions
@@ -401,31 +567,37 @@ pub trait TransferTransactionReceiver {
     ) -> Fallible<FinalizedTransferTx>;
 }
 
+#[cfg(not(feature = "verify-only"))]
 pub trait TransferTransactionMediator {
-    /// Justify the transaction by mediator.
-    fn justify_transaction<R: RngCore + CryptoRng>(
+    /// Justify the transaction by mediator. The mediator adds its justification to those
+    /// of the other mediators in `justified_transaction`. A finalized transaction converts
+    /// into a `JustifiedTransferTx` without justifications.
+    fn justify_transaction<T: RngCore + CryptoRng>(
         &self,
-        finalized_transaction: FinalizedTransferTx,
+        justified_transaction: JustifiedTransferTx,
         mediator_enc_keys: &EncryptionKeys,
         sender_account: &PubAccount,
         sender_init_balance: &EncryptedAmount,
         receiver_account: &PubAccount,
         auditors_enc_pub_keys: &[AuditorPubAccount],
         asset_id_hint: AssetId,
-        rng: &mut R,
+        rng: &mut T,
     ) -> Fallible<JustifiedTransferTx>;
 }
 
 pub trait TransferTransactionVerifier {
     /// Verify the initialized, finalized, and justified transactions.
-    fn verify_transaction<R: RngCore + CryptoRng>(
+    /// At least `mediators_threshold` of `mediators_pub_keys` must have justified
+    /// the transaction.
+    fn verify_transaction(
         &self,
         justified_transaction: &JustifiedTransferTx,
         sender_account: &PubAccount,
         sender_init_balance: &EncryptedAmount,
         receiver_account: &PubAccount,
         auditors_enc_pub_keys: &[AuditorPubAccount],
-        rng: &mut R,
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
     ) -> Fallible<()>;
 }
 
@@ -445,40 +617,421 @@ pub trait TransferTransactionAuditor {
 // -                         Reversal Confidential Transaction                         -
 // -------------------------------------------------------------------------------------
 
-/// Holds the public portion of the reversal transaction.
+/// Holds the memo for reversal of the confidential transaction sent by the mediator.
+/// The validators accept a single reversal for each `transfer_id`.
+#[derive(Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReversedTransferTxMemo {
+    pub transfer_id: u64,
+    pub enc_amount_using_sender: EncryptedAmount,
+    pub enc_amount_using_receiver: EncryptedAmount,
+}
+
+/// Holds the public portion of the reversal transaction, along with the proofs that it
+/// returns the transferred amount to the sender and the mediators' approvals of both.
+/// The first approval is the one of the mediator that reverses the transaction.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ReversedTransferTx {
-    _final_data: InitializedTransferTx,
-    _memo: ReversedTransferTxMemo,
+    pub justified_data: JustifiedTransferTx,
+    pub memo: ReversedTransferTxMemo,
+    pub amount_reencrypted_for_receiver_proof: CipherEqualRotatedPubKeyProof,
+    pub amount_equal_cipher_proof: CipherEqualDifferentPubKeyProof,
+    pub approvals: Vec<MediatorJustification>,
 }
 
-/// Holds the memo for reversal of the confidential transaction sent by the mediator.
-pub struct ReversedTransferTxMemo {
-    _enc_amount_using_receiver: EncryptedAmount,
-    _enc_refreshed_amount: EncryptedAmount,
-    _enc_asset_id_using_receiver: EncryptedAssetId,
+impl Versioned for ReversedTransferTx {
+    const TYPE_TAG: TypeTag = *b"MRTX";
+    const VERSION: Version = 1;
+}
+
+/// Holds the reversal transaction and the receiver's proof that its balance still
+/// covers the amount that goes back to the sender.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FinalizedReversedTransferTx {
+    pub reversed_data: ReversedTransferTx,
+    pub refreshed_enc_balance: EncryptedAmount,
+    pub balance_refreshed_same_proof: CipherEqualSamePubKeyProof,
+    pub non_neg_balance_proof: AggregatedInRangeProof,
 }
 
+impl Versioned for FinalizedReversedTransferTx {
+    const TYPE_TAG: TypeTag = *b"MFRX";
+    const VERSION: Version = 1;
+}
+
+#[cfg(not(feature = "verify-only"))]
 pub trait ReversedTransferTransactionMediator {
-    /// This function is called by the mediator to reverse and process the reversal of
-    /// the transaction. It corresponds to `ReverseCTX` of the MERCAT paper.
-    fn create(
+    /// This function is called by the mediator to reverse a justified transaction.
+    /// It corresponds to `ReverseCTX` of the MERCAT paper.
+    ///
+    /// The mediator decrypts the transferred amount and re-encrypts it under the keys
+    /// of the receiver and of the sender, so the validators can move it back without
+    /// learning it. The mediator signs the reversal of the transfer `transfer_id`.
+    fn reverse_transaction<T: RngCore + CryptoRng>(
+        &self,
+        justified_transaction: JustifiedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        sender_account: &PubAccount,
+        receiver_account: &PubAccount,
+        transfer_id: u64,
+        rng: &mut T,
+    ) -> Fallible<ReversedTransferTx>;
+
+    /// This function is called by the other mediators of a transaction to approve its
+    /// reversal. The mediator adds its signature of the reversal to the approvals of
+    /// the other mediators in `reversed_transaction`.
+    fn approve_reversed_transaction<T: RngCore + CryptoRng>(
         &self,
-        transaction_final_data: FinalizedTransferTx,
-        mediator_enc_keys: EncryptionSecKey,
-        state: TransferTxState,
-    ) -> Fallible<(ReversedTransferTx, TransferTxState)>;
+        reversed_transaction: ReversedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        rng: &mut T,
+    ) -> Fallible<ReversedTransferTx>;
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub trait ReversedTransferTransactionReceiver {
+    /// This function is called by the receiver of the reversed transaction to prove that
+    /// its balance still covers the amount that goes back to the sender.
+    ///
+    /// The receiver passes in the plain text of `receiver_init_balance` as
+    /// `receiver_balance`, which is checked against the encrypted balance before use.
+    fn finalize_reversed_transaction<T: RngCore + CryptoRng>(
+        &self,
+        reversed_transaction: ReversedTransferTx,
+        receiver_account: &Account,
+        receiver_init_balance: &EncryptedAmount,
+        receiver_balance: Balance,
+        amount: Balance,
+        rng: &mut T,
+    ) -> Fallible<FinalizedReversedTransferTx>;
 }
 
 pub trait ReversedTransferTransactionVerifier {
-    /// This function is called by validators to verify the reversal and processing of the
-    /// reversal transaction.
-    fn verify(
+    /// This function is called by validators to verify and process the reversal of
+    /// the transfer `transfer_id`. It returns the new encrypted balances of the sender
+    /// and the receiver.
+    ///
+    /// The transfer must have been justified by at least `mediators_threshold` of
+    /// `mediators_pub_keys`, and one of them must be the mediator that reverses it.
+    /// The reversal must in turn be approved by at least `mediators_threshold` of them,
+    /// including the mediator that reverses it, so that no single mediator can undo a
+    /// transfer that needed several of them.
+    fn verify_reversed_transaction(
         &self,
-        reverse_transaction_data: ReversedTransferTx,
-        state: TransferTxState,
-    ) -> Fallible<TransferTxState>;
+        reversed_transaction: &FinalizedReversedTransferTx,
+        transfer_id: u64,
+        sender_account: &PubAccount,
+        sender_balance: &EncryptedAmount,
+        receiver_account: &PubAccount,
+        receiver_balance: &EncryptedAmount,
+        mediator_pub_key: &EncryptionPubKey,
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
+    ) -> Fallible<(EncryptedAmount, EncryptedAmount)>;
+}
+
+// -------------------------------------------------------------------------------------
+// -                         Rejected Confidential Transaction                         -
+// -------------------------------------------------------------------------------------
+
+/// The reason a mediator gives for rejecting a confidential transaction.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RejectionReason {
+    /// The transferred amount does not match what the mediator expects.
+    InvalidAmount,
+    /// The transferred asset does not match what the mediator expects.
+    AssetMismatch,
+    /// The transaction breaks a policy of the mediator.
+    PolicyViolation,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            RejectionReason::InvalidAmount => "invalid-amount",
+            RejectionReason::AssetMismatch => "asset-mismatch",
+            RejectionReason::PolicyViolation => "policy-violation",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// The signature of a mediator that rejects a transaction, along with its reason.
+#[derive(Clone, Copy, Encode, Decode, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MediatorRejection {
+    pub mediator_pub_key: EncryptionPubKey,
+    pub reason: RejectionReason,
+    pub signature: Signature,
+}
+
+/// Holds a finalized transaction along with the rejections of its mediators.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RejectedTransferTx {
+    pub finalized_data: FinalizedTransferTx,
+    pub rejections: Vec<MediatorRejection>,
+}
+
+impl From<FinalizedTransferTx> for RejectedTransferTx {
+    /// Wraps a finalized transaction that none of the mediators has rejected yet.
+    fn from(finalized_data: FinalizedTransferTx) -> Self {
+        RejectedTransferTx {
+            finalized_data,
+            rejections: Vec::new(),
+        }
+    }
+}
+
+impl Versioned for RejectedTransferTx {
+    const TYPE_TAG: TypeTag = *b"MXTX";
+    const VERSION: Version = 1;
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub trait RejectedTransferTransactionMediator {
+    /// This function is called by a mediator to reject a finalized transaction. The
+    /// mediator adds its signature of the transaction and the reason to the rejections
+    /// of the other mediators in `rejected_transaction`. A finalized transaction converts
+    /// into a `RejectedTransferTx` without rejections.
+    fn reject_transaction<T: RngCore + CryptoRng>(
+        &self,
+        rejected_transaction: RejectedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        reason: RejectionReason,
+        rng: &mut T,
+    ) -> Fallible<RejectedTransferTx>;
+}
+
+pub trait RejectedTransferTransactionVerifier {
+    /// This function is called by validators to verify the mediators' rejection of
+    /// a transaction. It returns the sender's pending balance with the amount of the
+    /// rejected transaction released.
+    ///
+    /// `pending_transaction` is the initialized transaction that the validators verified
+    /// and withdrew from the sender's balance. The rejection must be of that transaction,
+    /// so that the released amount is the one that was withdrawn.
+    ///
+    /// A transaction that needs the justifications of `mediators_threshold` of the `n`
+    /// `mediators_pub_keys` is rejected once `n - mediators_threshold + 1` of them have
+    /// rejected it, since it can then no longer be justified.
+    fn verify_rejected_transaction(
+        &self,
+        rejected_transaction: &RejectedTransferTx,
+        pending_transaction: &InitializedTransferTx,
+        sender_account: &PubAccount,
+        sender_pending_balance: &EncryptedAmount,
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
+    ) -> Fallible<EncryptedAmount>;
+}
+
+// -------------------------------------------------------------------------------------
+// -                         Batched Confidential Transaction                          -
+// -------------------------------------------------------------------------------------
+
+/// Holds the part of a batched transaction that goes to one of its receivers.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchTransferLeg {
+    pub receiver_account_id: EncryptedAssetId,
+    pub enc_amount_using_sender: EncryptedAmount,
+    pub enc_amount_using_receiver: EncryptedAmount,
+    pub enc_asset_id_using_receiver: EncryptedAssetId,
+    pub amount_equal_cipher_proof: CipherEqualDifferentPubKeyProof,
+    pub asset_id_equal_cipher_with_sender_receiver_keys_proof: CipherEqualDifferentPubKeyProof,
+    pub amount_correctness_proof: CorrectnessProof,
+    pub mediators_payload: Vec<MediatorPayload>,
+}
+
+impl BatchTransferLeg {
+    /// Returns the payload of the mediator with the given key.
+    pub fn mediator_payload(
+        &self,
+        mediator_pub_key: &EncryptionPubKey,
+    ) -> Option<&MediatorPayload> {
+        self.mediators_payload
+            .iter()
+            .find(|payload| payload.mediator_pub_key == *mediator_pub_key)
+    }
+}
+
+/// Holds the memo of a batched transaction, which is shared by all of its legs.
+#[derive(Default, Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchTransferTxMemo {
+    pub sender_account_id: EncryptedAssetId,
+    pub refreshed_enc_balance: EncryptedAmount,
+    pub refreshed_enc_asset_id: EncryptedAssetId,
+}
+
+/// Holds the proofs and memo of a confidential transaction in which one sender pays
+/// several receivers of the same asset. The balance is refreshed once, and a single
+/// aggregated range proof shows that all the amounts are not negative and that their
+/// sum does not exceed the balance. Each leg carries the payloads of the mediators,
+/// while the auditors get the sum of the amounts.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchTransferTx {
+    pub memo: BatchTransferTxMemo,
+    pub legs: Vec<BatchTransferLeg>,
+    pub non_neg_amounts_and_enough_fund_proof: AggregatedInRangeProof,
+    pub balance_refreshed_same_proof: CipherEqualSamePubKeyProof,
+    pub asset_id_refreshed_same_proof: CipherEqualSamePubKeyProof,
+    pub asset_id_correctness_proof: CorrectnessProof,
+    pub auditors_payload: Vec<AuditorPayload>,
+}
+
+impl BatchTransferTx {
+    /// Returns the sum of the amounts of all the legs, encrypted using the sender's key.
+    /// This is what the validators withdraw from the sender's balance.
+    pub fn total_enc_amount_using_sender(&self) -> EncryptedAmount {
+        self.legs
+            .iter()
+            .fold(EncryptedAmount::default(), |total, leg| {
+                &total + &leg.enc_amount_using_sender
+            })
+    }
+}
+
+impl Versioned for BatchTransferTx {
+    const TYPE_TAG: TypeTag = *b"MBTX";
+    const VERSION: Version = 1;
+}
+
+/// Holds the proof of one of the receivers of a batched transaction that the asset id
+/// of its leg is the same as the one of its account.
+#[derive(Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchTransferReceipt {
+    pub receiver_account_id: EncryptedAssetId,
+    pub asset_id_from_sender_equal_to_receiver_proof: CipherEqualSamePubKeyProof,
+}
+
+/// Wrapper for a batched transaction and the receipts of its receivers.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FinalizedBatchTransferTx {
+    pub batch_data: BatchTransferTx,
+    pub receipts: Vec<BatchTransferReceipt>,
+}
+
+impl From<BatchTransferTx> for FinalizedBatchTransferTx {
+    /// Wraps a batched transaction that none of the receivers has finalized yet.
+    fn from(batch_data: BatchTransferTx) -> Self {
+        FinalizedBatchTransferTx {
+            batch_data,
+            receipts: Vec::new(),
+        }
+    }
+}
+
+impl Versioned for FinalizedBatchTransferTx {
+    const TYPE_TAG: TypeTag = *b"MFBX";
+    const VERSION: Version = 1;
+}
+
+/// Wrapper for a finalized batched transaction and the justifications of the mediators.
+#[derive(Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JustifiedBatchTransferTx {
+    pub finalized_data: FinalizedBatchTransferTx,
+    pub justifications: Vec<MediatorJustification>,
+}
+
+impl From<FinalizedBatchTransferTx> for JustifiedBatchTransferTx {
+    /// Wraps a finalized batched transaction that none of the mediators has justified yet.
+    fn from(finalized_data: FinalizedBatchTransferTx) -> Self {
+        JustifiedBatchTransferTx {
+            finalized_data,
+            justifications: Vec::new(),
+        }
+    }
+}
+
+impl Versioned for JustifiedBatchTransferTx {
+    const TYPE_TAG: TypeTag = *b"MJBX";
+    const VERSION: Version = 1;
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub trait BatchTransferTransactionSender {
+    /// This is called by the sender to pay each of `receivers` its amount in a single
+    /// transaction. The outputs can be safely placed on the chain.
+    ///
+    /// Each leg is encrypted for all the `mediators_pub_keys`, and the auditors get the
+    /// sum of the amounts.
+    ///
+    /// A batch pays at most `MAX_BATCH_RECEIVERS` receivers. The sender splits larger
+    /// payments into several batches, each of them spending the balance that the
+    /// previous one left.
+    fn create_batch_transaction<T: RngCore + CryptoRng>(
+        &self,
+        sender_account: &Account,
+        sender_init_balance: &EncryptedAmount,
+        sender_balance: Balance,
+        receivers: &[(PubAccount, Balance)],
+        mediators_pub_keys: &[EncryptionPubKey],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        rng: &mut T,
+    ) -> Fallible<BatchTransferTx>;
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub trait BatchTransferTransactionReceiver {
+    /// This is called by each of the receivers of a batched transaction to check its
+    /// amount and add its receipt to those of the other receivers in
+    /// `finalized_transaction`. A batched transaction converts into a
+    /// `FinalizedBatchTransferTx` without receipts.
+    fn finalize_batch_transaction<T: RngCore + CryptoRng>(
+        &self,
+        finalized_transaction: FinalizedBatchTransferTx,
+        receiver_account: Account,
+        amount: Balance,
+        rng: &mut T,
+    ) -> Fallible<FinalizedBatchTransferTx>;
+}
+
+#[cfg(not(feature = "verify-only"))]
+pub trait BatchTransferTransactionMediator {
+    /// Justify the batched transaction by mediator, after checking the amounts of all of
+    /// its legs. The mediator adds its justification to those of the other mediators in
+    /// `justified_transaction`. A finalized batched transaction converts into a
+    /// `JustifiedBatchTransferTx` without justifications.
+    fn justify_batch_transaction<T: RngCore + CryptoRng>(
+        &self,
+        justified_transaction: JustifiedBatchTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receivers_accounts: &[PubAccount],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        asset_id_hint: AssetId,
+        rng: &mut T,
+    ) -> Fallible<JustifiedBatchTransferTx>;
+}
+
+pub trait BatchTransferTransactionVerifier {
+    /// Verify the batched transaction, the receipts of all of its receivers and the
+    /// justifications of its mediators. `receivers_accounts` must be in the order of the
+    /// legs of the transaction. At least `mediators_threshold` of `mediators_pub_keys`
+    /// must have justified the transaction.
+    fn verify_batch_transaction(
+        &self,
+        justified_transaction: &JustifiedBatchTransferTx,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receivers_accounts: &[PubAccount],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
+    ) -> Fallible<()>;
 }
 
 pub mod account;
 pub mod asset;
+pub mod burn;
 pub mod transaction;
@@ -1,32 +1,59 @@
 use crate::{
-    Account, AuditorAccount, AuditorPayload, AuditorPubAccount, EncryptedAmount, EncryptionKeys,
-    EncryptionPubKey, FinalizedTransferTx, InitializedTransferTx, JustifiedTransferTx, PubAccount,
-    TransferTransactionAuditor, TransferTransactionMediator, TransferTransactionReceiver,
-    TransferTransactionSender, TransferTransactionVerifier, TransferTxMemo, TransferTxState,
-    TxSubstate,
+    account::{deposit, withdraw},
+    asset::verify_auditor_payload,
+    AuditorAccount, AuditorPubAccount, BatchTransferTransactionVerifier, EncryptedAmount,
+    EncryptionPubKey, FinalizedBatchTransferTx, FinalizedReversedTransferTx, FinalizedTransferTx,
+    InitializedTransferTx, JustifiedBatchTransferTx, JustifiedTransferTx, MediatorJustification,
+    PubAccount, RejectedTransferTransactionVerifier, RejectedTransferTx, RejectionReason,
+    ReversedTransferTransactionVerifier, ReversedTransferTxMemo, TransferTransactionAuditor,
+    TransferTransactionVerifier, TransferTxState, TxSubstate, MAX_BATCH_RECEIVERS,
+};
+#[cfg(not(feature = "verify-only"))]
+use crate::{
+    asset::add_auditor_payload, Account, BatchTransferLeg, BatchTransferReceipt,
+    BatchTransferTransactionMediator, BatchTransferTransactionReceiver,
+    BatchTransferTransactionSender, BatchTransferTx, BatchTransferTxMemo, EncryptionKeys,
+    MediatorPayload, MediatorRejection, RejectedTransferTransactionMediator,
+    ReversedTransferTransactionMediator, ReversedTransferTransactionReceiver, ReversedTransferTx,
+    TransferTransactionMediator, TransferTransactionReceiver, TransferTransactionSender,
+    TransferTxMemo,
 };
 use cryptography_core::{
     asset_proofs::{
+        batch_verifier::BatchVerifier,
         bulletproofs::PedersenGens,
-        ciphertext_refreshment_proof::{
-            CipherTextRefreshmentProverAwaitingChallenge, CipherTextRefreshmentVerifier,
-        },
-        correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
-        elgamal_encryption::encrypt_using_two_pub_keys,
-        encrypting_same_value_proof::{
-            EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
-        },
-        encryption_proofs::single_property_prover,
+        ciphertext_reencryption_proof::CipherTextReencryptionVerifier,
+        ciphertext_refreshment_proof::CipherTextRefreshmentVerifier,
+        correctness_proof::CorrectnessVerifier,
+        encrypting_same_value_proof::EncryptingSameValueVerifier,
         encryption_proofs::single_property_verifier,
         errors::{ErrorKind, Fallible},
-        range_proof::{prove_within_range, verify_within_range},
-        AssetId, Balance, CommitmentWitness, BALANCE_RANGE,
+        range_proof::verify_multiple,
+        BALANCE_RANGE,
+    },
+    curve25519_dalek::ristretto::RistrettoPoint,
+};
+#[cfg(not(feature = "verify-only"))]
+use cryptography_core::{
+    asset_proofs::{
+        ciphertext_reencryption_proof::CipherTextReencryptionProverAwaitingChallenge,
+        ciphertext_refreshment_proof::CipherTextRefreshmentProverAwaitingChallenge,
+        correctness_proof::CorrectnessProverAwaitingChallenge,
+        elgamal_encryption::encrypt_using_two_pub_keys,
+        encrypting_same_value_proof::EncryptingSameValueProverAwaitingChallenge,
+        encryption_proofs::single_property_prover, range_proof::prove_multiple, AssetId, Balance,
+        CommitmentWitness,
     },
     curve25519_dalek::scalar::Scalar,
 };
 
+use codec::Encode;
+#[cfg(not(feature = "verify-only"))]
+use merlin::{Transcript, TranscriptRng};
+#[cfg(not(feature = "verify-only"))]
 use rand_core::{CryptoRng, RngCore};
 use sp_std::vec::Vec;
+#[cfg(not(feature = "verify-only"))]
 use zeroize::Zeroizing;
 
 // -------------------------------------------------------------------------------------
@@ -35,16 +62,19 @@ use zeroize::Zeroizing;
 
 /// The sender of a confidential transaction. Sender creates a transaction
 /// and performs initial proofs.
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone, Debug)]
 pub struct CtxSender;
 
+#[cfg(not(feature = "verify-only"))]
 impl TransferTransactionSender for CtxSender {
     fn create_transaction<T: RngCore + CryptoRng>(
         &self,
         sender_account: &Account,
         sender_init_balance: &EncryptedAmount,
+        sender_balance: Balance,
         receiver_pub_account: &PubAccount,
-        mediator_pub_key: &EncryptionPubKey,
+        mediators_pub_keys: &[EncryptionPubKey],
         auditors_enc_pub_keys: &[AuditorPubAccount],
         amount: Balance,
         rng: &mut T,
@@ -54,9 +84,16 @@ impl TransferTransactionSender for CtxSender {
         let sender_pub_account = &sender_account.public;
         let receiver_pub_key = receiver_pub_account.owner_enc_pub_key;
 
-        // NOTE: If this decryption ends up being too slow, we can pass in the balance
-        // as input.
-        let balance = sender_enc_keys.secret.decrypt(sender_init_balance)?;
+        ensure!(
+            !mediators_pub_keys.is_empty(),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // Check that the given balance is the one that is encrypted in the account.
+        let balance = sender_balance;
+        sender_enc_keys
+            .secret
+            .verify(sender_init_balance, &balance.into())?;
         ensure!(
             balance >= amount,
             ErrorKind::NotEnoughFund {
@@ -65,129 +102,174 @@ impl TransferTransactionSender for CtxSender {
             }
         );
 
-        // Prove that the amount is not negative.
         let witness = CommitmentWitness::new(amount.into(), Scalar::random(rng));
         let amount_enc_blinding = witness.blinding();
-
-        let non_neg_amount_proof =
-            prove_within_range(amount.into(), amount_enc_blinding, BALANCE_RANGE, rng)?;
-
-        // Prove that the amount encrypted under different public keys are the same.
         let (sender_new_enc_amount, receiver_new_enc_amount) =
             encrypt_using_two_pub_keys(&witness, sender_enc_keys.public, receiver_pub_key);
-        let gens = PedersenGens::default();
-        let amount_equal_cipher_proof = single_property_prover(
-            EncryptingSameValueProverAwaitingChallenge {
-                pub_key1: sender_enc_keys.public,
-                pub_key2: receiver_pub_key,
-                w: Zeroizing::new(witness.clone()),
-                pc_gens: &gens,
-            },
-            rng,
-        )?;
 
-        // Refresh the encrypted balance and prove that the refreshment was done
-        // correctly.
+        // Refresh the encrypted balance.
         let balance_refresh_enc_blinding = Scalar::random(rng);
-        let refreshed_enc_balance =
-            sender_init_balance.refresh(&sender_enc_keys.secret, balance_refresh_enc_blinding)?;
-
-        let balance_refreshed_same_proof = single_property_prover(
-            CipherTextRefreshmentProverAwaitingChallenge::new(
-                sender_enc_keys.secret.clone(),
-                *sender_init_balance,
-                refreshed_enc_balance,
-                &gens,
-            ),
-            rng,
+        let refreshed_enc_balance = sender_init_balance.refresh_with_hint(
+            &sender_enc_keys.secret,
+            balance_refresh_enc_blinding,
+            &balance.into(),
         )?;
 
-        // Prove that the sender has enough funds.
-        let blinding = balance_refresh_enc_blinding - amount_enc_blinding;
-        let enough_fund_proof =
-            prove_within_range((balance - amount).into(), blinding, BALANCE_RANGE, rng)?;
-
-        // Refresh the encrypted asset id of the sender account and prove that the
-        // refreshment was done correctly.
+        // Refresh the encrypted asset id of the sender account and encrypt it using
+        // the receiver's pub key.
         let asset_id_refresh_enc_blinding = Scalar::random(rng);
         let refreshed_enc_asset_id = sender_pub_account.enc_asset_id.refresh_with_hint(
             &sender_enc_keys.secret,
             asset_id_refresh_enc_blinding,
             &asset_id.clone(),
         )?;
-
-        let asset_id_refreshed_same_proof = single_property_prover(
-            CipherTextRefreshmentProverAwaitingChallenge::new(
-                sender_enc_keys.secret.clone(),
-                sender_pub_account.enc_asset_id,
-                refreshed_enc_asset_id,
-                &gens,
-            ),
-            rng,
-        )?;
-
-        // Prove the new refreshed encrypted asset id is the same as the one
-        // encrypted by the receiver's pub key.
         let asset_id_witness_for_receiver =
             CommitmentWitness::new(asset_id, asset_id_refresh_enc_blinding);
         let enc_asset_id_using_receiver = receiver_pub_key.encrypt(&asset_id_witness_for_receiver);
-        let asset_id_equal_cipher_with_sender_receiver_keys_proof = single_property_prover(
-            EncryptingSameValueProverAwaitingChallenge {
-                pub_key1: sender_enc_keys.public,
-                pub_key2: receiver_pub_key,
-                w: Zeroizing::new(asset_id_witness_for_receiver.clone()),
-                pc_gens: &gens,
-            },
-            rng,
-        )?;
-
-        // Prepare the correctness proofs for the mediator.
-        let asset_id_witness_blinding_for_mediator = Scalar::random(rng);
-        let asset_id_witness_for_mediator =
-            CommitmentWitness::new(asset_id, asset_id_witness_blinding_for_mediator);
-        let enc_asset_id_for_mediator = mediator_pub_key.encrypt(&asset_id_witness_for_mediator);
 
-        let amount_witness_blinding_for_mediator = Scalar::random(rng);
-        let amount_witness_for_mediator =
-            CommitmentWitness::new(amount.into(), amount_witness_blinding_for_mediator);
-        let enc_amount_for_mediator =
-            mediator_pub_key.const_time_encrypt(&amount_witness_for_mediator, rng);
+        // Prepare the encrypted asset id and amount for each of the mediators.
+        let mediators_payload = mediators_pub_keys
+            .iter()
+            .map(|mediator_pub_key| {
+                let asset_id_witness_for_mediator =
+                    CommitmentWitness::new(asset_id, Scalar::random(rng));
+                let amount_witness_for_mediator =
+                    CommitmentWitness::new(amount.into(), Scalar::random(rng));
+
+                MediatorPayload {
+                    mediator_pub_key: *mediator_pub_key,
+                    enc_asset_id: mediator_pub_key.encrypt(&asset_id_witness_for_mediator),
+                    enc_amount: mediator_pub_key
+                        .const_time_encrypt(&amount_witness_for_mediator, rng),
+                }
+            })
+            .collect();
 
-        let asset_id_correctness_proof = single_property_prover(
-            CorrectnessProverAwaitingChallenge {
-                pub_key: receiver_pub_key,
-                w: asset_id_witness_for_receiver,
-                pc_gens: &gens,
-            },
-            rng,
-        )?;
+        // The proofs below are independent of each other, so each one gets its own rng
+        // and they are created concurrently when the `parallel` feature is enabled.
+        let mut range_proof_rng = fork_rng(b"range_proof", rng);
+        let mut auditors_rng = fork_rng(b"auditors", rng);
+        let mut same_value_rng = fork_rng(b"same_value_proofs", rng);
+        let mut refreshment_rng = fork_rng(b"refreshment_proofs", rng);
+        let mut correctness_rng = fork_rng(b"correctness_proofs", rng);
+        let gens = PedersenGens::default();
 
-        let amount_correctness_proof = single_property_prover(
-            CorrectnessProverAwaitingChallenge {
-                pub_key: sender_enc_keys.public,
-                w: witness.clone(),
-                pc_gens: &gens,
-            },
-            rng,
-        )?;
+        // Prove that the amount is not negative and that the sender has enough funds,
+        // using a single aggregated range proof.
+        let prove_range = || {
+            let blinding = balance_refresh_enc_blinding - amount_enc_blinding;
+            prove_multiple(
+                &[amount, balance - amount],
+                &[amount_enc_blinding, blinding],
+                BALANCE_RANGE,
+                &mut range_proof_rng,
+            )
+        };
 
         // Add the necessary payload for auditors.
-        let auditors_payload = add_transaction_auditor(
-            auditors_enc_pub_keys,
-            &sender_enc_keys.public,
-            &witness,
-            rng,
-        )?;
+        let prove_auditors = || {
+            add_auditor_payload(
+                auditors_enc_pub_keys,
+                &sender_enc_keys.public,
+                &witness,
+                &mut auditors_rng,
+            )
+        };
+
+        // Prove that the amount encrypted under different public keys are the same, and
+        // that the new refreshed encrypted asset id is the same as the one encrypted by
+        // the receiver's pub key.
+        let prove_same_values = || {
+            let amount_equal_cipher_proof = single_property_prover(
+                EncryptingSameValueProverAwaitingChallenge {
+                    pub_key1: sender_enc_keys.public,
+                    pub_key2: receiver_pub_key,
+                    w: Zeroizing::new(witness.clone()),
+                    pc_gens: &gens,
+                },
+                &mut same_value_rng,
+            );
+            let asset_id_equal_cipher_proof = single_property_prover(
+                EncryptingSameValueProverAwaitingChallenge {
+                    pub_key1: sender_enc_keys.public,
+                    pub_key2: receiver_pub_key,
+                    w: Zeroizing::new(asset_id_witness_for_receiver.clone()),
+                    pc_gens: &gens,
+                },
+                &mut same_value_rng,
+            );
+            (amount_equal_cipher_proof, asset_id_equal_cipher_proof)
+        };
+
+        // Prove that the balance and the asset id refreshments were done correctly.
+        let prove_refreshments = || {
+            let balance_refreshed_same_proof = single_property_prover(
+                CipherTextRefreshmentProverAwaitingChallenge::new(
+                    sender_enc_keys.secret.clone(),
+                    *sender_init_balance,
+                    refreshed_enc_balance,
+                    &gens,
+                ),
+                &mut refreshment_rng,
+            );
+            let asset_id_refreshed_same_proof = single_property_prover(
+                CipherTextRefreshmentProverAwaitingChallenge::new(
+                    sender_enc_keys.secret.clone(),
+                    sender_pub_account.enc_asset_id,
+                    refreshed_enc_asset_id,
+                    &gens,
+                ),
+                &mut refreshment_rng,
+            );
+            (balance_refreshed_same_proof, asset_id_refreshed_same_proof)
+        };
+
+        // Prepare the correctness proofs for the mediator.
+        let prove_correctness = || {
+            let asset_id_correctness_proof = single_property_prover(
+                CorrectnessProverAwaitingChallenge {
+                    pub_key: receiver_pub_key,
+                    w: asset_id_witness_for_receiver.clone(),
+                    pc_gens: &gens,
+                },
+                &mut correctness_rng,
+            );
+            let amount_correctness_proof = single_property_prover(
+                CorrectnessProverAwaitingChallenge {
+                    pub_key: sender_enc_keys.public,
+                    w: witness.clone(),
+                    pc_gens: &gens,
+                },
+                &mut correctness_rng,
+            );
+            (asset_id_correctness_proof, amount_correctness_proof)
+        };
+
+        let (
+            (non_neg_amount_and_enough_fund_proof, auditors_payload),
+            (same_value_proofs, (refreshment_proofs, correctness_proofs)),
+        ) = join(
+            || join(prove_range, prove_auditors),
+            || {
+                join(prove_same_values, || {
+                    join(prove_refreshments, prove_correctness)
+                })
+            },
+        );
+        let (amount_equal_cipher_proof, asset_id_equal_cipher_with_sender_receiver_keys_proof) =
+            same_value_proofs;
+        let (balance_refreshed_same_proof, asset_id_refreshed_same_proof) = refreshment_proofs;
+        let (asset_id_correctness_proof, amount_correctness_proof) = correctness_proofs;
 
         Ok(InitializedTransferTx {
-            amount_equal_cipher_proof,
-            non_neg_amount_proof,
-            enough_fund_proof,
-            asset_id_equal_cipher_with_sender_receiver_keys_proof,
-            balance_refreshed_same_proof,
-            asset_id_refreshed_same_proof,
-            asset_id_correctness_proof,
-            amount_correctness_proof,
+            amount_equal_cipher_proof: amount_equal_cipher_proof?,
+            non_neg_amount_and_enough_fund_proof: non_neg_amount_and_enough_fund_proof?,
+            asset_id_equal_cipher_with_sender_receiver_keys_proof:
+                asset_id_equal_cipher_with_sender_receiver_keys_proof?,
+            balance_refreshed_same_proof: balance_refreshed_same_proof?,
+            asset_id_refreshed_same_proof: asset_id_refreshed_same_proof?,
+            asset_id_correctness_proof: asset_id_correctness_proof?,
+            amount_correctness_proof: amount_correctness_proof?,
             memo: TransferTxMemo {
                 sender_account_id: sender_pub_account.enc_asset_id,
                 receiver_account_id: receiver_pub_account.enc_asset_id,
@@ -196,55 +278,244 @@ impl TransferTransactionSender for CtxSender {
                 refreshed_enc_balance,
                 refreshed_enc_asset_id,
                 enc_asset_id_using_receiver,
-                enc_asset_id_for_mediator,
-                enc_amount_for_mediator,
             },
-            auditors_payload,
+            auditors_payload: auditors_payload?,
+            mediators_payload,
         })
     }
 }
 
-fn add_transaction_auditor<T: RngCore + CryptoRng>(
-    auditors_enc_pub_keys: &[AuditorPubAccount],
-    sender_enc_pub_key: &EncryptionPubKey,
-    amount_witness: &CommitmentWitness,
-    rng: &mut T,
-) -> Fallible<Vec<AuditorPayload>> {
-    let gens = PedersenGens::default();
-
-    let mut payload_vec: Vec<AuditorPayload> = Vec::with_capacity(auditors_enc_pub_keys.len());
-    // Add the required payload for the auditors.
-    let _: Fallible<()> = auditors_enc_pub_keys
-        .iter()
-        .map(|auditor| {
-            let encrypted_amount = auditor
-                .encryption_public_key
-                .const_time_encrypt(amount_witness, rng);
+#[cfg(not(feature = "verify-only"))]
+impl BatchTransferTransactionSender for CtxSender {
+    fn create_batch_transaction<T: RngCore + CryptoRng>(
+        &self,
+        sender_account: &Account,
+        sender_init_balance: &EncryptedAmount,
+        sender_balance: Balance,
+        receivers: &[(PubAccount, Balance)],
+        mediators_pub_keys: &[EncryptionPubKey],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        rng: &mut T,
+    ) -> Fallible<BatchTransferTx> {
+        let sender_enc_keys = &sender_account.secret.enc_keys;
+        let asset_id = sender_account.secret.asset_id_witness.value();
+        let sender_pub_account = &sender_account.public;
+
+        ensure!(!receivers.is_empty(), ErrorKind::BatchTransferError);
+        ensure!(
+            receivers.len() <= MAX_BATCH_RECEIVERS,
+            ErrorKind::TooManyBatchReceivers {
+                max: MAX_BATCH_RECEIVERS as u32,
+                given: receivers.len() as u32,
+            }
+        );
+        ensure!(
+            !mediators_pub_keys.is_empty(),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // Check that the given balance is the one that is encrypted in the account,
+        // and that it covers the sum of the amounts.
+        let balance = sender_balance;
+        sender_enc_keys
+            .secret
+            .verify(sender_init_balance, &balance.into())?;
+        let total_amount = receivers
+            .iter()
+            .try_fold(0 as Balance, |total, (_, amount)| {
+                total.checked_add(*amount)
+            });
+        ensure!(
+            total_amount.map_or(false, |total_amount| balance >= total_amount),
+            ErrorKind::NotEnoughFund {
+                balance,
+                transaction_amount: total_amount.unwrap_or(Balance::MAX)
+            }
+        );
+        let total_amount = total_amount.unwrap_or_default();
+
+        // Refresh the encrypted balance and asset id once for all the legs.
+        let balance_refresh_enc_blinding = Scalar::random(rng);
+        let refreshed_enc_balance = sender_init_balance.refresh_with_hint(
+            &sender_enc_keys.secret,
+            balance_refresh_enc_blinding,
+            &balance.into(),
+        )?;
+        let asset_id_refresh_enc_blinding = Scalar::random(rng);
+        let refreshed_enc_asset_id = sender_pub_account.enc_asset_id.refresh_with_hint(
+            &sender_enc_keys.secret,
+            asset_id_refresh_enc_blinding,
+            &asset_id.clone(),
+        )?;
+        let asset_id_witness = CommitmentWitness::new(asset_id, asset_id_refresh_enc_blinding);
 
-            // Prove that the sender and auditor's ciphertexts are encrypting the same
-            // commitment witness.
+        let gens = PedersenGens::default();
+        let mut legs = Vec::with_capacity(receivers.len());
+        let mut amounts = Vec::with_capacity(receivers.len() + 1);
+        let mut amounts_enc_blindings = Vec::with_capacity(receivers.len() + 1);
+        for (receiver_pub_account, amount) in receivers {
+            let receiver_pub_key = receiver_pub_account.owner_enc_pub_key;
+            let witness = CommitmentWitness::new((*amount).into(), Scalar::random(rng));
+            let (enc_amount_using_sender, enc_amount_using_receiver) =
+                encrypt_using_two_pub_keys(&witness, sender_enc_keys.public, receiver_pub_key);
+            let enc_asset_id_using_receiver = receiver_pub_key.encrypt(&asset_id_witness);
+
+            // Prove that the amounts, and the asset ids, encrypted under the keys of
+            // the sender and of the receiver are the same.
             let amount_equal_cipher_proof = single_property_prover(
                 EncryptingSameValueProverAwaitingChallenge {
-                    pub_key1: *sender_enc_pub_key,
-                    pub_key2: auditor.encryption_public_key,
-                    w: Zeroizing::new(amount_witness.clone()),
+                    pub_key1: sender_enc_keys.public,
+                    pub_key2: receiver_pub_key,
+                    w: Zeroizing::new(witness.clone()),
+                    pc_gens: &gens,
+                },
+                rng,
+            )?;
+            let asset_id_equal_cipher_with_sender_receiver_keys_proof = single_property_prover(
+                EncryptingSameValueProverAwaitingChallenge {
+                    pub_key1: sender_enc_keys.public,
+                    pub_key2: receiver_pub_key,
+                    w: Zeroizing::new(asset_id_witness.clone()),
+                    pc_gens: &gens,
+                },
+                rng,
+            )?;
+
+            // Prepare the encrypted asset id and amount of the leg for each of the
+            // mediators, and the proof that lets them check the amount.
+            let mediators_payload = mediators_pub_keys
+                .iter()
+                .map(|mediator_pub_key| {
+                    let asset_id_witness_for_mediator =
+                        CommitmentWitness::new(asset_id, Scalar::random(rng));
+                    let amount_witness_for_mediator =
+                        CommitmentWitness::new((*amount).into(), Scalar::random(rng));
+
+                    MediatorPayload {
+                        mediator_pub_key: *mediator_pub_key,
+                        enc_asset_id: mediator_pub_key.encrypt(&asset_id_witness_for_mediator),
+                        enc_amount: mediator_pub_key
+                            .const_time_encrypt(&amount_witness_for_mediator, rng),
+                    }
+                })
+                .collect();
+            let amount_correctness_proof = single_property_prover(
+                CorrectnessProverAwaitingChallenge {
+                    pub_key: sender_enc_keys.public,
+                    w: witness.clone(),
                     pc_gens: &gens,
                 },
                 rng,
             )?;
 
-            let payload = AuditorPayload {
-                auditor_id: auditor.auditor_id,
-                encrypted_amount,
+            amounts.push(*amount);
+            amounts_enc_blindings.push(witness.blinding());
+            legs.push(BatchTransferLeg {
+                receiver_account_id: receiver_pub_account.enc_asset_id,
+                enc_amount_using_sender,
+                enc_amount_using_receiver,
+                enc_asset_id_using_receiver,
                 amount_equal_cipher_proof,
-            };
+                asset_id_equal_cipher_with_sender_receiver_keys_proof,
+                amount_correctness_proof,
+                mediators_payload,
+            });
+        }
+
+        // Add the payload for the auditors, who audit the sum of the amounts.
+        let total_amount_enc_blinding: Scalar = amounts_enc_blindings.iter().sum();
+        let auditors_payload = add_auditor_payload(
+            auditors_enc_pub_keys,
+            &sender_enc_keys.public,
+            &CommitmentWitness::new(total_amount.into(), total_amount_enc_blinding),
+            rng,
+        )?;
+
+        // Prove that the amounts are not negative and that the sender has enough funds
+        // for their sum, using a single aggregated range proof. The proof is padded
+        // with zeros, since it needs a power of two values.
+        amounts.push(balance - total_amount);
+        amounts_enc_blindings.push(balance_refresh_enc_blinding - total_amount_enc_blinding);
+        while !amounts.len().is_power_of_two() {
+            amounts.push(0);
+            amounts_enc_blindings.push(Scalar::random(rng));
+        }
+        let non_neg_amounts_and_enough_fund_proof =
+            prove_multiple(&amounts, &amounts_enc_blindings, BALANCE_RANGE, rng)?;
+
+        // Prove that the balance and the asset id refreshments were done correctly.
+        let balance_refreshed_same_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                sender_enc_keys.secret.clone(),
+                *sender_init_balance,
+                refreshed_enc_balance,
+                &gens,
+            ),
+            rng,
+        )?;
+        let asset_id_refreshed_same_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                sender_enc_keys.secret.clone(),
+                sender_pub_account.enc_asset_id,
+                refreshed_enc_asset_id,
+                &gens,
+            ),
+            rng,
+        )?;
+
+        // Prove the asset id to the mediators.
+        let asset_id_correctness_proof = single_property_prover(
+            CorrectnessProverAwaitingChallenge {
+                pub_key: sender_enc_keys.public,
+                w: asset_id_witness,
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
 
-            payload_vec.push(payload);
-            Ok(())
+        Ok(BatchTransferTx {
+            memo: BatchTransferTxMemo {
+                sender_account_id: sender_pub_account.enc_asset_id,
+                refreshed_enc_balance,
+                refreshed_enc_asset_id,
+            },
+            legs,
+            non_neg_amounts_and_enough_fund_proof,
+            balance_refreshed_same_proof,
+            asset_id_refreshed_same_proof,
+            asset_id_correctness_proof,
+            auditors_payload,
         })
-        .collect();
+    }
+}
+
+/// Derives an independent rng from the given one, so that proofs can be created
+/// without sharing the caller's rng.
+#[cfg(not(feature = "verify-only"))]
+fn fork_rng<T: RngCore + CryptoRng>(label: &'static [u8], rng: &mut T) -> TranscriptRng {
+    Transcript::new(label).build_rng().finalize(rng)
+}
+
+/// Runs the two closures concurrently when the `parallel` feature is enabled,
+/// and one after the other otherwise.
+#[cfg(all(feature = "parallel", not(feature = "verify-only")))]
+fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    rayon::join(a, b)
+}
 
-    Ok(payload_vec)
+#[cfg(not(any(feature = "parallel", feature = "verify-only")))]
+fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA,
+    B: FnOnce() -> RB,
+{
+    (a(), b())
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -253,9 +524,11 @@ fn add_transaction_auditor<T: RngCore + CryptoRng>(
 
 /// The receiver of a confidential transaction. Receiver finalizes and processes
 /// transaction.
+#[cfg(not(feature = "verify-only"))]
 #[derive(Clone, Debug)]
 pub struct CtxReceiver;
 
+#[cfg(not(feature = "verify-only"))]
 impl TransferTransactionReceiver for CtxReceiver {
     fn finalize_transaction<T: RngCore + CryptoRng>(
         &self,
@@ -297,48 +570,194 @@ impl TransferTransactionReceiver for CtxReceiver {
     }
 }
 
-// ------------------------------------------------------------------------------------------------
-// -                                           Mediator                                           -
-// ------------------------------------------------------------------------------------------------
-
-#[derive(Clone, Debug)]
-pub struct CtxMediator;
-
-impl TransferTransactionMediator for CtxMediator {
-    fn justify_transaction<R: RngCore + CryptoRng>(
+#[cfg(not(feature = "verify-only"))]
+impl BatchTransferTransactionReceiver for CtxReceiver {
+    fn finalize_batch_transaction<T: RngCore + CryptoRng>(
         &self,
-        finalized_transaction: FinalizedTransferTx,
-        mediator_enc_keys: &EncryptionKeys,
-        sender_account: &PubAccount,
-        sender_init_balance: &EncryptedAmount,
-        receiver_account: &PubAccount,
-        auditors_enc_pub_keys: &[AuditorPubAccount],
-        asset_id_hint: AssetId,
-        rng: &mut R,
-    ) -> Fallible<JustifiedTransferTx> {
-        // Verify receiver's part of the transaction.
-        let _ = verify_finalized_transaction(&finalized_transaction, receiver_account)?;
+        mut finalized_transaction: FinalizedBatchTransferTx,
+        receiver_account: Account,
+        amount: Balance,
+        rng: &mut T,
+    ) -> Fallible<FinalizedBatchTransferTx> {
+        let receiver_enc_sec = &receiver_account.secret.enc_keys.secret;
+        let receiver_account_id = receiver_account.public.enc_asset_id;
 
-        // Verify sender's part of the transaction.
-        // This includes checking the auditors' payload.
-        let init_tx_data = &finalized_transaction.init_data;
-        let _ = verify_initialized_transaction(
-            &init_tx_data,
-            sender_account,
-            sender_init_balance,
-            receiver_account,
-            auditors_enc_pub_keys,
-            rng,
-        )?;
+        // The receiver must be one of the receivers of the transaction, and must not
+        // have finalized it already.
+        let leg = finalized_transaction
+            .batch_data
+            .legs
+            .iter()
+            .find(|leg| leg.receiver_account_id == receiver_account_id)
+            .ok_or(ErrorKind::BatchTransferError)?;
+        ensure!(
+            finalized_transaction
+                .receipts
+                .iter()
+                .all(|receipt| receipt.receiver_account_id != receiver_account_id),
+            ErrorKind::BatchTransferError
+        );
 
-        let gens = &PedersenGens::default();
-        let tx_data = &init_tx_data;
+        // Check that the amount is correct.
+        receiver_enc_sec
+            .verify(&leg.enc_amount_using_receiver, &amount.into())
+            .map_err(|_| ErrorKind::TransactionAmountMismatch {
+                expected_amount: amount,
+            })?;
 
-        // Verify that the encrypted amount is correct.
-        let amount = mediator_enc_keys
-            .secret
-            .const_time_decrypt(&tx_data.memo.enc_amount_for_mediator)?;
-        single_property_verifier(
+        // Generate proof of equality of asset ids.
+        let gens = PedersenGens::default();
+        let prover = CipherTextRefreshmentProverAwaitingChallenge::new(
+            receiver_enc_sec.clone(),
+            receiver_account_id,
+            leg.enc_asset_id_using_receiver,
+            &gens,
+        );
+        let proof = single_property_prover(prover, rng)?;
+
+        finalized_transaction.receipts.push(BatchTransferReceipt {
+            receiver_account_id,
+            asset_id_from_sender_equal_to_receiver_proof: proof,
+        });
+
+        Ok(finalized_transaction)
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl ReversedTransferTransactionReceiver for CtxReceiver {
+    fn finalize_reversed_transaction<T: RngCore + CryptoRng>(
+        &self,
+        reversed_transaction: ReversedTransferTx,
+        receiver_account: &Account,
+        receiver_init_balance: &EncryptedAmount,
+        receiver_balance: Balance,
+        amount: Balance,
+        rng: &mut T,
+    ) -> Fallible<FinalizedReversedTransferTx> {
+        let receiver_enc_sec = &receiver_account.secret.enc_keys.secret;
+        let memo = &reversed_transaction.memo;
+        ensure!(
+            receiver_account.public.enc_asset_id
+                == reversed_transaction
+                    .justified_data
+                    .finalized_data
+                    .init_data
+                    .memo
+                    .receiver_account_id,
+            ErrorKind::AccountIdMismatch
+        );
+
+        // Check the given balance and amount against their cipher texts.
+        receiver_enc_sec.verify(receiver_init_balance, &receiver_balance.into())?;
+        receiver_enc_sec
+            .verify(&memo.enc_amount_using_receiver, &amount.into())
+            .map_err(|_| ErrorKind::TransactionAmountMismatch {
+                expected_amount: amount,
+            })?;
+        ensure!(
+            receiver_balance >= amount,
+            ErrorKind::NotEnoughFund {
+                balance: receiver_balance,
+                transaction_amount: amount
+            }
+        );
+
+        // Refresh the balance that remains after the reversal, so that its blinding is known
+        // and its range can be proven.
+        let remaining_balance = receiver_balance - amount;
+        let enc_remaining_balance =
+            withdraw(receiver_init_balance, &memo.enc_amount_using_receiver);
+        let balance_refresh_enc_blinding = Scalar::random(rng);
+        let refreshed_enc_balance = enc_remaining_balance.refresh_with_hint(
+            receiver_enc_sec,
+            balance_refresh_enc_blinding,
+            &remaining_balance.into(),
+        )?;
+
+        let gens = PedersenGens::default();
+        let balance_refreshed_same_proof = single_property_prover(
+            CipherTextRefreshmentProverAwaitingChallenge::new(
+                receiver_enc_sec.clone(),
+                enc_remaining_balance,
+                refreshed_enc_balance,
+                &gens,
+            ),
+            rng,
+        )?;
+        let non_neg_balance_proof = prove_multiple(
+            &[remaining_balance],
+            &[balance_refresh_enc_blinding],
+            BALANCE_RANGE,
+            rng,
+        )?;
+
+        Ok(FinalizedReversedTransferTx {
+            reversed_data: reversed_transaction,
+            refreshed_enc_balance,
+            balance_refreshed_same_proof,
+            non_neg_balance_proof,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// -                                           Mediator                                           -
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+pub struct CtxMediator;
+
+#[cfg(not(feature = "verify-only"))]
+impl TransferTransactionMediator for CtxMediator {
+    fn justify_transaction<T: RngCore + CryptoRng>(
+        &self,
+        mut justified_transaction: JustifiedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receiver_account: &PubAccount,
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        asset_id_hint: AssetId,
+        rng: &mut T,
+    ) -> Fallible<JustifiedTransferTx> {
+        let finalized_transaction = &justified_transaction.finalized_data;
+
+        // Verify receiver's part of the transaction.
+        let _ = verify_finalized_transaction(finalized_transaction, receiver_account)?;
+
+        // Verify sender's part of the transaction.
+        // This includes checking the auditors' payload.
+        let init_tx_data = &finalized_transaction.init_data;
+        let _ = verify_initialized_transaction(
+            &init_tx_data,
+            sender_account,
+            sender_init_balance,
+            receiver_account,
+            auditors_enc_pub_keys,
+        )?;
+
+        // The mediator must be one of the mediators of the transaction, and must not
+        // have justified it already.
+        let mediator_payload = init_tx_data
+            .mediator_payload(&mediator_enc_keys.public)
+            .ok_or(ErrorKind::MediatorPayloadError)?;
+        ensure!(
+            justified_transaction
+                .justifications
+                .iter()
+                .all(|justification| justification.mediator_pub_key != mediator_enc_keys.public),
+            ErrorKind::MediatorPayloadError
+        );
+
+        let gens = &PedersenGens::default();
+        let tx_data = &init_tx_data;
+
+        // Verify that the encrypted amount is correct.
+        let amount = mediator_enc_keys
+            .secret
+            .const_time_decrypt(&mediator_payload.enc_amount)?;
+        single_property_verifier(
             &CorrectnessVerifier {
                 value: amount.into(),
                 pub_key: sender_account.owner_enc_pub_key,
@@ -350,7 +769,7 @@ impl TransferTransactionMediator for CtxMediator {
 
         // Verify that the encrypted asset_id is correct.
         mediator_enc_keys.secret.verify(
-            &tx_data.memo.enc_asset_id_for_mediator,
+            &mediator_payload.enc_asset_id,
             &asset_id_hint.clone().into(),
         )?;
 
@@ -365,10 +784,310 @@ impl TransferTransactionMediator for CtxMediator {
             tx_data.asset_id_correctness_proof,
         )?;
 
-        Ok(JustifiedTransferTx {
-            finalized_data: finalized_transaction,
+        // Approve the transaction.
+        let message = justification_message(finalized_transaction);
+        let signature = mediator_enc_keys.secret.sign(&message, rng)?;
+        justified_transaction
+            .justifications
+            .push(MediatorJustification {
+                mediator_pub_key: mediator_enc_keys.public,
+                signature,
+            });
+
+        Ok(justified_transaction)
+    }
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl BatchTransferTransactionMediator for CtxMediator {
+    fn justify_batch_transaction<T: RngCore + CryptoRng>(
+        &self,
+        mut justified_transaction: JustifiedBatchTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receivers_accounts: &[PubAccount],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        asset_id_hint: AssetId,
+        rng: &mut T,
+    ) -> Fallible<JustifiedBatchTransferTx> {
+        let finalized_transaction = &justified_transaction.finalized_data;
+
+        // Verify the sender's and the receivers' parts of the transaction.
+        // This includes checking the auditors' payload.
+        let _ = verify_batch_transaction(
+            finalized_transaction,
+            sender_account,
+            sender_init_balance,
+            receivers_accounts,
+            auditors_enc_pub_keys,
+        )?;
+
+        // The mediator must not have justified the transaction already.
+        ensure!(
+            justified_transaction
+                .justifications
+                .iter()
+                .all(|justification| justification.mediator_pub_key != mediator_enc_keys.public),
+            ErrorKind::MediatorPayloadError
+        );
+
+        let gens = &PedersenGens::default();
+        let batch_data = &finalized_transaction.batch_data;
+        let asset_id = asset_id_hint;
+        let mut batch = BatchVerifier::new();
+        for leg in &batch_data.legs {
+            // The mediator must be one of the mediators of each leg.
+            let mediator_payload = leg
+                .mediator_payload(&mediator_enc_keys.public)
+                .ok_or(ErrorKind::MediatorPayloadError)?;
+
+            // Verify that the encrypted amount and asset id of the leg are correct.
+            let amount = mediator_enc_keys
+                .secret
+                .const_time_decrypt(&mediator_payload.enc_amount)?;
+            batch.add(
+                &CorrectnessVerifier {
+                    value: amount.into(),
+                    pub_key: sender_account.owner_enc_pub_key,
+                    cipher: leg.enc_amount_using_sender,
+                    pc_gens: &gens,
+                },
+                &leg.amount_correctness_proof,
+            );
+            mediator_enc_keys
+                .secret
+                .verify(&mediator_payload.enc_asset_id, &asset_id.clone().into())?;
+        }
+        batch.add(
+            &CorrectnessVerifier {
+                value: asset_id.into(),
+                pub_key: sender_account.owner_enc_pub_key,
+                cipher: batch_data.memo.refreshed_enc_asset_id,
+                pc_gens: &gens,
+            },
+            &batch_data.asset_id_correctness_proof,
+        );
+        batch.verify()?;
+
+        // Approve the transaction.
+        let message = batch_justification_message(finalized_transaction);
+        let signature = mediator_enc_keys.secret.sign(&message, rng)?;
+        justified_transaction
+            .justifications
+            .push(MediatorJustification {
+                mediator_pub_key: mediator_enc_keys.public,
+                signature,
+            });
+
+        Ok(justified_transaction)
+    }
+}
+
+/// The domain labels of the messages that the mediators sign.
+const JUSTIFICATION_LABEL: &[u8] = b"MercatTransferJustification";
+const BATCH_JUSTIFICATION_LABEL: &[u8] = b"MercatBatchTransferJustification";
+const REJECTION_LABEL: &[u8] = b"MercatTransferRejection";
+const REVERSAL_LABEL: &[u8] = b"MercatTransferReversal";
+
+/// The message that the mediators sign to justify a transaction.
+fn justification_message(finalized_transaction: &FinalizedTransferTx) -> Vec<u8> {
+    (JUSTIFICATION_LABEL, finalized_transaction).encode()
+}
+
+/// The message that the mediators sign to justify a batched transaction.
+fn batch_justification_message(finalized_transaction: &FinalizedBatchTransferTx) -> Vec<u8> {
+    (BATCH_JUSTIFICATION_LABEL, finalized_transaction).encode()
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl ReversedTransferTransactionMediator for CtxMediator {
+    fn reverse_transaction<T: RngCore + CryptoRng>(
+        &self,
+        justified_transaction: JustifiedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        sender_account: &PubAccount,
+        receiver_account: &PubAccount,
+        transfer_id: u64,
+        rng: &mut T,
+    ) -> Fallible<ReversedTransferTx> {
+        let tx_data = &justified_transaction.finalized_data.init_data;
+        ensure!(
+            sender_account.enc_asset_id == tx_data.memo.sender_account_id,
+            ErrorKind::AccountIdMismatch
+        );
+        ensure!(
+            receiver_account.enc_asset_id == tx_data.memo.receiver_account_id,
+            ErrorKind::AccountIdMismatch
+        );
+
+        // Only a mediator that has justified the transaction can reverse it.
+        ensure!(
+            justified_transaction
+                .justifications
+                .iter()
+                .any(|justification| justification.mediator_pub_key == mediator_enc_keys.public),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // Verify that the amount the mediator decrypts is the transferred amount.
+        let mediator_payload = tx_data
+            .mediator_payload(&mediator_enc_keys.public)
+            .ok_or(ErrorKind::MediatorPayloadError)?;
+        let gens = &PedersenGens::default();
+        let amount = mediator_enc_keys
+            .secret
+            .const_time_decrypt(&mediator_payload.enc_amount)?;
+        single_property_verifier(
+            &CorrectnessVerifier {
+                value: amount.into(),
+                pub_key: sender_account.owner_enc_pub_key,
+                cipher: tx_data.memo.enc_amount_using_sender,
+                pc_gens: &gens,
+            },
+            tx_data.amount_correctness_proof,
+        )?;
+
+        // Re-encrypt the amount under the receiver's and the sender's keys.
+        let witness = CommitmentWitness::new(amount.into(), Scalar::random(rng));
+        let (enc_amount_using_receiver, enc_amount_using_sender) = encrypt_using_two_pub_keys(
+            &witness,
+            receiver_account.owner_enc_pub_key,
+            sender_account.owner_enc_pub_key,
+        );
+
+        // Prove that the receiver's cipher text encrypts the same amount as the mediator's
+        // one, and that the sender's cipher text encrypts the same amount as the receiver's.
+        let amount_reencrypted_for_receiver_proof = single_property_prover(
+            CipherTextReencryptionProverAwaitingChallenge::new(
+                mediator_enc_keys.secret.clone(),
+                receiver_account.owner_enc_pub_key,
+                witness.clone(),
+                mediator_payload.enc_amount.elgamal_cipher,
+                &gens,
+            ),
+            rng,
+        )?;
+        let amount_equal_cipher_proof = single_property_prover(
+            EncryptingSameValueProverAwaitingChallenge {
+                pub_key1: receiver_account.owner_enc_pub_key,
+                pub_key2: sender_account.owner_enc_pub_key,
+                w: Zeroizing::new(witness),
+                pc_gens: &gens,
+            },
+            rng,
+        )?;
+
+        // Sign the reversal, which binds it to the transfer id.
+        let memo = ReversedTransferTxMemo {
+            transfer_id,
+            enc_amount_using_sender,
+            enc_amount_using_receiver,
+        };
+        let message = reversal_message(&justified_transaction, &memo);
+        let signature = mediator_enc_keys.secret.sign(&message, rng)?;
+
+        Ok(ReversedTransferTx {
+            justified_data: justified_transaction,
+            memo,
+            amount_reencrypted_for_receiver_proof,
+            amount_equal_cipher_proof,
+            approvals: vec![MediatorJustification {
+                mediator_pub_key: mediator_enc_keys.public,
+                signature,
+            }],
         })
     }
+
+    fn approve_reversed_transaction<T: RngCore + CryptoRng>(
+        &self,
+        reversed_transaction: ReversedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        rng: &mut T,
+    ) -> Fallible<ReversedTransferTx> {
+        // Only the mediators of the transaction can approve its reversal, each one at
+        // most once.
+        let mediator_pub_key = mediator_enc_keys.public;
+        ensure!(
+            reversed_transaction
+                .justified_data
+                .finalized_data
+                .init_data
+                .mediator_payload(&mediator_pub_key)
+                .is_some()
+                && reversed_transaction
+                    .approvals
+                    .iter()
+                    .all(|approval| approval.mediator_pub_key != mediator_pub_key),
+            ErrorKind::MediatorPayloadError
+        );
+
+        let message = reversal_message(
+            &reversed_transaction.justified_data,
+            &reversed_transaction.memo,
+        );
+        let signature = mediator_enc_keys.secret.sign(&message, rng)?;
+
+        let mut reversed_transaction = reversed_transaction;
+        reversed_transaction.approvals.push(MediatorJustification {
+            mediator_pub_key,
+            signature,
+        });
+        Ok(reversed_transaction)
+    }
+}
+
+/// The message that the mediator signs to reverse a transaction.
+fn reversal_message(
+    justified_transaction: &JustifiedTransferTx,
+    memo: &ReversedTransferTxMemo,
+) -> Vec<u8> {
+    (REVERSAL_LABEL, justified_transaction, memo).encode()
+}
+
+#[cfg(not(feature = "verify-only"))]
+impl RejectedTransferTransactionMediator for CtxMediator {
+    fn reject_transaction<T: RngCore + CryptoRng>(
+        &self,
+        rejected_transaction: RejectedTransferTx,
+        mediator_enc_keys: &EncryptionKeys,
+        reason: RejectionReason,
+        rng: &mut T,
+    ) -> Fallible<RejectedTransferTx> {
+        // Only the mediators of the transaction can reject it, each one at most once.
+        let mediator_pub_key = mediator_enc_keys.public;
+        ensure!(
+            rejected_transaction
+                .finalized_data
+                .init_data
+                .mediator_payload(&mediator_pub_key)
+                .is_some()
+                && rejected_transaction
+                    .rejections
+                    .iter()
+                    .all(|rejection| rejection.mediator_pub_key != mediator_pub_key),
+            ErrorKind::MediatorPayloadError
+        );
+
+        let message = rejection_message(&rejected_transaction.finalized_data, reason);
+        let signature = mediator_enc_keys.secret.sign(&message, rng)?;
+
+        let mut rejected_transaction = rejected_transaction;
+        rejected_transaction.rejections.push(MediatorRejection {
+            mediator_pub_key,
+            reason,
+            signature,
+        });
+        Ok(rejected_transaction)
+    }
+}
+
+/// The message that the mediator signs to reject a transaction.
+fn rejection_message(
+    finalized_transaction: &FinalizedTransferTx,
+    reason: RejectionReason,
+) -> Vec<u8> {
+    (REJECTION_LABEL, finalized_transaction, reason).encode()
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -380,14 +1099,15 @@ impl TransferTransactionMediator for CtxMediator {
 pub struct TransactionValidator;
 
 impl TransferTransactionVerifier for TransactionValidator {
-    fn verify_transaction<R: RngCore + CryptoRng>(
+    fn verify_transaction(
         &self,
         justified_transaction: &JustifiedTransferTx,
         sender_account: &PubAccount,
         sender_init_balance: &EncryptedAmount,
         receiver_account: &PubAccount,
         auditors_enc_pub_keys: &[AuditorPubAccount],
-        rng: &mut R,
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
     ) -> Fallible<()> {
         ensure!(
             sender_account.enc_asset_id
@@ -415,44 +1135,136 @@ impl TransferTransactionVerifier for TransactionValidator {
             sender_init_balance,
             receiver_account,
             auditors_enc_pub_keys,
-            rng,
         )?;
 
         verify_finalized_transaction(&finalized_transaction, receiver_account)?;
 
+        verify_justifications(
+            justified_transaction,
+            mediators_pub_keys,
+            mediators_threshold,
+        )?;
+
         Ok(())
     }
 }
 
-fn verify_initialized_transaction<R: RngCore + CryptoRng>(
-    transaction: &InitializedTransferTx,
-    sender_account: &PubAccount,
-    sender_init_balance: &EncryptedAmount,
-    receiver_account: &PubAccount,
-    auditors_enc_pub_keys: &[AuditorPubAccount],
-    rng: &mut R,
-) -> Fallible<TransferTxState> {
-    verify_initial_transaction_proofs(
-        transaction,
-        sender_account,
-        sender_init_balance,
-        receiver_account,
-        auditors_enc_pub_keys,
-        rng,
-    )?;
+impl ReversedTransferTransactionVerifier for TransactionValidator {
+    fn verify_reversed_transaction(
+        &self,
+        reversed_transaction: &FinalizedReversedTransferTx,
+        transfer_id: u64,
+        sender_account: &PubAccount,
+        sender_balance: &EncryptedAmount,
+        receiver_account: &PubAccount,
+        receiver_balance: &EncryptedAmount,
+        mediator_pub_key: &EncryptionPubKey,
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
+    ) -> Fallible<(EncryptedAmount, EncryptedAmount)> {
+        verify_reversed_transaction(
+            reversed_transaction,
+            transfer_id,
+            sender_account,
+            receiver_account,
+            receiver_balance,
+            mediator_pub_key,
+            mediators_pub_keys,
+            mediators_threshold,
+        )?;
 
-    Ok(TransferTxState::Initialization(TxSubstate::Validated))
+        // Move the amount back from the receiver to the sender.
+        let memo = &reversed_transaction.reversed_data.memo;
+        Ok((
+            deposit(sender_balance, &memo.enc_amount_using_sender),
+            withdraw(receiver_balance, &memo.enc_amount_using_receiver),
+        ))
+    }
 }
 
-fn verify_finalized_transaction(
-    transaction_final_data: &FinalizedTransferTx,
-    receiver_account: &PubAccount,
-) -> Fallible<TransferTxState> {
-    let memo = &transaction_final_data.init_data.memo;
+impl RejectedTransferTransactionVerifier for TransactionValidator {
+    fn verify_rejected_transaction(
+        &self,
+        rejected_transaction: &RejectedTransferTx,
+        pending_transaction: &InitializedTransferTx,
+        sender_account: &PubAccount,
+        sender_pending_balance: &EncryptedAmount,
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
+    ) -> Fallible<EncryptedAmount> {
+        verify_rejected_transaction(
+            rejected_transaction,
+            pending_transaction,
+            sender_account,
+            mediators_pub_keys,
+            mediators_threshold,
+        )?;
 
-    // In the initial transaction, the sender has encrypted the asset id
-    // using the receiver pub key. We verify that this encrypted asset id
-    // is the same as the one in the receiver account
+        // Release the amount that the sender's pending balance holds for the transaction.
+        let memo = &rejected_transaction.finalized_data.init_data.memo;
+        Ok(deposit(
+            sender_pending_balance,
+            &memo.enc_amount_using_sender,
+        ))
+    }
+}
+
+impl BatchTransferTransactionVerifier for TransactionValidator {
+    fn verify_batch_transaction(
+        &self,
+        justified_transaction: &JustifiedBatchTransferTx,
+        sender_account: &PubAccount,
+        sender_init_balance: &EncryptedAmount,
+        receivers_accounts: &[PubAccount],
+        auditors_enc_pub_keys: &[AuditorPubAccount],
+        mediators_pub_keys: &[EncryptionPubKey],
+        mediators_threshold: u32,
+    ) -> Fallible<()> {
+        verify_batch_transaction(
+            &justified_transaction.finalized_data,
+            sender_account,
+            sender_init_balance,
+            receivers_accounts,
+            auditors_enc_pub_keys,
+        )?;
+
+        verify_batch_justifications(
+            justified_transaction,
+            mediators_pub_keys,
+            mediators_threshold,
+        )?;
+
+        Ok(())
+    }
+}
+
+fn verify_initialized_transaction(
+    transaction: &InitializedTransferTx,
+    sender_account: &PubAccount,
+    sender_init_balance: &EncryptedAmount,
+    receiver_account: &PubAccount,
+    auditors_enc_pub_keys: &[AuditorPubAccount],
+) -> Fallible<TransferTxState> {
+    verify_initial_transaction_proofs(
+        transaction,
+        sender_account,
+        sender_init_balance,
+        receiver_account,
+        auditors_enc_pub_keys,
+    )?;
+
+    Ok(TransferTxState::Initialization(TxSubstate::Validated))
+}
+
+fn verify_finalized_transaction(
+    transaction_final_data: &FinalizedTransferTx,
+    receiver_account: &PubAccount,
+) -> Fallible<TransferTxState> {
+    let memo = &transaction_final_data.init_data.memo;
+
+    // In the initial transaction, the sender has encrypted the asset id
+    // using the receiver pub key. We verify that this encrypted asset id
+    // is the same as the one in the receiver account
     single_property_verifier(
         &CipherTextRefreshmentVerifier::new(
             receiver_account.owner_enc_pub_key,
@@ -466,20 +1278,267 @@ fn verify_finalized_transaction(
     Ok(TransferTxState::Finalization(TxSubstate::Validated))
 }
 
-fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
+fn verify_justifications(
+    transaction: &JustifiedTransferTx,
+    mediators_pub_keys: &[EncryptionPubKey],
+    mediators_threshold: u32,
+) -> Fallible<TransferTxState> {
+    let init_data = &transaction.finalized_data.init_data;
+    verify_mediators_justifications(
+        &transaction.justifications,
+        &justification_message(&transaction.finalized_data),
+        |mediator_pub_key| init_data.mediator_payload(mediator_pub_key).is_some(),
+        mediators_pub_keys,
+        mediators_threshold,
+    )
+}
+
+fn verify_batch_justifications(
+    transaction: &JustifiedBatchTransferTx,
+    mediators_pub_keys: &[EncryptionPubKey],
+    mediators_threshold: u32,
+) -> Fallible<TransferTxState> {
+    // The mediators must have the payload of every leg of the transaction.
+    let legs = &transaction.finalized_data.batch_data.legs;
+    verify_mediators_justifications(
+        &transaction.justifications,
+        &batch_justification_message(&transaction.finalized_data),
+        |mediator_pub_key| {
+            legs.iter()
+                .all(|leg| leg.mediator_payload(mediator_pub_key).is_some())
+        },
+        mediators_pub_keys,
+        mediators_threshold,
+    )
+}
+
+/// Verifies that at least `mediators_threshold` of `mediators_pub_keys` have signed
+/// `message`, and that `has_payload` holds for all of them.
+fn verify_mediators_justifications<F: Fn(&EncryptionPubKey) -> bool>(
+    justifications: &[MediatorJustification],
+    message: &[u8],
+    has_payload: F,
+    mediators_pub_keys: &[EncryptionPubKey],
+    mediators_threshold: u32,
+) -> Fallible<TransferTxState> {
+    ensure!(
+        mediators_threshold > 0 && mediators_threshold as usize <= mediators_pub_keys.len(),
+        ErrorKind::InvalidThresholdParameters {
+            threshold: mediators_threshold,
+            parties: mediators_pub_keys.len() as u32,
+        }
+    );
+
+    let mut approvers: Vec<&EncryptionPubKey> = Vec::with_capacity(justifications.len());
+    for justification in justifications {
+        // Each justification must come from a different mediator, who is one of the
+        // given mediators and one of the mediators of the transaction.
+        let mediator_pub_key = &justification.mediator_pub_key;
+        ensure!(
+            mediators_pub_keys.contains(mediator_pub_key)
+                && has_payload(mediator_pub_key)
+                && !approvers.contains(&mediator_pub_key),
+            ErrorKind::MediatorPayloadError
+        );
+        mediator_pub_key.verify_signature(message, &justification.signature)?;
+        approvers.push(mediator_pub_key);
+    }
+
+    ensure!(
+        approvers.len() >= mediators_threshold as usize,
+        ErrorKind::NotEnoughMediatorJustifications {
+            needed: mediators_threshold,
+            given: approvers.len() as u32,
+        }
+    );
+
+    Ok(TransferTxState::Justification(TxSubstate::Validated))
+}
+
+fn verify_reversed_transaction(
+    finalized_transaction: &FinalizedReversedTransferTx,
+    transfer_id: u64,
+    sender_account: &PubAccount,
+    receiver_account: &PubAccount,
+    receiver_balance: &EncryptedAmount,
+    mediator_pub_key: &EncryptionPubKey,
+    mediators_pub_keys: &[EncryptionPubKey],
+    mediators_threshold: u32,
+) -> Fallible<TransferTxState> {
+    let transaction = &finalized_transaction.reversed_data;
+    let reversed_memo = &transaction.memo;
+    let justified_data = &transaction.justified_data;
+    let init_data = &justified_data.finalized_data.init_data;
+    let memo = &init_data.memo;
+    ensure!(
+        sender_account.enc_asset_id == memo.sender_account_id,
+        ErrorKind::AccountIdMismatch
+    );
+    ensure!(
+        receiver_account.enc_asset_id == memo.receiver_account_id,
+        ErrorKind::AccountIdMismatch
+    );
+    ensure!(
+        reversed_memo.transfer_id == transfer_id,
+        ErrorKind::TransactionIdMismatch
+    );
+
+    // The transfer must be justified, and the mediator that reverses it must be one of
+    // the mediators that justified it.
+    verify_justifications(justified_data, mediators_pub_keys, mediators_threshold)?;
+    ensure!(
+        justified_data
+            .justifications
+            .iter()
+            .any(|justification| justification.mediator_pub_key == *mediator_pub_key),
+        ErrorKind::MediatorPayloadError
+    );
+    let mediator_payload = init_data
+        .mediator_payload(mediator_pub_key)
+        .ok_or(ErrorKind::MediatorPayloadError)?;
+
+    // The reversal must be approved by enough mediators, starting with the one that
+    // reverses it.
+    ensure!(
+        transaction
+            .approvals
+            .first()
+            .map(|approval| &approval.mediator_pub_key)
+            == Some(mediator_pub_key),
+        ErrorKind::MediatorPayloadError
+    );
+    verify_mediators_justifications(
+        &transaction.approvals,
+        &reversal_message(justified_data, reversed_memo),
+        |mediator_pub_key| init_data.mediator_payload(mediator_pub_key).is_some(),
+        mediators_pub_keys,
+        mediators_threshold,
+    )?;
+
+    let gens = &PedersenGens::default();
+    let mut batch = BatchVerifier::new();
+
+    // Verify that the mediator re-encrypted the amount it received for the receiver.
+    batch.add(
+        &CipherTextReencryptionVerifier {
+            pub_key1: *mediator_pub_key,
+            pub_key2: receiver_account.owner_enc_pub_key,
+            cipher1: mediator_payload.enc_amount.elgamal_cipher,
+            cipher2: reversed_memo.enc_amount_using_receiver,
+            pc_gens: &gens,
+        },
+        &transaction.amount_reencrypted_for_receiver_proof,
+    );
+
+    // Verify that the sender gets back the amount taken from the receiver.
+    batch.add(
+        &EncryptingSameValueVerifier {
+            pub_key1: receiver_account.owner_enc_pub_key,
+            pub_key2: sender_account.owner_enc_pub_key,
+            cipher1: reversed_memo.enc_amount_using_receiver,
+            cipher2: reversed_memo.enc_amount_using_sender,
+            pc_gens: &gens,
+        },
+        &transaction.amount_equal_cipher_proof,
+    );
+
+    // Verify that the receiver refreshed its balance after the reversal correctly.
+    let remaining_balance = withdraw(receiver_balance, &reversed_memo.enc_amount_using_receiver);
+    batch.add(
+        &CipherTextRefreshmentVerifier::new(
+            receiver_account.owner_enc_pub_key,
+            remaining_balance,
+            finalized_transaction.refreshed_enc_balance,
+            &gens,
+        ),
+        &finalized_transaction.balance_refreshed_same_proof,
+    );
+
+    batch.verify()?;
+
+    // Verify that the receiver's balance after the reversal is not negative.
+    verify_multiple(
+        &finalized_transaction.non_neg_balance_proof,
+        &[finalized_transaction.refreshed_enc_balance.y],
+        BALANCE_RANGE,
+    )?;
+
+    Ok(TransferTxState::Reversal(TxSubstate::Validated))
+}
+
+fn verify_rejected_transaction(
+    transaction: &RejectedTransferTx,
+    pending_transaction: &InitializedTransferTx,
+    sender_account: &PubAccount,
+    mediators_pub_keys: &[EncryptionPubKey],
+    mediators_threshold: u32,
+) -> Fallible<TransferTxState> {
+    ensure!(
+        mediators_threshold > 0 && mediators_threshold as usize <= mediators_pub_keys.len(),
+        ErrorKind::InvalidThresholdParameters {
+            threshold: mediators_threshold,
+            parties: mediators_pub_keys.len() as u32,
+        }
+    );
+    let finalized_transaction = &transaction.finalized_data;
+    ensure!(
+        sender_account.enc_asset_id == finalized_transaction.init_data.memo.sender_account_id,
+        ErrorKind::AccountIdMismatch
+    );
+    // Only the amount of the pending transaction can be released.
+    ensure!(
+        finalized_transaction.init_data.encode() == pending_transaction.encode(),
+        ErrorKind::PendingTransactionMismatch
+    );
+
+    let mut rejecters: Vec<&EncryptionPubKey> = Vec::with_capacity(transaction.rejections.len());
+    for rejection in &transaction.rejections {
+        // Each rejection must come from a different mediator, who is one of the given
+        // mediators and one of the mediators of the transaction.
+        let mediator_pub_key = &rejection.mediator_pub_key;
+        ensure!(
+            mediators_pub_keys.contains(mediator_pub_key)
+                && finalized_transaction
+                    .init_data
+                    .mediator_payload(mediator_pub_key)
+                    .is_some()
+                && !rejecters.contains(&mediator_pub_key),
+            ErrorKind::MediatorPayloadError
+        );
+        let message = rejection_message(finalized_transaction, rejection.reason);
+        mediator_pub_key.verify_signature(&message, &rejection.signature)?;
+        rejecters.push(mediator_pub_key);
+    }
+
+    // The transaction is rejected once the remaining mediators are too few to justify it.
+    let needed = (mediators_pub_keys.len() - mediators_threshold as usize + 1) as u32;
+    ensure!(
+        rejecters.len() >= needed as usize,
+        ErrorKind::NotEnoughMediatorRejections {
+            needed,
+            given: rejecters.len() as u32,
+        }
+    );
+
+    Ok(TransferTxState::Justification(TxSubstate::Rejected))
+}
+
+fn verify_initial_transaction_proofs(
     transaction: &InitializedTransferTx,
     sender_account: &PubAccount,
     sender_init_balance: &EncryptedAmount,
     receiver_account: &PubAccount,
     auditors_enc_pub_keys: &[AuditorPubAccount],
-    rng: &mut R,
 ) -> Fallible<()> {
     let memo = &transaction.memo;
     let init_data = &transaction;
     let gens = &PedersenGens::default();
 
+    // The sigma proofs of the transaction are verified together in one batch.
+    let mut batch = BatchVerifier::new();
+
     // Verify that the encrypted amounts are equal.
-    single_property_verifier(
+    batch.add(
         &EncryptingSameValueVerifier {
             pub_key1: sender_account.owner_enc_pub_key,
             pub_key2: receiver_account.owner_enc_pub_key,
@@ -487,41 +1546,35 @@ fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
             cipher2: memo.enc_amount_using_receiver,
             pc_gens: &gens,
         },
-        init_data.amount_equal_cipher_proof,
-    )?;
-
-    // Verify that the amount is not negative.
-    verify_within_range(&init_data.non_neg_amount_proof, rng)?;
+        &init_data.amount_equal_cipher_proof,
+    );
 
     // verify that the balance refreshment was done correctly.
-    single_property_verifier(
+    batch.add(
         &CipherTextRefreshmentVerifier::new(
             sender_account.owner_enc_pub_key,
             *sender_init_balance,
             memo.refreshed_enc_balance,
             &gens,
         ),
-        init_data.balance_refreshed_same_proof,
-    )?;
-
-    // Verify that the balance has enough fund.
-    verify_within_range(&init_data.enough_fund_proof, rng)?;
+        &init_data.balance_refreshed_same_proof,
+    );
 
     // Verify that the asset id refreshment was done correctly.
-    single_property_verifier(
+    batch.add(
         &CipherTextRefreshmentVerifier::new(
             sender_account.owner_enc_pub_key,
             sender_account.enc_asset_id,
             memo.refreshed_enc_asset_id,
             &gens,
         ),
-        init_data.asset_id_refreshed_same_proof,
-    )?;
+        &init_data.asset_id_refreshed_same_proof,
+    );
 
     // In the initial transaction, the sender has encrypted the asset id
     // using the receiver pub key. We verify that this encrypted asset id
     // is the same as the one in the sender account.
-    single_property_verifier(
+    batch.add(
         &EncryptingSameValueVerifier {
             pub_key1: sender_account.owner_enc_pub_key,
             pub_key2: receiver_account.owner_enc_pub_key,
@@ -529,7 +1582,18 @@ fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
             cipher2: memo.enc_asset_id_using_receiver,
             pc_gens: &gens,
         },
-        init_data.asset_id_equal_cipher_with_sender_receiver_keys_proof,
+        &init_data.asset_id_equal_cipher_with_sender_receiver_keys_proof,
+    );
+
+    batch.verify()?;
+
+    // Verify that the amount is not negative and that the balance has enough fund.
+    // The range proof must be over the amount and the refreshed balance minus the amount.
+    let remaining_balance = &memo.refreshed_enc_balance - &memo.enc_amount_using_sender;
+    verify_multiple(
+        &init_data.non_neg_amount_and_enough_fund_proof,
+        &[memo.enc_amount_using_sender.y, remaining_balance.y],
+        BALANCE_RANGE,
     )?;
 
     // Verify that all auditors' payload is included, and
@@ -544,48 +1608,145 @@ fn verify_initial_transaction_proofs<R: RngCore + CryptoRng>(
     Ok(())
 }
 
-fn verify_auditor_payload(
-    auditors_payload: &[AuditorPayload],
+fn verify_batch_transaction(
+    transaction: &FinalizedBatchTransferTx,
+    sender_account: &PubAccount,
+    sender_init_balance: &EncryptedAmount,
+    receivers_accounts: &[PubAccount],
     auditors_enc_pub_keys: &[AuditorPubAccount],
-    sender_enc_pub_key: EncryptionPubKey,
-    sender_enc_amount: EncryptedAmount,
-) -> Fallible<()> {
+) -> Fallible<TransferTxState> {
+    let batch_data = &transaction.batch_data;
+    let memo = &batch_data.memo;
+    let legs = &batch_data.legs;
+    ensure!(
+        sender_account.enc_asset_id == memo.sender_account_id,
+        ErrorKind::AccountIdMismatch
+    );
     ensure!(
-        auditors_payload.len() == auditors_enc_pub_keys.len(),
-        ErrorKind::AuditorPayloadError
+        legs.len() <= MAX_BATCH_RECEIVERS,
+        ErrorKind::TooManyBatchReceivers {
+            max: MAX_BATCH_RECEIVERS as u32,
+            given: legs.len() as u32,
+        }
+    );
+
+    // Each leg must go to a different receiver, and each receiver must have finalized
+    // its leg.
+    ensure!(
+        !legs.is_empty()
+            && legs.len() == receivers_accounts.len()
+            && transaction.receipts.len() == legs.len(),
+        ErrorKind::BatchTransferError
     );
+    for (i, (leg, receiver_account)) in legs.iter().zip(receivers_accounts).enumerate() {
+        ensure!(
+            receiver_account.enc_asset_id == leg.receiver_account_id,
+            ErrorKind::AccountIdMismatch
+        );
+        ensure!(
+            legs[..i]
+                .iter()
+                .all(|other_leg| other_leg.receiver_account_id != leg.receiver_account_id),
+            ErrorKind::BatchTransferError
+        );
+    }
 
+    // The sigma proofs of the transaction and of the receipts are verified together
+    // in one batch.
     let gens = &PedersenGens::default();
-    let _: Fallible<()> = auditors_enc_pub_keys
+    let mut batch = BatchVerifier::new();
+
+    // Verify that the balance and the asset id refreshments were done correctly.
+    batch.add(
+        &CipherTextRefreshmentVerifier::new(
+            sender_account.owner_enc_pub_key,
+            *sender_init_balance,
+            memo.refreshed_enc_balance,
+            &gens,
+        ),
+        &batch_data.balance_refreshed_same_proof,
+    );
+    batch.add(
+        &CipherTextRefreshmentVerifier::new(
+            sender_account.owner_enc_pub_key,
+            sender_account.enc_asset_id,
+            memo.refreshed_enc_asset_id,
+            &gens,
+        ),
+        &batch_data.asset_id_refreshed_same_proof,
+    );
+
+    for (leg, receiver_account) in legs.iter().zip(receivers_accounts) {
+        // Verify that the encrypted amounts are equal.
+        batch.add(
+            &EncryptingSameValueVerifier {
+                pub_key1: sender_account.owner_enc_pub_key,
+                pub_key2: receiver_account.owner_enc_pub_key,
+                cipher1: leg.enc_amount_using_sender,
+                cipher2: leg.enc_amount_using_receiver,
+                pc_gens: &gens,
+            },
+            &leg.amount_equal_cipher_proof,
+        );
+
+        // Verify that the asset id encrypted using the receiver's pub key is the
+        // same as the one in the sender account.
+        batch.add(
+            &EncryptingSameValueVerifier {
+                pub_key1: sender_account.owner_enc_pub_key,
+                pub_key2: receiver_account.owner_enc_pub_key,
+                cipher1: memo.refreshed_enc_asset_id,
+                cipher2: leg.enc_asset_id_using_receiver,
+                pc_gens: &gens,
+            },
+            &leg.asset_id_equal_cipher_with_sender_receiver_keys_proof,
+        );
+
+        // Verify that it is also the same as the one in the receiver account.
+        let receipt = transaction
+            .receipts
+            .iter()
+            .find(|receipt| receipt.receiver_account_id == leg.receiver_account_id)
+            .ok_or(ErrorKind::BatchTransferError)?;
+        batch.add(
+            &CipherTextRefreshmentVerifier::new(
+                receiver_account.owner_enc_pub_key,
+                receiver_account.enc_asset_id,
+                leg.enc_asset_id_using_receiver,
+                &gens,
+            ),
+            &receipt.asset_id_from_sender_equal_to_receiver_proof,
+        );
+    }
+
+    batch.verify()?;
+
+    // Verify that the amounts are not negative and that the balance has enough fund.
+    // The range proof must be over the amounts of the legs and over the refreshed
+    // balance minus their sum, followed by the padding.
+    let remaining_balance =
+        &memo.refreshed_enc_balance - &batch_data.total_enc_amount_using_sender();
+    let commitments: Vec<RistrettoPoint> = legs
         .iter()
-        .map(|auditor| {
-            let mut found_auditor = false;
-            let _: Fallible<()> = auditors_payload
-                .iter()
-                .map(|payload| {
-                    if auditor.auditor_id == payload.auditor_id {
-                        // Verify that the encrypted amounts are equal.
-                        single_property_verifier(
-                            &EncryptingSameValueVerifier {
-                                pub_key1: sender_enc_pub_key,
-                                pub_key2: auditor.encryption_public_key,
-                                cipher1: sender_enc_amount,
-                                cipher2: payload.encrypted_amount.elgamal_cipher,
-                                pc_gens: &gens,
-                            },
-                            payload.amount_equal_cipher_proof,
-                        )?;
-                        found_auditor |= true;
-                    }
-                    Ok(())
-                })
-                .collect();
-            ensure!(found_auditor, ErrorKind::AuditorPayloadError);
-            Ok(())
-        })
+        .map(|leg| leg.enc_amount_using_sender.y)
+        .chain(Some(remaining_balance.y))
         .collect();
+    verify_multiple(
+        &batch_data.non_neg_amounts_and_enough_fund_proof,
+        &commitments,
+        BALANCE_RANGE,
+    )?;
 
-    Ok(())
+    // Verify that all auditors' payload is included, and that the auditors' ciphertexts
+    // encrypt the sum of the amounts.
+    verify_auditor_payload(
+        &batch_data.auditors_payload,
+        auditors_enc_pub_keys,
+        sender_account.owner_enc_pub_key,
+        batch_data.total_enc_amount_using_sender(),
+    )?;
+
+    Ok(TransferTxState::Finalization(TxSubstate::Validated))
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -666,16 +1827,18 @@ mod tests {
     use super::*;
     use crate::{
         account::{deposit, withdraw},
-        EncryptedAmount, EncryptedAmountWithHint, EncryptedAssetId, EncryptionKeys,
-        EncryptionPubKey, SecAccount, TransferTxMemo,
+        EncryptedAmount, EncryptedAssetId, EncryptionKeys, EncryptionPubKey, SecAccount,
+        TransferTxMemo,
     };
+    use codec::{Decode, Encode};
     use cryptography_core::{
         asset_proofs::{
             ciphertext_refreshment_proof::CipherEqualSamePubKeyProof,
             correctness_proof::CorrectnessProof,
             encrypting_same_value_proof::CipherEqualDifferentPubKeyProof,
-            range_proof::InRangeProof, AssetId, ElgamalSecretKey,
+            range_proof::AggregatedInRangeProof, AssetId, ElgamalSecretKey,
         },
+        codec_wrapper::Versioned,
         curve25519_dalek::scalar::Scalar,
     };
     use rand::rngs::StdRng;
@@ -685,6 +1848,110 @@ mod tests {
 
     // -------------------------- mock helper methods -----------------------
 
+    /// Sends a transaction through its versioned wire format, as it would be stored on the chain.
+    fn through_wire<T: Versioned>(tx: &T) -> T {
+        T::decode_versioned(&mut &tx.encode_versioned()[..]).unwrap()
+    }
+
+    /// Checks `value` against the definition `name` of the checked-in JSON schema.
+    /// Supports only the keywords used by the schema.
+    #[cfg(feature = "serde_all")]
+    fn check_schema(value: &serde_json::Value, name: &str) {
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../../schemas/cryptography.schema.json")).unwrap();
+        let definitions = &schema["definitions"];
+        check_schema_node(value, &definitions[name], definitions, name);
+    }
+
+    #[cfg(feature = "serde_all")]
+    fn check_schema_node(
+        value: &serde_json::Value,
+        node: &serde_json::Value,
+        definitions: &serde_json::Value,
+        path: &str,
+    ) {
+        use serde_json::Value;
+
+        if let Some(reference) = node["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/definitions/");
+            assert!(
+                !definitions[name].is_null(),
+                "{}: unknown {}",
+                path,
+                reference
+            );
+            return check_schema_node(value, &definitions[name], definitions, path);
+        }
+        match node["type"].as_str() {
+            Some("object") => {
+                let fields = value.as_object().expect(path);
+                let properties = node["properties"].as_object().expect(path);
+                for key in fields.keys() {
+                    assert!(
+                        properties.contains_key(key),
+                        "{}: unexpected `{}`",
+                        path,
+                        key
+                    );
+                }
+                for (key, property) in properties {
+                    let field = fields
+                        .get(key)
+                        .unwrap_or_else(|| panic!("{}: no `{}`", path, key));
+                    check_schema_node(field, property, definitions, &format!("{}.{}", path, key));
+                }
+            }
+            Some("array") => {
+                let elements = value.as_array().expect(path);
+                let len = elements.len() as u64;
+                assert!(
+                    node["minItems"].as_u64().map_or(true, |min| len >= min),
+                    "{}",
+                    path
+                );
+                assert!(
+                    node["maxItems"].as_u64().map_or(true, |max| len <= max),
+                    "{}",
+                    path
+                );
+                for (index, element) in elements.iter().enumerate() {
+                    let item = match &node["items"] {
+                        Value::Array(items) => &items[index],
+                        item => item,
+                    };
+                    check_schema_node(element, item, definitions, &format!("{}[{}]", path, index));
+                }
+            }
+            Some("string") => {
+                let string = value.as_str().expect(path);
+                let hex = !string.is_empty()
+                    && string
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+                let pattern = node["pattern"].as_str().expect(path);
+                let len_ok = match pattern
+                    .strip_prefix("^[0-9a-f]{")
+                    .and_then(|len| len.strip_suffix("}$"))
+                {
+                    Some(len) => string.len() == len.parse::<usize>().unwrap(),
+                    None => {
+                        assert_eq!(pattern, "^([0-9a-f]{2})+$", "{}", path);
+                        string.len() % 2 == 0
+                    }
+                };
+                assert!(
+                    hex && len_ok,
+                    "{}: `{}` does not match {}",
+                    path,
+                    string,
+                    pattern
+                );
+            }
+            Some("integer") => assert!(value.is_u64(), "{}", path),
+            other => panic!("{}: unsupported schema type {:?}", path, other),
+        }
+    }
+
     fn mock_gen_enc_key_pair(seed: u8) -> EncryptionKeys {
         let mut rng = StdRng::from_seed([seed; 32]);
         let elg_secret = ElgamalSecretKey::new(Scalar::random(&mut rng));
@@ -711,8 +1978,6 @@ mod tests {
             refreshed_enc_balance: EncryptedAmount::default(),
             refreshed_enc_asset_id: EncryptedAssetId::default(),
             enc_asset_id_using_receiver,
-            enc_asset_id_for_mediator: EncryptedAssetId::default(),
-            enc_amount_for_mediator: EncryptedAmountWithHint::default(),
         }
     }
 
@@ -745,13 +2010,13 @@ mod tests {
             asset_id_equal_cipher_with_sender_receiver_keys_proof:
                 CipherEqualDifferentPubKeyProof::default(),
             amount_equal_cipher_proof: CipherEqualDifferentPubKeyProof::default(),
-            non_neg_amount_proof: InRangeProof::build(rng),
-            enough_fund_proof: InRangeProof::build(rng),
+            non_neg_amount_and_enough_fund_proof: AggregatedInRangeProof::build(rng),
             balance_refreshed_same_proof: CipherEqualSamePubKeyProof::default(),
             asset_id_refreshed_same_proof: CipherEqualSamePubKeyProof::default(),
             amount_correctness_proof: CorrectnessProof::default(),
             asset_id_correctness_proof: CorrectnessProof::default(),
             auditors_payload: [].to_vec(),
+            mediators_payload: [].to_vec(),
         }
     }
 
@@ -796,184 +2061,1738 @@ mod tests {
             &mut rng,
         );
 
-        result.unwrap();
-        // Correctness of the proof will be verified in the verify function
+        result.unwrap();
+        // Correctness of the proof will be verified in the verify function
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_finalize_ctx_amount_mismatch_error() {
+        let ctx_receiver = CtxReceiver;
+        let expected_amount = 10;
+        let received_amount = 20;
+        let asset_id = AssetId::from(20);
+        let balance = 0;
+        let mut rng = StdRng::from_seed([17u8; 32]);
+
+        let receiver_enc_keys = mock_gen_enc_key_pair(17u8);
+
+        let ctx_init_data = mock_ctx_init_data(
+            receiver_enc_keys.public,
+            received_amount,
+            asset_id.clone(),
+            &mut rng,
+        );
+        let receiver_account = Account {
+            public: mock_gen_account(
+                receiver_enc_keys.public,
+                asset_id.clone(),
+                balance,
+                &mut rng,
+            )
+            .unwrap()
+            .0,
+            secret: SecAccount {
+                enc_keys: receiver_enc_keys,
+                asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
+            },
+        };
+
+        let result = ctx_receiver.finalize_transaction(
+            ctx_init_data,
+            receiver_account,
+            expected_amount,
+            &mut rng,
+        );
+
+        assert_err!(
+            result,
+            ErrorKind::TransactionAmountMismatch { expected_amount }
+        );
+    }
+
+    // ------------------------------ Test simple scenarios
+
+    fn ctx_create_finalize_validate_helper(
+        sender_balance: Balance,
+        receiver_balance: Balance,
+        amount: Balance,
+    ) -> JustifiedTransferTx {
+        let sender = CtxSender;
+        let receiver = CtxReceiver;
+        let mediator = CtxMediator;
+        let tx_validator = TransactionValidator;
+        let asset_id = AssetId::from(20);
+
+        let mut rng = StdRng::from_seed([17u8; 32]);
+
+        let sender_enc_keys = mock_gen_enc_key_pair(10u8);
+
+        let receiver_enc_keys = mock_gen_enc_key_pair(12u8);
+
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+
+        let (receiver_pub_account, receiver_init_balance) = mock_gen_account(
+            receiver_enc_keys.public,
+            asset_id.clone(),
+            receiver_balance,
+            &mut rng,
+        )
+        .unwrap();
+        let receiver_account = Account {
+            public: receiver_pub_account,
+            secret: SecAccount {
+                enc_keys: receiver_enc_keys.clone(),
+                asset_id_witness: CommitmentWitness::from((asset_id.clone().into(), &mut rng)),
+            },
+        };
+
+        let (sender_pub_account, sender_init_balance) = mock_gen_account(
+            sender_enc_keys.public,
+            asset_id.clone(),
+            sender_balance,
+            &mut rng,
+        )
+        .unwrap();
+        let sender_account = Account {
+            public: sender_pub_account,
+            secret: SecAccount {
+                enc_keys: sender_enc_keys.clone(),
+                asset_id_witness: CommitmentWitness::from((asset_id.clone().into(), &mut rng)),
+            },
+        };
+
+        // Create the transaction and check its result and state
+        let result = sender.create_transaction(
+            &sender_account,
+            &sender_init_balance,
+            sender_balance,
+            &receiver_account.public,
+            &[mediator_enc_keys.public],
+            &[],
+            amount,
+            &mut rng,
+        );
+        let ctx_init_data = through_wire(&result.unwrap());
+
+        // Finalize the transaction and check its state.
+        let result = receiver.finalize_transaction(
+            ctx_init_data,
+            receiver_account.clone(),
+            amount,
+            &mut rng,
+        );
+        let ctx_finalized_data = through_wire(&result.unwrap());
+
+        // Justify the transaction
+        let result = mediator.justify_transaction(
+            ctx_finalized_data.into(),
+            &mediator_enc_keys,
+            &sender_account.public,
+            &sender_init_balance,
+            &receiver_account.public,
+            &[],
+            asset_id,
+            &mut rng,
+        );
+        let justified_finalized_ctx_data = through_wire(&result.unwrap());
+
+        assert!(tx_validator
+            .verify_transaction(
+                &justified_finalized_ctx_data,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                &[mediator_enc_keys.public],
+                1,
+            )
+            .is_ok());
+
+        // ----------------------- Processing
+        // Check that the transferred amount is added to the receiver's account balance
+        // and subtracted from sender's balance.
+        let updated_sender_balance = withdraw(
+            &sender_init_balance,
+            &justified_finalized_ctx_data
+                .finalized_data
+                .init_data
+                .memo
+                .enc_amount_using_sender,
+        );
+        let updated_receiver_balance = deposit(
+            &receiver_init_balance,
+            &justified_finalized_ctx_data
+                .finalized_data
+                .init_data
+                .memo
+                .enc_amount_using_receiver,
+        );
+
+        assert!(sender_enc_keys
+            .secret
+            .verify(&updated_sender_balance, &(sender_balance - amount).into())
+            .is_ok());
+        assert!(receiver_enc_keys
+            .secret
+            .verify(
+                &updated_receiver_balance,
+                &(receiver_balance + amount).into()
+            )
+            .is_ok());
+
+        justified_finalized_ctx_data
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ctx_create_finalize_validate_success() {
+        ctx_create_finalize_validate_helper(40, 0, 30);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    #[cfg(feature = "serde_all")]
+    fn test_ctx_json_matches_schema() {
+        let tx = ctx_create_finalize_validate_helper(40, 0, 30);
+        let value = serde_json::to_value(&tx).unwrap();
+        check_schema(&value, "mercat.JustifiedTransferTx");
+
+        let decoded: JustifiedTransferTx = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.encode_versioned(), tx.encode_versioned());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ctx_create_finalize_validate_64_bit_balances() {
+        // Amounts with 18 decimals do not fit in 32 bits.
+        let one_token: Balance = 1_000_000_000_000_000_000;
+        ctx_create_finalize_validate_helper(10 * one_token, 3 * one_token, 5 * one_token / 2);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ctx_create_wrong_sender_balance() {
+        let asset_id = AssetId::from(20);
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+        let (receiver_account, _) = account_create_helper([18u8; 32], 12u8, 0, asset_id.clone());
+        let (sender_account, sender_init_balance) =
+            account_create_helper([19u8; 32], 10u8, 40, asset_id);
+
+        // The sender claims to have more than their encrypted balance.
+        let result = CtxSender.create_transaction(
+            &sender_account,
+            &sender_init_balance,
+            50,
+            &receiver_account.public,
+            &[mediator_enc_keys.public],
+            &[],
+            45,
+            &mut rng,
+        );
+        assert_err!(result, ErrorKind::CipherTextDecryptionError);
+
+        // The sender tries to send more than their balance.
+        let result = CtxSender.create_transaction(
+            &sender_account,
+            &sender_init_balance,
+            40,
+            &receiver_account.public,
+            &[mediator_enc_keys.public],
+            &[],
+            45,
+            &mut rng,
+        );
+        assert_err!(
+            result,
+            ErrorKind::NotEnoughFund {
+                balance: 40,
+                transaction_amount: 45
+            }
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ctx_range_proof_not_bound_to_amount() {
+        let asset_id = AssetId::from(20);
+        let mut rng = StdRng::from_seed([17u8; 32]);
+        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+        let (receiver_account, _) = account_create_helper([18u8; 32], 12u8, 0, asset_id.clone());
+        let (sender_account, sender_init_balance) =
+            account_create_helper([19u8; 32], 10u8, 40, asset_id);
+
+        let ctx_init_data = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                40,
+                &receiver_account.public,
+                &[mediator_enc_keys.public],
+                &[],
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        assert!(verify_initialized_transaction(
+            &ctx_init_data,
+            &sender_account.public,
+            &sender_init_balance,
+            &receiver_account.public,
+            &[],
+        )
+        .is_ok());
+
+        // A valid range proof over other commitments is rejected.
+        let mut tampered_init_data = ctx_init_data.clone();
+        tampered_init_data.non_neg_amount_and_enough_fund_proof = prove_multiple(
+            &[30, 10],
+            &[Scalar::random(&mut rng), Scalar::random(&mut rng)],
+            BALANCE_RANGE,
+            &mut rng,
+        )
+        .unwrap();
+        assert_err!(
+            verify_initialized_transaction(
+                &tampered_init_data,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+            ),
+            ErrorKind::VerificationError
+        );
+
+        // So is a range proof over fewer bits.
+        let mut tampered_init_data = ctx_init_data;
+        tampered_init_data
+            .non_neg_amount_and_enough_fund_proof
+            .range = 32;
+        assert_err!(
+            verify_initialized_transaction(
+                &tampered_init_data,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+            ),
+            ErrorKind::VerificationError
+        );
+    }
+
+    // ------------------------------ Test Multiple Mediators
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_ctx_multiple_mediators() {
+        let asset_id = AssetId::from(20);
+        let mut rng = StdRng::from_seed([26u8; 32]);
+        let mediators: Vec<EncryptionKeys> = (150u8..153).map(mock_gen_enc_key_pair).collect();
+        let mediators_pub_keys: Vec<EncryptionPubKey> =
+            mediators.iter().map(|mediator| mediator.public).collect();
+        let outsider = mock_gen_enc_key_pair(153u8);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, 40, asset_id.clone());
+        let (receiver_account, _) = account_create_helper([18u8; 32], 120u8, 0, asset_id.clone());
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                40,
+                &receiver_account.public,
+                &mediators_pub_keys,
+                &[],
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(ctx_init.mediators_payload.len(), 3);
+        let ctx_final = CtxReceiver
+            .finalize_transaction(ctx_init, receiver_account.clone(), 30, &mut rng)
+            .unwrap();
+
+        let mut justify = |tx: JustifiedTransferTx, mediator: &EncryptionKeys| {
+            CtxMediator.justify_transaction(
+                tx,
+                mediator,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                asset_id.clone(),
+                &mut rng,
+            )
+        };
+
+        // Two of the three mediators justify the transaction, one after the other.
+        let ctx_just = justify(ctx_final.into(), &mediators[0]).unwrap();
+        let ctx_just = through_wire(&justify(ctx_just, &mediators[2]).unwrap());
+        assert_eq!(ctx_just.justifications.len(), 2);
+
+        // A mediator cannot justify twice, and only the mediators of the transaction can.
+        assert_err!(
+            justify(ctx_just.clone(), &mediators[0]),
+            ErrorKind::MediatorPayloadError
+        );
+        assert_err!(
+            justify(ctx_just.clone(), &outsider),
+            ErrorKind::MediatorPayloadError
+        );
+
+        let verify =
+            |tx: &JustifiedTransferTx, mediators_pub_keys: &[EncryptionPubKey], threshold| {
+                TransactionValidator.verify_transaction(
+                    tx,
+                    &sender_account.public,
+                    &sender_init_balance,
+                    &receiver_account.public,
+                    &[],
+                    mediators_pub_keys,
+                    threshold,
+                )
+            };
+
+        assert!(verify(&ctx_just, &mediators_pub_keys, 1).is_ok());
+        assert!(verify(&ctx_just, &mediators_pub_keys, 2).is_ok());
+        assert_err!(
+            verify(&ctx_just, &mediators_pub_keys, 3),
+            ErrorKind::NotEnoughMediatorJustifications {
+                needed: 3,
+                given: 2
+            }
+        );
+        assert_err!(
+            verify(&ctx_just, &mediators_pub_keys, 0),
+            ErrorKind::InvalidThresholdParameters {
+                threshold: 0,
+                parties: 3
+            }
+        );
+
+        // The justifications must come from the mediators that the validators expect.
+        assert_err!(
+            verify(&ctx_just, &mediators_pub_keys[..2], 1),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // The same justification cannot count twice.
+        let mut cheating_tx = ctx_just.clone();
+        cheating_tx.justifications[1] = cheating_tx.justifications[0];
+        assert_err!(
+            verify(&cheating_tx, &mediators_pub_keys, 2),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // Each justification must be signed by its own mediator.
+        let mut cheating_tx = ctx_just;
+        cheating_tx.justifications[1].signature = cheating_tx.justifications[0].signature;
+        assert_err!(
+            verify(&cheating_tx, &mediators_pub_keys, 2),
+            ErrorKind::SignatureVerificationError
+        );
+    }
+
+    // ------------------------------ Test Reversal
+
+    /// Creates, justifies, validates and processes a transfer of `amount`, and returns the
+    /// accounts, their balances after the transfer, the mediator's keys and the transaction.
+    fn justified_ctx_helper(
+        sender_balance: Balance,
+        receiver_balance: Balance,
+        amount: Balance,
+    ) -> (
+        (Account, EncryptedAmount),
+        (Account, EncryptedAmount),
+        EncryptionKeys,
+        JustifiedTransferTx,
+    ) {
+        let asset_id = AssetId::from(20);
+        let mut rng = StdRng::from_seed([21u8; 32]);
+        let mediator_enc_keys = mock_gen_enc_key_pair(140u8);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, sender_balance, asset_id.clone());
+        let (receiver_account, receiver_init_balance) =
+            account_create_helper([18u8; 32], 120u8, receiver_balance, asset_id.clone());
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                sender_balance,
+                &receiver_account.public,
+                &[mediator_enc_keys.public],
+                &[],
+                amount,
+                &mut rng,
+            )
+            .unwrap();
+        let ctx_final = CtxReceiver
+            .finalize_transaction(ctx_init, receiver_account.clone(), amount, &mut rng)
+            .unwrap();
+        let ctx_just = CtxMediator
+            .justify_transaction(
+                ctx_final.into(),
+                &mediator_enc_keys,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                asset_id,
+                &mut rng,
+            )
+            .unwrap();
+        TransactionValidator
+            .verify_transaction(
+                &ctx_just,
+                &sender_account.public,
+                &sender_init_balance,
+                &receiver_account.public,
+                &[],
+                &[mediator_enc_keys.public],
+                1,
+            )
+            .unwrap();
+
+        let memo = &ctx_just.finalized_data.init_data.memo;
+        let sender_balance = withdraw(&sender_init_balance, &memo.enc_amount_using_sender);
+        let receiver_balance = deposit(&receiver_init_balance, &memo.enc_amount_using_receiver);
+        (
+            (sender_account, sender_balance),
+            (receiver_account, receiver_balance),
+            mediator_enc_keys,
+            ctx_just,
+        )
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reversal_states() {
+        let started = TransferTxState::Reversal(TxSubstate::Started);
+        let validated = TransferTxState::Reversal(TxSubstate::Validated);
+        let rejected = TransferTxState::Reversal(TxSubstate::Rejected);
+
+        assert_eq!(started.to_string(), "transfer-reversal-started");
+        assert_eq!(validated.to_string(), "transfer-reversal-validated");
+        assert_eq!(rejected.to_string(), "transfer-reversal-rejected");
+        assert_eq!(format!("{:?}", started), "reversal_started");
+        assert_eq!(format!("{:?}", validated), "reversal_validated");
+        assert_eq!(format!("{:?}", rejected), "reversal_rejected");
+
+        // The states survive their encoding, and differ from those of the other steps.
+        for state in &[started, validated, rejected] {
+            assert_eq!(
+                TransferTxState::decode(&mut &state.encode()[..]).unwrap(),
+                *state
+            );
+        }
+        assert_ne!(
+            validated,
+            TransferTxState::Justification(TxSubstate::Validated)
+        );
+    }
+
+    /// The id that the tests give to the reversed transfer.
+    const TRANSFER_ID: u64 = 7;
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reverse_ctx_success() {
+        let mut rng = StdRng::from_seed([22u8; 32]);
+        let ((sender_account, sender_balance), (receiver_account, receiver_balance), mediator, tx) =
+            justified_ctx_helper(40, 10, 30);
+
+        // The mediator starts the reversal, and the receiver proves that its balance still
+        // covers the amount.
+        let reversed_tx = CtxMediator
+            .reverse_transaction(
+                tx,
+                &mediator,
+                &sender_account.public,
+                &receiver_account.public,
+                TRANSFER_ID,
+                &mut rng,
+            )
+            .unwrap();
+        let reversed_tx = through_wire(&reversed_tx);
+        let finalized_tx = CtxReceiver
+            .finalize_reversed_transaction(
+                reversed_tx,
+                &receiver_account,
+                &receiver_balance,
+                40,
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        let finalized_tx = through_wire(&finalized_tx);
+
+        // The validators validate it and move the amount back to the sender.
+        assert_eq!(
+            verify_reversed_transaction(
+                &finalized_tx,
+                TRANSFER_ID,
+                &sender_account.public,
+                &receiver_account.public,
+                &receiver_balance,
+                &mediator.public,
+                &[mediator.public],
+                1,
+            )
+            .unwrap(),
+            TransferTxState::Reversal(TxSubstate::Validated)
+        );
+        let (updated_sender_balance, updated_receiver_balance) = TransactionValidator
+            .verify_reversed_transaction(
+                &finalized_tx,
+                TRANSFER_ID,
+                &sender_account.public,
+                &sender_balance,
+                &receiver_account.public,
+                &receiver_balance,
+                &mediator.public,
+                &[mediator.public],
+                1,
+            )
+            .unwrap();
+
+        assert!(sender_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_sender_balance, &40u64.into())
+            .is_ok());
+        assert!(receiver_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_receiver_balance, &10u64.into())
+            .is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    #[cfg(feature = "serde_all")]
+    fn test_reverse_ctx_json_matches_schema() {
+        let mut rng = StdRng::from_seed([22u8; 32]);
+        let ((sender_account, _), (receiver_account, receiver_balance), mediator, tx) =
+            justified_ctx_helper(40, 10, 30);
+        let reversed_tx = CtxMediator
+            .reverse_transaction(
+                tx,
+                &mediator,
+                &sender_account.public,
+                &receiver_account.public,
+                TRANSFER_ID,
+                &mut rng,
+            )
+            .unwrap();
+        let tx = CtxReceiver
+            .finalize_reversed_transaction(
+                reversed_tx,
+                &receiver_account,
+                &receiver_balance,
+                40,
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        let value = serde_json::to_value(&tx).unwrap();
+        check_schema(&value, "mercat.FinalizedReversedTransferTx");
+
+        let decoded: FinalizedReversedTransferTx = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.encode_versioned(), tx.encode_versioned());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reverse_ctx_rejected() {
+        let mut rng = StdRng::from_seed([23u8; 32]);
+        let ((sender_account, sender_balance), (receiver_account, receiver_balance), mediator, tx) =
+            justified_ctx_helper(40, 10, 30);
+        let other_mediator = mock_gen_enc_key_pair(141u8);
+
+        // Only a mediator that justified the transaction can reverse it.
+        let result = CtxMediator.reverse_transaction(
+            tx.clone(),
+            &other_mediator,
+            &sender_account.public,
+            &receiver_account.public,
+            TRANSFER_ID,
+            &mut rng,
+        );
+        assert_err!(result, ErrorKind::MediatorPayloadError);
+        let mut unjustified_tx = tx.clone();
+        unjustified_tx.justifications.clear();
+        let result = CtxMediator.reverse_transaction(
+            unjustified_tx,
+            &mediator,
+            &sender_account.public,
+            &receiver_account.public,
+            TRANSFER_ID,
+            &mut rng,
+        );
+        assert_err!(result, ErrorKind::MediatorPayloadError);
+
+        // The mediator cannot reverse the transaction between other accounts.
+        let result = CtxMediator.reverse_transaction(
+            tx.clone(),
+            &mediator,
+            &receiver_account.public,
+            &sender_account.public,
+            TRANSFER_ID,
+            &mut rng,
+        );
+        assert_err!(result, ErrorKind::AccountIdMismatch);
+
+        let reversed_tx = CtxMediator
+            .reverse_transaction(
+                tx,
+                &mediator,
+                &sender_account.public,
+                &receiver_account.public,
+                TRANSFER_ID,
+                &mut rng,
+            )
+            .unwrap();
+
+        // The receiver cannot give back more than its balance, nor another amount.
+        let (_, twenty) = receiver_account
+            .public
+            .owner_enc_pub_key
+            .encrypt_value(20u64.into(), &mut rng);
+        let spent_receiver_balance = withdraw(&receiver_balance, &twenty);
+        let result = CtxReceiver.finalize_reversed_transaction(
+            reversed_tx.clone(),
+            &receiver_account,
+            &spent_receiver_balance,
+            20,
+            30,
+            &mut rng,
+        );
+        assert_err!(
+            result,
+            ErrorKind::NotEnoughFund {
+                balance: 20,
+                transaction_amount: 30
+            }
+        );
+        let result = CtxReceiver.finalize_reversed_transaction(
+            reversed_tx.clone(),
+            &receiver_account,
+            &receiver_balance,
+            40,
+            29,
+            &mut rng,
+        );
+        assert_err!(
+            result,
+            ErrorKind::TransactionAmountMismatch {
+                expected_amount: 29
+            }
+        );
+
+        let finalized_tx = CtxReceiver
+            .finalize_reversed_transaction(
+                reversed_tx,
+                &receiver_account,
+                &receiver_balance,
+                40,
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        let verify = |finalized_tx: &FinalizedReversedTransferTx,
+                      transfer_id: u64,
+                      receiver_balance: &EncryptedAmount,
+                      mediator_pub_key: &EncryptionPubKey,
+                      mediators_pub_keys: &[EncryptionPubKey],
+                      mediators_threshold: u32| {
+            TransactionValidator.verify_reversed_transaction(
+                finalized_tx,
+                transfer_id,
+                &sender_account.public,
+                &sender_balance,
+                &receiver_account.public,
+                receiver_balance,
+                mediator_pub_key,
+                mediators_pub_keys,
+                mediators_threshold,
+            )
+        };
+        let mediators = [mediator.public];
+        let resign = |finalized_tx: &mut FinalizedReversedTransferTx, rng: &mut StdRng| {
+            let reversed_tx = &mut finalized_tx.reversed_data;
+            let message = reversal_message(&reversed_tx.justified_data, &reversed_tx.memo);
+            reversed_tx.approvals[0].signature = mediator.secret.sign(&message, rng).unwrap();
+        };
+
+        // The reversal is bound to the id of the reversed transfer.
+        assert_err!(
+            verify(
+                &finalized_tx,
+                TRANSFER_ID + 1,
+                &receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::TransactionIdMismatch
+        );
+        let mut replayed_tx = finalized_tx.clone();
+        replayed_tx.reversed_data.memo.transfer_id = TRANSFER_ID + 1;
+        assert_err!(
+            verify(
+                &replayed_tx,
+                TRANSFER_ID + 1,
+                &receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::SignatureVerificationError
+        );
+
+        // The reversal must come from a mediator that justified the transaction, and the
+        // transaction must have enough justifications.
+        assert_err!(
+            verify(
+                &finalized_tx,
+                TRANSFER_ID,
+                &receiver_balance,
+                &other_mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::MediatorPayloadError
+        );
+        assert_err!(
+            verify(
+                &finalized_tx,
+                TRANSFER_ID,
+                &receiver_balance,
+                &mediator.public,
+                &[mediator.public, other_mediator.public],
+                2
+            ),
+            ErrorKind::NotEnoughMediatorJustifications {
+                needed: 2,
+                given: 1
+            }
+        );
+        let mut unjustified_tx = finalized_tx.clone();
+        unjustified_tx
+            .reversed_data
+            .justified_data
+            .justifications
+            .clear();
+        resign(&mut unjustified_tx, &mut rng);
+        assert_err!(
+            verify(
+                &unjustified_tx,
+                TRANSFER_ID,
+                &receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::NotEnoughMediatorJustifications {
+                needed: 1,
+                given: 0
+            }
+        );
+
+        // The sender cannot get back more than the receiver gives back.
+        let mut cheating_tx = finalized_tx.clone();
+        let (_, one) = sender_account
+            .public
+            .owner_enc_pub_key
+            .encrypt_value(1u64.into(), &mut rng);
+        cheating_tx.reversed_data.memo.enc_amount_using_sender += &one;
+        resign(&mut cheating_tx, &mut rng);
+        assert_err!(
+            verify(
+                &cheating_tx,
+                TRANSFER_ID,
+                &receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::BatchVerificationError { index: 1 }
+        );
+
+        // The receiver cannot give back less than they received.
+        let mut cheating_tx = finalized_tx.clone();
+        let (_, one) = receiver_account
+            .public
+            .owner_enc_pub_key
+            .encrypt_value(1u64.into(), &mut rng);
+        cheating_tx.reversed_data.memo.enc_amount_using_receiver -= &one;
+        resign(&mut cheating_tx, &mut rng);
+        assert_err!(
+            verify(
+                &cheating_tx,
+                TRANSFER_ID,
+                &receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::BatchVerificationError { index: 0 }
+        );
+
+        // The receiver's proofs are bound to its current balance.
+        assert_err!(
+            verify(
+                &finalized_tx,
+                TRANSFER_ID,
+                &spent_receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::BatchVerificationError { index: 2 }
+        );
+        let mut cheating_tx = finalized_tx;
+        cheating_tx.non_neg_balance_proof =
+            prove_multiple(&[10], &[Scalar::random(&mut rng)], BALANCE_RANGE, &mut rng).unwrap();
+        assert_err!(
+            verify(
+                &cheating_tx,
+                TRANSFER_ID,
+                &receiver_balance,
+                &mediator.public,
+                &mediators,
+                1
+            ),
+            ErrorKind::VerificationError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reverse_ctx_multiple_mediators() {
+        let asset_id = AssetId::from(20);
+        let mut rng = StdRng::from_seed([28u8; 32]);
+        let mediators: Vec<EncryptionKeys> = (160u8..163).map(mock_gen_enc_key_pair).collect();
+        let mediators_pub_keys: Vec<EncryptionPubKey> =
+            mediators.iter().map(|mediator| mediator.public).collect();
+        let outsider = mock_gen_enc_key_pair(163u8);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, 40, asset_id.clone());
+        let (receiver_account, receiver_init_balance) =
+            account_create_helper([18u8; 32], 120u8, 10, asset_id.clone());
+
+        // Two of the three mediators justify the transfer.
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                40,
+                &receiver_account.public,
+                &mediators_pub_keys,
+                &[],
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        let ctx_final = CtxReceiver
+            .finalize_transaction(ctx_init, receiver_account.clone(), 30, &mut rng)
+            .unwrap();
+        let mut justified_tx: JustifiedTransferTx = ctx_final.into();
+        for mediator in &mediators[..2] {
+            justified_tx = CtxMediator
+                .justify_transaction(
+                    justified_tx,
+                    mediator,
+                    &sender_account.public,
+                    &sender_init_balance,
+                    &receiver_account.public,
+                    &[],
+                    asset_id.clone(),
+                    &mut rng,
+                )
+                .unwrap();
+        }
+        let memo = &justified_tx.finalized_data.init_data.memo;
+        let sender_balance = withdraw(&sender_init_balance, &memo.enc_amount_using_sender);
+        let receiver_balance = deposit(&receiver_init_balance, &memo.enc_amount_using_receiver);
+
+        let finalize = |reversed_tx: ReversedTransferTx, rng: &mut StdRng| {
+            CtxReceiver
+                .finalize_reversed_transaction(
+                    reversed_tx,
+                    &receiver_account,
+                    &receiver_balance,
+                    40,
+                    30,
+                    rng,
+                )
+                .unwrap()
+        };
+        let verify = |finalized_tx: &FinalizedReversedTransferTx| {
+            TransactionValidator.verify_reversed_transaction(
+                finalized_tx,
+                TRANSFER_ID,
+                &sender_account.public,
+                &sender_balance,
+                &receiver_account.public,
+                &receiver_balance,
+                &mediators[0].public,
+                &mediators_pub_keys,
+                2,
+            )
+        };
+
+        // With a threshold of 2 out of 3, the mediator that reverses the transfer
+        // cannot undo it alone.
+        let reversed_tx = CtxMediator
+            .reverse_transaction(
+                justified_tx,
+                &mediators[0],
+                &sender_account.public,
+                &receiver_account.public,
+                TRANSFER_ID,
+                &mut rng,
+            )
+            .unwrap();
+        assert_err!(
+            verify(&finalize(reversed_tx.clone(), &mut rng)),
+            ErrorKind::NotEnoughMediatorJustifications {
+                needed: 2,
+                given: 1
+            }
+        );
+
+        // A mediator cannot approve twice, and only the mediators of the transaction can.
+        assert_err!(
+            CtxMediator.approve_reversed_transaction(reversed_tx.clone(), &mediators[0], &mut rng),
+            ErrorKind::MediatorPayloadError
+        );
+        assert_err!(
+            CtxMediator.approve_reversed_transaction(reversed_tx.clone(), &outsider, &mut rng),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // Once another mediator of the transaction approves it, the reversal goes through.
+        let approved_tx = CtxMediator
+            .approve_reversed_transaction(reversed_tx, &mediators[2], &mut rng)
+            .unwrap();
+        let finalized_tx = through_wire(&finalize(approved_tx, &mut rng));
+        let (updated_sender_balance, updated_receiver_balance) = verify(&finalized_tx).unwrap();
+        assert!(sender_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_sender_balance, &40u64.into())
+            .is_ok());
+        assert!(receiver_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_receiver_balance, &10u64.into())
+            .is_ok());
+
+        // The approval of the mediator that reverses the transfer comes first.
+        let mut reordered_tx = finalized_tx;
+        reordered_tx.reversed_data.approvals.swap(0, 1);
+        assert_err!(verify(&reordered_tx), ErrorKind::MediatorPayloadError);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reject_ctx_success() {
+        let mut rng = StdRng::from_seed([24u8; 32]);
+        let ((sender_account, sender_pending_balance), _, mediator, tx) =
+            justified_ctx_helper(40, 10, 30);
+        let pending_tx = tx.finalized_data.init_data.clone();
+
+        // The mediator rejects the transaction instead of justifying it.
+        let rejected_tx = CtxMediator
+            .reject_transaction(
+                tx.finalized_data.into(),
+                &mediator,
+                RejectionReason::AssetMismatch,
+                &mut rng,
+            )
+            .unwrap();
+        let rejected_tx = through_wire(&rejected_tx);
+        assert_eq!(rejected_tx.rejections.len(), 1);
+        assert_eq!(
+            rejected_tx.rejections[0].reason,
+            RejectionReason::AssetMismatch
+        );
+
+        // The validators verify it and release the sender's pending balance.
+        assert_eq!(
+            verify_rejected_transaction(
+                &rejected_tx,
+                &pending_tx,
+                &sender_account.public,
+                &[mediator.public],
+                1
+            )
+            .unwrap(),
+            TransferTxState::Justification(TxSubstate::Rejected)
+        );
+        let updated_sender_balance = TransactionValidator
+            .verify_rejected_transaction(
+                &rejected_tx,
+                &pending_tx,
+                &sender_account.public,
+                &sender_pending_balance,
+                &[mediator.public],
+                1,
+            )
+            .unwrap();
+
+        assert!(sender_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_sender_balance, &40u64.into())
+            .is_ok());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reject_ctx_invalid() {
+        let mut rng = StdRng::from_seed([25u8; 32]);
+        let ((sender_account, sender_pending_balance), (receiver_account, _), mediator, tx) =
+            justified_ctx_helper(40, 10, 30);
+        let other_mediator = mock_gen_enc_key_pair(141u8);
+        let pending_tx = tx.finalized_data.init_data.clone();
+
+        let rejected_tx = CtxMediator
+            .reject_transaction(
+                tx.finalized_data.into(),
+                &mediator,
+                RejectionReason::PolicyViolation,
+                &mut rng,
+            )
+            .unwrap();
+        let verify = |rejected_tx: &RejectedTransferTx,
+                      sender_account: &PubAccount,
+                      mediator_pub_key: &EncryptionPubKey| {
+            TransactionValidator.verify_rejected_transaction(
+                rejected_tx,
+                &pending_tx,
+                sender_account,
+                &sender_pending_balance,
+                &[*mediator_pub_key],
+                1,
+            )
+        };
+
+        // The rejection must come from the mediator of the transaction.
+        assert_err!(
+            verify(&rejected_tx, &sender_account.public, &other_mediator.public),
+            ErrorKind::MediatorPayloadError
+        );
+        let mut forged_tx = rejected_tx.clone();
+        forged_tx.rejections[0].signature = other_mediator
+            .secret
+            .sign(
+                &rejection_message(&forged_tx.finalized_data, forged_tx.rejections[0].reason),
+                &mut rng,
+            )
+            .unwrap();
+        assert_err!(
+            verify(&forged_tx, &sender_account.public, &mediator.public),
+            ErrorKind::SignatureVerificationError
+        );
+
+        // The reason is signed along with the transaction.
+        let mut tampered_tx = rejected_tx.clone();
+        tampered_tx.rejections[0].reason = RejectionReason::InvalidAmount;
+        assert_err!(
+            verify(&tampered_tx, &sender_account.public, &mediator.public),
+            ErrorKind::SignatureVerificationError
+        );
+
+        // A transaction without rejections is not rejected.
+        let mut unrejected_tx = rejected_tx.clone();
+        unrejected_tx.rejections.clear();
+        assert_err!(
+            verify(&unrejected_tx, &sender_account.public, &mediator.public),
+            ErrorKind::NotEnoughMediatorRejections {
+                needed: 1,
+                given: 0
+            }
+        );
+
+        // Only the sender's pending balance is released.
+        assert_err!(
+            verify(&rejected_tx, &receiver_account.public, &mediator.public),
+            ErrorKind::AccountIdMismatch
+        );
+
+        // A rejection with a forged amount does not release more than the pending
+        // transaction withdrew, even if the mediator signs it.
+        let (_, forged_amount) = sender_account
+            .public
+            .owner_enc_pub_key
+            .encrypt_value(1000u64.into(), &mut rng);
+        let mut forged_tx = rejected_tx.finalized_data.clone();
+        forged_tx.init_data.memo.enc_amount_using_sender = forged_amount;
+        let forged_tx = CtxMediator
+            .reject_transaction(
+                forged_tx.into(),
+                &mediator,
+                RejectionReason::PolicyViolation,
+                &mut rng,
+            )
+            .unwrap();
+        assert_err!(
+            verify(&forged_tx, &sender_account.public, &mediator.public),
+            ErrorKind::PendingTransactionMismatch
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_reject_ctx_multiple_mediators() {
+        let asset_id = AssetId::from(20);
+        let mut rng = StdRng::from_seed([27u8; 32]);
+        let mediators: Vec<EncryptionKeys> = (150u8..153).map(mock_gen_enc_key_pair).collect();
+        let mediators_pub_keys: Vec<EncryptionPubKey> =
+            mediators.iter().map(|mediator| mediator.public).collect();
+        let outsider = mock_gen_enc_key_pair(153u8);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([17u8; 32], 100u8, 40, asset_id.clone());
+        let (receiver_account, _) = account_create_helper([18u8; 32], 120u8, 0, asset_id);
+
+        let ctx_init = CtxSender
+            .create_transaction(
+                &sender_account,
+                &sender_init_balance,
+                40,
+                &receiver_account.public,
+                &mediators_pub_keys,
+                &[],
+                30,
+                &mut rng,
+            )
+            .unwrap();
+        let sender_pending_balance =
+            withdraw(&sender_init_balance, &ctx_init.memo.enc_amount_using_sender);
+        let pending_tx = ctx_init.clone();
+        let ctx_final = CtxReceiver
+            .finalize_transaction(ctx_init, receiver_account, 30, &mut rng)
+            .unwrap();
+
+        let mut reject = |tx: RejectedTransferTx, mediator: &EncryptionKeys| {
+            CtxMediator.reject_transaction(tx, mediator, RejectionReason::PolicyViolation, &mut rng)
+        };
+        let verify = |tx: &RejectedTransferTx, threshold| {
+            TransactionValidator.verify_rejected_transaction(
+                tx,
+                &pending_tx,
+                &sender_account.public,
+                &sender_pending_balance,
+                &mediators_pub_keys,
+                threshold,
+            )
+        };
+
+        // With a threshold of 2 out of 3, a single mediator cannot reject the transaction,
+        // since the two other mediators can still justify it.
+        let rejected_by_one = reject(ctx_final.into(), &mediators[1]).unwrap();
+        assert_err!(
+            verify(&rejected_by_one, 2),
+            ErrorKind::NotEnoughMediatorRejections {
+                needed: 2,
+                given: 1
+            }
+        );
+        assert!(verify(&rejected_by_one, 3).is_ok());
+
+        // A mediator cannot reject twice, and only the mediators of the transaction can.
+        assert_err!(
+            reject(rejected_by_one.clone(), &mediators[1]),
+            ErrorKind::MediatorPayloadError
+        );
+        assert_err!(
+            reject(rejected_by_one.clone(), &outsider),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // Once a second mediator rejects it, the transaction can no longer be justified.
+        let rejected_by_two = through_wire(&reject(rejected_by_one, &mediators[2]).unwrap());
+        let updated_sender_balance = verify(&rejected_by_two, 2).unwrap();
+        assert!(sender_account
+            .secret
+            .enc_keys
+            .secret
+            .verify(&updated_sender_balance, &40u64.into())
+            .is_ok());
+        assert_err!(
+            verify(&rejected_by_two, 1),
+            ErrorKind::NotEnoughMediatorRejections {
+                needed: 3,
+                given: 2
+            }
+        );
+        assert_err!(
+            verify(&rejected_by_two, 4),
+            ErrorKind::InvalidThresholdParameters {
+                threshold: 4,
+                parties: 3
+            }
+        );
+
+        // The same rejection cannot count twice.
+        let mut cheating_tx = rejected_by_two;
+        cheating_tx.rejections[1] = cheating_tx.rejections[0];
+        assert_err!(verify(&cheating_tx, 2), ErrorKind::MediatorPayloadError);
     }
 
-    #[test]
-    #[wasm_bindgen_test]
-    fn test_finalize_ctx_amount_mismatch_error() {
-        let ctx_receiver = CtxReceiver;
-        let expected_amount = 10;
-        let received_amount = 20;
+    // ------------------------------ Test Batched Transactions
+
+    /// Creates, finalizes and justifies a batched transaction of `amounts`, and returns the
+    /// accounts, the keys of the two mediators, the auditor and the transaction.
+    fn batch_ctx_helper(
+        sender_balance: Balance,
+        amounts: &[Balance],
+    ) -> (
+        (Account, EncryptedAmount),
+        Vec<(Account, EncryptedAmount)>,
+        Vec<EncryptionKeys>,
+        AuditorAccount,
+        JustifiedBatchTransferTx,
+    ) {
         let asset_id = AssetId::from(20);
-        let balance = 0;
-        let mut rng = StdRng::from_seed([17u8; 32]);
-
-        let receiver_enc_keys = mock_gen_enc_key_pair(17u8);
+        let mut rng = StdRng::from_seed([27u8; 32]);
+        let (sender_account, sender_init_balance) =
+            account_create_helper([28u8; 32], 170u8, sender_balance, asset_id.clone());
+        let receivers: Vec<(Account, EncryptedAmount)> = (0..amounts.len())
+            .map(|i| {
+                account_create_helper([29u8 + i as u8; 32], 160u8 + i as u8, 5, asset_id.clone())
+            })
+            .collect();
+        let receivers_accounts: Vec<PubAccount> = receivers
+            .iter()
+            .map(|(receiver_account, _)| receiver_account.public.clone())
+            .collect();
+        let mediators: Vec<EncryptionKeys> = (180u8..182).map(mock_gen_enc_key_pair).collect();
+        let mediators_pub_keys: Vec<EncryptionPubKey> =
+            mediators.iter().map(|mediator| mediator.public).collect();
+        let auditor = AuditorAccount {
+            auditor_id: [18u8; 32],
+            encryption_key: mock_gen_enc_key_pair(182u8),
+        };
+        let auditors = [AuditorPubAccount {
+            auditor_id: auditor.auditor_id,
+            encryption_public_key: auditor.encryption_key.public,
+        }];
 
-        let ctx_init_data = mock_ctx_init_data(
-            receiver_enc_keys.public,
-            received_amount,
-            asset_id.clone(),
-            &mut rng,
-        );
-        let receiver_account = Account {
-            public: mock_gen_account(
-                receiver_enc_keys.public,
-                asset_id.clone(),
-                balance,
+        let payments: Vec<(PubAccount, Balance)> = receivers_accounts
+            .iter()
+            .zip(amounts)
+            .map(|(receiver_account, amount)| (receiver_account.clone(), *amount))
+            .collect();
+        let batch_tx = CtxSender
+            .create_batch_transaction(
+                &sender_account,
+                &sender_init_balance,
+                sender_balance,
+                &payments,
+                &mediators_pub_keys,
+                &auditors,
                 &mut rng,
             )
-            .unwrap()
-            .0,
-            secret: SecAccount {
-                enc_keys: receiver_enc_keys,
-                asset_id_witness: CommitmentWitness::from((asset_id.into(), &mut rng)),
-            },
-        };
+            .unwrap();
 
-        let result = ctx_receiver.finalize_transaction(
-            ctx_init_data,
-            receiver_account,
-            expected_amount,
-            &mut rng,
-        );
+        // The receivers finalize the transaction in reverse order.
+        let mut finalized_tx: FinalizedBatchTransferTx = through_wire(&batch_tx).into();
+        for ((receiver_account, _), amount) in receivers.iter().zip(amounts).rev() {
+            finalized_tx = CtxReceiver
+                .finalize_batch_transaction(
+                    finalized_tx,
+                    receiver_account.clone(),
+                    *amount,
+                    &mut rng,
+                )
+                .unwrap();
+        }
 
-        assert_err!(
-            result,
-            ErrorKind::TransactionAmountMismatch { expected_amount }
-        );
+        // Both mediators justify the transaction.
+        let mut justified_tx: JustifiedBatchTransferTx = through_wire(&finalized_tx).into();
+        for mediator in &mediators {
+            justified_tx = CtxMediator
+                .justify_batch_transaction(
+                    justified_tx,
+                    mediator,
+                    &sender_account.public,
+                    &sender_init_balance,
+                    &receivers_accounts,
+                    &auditors,
+                    asset_id.clone(),
+                    &mut rng,
+                )
+                .unwrap();
+        }
+
+        (
+            (sender_account, sender_init_balance),
+            receivers,
+            mediators,
+            auditor,
+            through_wire(&justified_tx),
+        )
     }
 
-    // ------------------------------ Test simple scenarios
+    /// Returns the public accounts of the given accounts.
+    fn pub_accounts(accounts: &[(Account, EncryptedAmount)]) -> Vec<PubAccount> {
+        accounts
+            .iter()
+            .map(|(account, _)| account.public.clone())
+            .collect()
+    }
+
+    /// Returns the public account of the given auditor.
+    fn auditor_pub_account(auditor: &AuditorAccount) -> AuditorPubAccount {
+        AuditorPubAccount {
+            auditor_id: auditor.auditor_id,
+            encryption_public_key: auditor.encryption_key.public,
+        }
+    }
 
     #[test]
     #[wasm_bindgen_test]
-    fn test_ctx_create_finalize_validate_success() {
-        let sender = CtxSender;
-        let receiver = CtxReceiver;
-        let mediator = CtxMediator;
-        let tx_validator = TransactionValidator;
-        let asset_id = AssetId::from(20);
-        let sender_balance = 40;
-        let receiver_balance = 0;
-        let amount = 30;
+    fn test_batch_ctx_success() {
+        // Three amounts and the remaining balance fill the range proof, while two
+        // amounts need padding.
+        for amounts in &[vec![10, 20, 30], vec![25, 75]] {
+            let (
+                (sender_account, sender_init_balance),
+                receivers,
+                mediators,
+                auditor,
+                justified_tx,
+            ) = batch_ctx_helper(100, amounts);
+            let mediators_pub_keys: Vec<EncryptionPubKey> =
+                mediators.iter().map(|mediator| mediator.public).collect();
+
+            assert!(TransactionValidator
+                .verify_batch_transaction(
+                    &justified_tx,
+                    &sender_account.public,
+                    &sender_init_balance,
+                    &pub_accounts(&receivers),
+                    &[auditor_pub_account(&auditor)],
+                    &mediators_pub_keys,
+                    2,
+                )
+                .is_ok());
 
-        let mut rng = StdRng::from_seed([17u8; 32]);
+            // ----------------------- Processing
+            let batch_tx = &justified_tx.finalized_data.batch_data;
+            let updated_sender_balance = withdraw(
+                &sender_init_balance,
+                &batch_tx.total_enc_amount_using_sender(),
+            );
+            assert!(sender_account
+                .secret
+                .enc_keys
+                .secret
+                .verify(
+                    &updated_sender_balance,
+                    &(100 - amounts.iter().sum::<Balance>()).into()
+                )
+                .is_ok());
+            for ((receiver_account, receiver_init_balance), (leg, amount)) in
+                receivers.iter().zip(batch_tx.legs.iter().zip(amounts))
+            {
+                let updated_receiver_balance =
+                    deposit(receiver_init_balance, &leg.enc_amount_using_receiver);
+                assert!(receiver_account
+                    .secret
+                    .enc_keys
+                    .secret
+                    .verify(&updated_receiver_balance, &(5 + amount).into())
+                    .is_ok());
+            }
 
-        let sender_enc_keys = mock_gen_enc_key_pair(10u8);
+            // ----------------------- Auditing
+            // The auditor sees the sum of the amounts.
+            assert_eq!(batch_tx.auditors_payload.len(), 1);
+            assert_eq!(
+                auditor
+                    .encryption_key
+                    .secret
+                    .const_time_decrypt(&batch_tx.auditors_payload[0].encrypted_amount)
+                    .unwrap(),
+                amounts.iter().sum::<Balance>()
+            );
+        }
+    }
 
-        let receiver_enc_keys = mock_gen_enc_key_pair(12u8);
+    #[test]
+    #[wasm_bindgen_test]
+    #[cfg(feature = "serde_all")]
+    fn test_batch_ctx_json_matches_schema() {
+        let (_, _, _, _, tx) = batch_ctx_helper(100, &[25, 75]);
+        let value = serde_json::to_value(&tx).unwrap();
+        check_schema(&value, "mercat.JustifiedBatchTransferTx");
+
+        let decoded: JustifiedBatchTransferTx = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.encode_versioned(), tx.encode_versioned());
+    }
 
-        let mediator_enc_keys = mock_gen_enc_key_pair(14u8);
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_batch_ctx_invalid() {
+        let mut rng = StdRng::from_seed([30u8; 32]);
+        let ((sender_account, sender_init_balance), receivers, mediators, auditor, justified_tx) =
+            batch_ctx_helper(100, &[10, 20, 30]);
+        let receivers_accounts = pub_accounts(&receivers);
+        let mediators_pub_keys: Vec<EncryptionPubKey> =
+            mediators.iter().map(|mediator| mediator.public).collect();
+        let auditors = [auditor_pub_account(&auditor)];
+        let payments: Vec<(PubAccount, Balance)> = receivers_accounts
+            .iter()
+            .map(|receiver_account| (receiver_account.clone(), 40))
+            .collect();
 
-        let (receiver_pub_account, receiver_init_balance) = mock_gen_account(
-            receiver_enc_keys.public,
-            asset_id.clone(),
-            receiver_balance,
-            &mut rng,
-        )
-        .unwrap();
-        let receiver_account = Account {
-            public: receiver_pub_account,
-            secret: SecAccount {
-                enc_keys: receiver_enc_keys.clone(),
-                asset_id_witness: CommitmentWitness::from((asset_id.clone().into(), &mut rng)),
-            },
+        // The sender must pay someone, must have enough funds for the sum, and must
+        // involve mediators.
+        let create = |payments: &[(PubAccount, Balance)],
+                      mediators_pub_keys: &[EncryptionPubKey],
+                      rng: &mut StdRng| {
+            CtxSender.create_batch_transaction(
+                &sender_account,
+                &sender_init_balance,
+                100,
+                payments,
+                mediators_pub_keys,
+                &auditors,
+                rng,
+            )
         };
+        assert_err!(
+            create(&[], &mediators_pub_keys, &mut rng),
+            ErrorKind::BatchTransferError
+        );
+        assert_err!(
+            create(&payments, &mediators_pub_keys, &mut rng),
+            ErrorKind::NotEnoughFund {
+                balance: 100,
+                transaction_amount: 120
+            }
+        );
+        assert_err!(
+            create(&payments[..1], &[], &mut rng),
+            ErrorKind::MediatorPayloadError
+        );
+        let too_many_payments = vec![(receivers_accounts[0].clone(), 1); MAX_BATCH_RECEIVERS + 1];
+        assert_err!(
+            create(&too_many_payments, &mediators_pub_keys, &mut rng),
+            ErrorKind::TooManyBatchReceivers { max: 63, given: 64 }
+        );
 
-        let (sender_pub_account, sender_init_balance) = mock_gen_account(
-            sender_enc_keys.public,
-            asset_id.clone(),
-            sender_balance,
-            &mut rng,
-        )
-        .unwrap();
-        let sender_account = Account {
-            public: sender_pub_account,
-            secret: SecAccount {
-                enc_keys: sender_enc_keys.clone(),
-                asset_id_witness: CommitmentWitness::from((asset_id.clone().into(), &mut rng)),
-            },
+        // Each receiver checks its own amount, and finalizes its leg only once.
+        let finalized_tx = &justified_tx.finalized_data;
+        let batch_tx: FinalizedBatchTransferTx = finalized_tx.batch_data.clone().into();
+        let (receiver_account, _) = &receivers[0];
+        assert_err!(
+            CtxReceiver.finalize_batch_transaction(
+                batch_tx.clone(),
+                receiver_account.clone(),
+                20,
+                &mut rng
+            ),
+            ErrorKind::TransactionAmountMismatch {
+                expected_amount: 20
+            }
+        );
+        assert_err!(
+            CtxReceiver.finalize_batch_transaction(
+                finalized_tx.clone(),
+                receiver_account.clone(),
+                10,
+                &mut rng
+            ),
+            ErrorKind::BatchTransferError
+        );
+        let (outsider_account, _) = account_create_helper([31u8; 32], 171u8, 0, AssetId::from(20));
+        assert_err!(
+            CtxReceiver.finalize_batch_transaction(batch_tx, outsider_account, 10, &mut rng),
+            ErrorKind::BatchTransferError
+        );
+
+        let verify = |justified_tx: &JustifiedBatchTransferTx,
+                      receivers_accounts: &[PubAccount]| {
+            TransactionValidator.verify_batch_transaction(
+                justified_tx,
+                &sender_account.public,
+                &sender_init_balance,
+                receivers_accounts,
+                &auditors,
+                &mediators_pub_keys,
+                2,
+            )
         };
 
-        // Create the transaction and check its result and state
-        let result = sender.create_transaction(
-            &sender_account,
-            &sender_init_balance,
-            &receiver_account.public,
-            &mediator_enc_keys.public,
-            &[],
-            amount,
-            &mut rng,
+        // All the receivers must have finalized their legs.
+        let mut unfinalized_tx = justified_tx.clone();
+        unfinalized_tx.finalized_data.receipts.pop();
+        assert_err!(
+            verify(&unfinalized_tx, &receivers_accounts),
+            ErrorKind::BatchTransferError
         );
-        let ctx_init_data = result.unwrap();
 
-        // Finalize the transaction and check its state.
-        let result = receiver.finalize_transaction(
-            ctx_init_data,
-            receiver_account.clone(),
-            amount,
-            &mut rng,
+        // The receivers are given in the order of the legs.
+        let mut reordered_accounts = receivers_accounts.clone();
+        reordered_accounts.swap(0, 1);
+        assert_err!(
+            verify(&justified_tx, &reordered_accounts),
+            ErrorKind::AccountIdMismatch
+        );
+        assert_err!(
+            verify(&justified_tx, &receivers_accounts[..2]),
+            ErrorKind::BatchTransferError
         );
-        let ctx_finalized_data = result.unwrap();
 
-        // Justify the transaction
-        let result = mediator.justify_transaction(
-            ctx_finalized_data,
-            &mediator_enc_keys,
-            &sender_account.public,
-            &sender_init_balance,
-            &receiver_account.public,
-            &[],
-            asset_id,
-            &mut rng,
+        // The verifier rejects batches that are too large up front.
+        let mut oversized_tx = justified_tx.clone();
+        let legs = &mut oversized_tx.finalized_data.batch_data.legs;
+        legs.resize(MAX_BATCH_RECEIVERS + 1, legs[0].clone());
+        assert_err!(
+            verify(&oversized_tx, &receivers_accounts),
+            ErrorKind::TooManyBatchReceivers { max: 63, given: 64 }
         );
-        let justified_finalized_ctx_data = result.unwrap();
 
-        assert!(tx_validator
-            .verify_transaction(
-                &justified_finalized_ctx_data,
+        // The range proof must be over the amounts of the transaction.
+        let (_, _, _, _, other_justified_tx) = batch_ctx_helper(100, &[30, 20, 10]);
+        let mut forged_tx = justified_tx.clone();
+        forged_tx
+            .finalized_data
+            .batch_data
+            .non_neg_amounts_and_enough_fund_proof = other_justified_tx
+            .finalized_data
+            .batch_data
+            .non_neg_amounts_and_enough_fund_proof;
+        assert_err!(
+            verify(&forged_tx, &receivers_accounts),
+            ErrorKind::VerificationError
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_batch_ctx_mediators_and_auditors() {
+        let mut rng = StdRng::from_seed([32u8; 32]);
+        let ((sender_account, sender_init_balance), receivers, mediators, auditor, justified_tx) =
+            batch_ctx_helper(100, &[10, 20, 30]);
+        let receivers_accounts = pub_accounts(&receivers);
+        let mediators_pub_keys: Vec<EncryptionPubKey> =
+            mediators.iter().map(|mediator| mediator.public).collect();
+        let auditors = [auditor_pub_account(&auditor)];
+        let outsider = mock_gen_enc_key_pair(183u8);
+
+        let verify =
+            |justified_tx: &JustifiedBatchTransferTx, auditors: &[AuditorPubAccount], threshold| {
+                TransactionValidator.verify_batch_transaction(
+                    justified_tx,
+                    &sender_account.public,
+                    &sender_init_balance,
+                    &receivers_accounts,
+                    auditors,
+                    &mediators_pub_keys,
+                    threshold,
+                )
+            };
+        let mut justify = |justified_tx: JustifiedBatchTransferTx, mediator: &EncryptionKeys| {
+            CtxMediator.justify_batch_transaction(
+                justified_tx,
+                mediator,
                 &sender_account.public,
                 &sender_init_balance,
-                &receiver_account.public,
-                &[],
+                &receivers_accounts,
+                &auditors,
+                AssetId::from(20),
                 &mut rng,
             )
-            .is_ok());
+        };
 
-        // ----------------------- Processing
-        // Check that the transferred amount is added to the receiver's account balance
-        // and subtracted from sender's balance.
-        let updated_sender_balance = withdraw(
-            &sender_init_balance,
-            &justified_finalized_ctx_data
-                .finalized_data
-                .init_data
-                .memo
-                .enc_amount_using_sender,
+        // The batch needs the justifications of enough of its mediators.
+        let mut half_justified_tx = justified_tx.clone();
+        half_justified_tx.justifications.pop();
+        assert!(verify(&half_justified_tx, &auditors, 1).is_ok());
+        assert_err!(
+            verify(&half_justified_tx, &auditors, 2),
+            ErrorKind::NotEnoughMediatorJustifications {
+                needed: 2,
+                given: 1
+            }
         );
-        let updated_receiver_balance = deposit(
-            &receiver_init_balance,
-            &justified_finalized_ctx_data
-                .finalized_data
-                .init_data
-                .memo
-                .enc_amount_using_receiver,
+        assert_err!(
+            verify(&justified_tx, &auditors, 3),
+            ErrorKind::InvalidThresholdParameters {
+                threshold: 3,
+                parties: 2
+            }
         );
 
-        assert!(sender_enc_keys
-            .secret
-            .verify(&updated_sender_balance, &(sender_balance - amount).into())
-            .is_ok());
-        assert!(receiver_enc_keys
-            .secret
-            .verify(
-                &updated_receiver_balance,
-                &(receiver_balance + amount).into()
-            )
-            .is_ok());
+        // A mediator cannot justify twice, and only the mediators of the transaction can.
+        assert_err!(
+            justify(half_justified_tx.clone(), &mediators[0]),
+            ErrorKind::MediatorPayloadError
+        );
+        assert_err!(
+            justify(half_justified_tx.clone(), &outsider),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // The mediators must have the payload of every leg.
+        let mut unmediated_tx = half_justified_tx;
+        unmediated_tx.finalized_data.batch_data.legs[1]
+            .mediators_payload
+            .pop();
+        assert_err!(
+            justify(unmediated_tx.clone(), &mediators[1]),
+            ErrorKind::MediatorPayloadError
+        );
+        unmediated_tx.justifications = vec![justified_tx.justifications[1]];
+        assert_err!(
+            verify(&unmediated_tx, &auditors, 1),
+            ErrorKind::MediatorPayloadError
+        );
+
+        // The payloads of all the auditors must be included.
+        let other_auditor = AuditorPubAccount {
+            auditor_id: [19u8; 32],
+            encryption_public_key: outsider.public,
+        };
+        assert_err!(
+            verify(&justified_tx, &[auditors[0].clone(), other_auditor], 2),
+            ErrorKind::AuditorPayloadError
+        );
+        let mut unaudited_tx = justified_tx.clone();
+        unaudited_tx
+            .finalized_data
+            .batch_data
+            .auditors_payload
+            .clear();
+        assert_err!(
+            verify(&unaudited_tx, &auditors, 2),
+            ErrorKind::AuditorPayloadError
+        );
+
+        // The auditors' payload must encrypt the sum of the amounts.
+        let (_, _, _, _, other_justified_tx) = batch_ctx_helper(100, &[10, 20, 40]);
+        let mut tampered_tx = justified_tx;
+        tampered_tx.finalized_data.batch_data.auditors_payload = other_justified_tx
+            .finalized_data
+            .batch_data
+            .auditors_payload;
+        assert_err!(
+            verify(&tampered_tx, &auditors, 2),
+            ErrorKind::VerificationError
+        );
     }
 
     // ------------------------------ Test Auditing Logic
@@ -1034,8 +3853,9 @@ mod tests {
             .create_transaction(
                 &sender_account,
                 &sender_init_balance,
+                sender_balance,
                 &receiver_account.public,
-                &mediator_enc_keys.public,
+                &[mediator_enc_keys.public],
                 sender_auditor_list,
                 amount,
                 &mut rng,
@@ -1049,7 +3869,7 @@ mod tests {
 
         // Justify the transaction
         let result = mediator.justify_transaction(
-            ctx_final,
+            ctx_final.into(),
             &mediator_enc_keys,
             &sender_account.public,
             &sender_init_balance,
@@ -1071,7 +3891,8 @@ mod tests {
             &sender_init_balance,
             &receiver_account.public,
             validator_auditor_list,
-            &mut rng,
+            &[mediator_enc_keys.public],
+            1,
         );
 
         if validator_check_fails {
@@ -4,16 +4,16 @@ use mercat::{
     asset::AssetIssuer,
     cryptography_core::{
         asset_proofs::{
-            AssetId, CipherText, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
+            AssetId, Balance, CipherText, CommitmentWitness, ElgamalPublicKey, ElgamalSecretKey,
         },
         curve25519_dalek::scalar::Scalar,
     },
     transaction::{CtxMediator, CtxReceiver, CtxSender},
     Account as MercatAccount, AccountCreatorInitializer, AssetTransactionIssuer, EncryptedAmount,
-    EncryptionKeys, FinalizedTransferTx, InitializedAssetTx, InitializedTransferTx,
-    MediatorAccount as MercatMediatorAccount, PubAccount as MercatPubAccount, PubAccountTx,
-    SecAccount, TransferTransactionMediator, TransferTransactionReceiver,
-    TransferTransactionSender,
+    EncryptedAmountWithHint, EncryptionKeys, FinalizedTransferTx, InitializedAssetTx,
+    InitializedTransferTx, MediatorAccount as MercatMediatorAccount,
+    PubAccount as MercatPubAccount, PubAccountTx, SecAccount, TransferTransactionMediator,
+    TransferTransactionReceiver, TransferTransactionSender,
 };
 use rand_core::OsRng;
 use serde::Serialize;
@@ -339,7 +339,7 @@ pub fn create_mediator_account() -> CreateMediatorAccountOutput {
 /// Creates a Zero Knowledge Proof of minting a confidential asset.
 ///
 /// # Arguments
-/// * `amount`: An integer with a max value of `2^32` representing the mint amount.
+/// * `amount`: An integer with a max value of `2^64 - 1` representing the mint amount.
 /// * `issuer_account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
 ///
 /// # Outputs
@@ -349,7 +349,7 @@ pub fn create_mediator_account() -> CreateMediatorAccountOutput {
 /// * `Base64DecodingError`: If the `issuer_account` cannot be decoded from base64.
 /// * `DeserializationError`: If the `issuer_account` cannot be deserialized to a mercat account.
 #[wasm_bindgen]
-pub fn mint_asset(amount: u32, issuer_account: Account) -> Fallible<MintAssetOutput> {
+pub fn mint_asset(amount: Balance, issuer_account: Account) -> Fallible<MintAssetOutput> {
     let mut rng = OsRng;
     let asset_tx: InitializedAssetTx = AssetIssuer
         .initialize_asset_transaction(&issuer_account.to_mercat()?, &[], amount, &mut rng)
@@ -365,10 +365,12 @@ pub fn mint_asset(amount: u32, issuer_account: Account) -> Fallible<MintAssetOut
 /// information of the receiver and the mediator.
 ///
 /// # Arguments
-/// * `amount`: An integer with a max value of `2^32` representing the mint amount.
+/// * `amount`: An integer with a max value of `2^64 - 1` representing the mint amount.
 /// * `sender_account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
 /// * `encrypted_pending_balance`: Sender's encrypted pending balance. Can be obtained from the
 ///                                chain.
+/// * `pending_balance`: Sender's decrypted pending balance. It is checked against
+///                      `encrypted_pending_balance`.
 /// * `receiver_public_account`: Receiver's public account. Can be obtained from the chain.
 /// * `mediator_public_key`: Mediator's public key. Can be obtained from the chain.
 ///
@@ -381,9 +383,10 @@ pub fn mint_asset(amount: u32, issuer_account: Account) -> Fallible<MintAssetOut
 /// * `TransactionCreationError`: If the mercat library throws an error when creating the proof.
 #[wasm_bindgen]
 pub fn create_transaction(
-    amount: u32,
+    amount: Balance,
     sender_account: Account,
     encrypted_pending_balance: Base64,
+    pending_balance: Balance,
     receiver_public_account: PubAccount,
     mediator_public_key: Base64,
 ) -> Fallible<CreateTransactionOutput> {
@@ -393,8 +396,9 @@ pub fn create_transaction(
         .create_transaction(
             &sender_account.to_mercat()?,
             &decode::<CipherText>(encrypted_pending_balance)?,
+            pending_balance,
             &receiver_public_account.to_mercat()?,
-            &decode::<ElgamalPublicKey>(mediator_public_key)?,
+            &[decode::<ElgamalPublicKey>(mediator_public_key)?],
             &[],
             amount,
             &mut rng,
@@ -411,7 +415,7 @@ pub fn create_transaction(
 /// information of the sender.
 ///
 /// # Arguments
-/// * `amount`: An integer with a max value of `2^32` representing the mint amount.
+/// * `amount`: An integer with a max value of `2^64 - 1` representing the mint amount.
 /// * `init_tx`: The initialized transaction proof. Can be obtained from the chain.
 /// * `receiver_account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
 ///
@@ -424,7 +428,7 @@ pub fn create_transaction(
 /// * `TransactionFinalizationError`: If the mercat library throws an error when creating the proof.
 #[wasm_bindgen]
 pub fn finalize_transaction(
-    amount: u32,
+    amount: Balance,
     init_tx: Base64,
     receiver_account: Account,
 ) -> Fallible<FinalizedTransactionOutput> {
@@ -479,7 +483,7 @@ pub fn justify_transaction(
 
     let justified_tx = CtxMediator
         .justify_transaction(
-            decode::<FinalizedTransferTx>(finalized_tx)?,
+            decode::<FinalizedTransferTx>(finalized_tx)?.into(),
             &mediator_account.to_mercat()?.encryption_key,
             &sender_public_account.to_mercat()?,
             &decode::<EncryptedAmount>(sender_encrypted_pending_balance)?,
@@ -496,20 +500,22 @@ pub fn justify_transaction(
 }
 
 /// Decrypts an `encrypted_value` given the secret account information.
+/// Only values that are less than `2^32` can be decrypted this way. Use `decrypt_with_hint`
+/// for larger values.
 ///
 /// # Arguments
 /// * `encrypted_value`: The encrypted value.
 /// * `account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
 ///
 /// # Outputs
-/// * `u32`: The decrypted value.
+/// * `Balance`: The decrypted value.
 ///
 /// # Errors
 /// * `Base64DecodingError`: If either of the inputs cannot be decoded from base64.
 /// * `DeserializationError`: If either of the inputs cannot be deserialized to a mercat account.
 /// * `DecryptionError`: If the mercat library throws an error while decrypting the value.
 #[wasm_bindgen]
-pub fn decrypt(encrypted_value: Base64, account: Account) -> Fallible<u32> {
+pub fn decrypt(encrypted_value: Base64, account: Account) -> Fallible<Balance> {
     let enc_balance = decode::<EncryptedAmount>(encrypted_value)?;
     let account = account.to_mercat()?;
 
@@ -523,6 +529,67 @@ pub fn decrypt(encrypted_value: Base64, account: Account) -> Fallible<u32> {
     Ok(decrypted_value)
 }
 
+/// Encrypts a `balance` for the owner of the `account`, such that it can later be used as the
+/// hint of `decrypt_with_hint`.
+///
+/// # Arguments
+/// * `balance`: An integer with a max value of `2^64 - 1` representing the expected balance.
+/// * `account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
+///
+/// # Outputs
+/// * `Base64`: The encrypted balance hint.
+///
+/// # Errors
+/// * `Base64DecodingError`: If the `account` cannot be decoded from base64.
+/// * `DeserializationError`: If the `account` cannot be deserialized to a mercat account.
+#[wasm_bindgen]
+pub fn encrypt_balance_hint(balance: Balance, account: Account) -> Fallible<Base64> {
+    let mut rng = OsRng;
+    let account = account.to_mercat()?;
+
+    let (_, hint) = account
+        .public
+        .owner_enc_pub_key
+        .const_time_encrypt_value(balance.into(), &mut rng);
+
+    Ok(base64::encode(hint.encode()))
+}
+
+/// Decrypts an `encrypted_value` of any size given the secret account information and a
+/// `hint` that encrypts the same value.
+///
+/// # Arguments
+/// * `encrypted_value`: The encrypted value.
+/// * `hint`: The encrypted hint. Can be obtained from `encrypt_balance_hint`.
+/// * `account`: The mercat account. Can be obtained from `CreateAccountOutput.account`.
+///
+/// # Outputs
+/// * `Balance`: The decrypted value.
+///
+/// # Errors
+/// * `Base64DecodingError`: If either of the inputs cannot be decoded from base64.
+/// * `DeserializationError`: If either of the inputs cannot be deserialized.
+/// * `DecryptionError`: If the hint does not encrypt the same value as `encrypted_value`.
+#[wasm_bindgen]
+pub fn decrypt_with_hint(
+    encrypted_value: Base64,
+    hint: Base64,
+    account: Account,
+) -> Fallible<Balance> {
+    let enc_balance = decode::<EncryptedAmount>(encrypted_value)?;
+    let hint = decode::<EncryptedAmountWithHint>(hint)?;
+    let account = account.to_mercat()?;
+
+    let decrypted_value = account
+        .secret
+        .enc_keys
+        .secret
+        .decrypt_with_hint(&enc_balance, &hint)
+        .map_err(|_| WasmError::DecryptionError)?;
+
+    Ok(decrypted_value)
+}
+
 // ------------------------------------------------------------------------------------
 // -                               Internal Functions                                 -
 // ------------------------------------------------------------------------------------
@@ -21,7 +21,10 @@ pub mod verifier;
 use blake2::{Blake2b, Digest};
 use cryptography_core::{
     cdd_claim::{CddClaimData, CddId},
-    codec_wrapper::{RistrettoPointEncoder, ScalarEncoder, RISTRETTO_POINT_SIZE, SCALAR_SIZE},
+    codec_wrapper::{
+        RistrettoPointEncoder, ScalarEncoder, TypeTag, Version, Versioned, RISTRETTO_POINT_SIZE,
+        SCALAR_SIZE,
+    },
     curve25519_dalek::{
         ristretto::{CompressedRistretto, RistrettoPoint},
         scalar::Scalar,
@@ -46,7 +49,10 @@ pub struct Verifier;
 
 /// The initial private set of PUIS.
 #[derive(PartialEq)]
-pub struct PrivateUids(pub Vec<Scalar>);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PrivateUids(
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))] pub Vec<Scalar>,
+);
 
 impl Encode for PrivateUids {
     #[inline]
@@ -75,7 +81,11 @@ impl Decode for PrivateUids {
 
 /// The committed and padded version of the private set of PUIS.
 #[derive(Clone, Debug, PartialEq)]
-pub struct CommittedUids(pub Vec<RistrettoPoint>);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CommittedUids(
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
+    pub  Vec<RistrettoPoint>,
+);
 
 impl Encode for CommittedUids {
     #[inline]
@@ -116,9 +126,17 @@ impl Decode for CommittedUids {
     }
 }
 
+impl Versioned for CommittedUids {
+    const TYPE_TAG: TypeTag = *b"PUID";
+    const VERSION: Version = 1;
+}
+
 /// The Zero-Knowledge challenge.
 #[derive(PartialEq)]
-pub struct Challenge(pub Scalar);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Challenge(
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))] pub Scalar,
+);
 
 /// Holds the initial messages in the Zero-Knowledge Proofs sent by CDD Provider.
 #[derive(Clone, Debug, PartialEq)]
@@ -128,8 +146,10 @@ pub struct ZKPInitialmessage {
     cdd_id_second_half_proof: InitialMessage,
     uid_commitment_proof: InitialMessage,
     /// Committed CDD ID. Corresponding to g^uID * h^DID * f^{hash(uID, DID)}`.
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     a: RistrettoPoint,
     /// Committed version of the second half CDD ID. Corresponding to (h^DID*f^{hash(uID, DID)})^r.
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     b: RistrettoPoint,
 }
 
@@ -176,6 +196,11 @@ impl Decode for ZKPInitialmessage {
     }
 }
 
+impl Versioned for ZKPInitialmessage {
+    const TYPE_TAG: TypeTag = *b"PINI";
+    const VERSION: Version = 1;
+}
+
 /// Holds the CDD Provider's response to the PUIS challenge.
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -185,9 +210,16 @@ pub struct ZKPFinalResponse {
     uid_commitment_proof_response: FinalResponse,
 }
 
+impl Versioned for ZKPFinalResponse {
+    const TYPE_TAG: TypeTag = *b"PFIN";
+    const VERSION: Version = 1;
+}
+
 /// Holds PUIS secret data.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VerifierSecrets {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     rand: Scalar,
 }
 
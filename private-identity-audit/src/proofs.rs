@@ -39,7 +39,9 @@ const PROOF_CHALLENGE_LABEL: &[u8] = b"PolymathPrivateIdentityAuditChallenge";
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InitialMessage {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     a: RistrettoPoint,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     pub generators: Vec<RistrettoPoint>,
 }
 
@@ -96,6 +98,7 @@ impl Decode for InitialMessage {
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FinalResponse {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     response: Vec<Scalar>,
 }
 
@@ -125,7 +128,9 @@ impl Decode for FinalResponse {
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Secrets {
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     rands: Vec<Scalar>,
+    #[cfg_attr(feature = "serde", serde(with = "cryptography_core::serde_hex"))]
     secrets: Vec<Scalar>,
 }
 
@@ -0,0 +1,56 @@
+//! Vectors of the twisted ElGamal encryption and of its constant-time variant with a hint.
+
+use crate::{encoded, scalar_hex, seeded_rng, through_encoding, VectorFile};
+use cryptography_core::{
+    asset_proofs::{Balance, CommitmentWitness, ElgamalSecretKey},
+    curve25519_dalek::scalar::Scalar,
+};
+use serde_json::json;
+
+/// The values to encrypt, along with the seeds that create the keys and the blindings.
+const CASES: [(u8, Balance); 5] = [
+    (1, 0),
+    (2, 1),
+    (3, 42),
+    (4, u32::MAX as Balance),
+    (5, Balance::MAX),
+];
+
+pub fn vectors() -> VectorFile {
+    let vectors = CASES
+        .iter()
+        .map(|&(seed, value)| {
+            let mut rng = seeded_rng(seed);
+            let secret_key = ElgamalSecretKey::new(Scalar::random(&mut rng));
+            let public_key = secret_key.get_public_key();
+
+            let witness = CommitmentWitness::new(value.into(), Scalar::random(&mut rng));
+            let cipher = public_key.encrypt(&witness);
+            let cipher_with_hint = public_key.const_time_encrypt(&witness, &mut rng);
+
+            secret_key
+                .verify(&through_encoding(&cipher), &value.into())
+                .expect("The cipher text encrypts the value");
+            let decrypted = secret_key.const_time_decrypt(&through_encoding(&cipher_with_hint));
+            assert_eq!(decrypted.unwrap(), value);
+
+            json!({
+                "seed": seed,
+                "secret_key": encoded(&secret_key),
+                "public_key": encoded(&public_key),
+                "value": value,
+                "blinding": scalar_hex(&witness.blinding()),
+                "cipher_text": encoded(&cipher),
+                "cipher_text_with_hint": encoded(&cipher_with_hint),
+            })
+        })
+        .collect();
+
+    VectorFile {
+        name: "elgamal",
+        description: "Twisted ElGamal encryptions of `value` with `blinding` under `public_key`. \
+                      `cipher_text_with_hint` is the constant-time encryption of the same value \
+                      and blinding, which `secret_key` decrypts without a discrete log.",
+        vectors,
+    }
+}
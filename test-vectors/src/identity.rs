@@ -0,0 +1,85 @@
+//! Vectors of the CDD ids and of the scope claim proofs of the confidential identity library.
+
+use crate::{encoded, scalar_hex, seeded_rng, through_encoding, VectorFile};
+use confidential_identity::{
+    claim_proofs::{Investor, Verifier},
+    CddClaimData, InvestorTrait, ScopeClaimData, VerifierTrait,
+};
+use cryptography_core::cdd_claim::compute_cdd_id;
+use serde_json::{json, Value};
+
+/// The investor DIDs, unique ids and scope DIDs of the claims, as the byte strings that
+/// `CddClaimData::new` and `ScopeClaimData::new` hash into scalars.
+const CLAIMS: [(&str, &str, &str); 3] = [
+    ("did:poly:investor-1", "uid-1", "did:poly:ticker-1"),
+    ("did:poly:investor-2", "uid-2", "did:poly:ticker-1"),
+    ("did:poly:investor-1", "uid-1", "did:poly:ticker-2"),
+];
+
+pub fn cdd_id_vectors() -> VectorFile {
+    let vectors = CLAIMS
+        .iter()
+        .map(|&(investor_did, investor_unique_id, _)| {
+            let cdd_claim =
+                CddClaimData::new(investor_did.as_bytes(), investor_unique_id.as_bytes());
+            let cdd_id = compute_cdd_id(&cdd_claim);
+
+            json!({
+                "investor_did": investor_did,
+                "investor_unique_id": investor_unique_id,
+                "cdd_claim": encoded(&cdd_claim),
+                "cdd_id": encoded(&cdd_id),
+            })
+        })
+        .collect();
+
+    VectorFile {
+        name: "cdd_id",
+        description: "CDD ids of claims built with `CddClaimData::new` from the UTF-8 bytes of \
+                      `investor_did` and `investor_unique_id`. `cdd_claim` is the encoding of \
+                      the resulting scalars.",
+        vectors,
+    }
+}
+
+pub fn scope_claim_vectors() -> VectorFile {
+    let vectors = CLAIMS
+        .iter()
+        .enumerate()
+        .map(|(index, &(investor_did, investor_unique_id, scope_did))| {
+            let seed = 20 + index as u8;
+            let mut rng = seeded_rng(seed);
+            let cdd_claim =
+                CddClaimData::new(investor_did.as_bytes(), investor_unique_id.as_bytes());
+            let scope_claim =
+                ScopeClaimData::new(scope_did.as_bytes(), investor_unique_id.as_bytes());
+            let cdd_id = compute_cdd_id(&cdd_claim);
+
+            let proof = Investor::create_scope_claim_proof(&cdd_claim, &scope_claim, &mut rng);
+            Verifier::verify_scope_claim_proof(
+                &through_encoding(&proof),
+                &cdd_claim.investor_did,
+                &scope_claim.scope_did,
+                &cdd_id,
+            )
+            .expect("The proof verifies");
+
+            json!({
+                "seed": seed,
+                "investor_did": scalar_hex(&cdd_claim.investor_did),
+                "scope_did": scalar_hex(&scope_claim.scope_did),
+                "cdd_id": encoded(&cdd_id),
+                "encoding": encoded(&proof),
+            })
+        })
+        .collect();
+
+    VectorFile {
+        name: "scope_claim",
+        description: "Scope claim proofs for the claims of the `cdd_id` vectors. Each \
+                      `encoding` verifies against `investor_did`, `scope_did` and `cdd_id`; it \
+                      holds the scope id, so claims of the same investor under the same scope \
+                      share it.",
+        vectors,
+    }
+}
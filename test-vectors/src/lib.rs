@@ -0,0 +1,133 @@
+//! Known-answer test vectors for the proof systems of this repository.
+//!
+//! Each proof system has a JSON file in the `vectors` directory. A vector holds the inputs of
+//! an operation and the expected SCALE encoding of its output, all in lowercase hex. Proofs are
+//! randomized, so each proof vector also holds the seed of the `StdRng` that created it;
+//! other implementations check that they decode the expected encoding, that the proof verifies
+//! against the given statement, and that re-encoding it gives back the same bytes.
+//!
+//! The files are written by the generator binary:
+//! ```text
+//! cargo run -p test_vectors
+//! ```
+//! The tests of this crate regenerate all the vectors and fail if they differ from the
+//! checked-in files, so any change to an encoding or to a proof system must come with
+//! regenerated vectors.
+
+use codec::{Decode, Encode};
+use cryptography_core::curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand::{rngs::StdRng, SeedableRng};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+pub mod elgamal;
+pub mod identity;
+pub mod pial;
+pub mod range_proofs;
+pub mod sigma_proofs;
+
+/// The test vectors of one proof system, stored as `vectors/<name>.json`.
+pub struct VectorFile {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub vectors: Vec<Value>,
+}
+
+impl VectorFile {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "description": self.description,
+            "vectors": self.vectors,
+        })
+    }
+}
+
+/// Generates the test vectors of all the proof systems.
+pub fn all_vectors() -> Vec<VectorFile> {
+    vec![
+        elgamal::vectors(),
+        sigma_proofs::vectors(),
+        range_proofs::vectors(),
+        identity::cdd_id_vectors(),
+        identity::scope_claim_vectors(),
+        pial::vectors(),
+    ]
+}
+
+/// The directory of the checked-in vectors.
+pub fn vectors_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("vectors")
+}
+
+/// The deterministic rng of the vectors, seeded with `[seed; 32]`.
+pub fn seeded_rng(seed: u8) -> StdRng {
+    StdRng::from_seed([seed; 32])
+}
+
+/// The SCALE encoding of `value` in hex.
+pub fn encoded<T: Encode>(value: &T) -> String {
+    hex::encode(value.encode())
+}
+
+/// Sends `value` through its SCALE encoding, so that the vectors are checked against what
+/// a verifier decodes.
+pub fn through_encoding<T: Encode + Decode>(value: &T) -> T {
+    T::decode(&mut &value.encode()[..]).expect("The encoding decodes")
+}
+
+/// The canonical little-endian bytes of `scalar` in hex.
+pub fn scalar_hex(scalar: &Scalar) -> String {
+    hex::encode(scalar.as_bytes())
+}
+
+/// The compressed bytes of `point` in hex.
+pub fn point_hex(point: &RistrettoPoint) -> String {
+    hex::encode(point.compress().as_bytes())
+}
+
+// ------------------------------------------------------------------------
+// Tests
+// ------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn vectors_match_checked_in_files() {
+        let files = all_vectors();
+        let missing: Vec<PathBuf> = files
+            .iter()
+            .map(|file| vectors_dir().join(format!("{}.json", file.name)))
+            .filter(|path| !path.is_file())
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "The vectors {:?} are not checked in. Generate them with `cargo run -p test_vectors` \
+             and commit the `vectors` directory.",
+            missing
+        );
+
+        for file in files {
+            let path = vectors_dir().join(format!("{}.json", file.name));
+            let content = fs::read_to_string(&path).unwrap();
+            let expected: Value = serde_json::from_str(&content)
+                .unwrap_or_else(|error| panic!("{:?} is not valid JSON: {}", path, error));
+            assert!(
+                expected == file.to_json(),
+                "The `{}` vectors drifted from {:?}. If the change is intended, regenerate \
+                 the vectors with `cargo run -p test_vectors`.",
+                file.name,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn vectors_are_deterministic() {
+        let first: Vec<Value> = all_vectors().iter().map(VectorFile::to_json).collect();
+        let second: Vec<Value> = all_vectors().iter().map(VectorFile::to_json).collect();
+        assert_eq!(first, second);
+    }
+}
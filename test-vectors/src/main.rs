@@ -0,0 +1,18 @@
+//! Writes the test vectors of all the proof systems to the `vectors` directory.
+
+use std::fs;
+use test_vectors::{all_vectors, vectors_dir};
+
+fn main() {
+    let dir = vectors_dir();
+    fs::create_dir_all(&dir).expect("Failed to create the vectors directory");
+
+    for file in all_vectors() {
+        let path = dir.join(format!("{}.json", file.name));
+        let mut content =
+            serde_json::to_string_pretty(&file.to_json()).expect("Failed to serialize vectors");
+        content.push('\n');
+        fs::write(&path, content).expect("Failed to write vectors");
+        println!("Wrote {} vectors to {:?}", file.vectors.len(), path);
+    }
+}
@@ -0,0 +1,87 @@
+//! Vectors of the private identity audit protocol.
+
+use crate::{encoded, scalar_hex, seeded_rng, through_encoding, VectorFile};
+use cryptography_core::{
+    cdd_claim::{compute_cdd_id, CddClaimData, CddId},
+    curve25519_dalek::scalar::Scalar,
+};
+use private_identity_audit::{
+    uuid_to_scalar, verifier::gen_random_uuids, CommittedSetGenerator, PrivateUids, ProofGenerator,
+    ProofVerifier, Prover, Verifier, VerifierSetGenerator,
+};
+use serde_json::{json, Value};
+
+/// The number of unique ids of PUIS, the number of them that have a claim, and the padded
+/// size of the committed set.
+const UIDS: usize = 4;
+const CLAIMS: usize = 2;
+const MIN_SET_SIZE: usize = 8;
+
+pub fn vectors() -> VectorFile {
+    VectorFile {
+        name: "pial",
+        description: "A run of the private identity audit protocol. PUIS commits to `uids` \
+                      padded to `min_set_size` with the `committed_set_seed` rng, and the CDD \
+                      provider proves the `claims` against that set with the `proofs_seed` \
+                      rng. `verifier_secrets` verifies the proofs against the `cdd_ids` and \
+                      the re-committed set.",
+        vectors: vec![audit(30, 31, 32)],
+    }
+}
+
+fn audit(uids_seed: u8, committed_set_seed: u8, proofs_seed: u8) -> Value {
+    let mut rng = seeded_rng(uids_seed);
+    let uuids = gen_random_uuids(UIDS, &mut rng);
+    let uids: Vec<Scalar> = uuids.iter().map(|&uuid| uuid_to_scalar(uuid)).collect();
+    let claims: Vec<CddClaimData> = uids[..CLAIMS]
+        .iter()
+        .map(|&investor_unique_id| CddClaimData {
+            investor_did: Scalar::random(&mut rng),
+            investor_unique_id,
+        })
+        .collect();
+    let cdd_ids: Vec<CddId> = claims.iter().map(compute_cdd_id).collect();
+
+    let (verifier_secrets, committed_uids) = VerifierSetGenerator::generate_committed_set(
+        PrivateUids(uids),
+        Some(MIN_SET_SIZE),
+        &mut seeded_rng(committed_set_seed),
+    )
+    .unwrap();
+    let (initial_messages, final_responses, re_committed_uids) = Prover::generate_proofs(
+        &claims,
+        &through_encoding(&committed_uids),
+        &mut seeded_rng(proofs_seed),
+    )
+    .unwrap();
+
+    let results = Verifier::verify_proofs(
+        &through_encoding(&initial_messages),
+        &through_encoding(&final_responses),
+        &cdd_ids,
+        &through_encoding(&verifier_secrets),
+        &through_encoding(&re_committed_uids),
+    );
+    assert!(results.iter().all(Result::is_ok), "All the proofs verify");
+
+    json!({
+        "uids_seed": uids_seed,
+        "committed_set_seed": committed_set_seed,
+        "proofs_seed": proofs_seed,
+        "uids": uuids.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        "min_set_size": MIN_SET_SIZE,
+        "claims": claims
+            .iter()
+            .map(|claim| json!({
+                "investor_did": scalar_hex(&claim.investor_did),
+                "investor_unique_id": scalar_hex(&claim.investor_unique_id),
+            }))
+            .collect::<Vec<_>>(),
+        "cdd_ids": cdd_ids.iter().map(encoded).collect::<Vec<_>>(),
+        "verifier_secrets": encoded(&verifier_secrets),
+        "committed_uids": encoded(&committed_uids),
+        "initial_messages": encoded(&initial_messages),
+        "final_responses": encoded(&final_responses),
+        "re_committed_uids": encoded(&re_committed_uids),
+    })
+}
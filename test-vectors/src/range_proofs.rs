@@ -0,0 +1,97 @@
+//! Vectors of the Bulletproofs range proofs of `cryptography_core::asset_proofs::range_proof`.
+
+use crate::{encoded, scalar_hex, seeded_rng, through_encoding, VectorFile};
+use cryptography_core::{
+    asset_proofs::{
+        bulletproofs::PedersenGens,
+        range_proof::{
+            prove_multiple, prove_within_interval, prove_within_range, verify_multiple,
+            verify_within_interval, verify_within_range,
+        },
+        Balance, CommitmentWitness, ElgamalSecretKey, BALANCE_RANGE,
+    },
+    curve25519_dalek::scalar::Scalar,
+};
+use serde_json::{json, Value};
+
+pub fn vectors() -> VectorFile {
+    VectorFile {
+        name: "range_proofs",
+        description: "Range proofs over the default Pedersen generators. `in_range` proves that \
+                      the committed value lies in `[0, 2^range)`, `aggregated` does so for \
+                      several values at once, and `in_interval` proves that the value encrypted \
+                      by `cipher_text` lies in `[min, max]`.",
+        vectors: vec![
+            in_range(10, 0),
+            in_range(11, 42),
+            in_range(12, Balance::MAX),
+            aggregated(13, &[0, 1, 42, Balance::MAX]),
+            in_interval(14, 42, 10, 100),
+            in_interval(15, 1_000, 1_000, 1_000_000),
+        ],
+    }
+}
+
+fn in_range(seed: u8, value: Balance) -> Value {
+    let mut rng = seeded_rng(seed);
+    let blinding = Scalar::random(&mut rng);
+
+    let proof = prove_within_range(value, blinding, BALANCE_RANGE, &mut rng).unwrap();
+    verify_within_range(&through_encoding(&proof)).expect("The proof verifies");
+
+    json!({
+        "proof": "in_range",
+        "seed": seed,
+        "value": value,
+        "blinding": scalar_hex(&blinding),
+        "range": BALANCE_RANGE,
+        "encoding": encoded(&proof),
+    })
+}
+
+fn aggregated(seed: u8, values: &[Balance]) -> Value {
+    let mut rng = seeded_rng(seed);
+    let blindings: Vec<Scalar> = values.iter().map(|_| Scalar::random(&mut rng)).collect();
+
+    let gens = PedersenGens::default();
+    let commitments: Vec<_> = values
+        .iter()
+        .zip(&blindings)
+        .map(|(value, blinding)| gens.commit(Scalar::from(*value), *blinding))
+        .collect();
+
+    let proof = prove_multiple(values, &blindings, BALANCE_RANGE, &mut rng).unwrap();
+    verify_multiple(&through_encoding(&proof), &commitments, BALANCE_RANGE)
+        .expect("The proof verifies");
+
+    json!({
+        "proof": "aggregated",
+        "seed": seed,
+        "values": values,
+        "blindings": blindings.iter().map(scalar_hex).collect::<Vec<_>>(),
+        "range": BALANCE_RANGE,
+        "encoding": encoded(&proof),
+    })
+}
+
+fn in_interval(seed: u8, value: Balance, min: Balance, max: Balance) -> Value {
+    let mut rng = seeded_rng(seed);
+    let public_key = ElgamalSecretKey::new(Scalar::random(&mut rng)).get_public_key();
+    let witness = CommitmentWitness::new(value.into(), Scalar::random(&mut rng));
+    let cipher = public_key.encrypt(&witness);
+
+    let proof = prove_within_interval(value, witness.blinding(), min, max, &mut rng).unwrap();
+    verify_within_interval(&through_encoding(&proof), &cipher, min, max)
+        .expect("The proof verifies");
+
+    json!({
+        "proof": "in_interval",
+        "seed": seed,
+        "value": value,
+        "min": min,
+        "max": max,
+        "public_key": encoded(&public_key),
+        "cipher_text": encoded(&cipher),
+        "encoding": encoded(&proof),
+    })
+}
@@ -0,0 +1,345 @@
+//! Vectors of the non-interactive sigma proofs of `cryptography_core::asset_proofs`.
+
+use crate::{encoded, point_hex, scalar_hex, seeded_rng, through_encoding, VectorFile};
+use cryptography_core::{
+    asset_proofs::{
+        bulletproofs::PedersenGens,
+        ciphertext_reencryption_proof::{
+            CipherTextReencryptionProverAwaitingChallenge, CipherTextReencryptionVerifier,
+        },
+        ciphertext_refreshment_proof::{
+            CipherTextRefreshmentProverAwaitingChallenge, CipherTextRefreshmentVerifier,
+        },
+        correctness_proof::{CorrectnessProverAwaitingChallenge, CorrectnessVerifier},
+        decryption_proof::{DecryptionProverAwaitingChallenge, DecryptionVerifier},
+        encrypting_same_value_proof::{
+            EncryptingSameValueProverAwaitingChallenge, EncryptingSameValueVerifier,
+        },
+        encryption_proofs::{single_property_prover, single_property_verifier},
+        membership_proof::{MembershipProofVerifier, MembershipProverAwaitingChallenge},
+        non_membership_proof::{NonMembershipProofVerifier, NonMembershipProverAwaitingChallenge},
+        one_out_of_many_proof::OooNProofGenerators,
+        threshold_elgamal::{PartialDecryptionProverAwaitingChallenge, PartialDecryptionVerifier},
+        wellformedness_proof::{WellformednessProverAwaitingChallenge, WellformednessVerifier},
+        Balance, ElgamalPublicKey, ElgamalSecretKey,
+    },
+    curve25519_dalek::scalar::Scalar,
+};
+use rand::rngs::StdRng;
+use serde_json::{json, Value};
+use zeroize::Zeroizing;
+
+/// The encrypted value of the proofs about cipher texts.
+const VALUE: Balance = 42;
+
+/// The size of the elements set of the membership proofs is `BASE^EXPONENT`.
+const BASE: u32 = 4;
+const EXPONENT: u32 = 3;
+
+pub fn vectors() -> VectorFile {
+    VectorFile {
+        name: "sigma_proofs",
+        description: "Non-interactive sigma proofs, as `(initial message, final response)`. \
+                      Each `encoding` verifies against its `statement`, and the seed recreates \
+                      it with `single_property_prover`. The elements sets of the (non-)membership \
+                      proofs are committed with the default Pedersen generators.",
+        vectors: vec![
+            correctness(1),
+            wellformedness(2),
+            encrypting_same_value(3),
+            ciphertext_refreshment(4),
+            ciphertext_reencryption(5),
+            decryption(6),
+            membership(7),
+            non_membership(8),
+            partial_decryption(9),
+        ],
+    }
+}
+
+fn keys(rng: &mut StdRng) -> (ElgamalSecretKey, ElgamalPublicKey) {
+    let secret_key = ElgamalSecretKey::new(Scalar::random(rng));
+    let public_key = secret_key.get_public_key();
+    (secret_key, public_key)
+}
+
+fn vector(proof: &str, seed: u8, statement: Value, encoding: String) -> Value {
+    json!({
+        "proof": proof,
+        "seed": seed,
+        "statement": statement,
+        "encoding": encoding,
+    })
+}
+
+fn correctness(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (_, public_key) = keys(&mut rng);
+    let (witness, cipher) = public_key.encrypt_value(VALUE.into(), &mut rng);
+
+    let prover = CorrectnessProverAwaitingChallenge {
+        pub_key: public_key,
+        w: witness,
+        pc_gens: &gens,
+    };
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = CorrectnessVerifier {
+        value: VALUE.into(),
+        pub_key: public_key,
+        cipher,
+        pc_gens: &gens,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "value": VALUE,
+        "public_key": encoded(&public_key),
+        "cipher_text": encoded(&cipher),
+    });
+    vector("correctness", seed, statement, encoded(&proof))
+}
+
+fn wellformedness(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (_, public_key) = keys(&mut rng);
+    let (witness, cipher) = public_key.encrypt_value(VALUE.into(), &mut rng);
+
+    let prover = WellformednessProverAwaitingChallenge {
+        pub_key: public_key,
+        w: Zeroizing::new(witness),
+        pc_gens: &gens,
+    };
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = WellformednessVerifier {
+        pub_key: public_key,
+        cipher,
+        pc_gens: &gens,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "public_key": encoded(&public_key),
+        "cipher_text": encoded(&cipher),
+    });
+    vector("wellformedness", seed, statement, encoded(&proof))
+}
+
+fn encrypting_same_value(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (_, public_key1) = keys(&mut rng);
+    let (_, public_key2) = keys(&mut rng);
+    let (witness, cipher1) = public_key1.encrypt_value(VALUE.into(), &mut rng);
+    let cipher2 = public_key2.encrypt(&witness);
+
+    let prover = EncryptingSameValueProverAwaitingChallenge {
+        pub_key1: public_key1,
+        pub_key2: public_key2,
+        w: Zeroizing::new(witness),
+        pc_gens: &gens,
+    };
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = EncryptingSameValueVerifier {
+        pub_key1: public_key1,
+        pub_key2: public_key2,
+        cipher1,
+        cipher2,
+        pc_gens: &gens,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "public_key1": encoded(&public_key1),
+        "public_key2": encoded(&public_key2),
+        "cipher_text1": encoded(&cipher1),
+        "cipher_text2": encoded(&cipher2),
+    });
+    vector("encrypting_same_value", seed, statement, encoded(&proof))
+}
+
+fn ciphertext_refreshment(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (secret_key, public_key) = keys(&mut rng);
+    let (_, cipher1) = public_key.encrypt_value(VALUE.into(), &mut rng);
+    let cipher2 = cipher1
+        .refresh(&secret_key, Scalar::random(&mut rng))
+        .unwrap();
+
+    let prover =
+        CipherTextRefreshmentProverAwaitingChallenge::new(secret_key, cipher1, cipher2, &gens);
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = CipherTextRefreshmentVerifier::new(public_key, cipher1, cipher2, &gens);
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "public_key": encoded(&public_key),
+        "cipher_text1": encoded(&cipher1),
+        "cipher_text2": encoded(&cipher2),
+    });
+    vector("ciphertext_refreshment", seed, statement, encoded(&proof))
+}
+
+fn ciphertext_reencryption(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (secret_key1, public_key1) = keys(&mut rng);
+    let (_, public_key2) = keys(&mut rng);
+    let (_, cipher1) = public_key1.encrypt_value(VALUE.into(), &mut rng);
+    let (witness2, cipher2) = public_key2.encrypt_value(VALUE.into(), &mut rng);
+
+    let prover = CipherTextReencryptionProverAwaitingChallenge::new(
+        secret_key1,
+        public_key2,
+        witness2,
+        cipher1,
+        &gens,
+    );
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = CipherTextReencryptionVerifier {
+        pub_key1: public_key1,
+        pub_key2: public_key2,
+        cipher1,
+        cipher2,
+        pc_gens: &gens,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "public_key1": encoded(&public_key1),
+        "public_key2": encoded(&public_key2),
+        "cipher_text1": encoded(&cipher1),
+        "cipher_text2": encoded(&cipher2),
+    });
+    vector("ciphertext_reencryption", seed, statement, encoded(&proof))
+}
+
+fn decryption(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (secret_key, public_key) = keys(&mut rng);
+    let (_, cipher) = public_key.encrypt_value(VALUE.into(), &mut rng);
+
+    let prover = DecryptionProverAwaitingChallenge {
+        secret_key,
+        cipher,
+        value: VALUE.into(),
+        pc_gens: &gens,
+    };
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = DecryptionVerifier {
+        value: VALUE.into(),
+        pub_key: public_key,
+        cipher,
+        pc_gens: &gens,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "value": VALUE,
+        "public_key": encoded(&public_key),
+        "cipher_text": encoded(&cipher),
+    });
+    vector("decryption", seed, statement, encoded(&proof))
+}
+
+/// The even numbers `0, 2, ..., 2 * (BASE^EXPONENT - 1)`.
+fn even_elements() -> Vec<u64> {
+    (0..BASE.pow(EXPONENT) as u64).map(|m| 2 * m).collect()
+}
+
+fn membership(seed: u8) -> Value {
+    let generators = OooNProofGenerators::new(BASE, EXPONENT);
+    let mut rng = seeded_rng(seed);
+    let elements = even_elements();
+    let elements_set: Vec<Scalar> = elements.iter().map(|&e| Scalar::from(e)).collect();
+    let secret = Scalar::from(VALUE);
+    let blinding = Scalar::random(&mut rng);
+    let commitment = generators.com_gens.commit(secret, blinding);
+
+    let prover = MembershipProverAwaitingChallenge::new(
+        secret,
+        blinding,
+        &generators,
+        &elements_set,
+        BASE,
+        EXPONENT,
+    )
+    .unwrap();
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = MembershipProofVerifier {
+        secret_element_com: commitment,
+        elements_set: &elements_set,
+        generators: &generators,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "base": BASE,
+        "exponent": EXPONENT,
+        "elements_set": elements,
+        "commitment": point_hex(&commitment),
+    });
+    vector("membership", seed, statement, encoded(&proof))
+}
+
+fn non_membership(seed: u8) -> Value {
+    let generators = OooNProofGenerators::new(BASE, EXPONENT);
+    let mut rng = seeded_rng(seed);
+    let elements = even_elements();
+    let elements_set: Vec<Scalar> = elements.iter().map(|&e| Scalar::from(e)).collect();
+    let secret = Scalar::from(VALUE + 1);
+    let blinding = Scalar::random(&mut rng);
+    let commitment = generators.com_gens.commit(secret, blinding);
+
+    let prover =
+        NonMembershipProverAwaitingChallenge::new(secret, blinding, &generators, &elements_set)
+            .unwrap();
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = NonMembershipProofVerifier {
+        secret_element_com: commitment,
+        elements_set: &elements_set,
+        generators: &generators,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "elements_set": elements,
+        "commitment": point_hex(&commitment),
+    });
+    vector("non_membership", seed, statement, encoded(&proof))
+}
+
+fn partial_decryption(seed: u8) -> Value {
+    let gens = PedersenGens::default();
+    let mut rng = seeded_rng(seed);
+    let (_, public_key) = keys(&mut rng);
+    let (_, cipher) = public_key.encrypt_value(VALUE.into(), &mut rng);
+    let key_share = Scalar::random(&mut rng);
+    let verification_key = key_share * gens.B_blinding;
+    let share = key_share * cipher.x;
+
+    let prover = PartialDecryptionProverAwaitingChallenge {
+        secret: key_share,
+        x: cipher.x,
+        pc_gens: &gens,
+    };
+    let proof = single_property_prover(prover, &mut rng).unwrap();
+    let verifier = PartialDecryptionVerifier {
+        verification_key,
+        x: cipher.x,
+        share,
+        pc_gens: &gens,
+    };
+    single_property_verifier(&verifier, through_encoding(&proof)).expect("The proof verifies");
+
+    let statement = json!({
+        "key_share": scalar_hex(&key_share),
+        "verification_key": point_hex(&verification_key),
+        "x": point_hex(&cipher.x),
+        "share": point_hex(&share),
+    });
+    vector("partial_decryption", seed, statement, encoded(&proof))
+}